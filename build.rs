@@ -0,0 +1,318 @@
+// 编译期读取 idl/ 目录下的Anchor IDL，生成指令枚举、鉴别器、参数布局与账户名，并检查鉴别器冲突；
+// 解析器按生成的指令枚举分派，历史鉴别器也归入对应的枚举变体
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+const IDL_DIR: &str = "idl";
+
+// 无法由指令名推导的历史鉴别器：(程序, 常量名, 对应的Anchor指令名, 字节)
+const LEGACY: &[(&str, &str, &str, [u8; 8])] = &[
+    ("PUMP", "CREATE_LEGACY", "create", [54, 49, 138, 255, 162, 99, 87, 199]),
+    ("PUMP", "INITIALIZE_LEGACY", "initialize", [103, 232, 80, 22, 46, 244, 138, 11]),
+    ("PUMP", "SELL_LEGACY", "sell", [157, 141, 99, 91, 56, 32, 241, 199]),
+    ("PUMP", "SET_PARAMS_LEGACY", "set_params", [186, 127, 135, 21, 36, 67, 77, 55]),
+    ("PUMP", "WITHDRAW_LEGACY", "withdraw", [16, 9, 233, 100, 246, 18, 249, 253]),
+    ("BOOP", "CREATE", "create", [191, 19, 103, 26, 245, 85, 112, 105]),
+    ("BOOP", "SELL", "sell", [25, 169, 76, 76, 84, 153, 195, 216]),
+    ("BOOP", "SET_PARAMS", "set_params", [235, 129, 153, 118, 219, 194, 131, 246]),
+    ("BOOP", "UPDATE_AUTHORITY", "update_authority", [167, 17, 172, 137, 241, 116, 201, 161]),
+];
+
+struct IdlInstruction {
+    name: String,
+    discriminator: [u8; 8],
+    accounts: Vec<String>,
+    args: Vec<(String, String)>,
+}
+
+struct IdlProgram {
+    module: String,
+    prefix: String,
+    address: String,
+    instructions: Vec<IdlInstruction>,
+}
+
+fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("global:{}", name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+// 将IDL类型转换为规范字符串，格式与 idl::IdlType 的 FromStr 实现一致
+fn render_type(ty: &Value) -> String {
+    match ty {
+        Value::String(s) if s == "publicKey" => "pubkey".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Object(map) => {
+            if let Some(inner) = map.get("option") {
+                format!("option<{}>", render_type(inner))
+            } else if let Some(inner) = map.get("vec") {
+                format!("vec<{}>", render_type(inner))
+            } else if let Some(Value::Array(array)) = map.get("array") {
+                format!("[{};{}]", render_type(&array[0]), array[1])
+            } else if let Some(defined) = map.get("defined") {
+                let name = defined.get("name").unwrap_or(defined);
+                format!("defined:{}", name.as_str().unwrap_or_default())
+            } else {
+                panic!("不支持的IDL类型: {}", ty)
+            }
+        }
+        _ => panic!("不支持的IDL类型: {}", ty),
+    }
+}
+
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) => c.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn load_program(path: &Path) -> IdlProgram {
+    let content = fs::read_to_string(path).unwrap();
+    let idl: Value = serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("无法解析IDL {}: {}", path.display(), e));
+    let module = path.file_stem().unwrap().to_str().unwrap().to_string();
+
+    let instructions = idl["instructions"]
+        .as_array()
+        .unwrap_or_else(|| panic!("{} 缺少instructions", path.display()))
+        .iter()
+        .map(|ix| {
+            let name = ix["name"].as_str().unwrap().to_string();
+            // IDL中给出鉴别器时直接使用，否则按Anchor规则推导
+            let discriminator = match ix.get("discriminator").and_then(Value::as_array) {
+                Some(bytes) => {
+                    let bytes: Vec<u8> = bytes
+                        .iter()
+                        .map(|b| {
+                            b.as_u64()
+                                .and_then(|b| u8::try_from(b).ok())
+                                .unwrap_or_else(|| panic!("{} 指令 {} 的鉴别器包含非字节值 {}", path.display(), name, b))
+                        })
+                        .collect();
+                    // 长度必须恰好为8，截断或补零会得到与链上不同的鉴别器
+                    bytes.as_slice().try_into().unwrap_or_else(|_| {
+                        panic!("{} 指令 {} 的鉴别器长度为 {}，应为8", path.display(), name, bytes.len())
+                    })
+                }
+                None => anchor_discriminator(&name),
+            };
+            let accounts = ix["accounts"]
+                .as_array()
+                .map(|a| a.iter().map(|acc| acc["name"].as_str().unwrap().to_string()).collect())
+                .unwrap_or_default();
+            let args = ix["args"]
+                .as_array()
+                .map(|a| {
+                    a.iter()
+                        .map(|arg| (arg["name"].as_str().unwrap().to_string(), render_type(&arg["type"])))
+                        .collect()
+                })
+                .unwrap_or_default();
+            IdlInstruction { name, discriminator, accounts, args }
+        })
+        .collect();
+
+    IdlProgram {
+        prefix: module.to_uppercase().replace('_', ""),
+        address: idl["address"].as_str().unwrap_or_default().to_string(),
+        module,
+        instructions,
+    }
+}
+
+fn load_programs() -> Vec<IdlProgram> {
+    println!("cargo:rerun-if-changed={}", IDL_DIR);
+    let mut paths: Vec<_> = fs::read_dir(IDL_DIR)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            println!("cargo:rerun-if-changed={}", path.display());
+            load_program(path)
+        })
+        .collect()
+}
+
+// 生成鉴别器常量并检查冲突
+fn generate_discriminators(programs: &[IdlProgram]) -> String {
+    let mut entries = Vec::new();
+    // 所有IDL指令的鉴别器 -> 指令名
+    let mut by_name: HashMap<[u8; 8], &str> = HashMap::new();
+    for program in programs {
+        for ix in &program.instructions {
+            by_name.insert(ix.discriminator, &ix.name);
+            entries.push((
+                program.prefix.clone(),
+                ix.name.to_uppercase(),
+                ix.name.clone(),
+                ix.discriminator,
+            ));
+        }
+    }
+
+    // 历史鉴别器不能与其他指令名推导出的鉴别器相同
+    for (program, const_name, name, discriminator) in LEGACY {
+        if let Some(other) = by_name.get(discriminator) {
+            if other != name {
+                panic!(
+                    "{}_{} 声明为 {}，但其鉴别器等于 sha256(\"global:{}\")",
+                    program, const_name, name, other
+                );
+            }
+        }
+        entries.push((program.to_string(), const_name.to_string(), name.to_string(), *discriminator));
+    }
+
+    // 同一程序内鉴别器不能重复
+    let mut seen: HashMap<(&str, [u8; 8]), &str> = HashMap::new();
+    for (program, const_name, _, discriminator) in &entries {
+        if let Some(previous) = seen.insert((program.as_str(), *discriminator), const_name.as_str()) {
+            panic!(
+                "{} 程序内鉴别器冲突: {} 与 {} 均为 {:?}",
+                program, previous, const_name, discriminator
+            );
+        }
+    }
+
+    let mut out = String::new();
+    for (program, const_name, name, discriminator) in &entries {
+        writeln!(out, "// {} {}", program.to_lowercase(), name).unwrap();
+        writeln!(out, "pub const {}_{}: [u8; 8] = {:?};", program, const_name, discriminator).unwrap();
+    }
+
+    // 供测试遍历的完整列表：(程序, 常量名, 鉴别器)
+    writeln!(out, "pub const ALL_DISCRIMINATORS: &[(&str, &str, [u8; 8])] = &[").unwrap();
+    for (program, const_name, _, _) in &entries {
+        writeln!(
+            out,
+            "    ({:?}, {:?}, {}_{}),",
+            program.to_lowercase(), const_name, program, const_name
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    out
+}
+
+// 为每个IDL生成一个模块：指令枚举、指令定义表、历史鉴别器与程序定义
+fn generate_programs(programs: &[IdlProgram]) -> String {
+    let mut out = String::new();
+
+    for program in programs {
+        // 该程序的历史鉴别器；指令名不在IDL中的作为额外的枚举变体，排在IDL指令之后
+        let legacy: Vec<_> = LEGACY.iter().filter(|(prefix, ..)| *prefix == program.prefix).collect();
+        let mut variants: Vec<String> = program.instructions.iter().map(|ix| to_camel_case(&ix.name)).collect();
+        for (_, _, name, _) in &legacy {
+            let variant = to_camel_case(name);
+            if !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+
+        writeln!(out, "pub mod {} {{", program.module).unwrap();
+        writeln!(out, "    use crate::idl::{{IdlInstructionDef, IdlProgramDef}};\n").unwrap();
+        writeln!(out, "    pub const PROGRAM_ID: &str = {:?};\n", program.address).unwrap();
+
+        writeln!(out, "    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]").unwrap();
+        writeln!(out, "    pub enum Instruction {{").unwrap();
+        for (i, variant) in variants.iter().enumerate() {
+            if i == program.instructions.len() {
+                writeln!(out, "        // 历史指令，当前IDL中已没有").unwrap();
+            }
+            writeln!(out, "        {},", variant).unwrap();
+        }
+        writeln!(out, "    }}\n").unwrap();
+
+        writeln!(out, "    pub const INSTRUCTIONS: &[IdlInstructionDef] = &[").unwrap();
+        for ix in &program.instructions {
+            writeln!(out, "        IdlInstructionDef {{").unwrap();
+            writeln!(out, "            name: {:?},", ix.name).unwrap();
+            writeln!(out, "            discriminator: {:?},", ix.discriminator).unwrap();
+            writeln!(out, "            accounts: &{:?},", ix.accounts).unwrap();
+            writeln!(out, "            args: &{:?},", ix.args).unwrap();
+            writeln!(out, "        }},").unwrap();
+        }
+        writeln!(out, "    ];\n").unwrap();
+
+        writeln!(out, "    // 历史鉴别器与对应的指令").unwrap();
+        writeln!(out, "    pub const LEGACY: &[([u8; 8], Instruction)] = &[").unwrap();
+        for (_, _, name, discriminator) in &legacy {
+            writeln!(out, "        ({:?}, Instruction::{}),", discriminator, to_camel_case(name)).unwrap();
+        }
+        writeln!(out, "    ];\n").unwrap();
+
+        writeln!(
+            out,
+            "    pub const PROGRAM: IdlProgramDef = IdlProgramDef {{ name: {:?}, address: PROGRAM_ID, instructions: INSTRUCTIONS }};\n",
+            program.module
+        )
+        .unwrap();
+
+        writeln!(out, "    impl Instruction {{").unwrap();
+        writeln!(out, "        pub const ALL: &'static [Instruction] = &[").unwrap();
+        for variant in &variants {
+            writeln!(out, "            Instruction::{},", variant).unwrap();
+        }
+        writeln!(out, "        ];\n").unwrap();
+        writeln!(out, "        // 先按IDL鉴别器查找，再查历史鉴别器").unwrap();
+        writeln!(out, "        pub fn from_discriminator(data: &[u8]) -> Option<Self> {{").unwrap();
+        writeln!(out, "            INSTRUCTIONS").unwrap();
+        writeln!(out, "                .iter()").unwrap();
+        writeln!(out, "                .position(|def| data.starts_with(&def.discriminator))").unwrap();
+        writeln!(out, "                .map(|i| Self::ALL[i])").unwrap();
+        writeln!(
+            out,
+            "                .or_else(|| LEGACY.iter().find(|(discriminator, _)| data.starts_with(discriminator)).map(|(_, ix)| *ix))"
+        )
+        .unwrap();
+        writeln!(out, "        }}\n").unwrap();
+        writeln!(out, "        // IDL中的指令定义，历史指令没有").unwrap();
+        writeln!(out, "        pub fn def(&self) -> Option<&'static IdlInstructionDef> {{").unwrap();
+        writeln!(out, "            INSTRUCTIONS.get(*self as usize)").unwrap();
+        writeln!(out, "        }}\n").unwrap();
+        writeln!(out, "        pub fn name(&self) -> &'static str {{").unwrap();
+        writeln!(out, "            match self {{").unwrap();
+        for variant in &variants {
+            writeln!(out, "                Instruction::{} => {:?},", variant, variant).unwrap();
+        }
+        writeln!(out, "            }}").unwrap();
+        writeln!(out, "        }}").unwrap();
+        writeln!(out, "    }}").unwrap();
+        writeln!(out, "}}\n").unwrap();
+    }
+
+    writeln!(out, "pub const PROGRAMS: &[crate::idl::IdlProgramDef] = &[").unwrap();
+    for program in programs {
+        writeln!(out, "    {}::PROGRAM,", program.module).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    out
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let programs = load_programs();
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    fs::write(Path::new(&out_dir).join("discriminators.rs"), generate_discriminators(&programs)).unwrap();
+    fs::write(Path::new(&out_dir).join("idl_programs.rs"), generate_programs(&programs)).unwrap();
+}
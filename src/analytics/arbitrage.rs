@@ -0,0 +1,293 @@
+// 跨场所套利：按mint保存各池子以SOL计价的现价，价格变化时比较同一mint的全部池子：
+// 在扣除手续费后最便宜的池子买入、最贵的池子卖出，净价差超过threshold_bps时输出机会事件，带上触发的slot与交易。
+// 本项目只解码PumpSwap：现价取自PoolRegistry（CreatePool / pump的Migrate / Buy / Sell估算的储备），
+// 因此内置的比较只在同一mint的多个PumpSwap池子之间进行。Raydium / Meteora等场所没有解码器，
+// 作为库使用时由外部解码器通过price_updated报告。比较的是边际价格，没有考虑成交量对价格的影响
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::analytics::{EventSink, PoolRegistry, Venue};
+use crate::config::ArbitrageConfig;
+use crate::labels;
+use crate::models::events::account;
+use crate::models::pump_parser::PumpInstructionType;
+use crate::models::pumpamm_parser::PumpAmmInstructionType;
+use crate::models::token_parser::WSOL_MINT;
+use crate::models::TransactionResults;
+
+const BPS: f64 = 10_000.0;
+
+// 一个池子中mint的现价（每个代币最小单位的lamports）与交易费率
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenuePrice {
+    pub venue: Venue,
+    pub pool: String,
+    pub price: f64,
+    pub fee_bps: u64,
+    // 价格所在的slot
+    pub slot: u64,
+}
+
+impl VenuePrice {
+    // 买入一个代币最小单位的成本（含手续费）
+    fn buy_cost(&self) -> f64 {
+        self.price * (1.0 + self.fee_bps as f64 / BPS)
+    }
+
+    // 卖出一个代币最小单位的所得（扣除手续费）
+    fn sell_proceeds(&self) -> f64 {
+        self.price * (1.0 - self.fee_bps as f64 / BPS)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "venue": self.venue.as_str(),
+            "pool": self.pool,
+            "price": self.price,
+            "fee_bps": self.fee_bps,
+            "slot": self.slot,
+        })
+    }
+}
+
+// 一次套利机会：在buy池子买入、在sell池子卖出
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opportunity {
+    pub mint: String,
+    pub buy: VenuePrice,
+    pub sell: VenuePrice,
+    // 扣除两边手续费后的价差（基点）
+    pub net_bps: f64,
+    pub slot: u64,
+    pub tx: String,
+}
+
+impl Opportunity {
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "mint": self.mint,
+            "buy": self.buy.to_json(),
+            "sell": self.sell.to_json(),
+            "net_bps": self.net_bps,
+            "slot": self.slot,
+            "tx": self.tx,
+        })
+    }
+}
+
+impl fmt::Display for Opportunity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} buy={}:{}@{} sell={}:{}@{} net_bps={:.1} slot={} tx={}",
+            labels::text("arbitrage"),
+            self.mint,
+            self.buy.venue,
+            self.buy.pool,
+            self.buy.price,
+            self.sell.venue,
+            self.sell.pool,
+            self.sell.price,
+            self.net_bps,
+            self.slot,
+            self.tx
+        )
+    }
+}
+
+pub struct ArbitrageDetector {
+    // mint -> 各池子的最新价格
+    prices: HashMap<String, Vec<VenuePrice>>,
+    // mint -> 上一次输出的机会，价格没有变化时不重复输出
+    last: HashMap<String, Opportunity>,
+    threshold_bps: f64,
+    max_age_slots: u64,
+    // 下一次清理过期价格的slot
+    next_sweep: u64,
+    opportunities: u64,
+}
+
+impl ArbitrageDetector {
+    #[allow(dead_code)]
+    pub fn new(config: &ArbitrageConfig) -> Self {
+        Self {
+            prices: HashMap::new(),
+            last: HashMap::new(),
+            threshold_bps: config.threshold_bps as f64,
+            max_age_slots: config.max_age_slots,
+            next_sweep: 0,
+            opportunities: 0,
+        }
+    }
+
+    // 在pools.record(results)之后调用：本slot中建池（包括pump的Migrate）或买卖过的以WSOL计价的PumpSwap池子按注册表更新价格，
+    // 再按entry中的顺序比较涉及的mint；触发交易是该mint最后一条改变价格的指令
+    #[allow(dead_code)]
+    pub fn record<'a, S: EventSink<Opportunity>>(&mut self, results: &'a TransactionResults, pools: &PoolRegistry, sink: &mut S) {
+        let slot = results.current_slot;
+        let wsol = WSOL_MINT.to_string();
+        // 池子 -> 最后一条指令的 (交易序号, 指令序号, 签名)
+        let mut touched: HashMap<String, (usize, usize, &str)> = HashMap::new();
+        let mut touch = |pool: Option<String>, order: (usize, usize, &'a str)| {
+            let Some(pool) = pool else {
+                return;
+            };
+            let last = touched.entry(pool).or_insert(order);
+            if (order.0, order.1) > (last.0, last.1) {
+                *last = order;
+            }
+        };
+        for tx in &results.pumpamm_transactions {
+            let index = results.transaction_index(&tx.signature).unwrap_or(usize::MAX);
+            for (position, ix) in tx.instructions.iter().enumerate() {
+                if matches!(
+                    ix.instruction_type,
+                    Some(PumpAmmInstructionType::CreatePool | PumpAmmInstructionType::Buy | PumpAmmInstructionType::Sell)
+                ) {
+                    touch(account(ix.account_layout(), &ix.accounts, "pool"), (index, position, &tx.signature));
+                }
+            }
+        }
+        // pump的Migrate通过CPI建池
+        for tx in &results.pump_transactions {
+            let index = results.transaction_index(&tx.signature).unwrap_or(usize::MAX);
+            for (position, ix) in tx.instructions.iter().enumerate() {
+                if ix.instruction_type == Some(PumpInstructionType::Migrate) {
+                    touch(account(ix.account_layout(), &ix.accounts, "pool"), (index, position, &tx.signature));
+                }
+            }
+        }
+
+        // mint -> 触发交易，按触发顺序
+        let mut mints: Vec<(String, (usize, usize, &str))> = Vec::new();
+        for (pool, order) in touched {
+            let Some(state) = pools.get(&pool).filter(|state| state.quote_mint == wsol) else {
+                continue;
+            };
+            let Some(price) = state.spot_price() else {
+                continue;
+            };
+            self.update(
+                &state.base_mint,
+                VenuePrice {
+                    venue: Venue::PumpSwap,
+                    pool,
+                    price,
+                    fee_bps: pools.fees(state).total_bps(),
+                    slot,
+                },
+            );
+            match mints.iter_mut().find(|(mint, _)| *mint == state.base_mint) {
+                Some((_, last)) if (order.0, order.1) > (last.0, last.1) => *last = order,
+                Some(_) => {}
+                None => mints.push((state.base_mint.clone(), order)),
+            }
+        }
+        mints.sort_by_key(|(_, (index, position, _))| (*index, *position));
+        for (mint, (_, _, tx)) in mints {
+            self.evaluate(&mint, slot, tx, sink);
+        }
+        self.sweep(slot);
+    }
+
+    // 报告其他场所（例如Raydium / Meteora）池子的现价并立即比较；本项目没有这些场所的解码器，供外部解码器调用，
+    // price是每个代币最小单位的lamports
+    #[allow(dead_code)]
+    pub fn price_updated<S: EventSink<Opportunity>>(&mut self, mint: &str, price: VenuePrice, tx: &str, sink: &mut S) {
+        let slot = price.slot;
+        self.update(mint, price);
+        self.evaluate(mint, slot, tx, sink);
+    }
+
+    fn update(&mut self, mint: &str, price: VenuePrice) {
+        let prices = self.prices.entry(mint.to_string()).or_default();
+        match prices.iter_mut().find(|current| current.pool == price.pool) {
+            Some(current) => *current = price,
+            None => prices.push(price),
+        }
+    }
+
+    // 每max_age_slots个slot去掉全部过期的价格：不再有变化的mint不会经过evaluate，否则会一直留在内存中
+    fn sweep(&mut self, slot: u64) {
+        if slot < self.next_sweep {
+            return;
+        }
+        self.next_sweep = slot.saturating_add(self.max_age_slots);
+        let max_age_slots = self.max_age_slots;
+        self.prices.retain(|_, prices| {
+            prices.retain(|price| price.slot.saturating_add(max_age_slots) >= slot);
+            !prices.is_empty()
+        });
+        let prices = &self.prices;
+        self.last.retain(|mint, _| prices.contains_key(mint));
+    }
+
+    // 比较mint在各池子的价格，去掉过期的价格；最好的一对超过阈值且与上次输出不同时输出
+    fn evaluate<S: EventSink<Opportunity>>(&mut self, mint: &str, slot: u64, tx: &str, sink: &mut S) {
+        let Some(prices) = self.prices.get_mut(mint) else {
+            return;
+        };
+        let max_age_slots = self.max_age_slots;
+        prices.retain(|price| price.slot.saturating_add(max_age_slots) >= slot);
+        if prices.is_empty() {
+            self.prices.remove(mint);
+            self.last.remove(mint);
+            return;
+        }
+
+        let mut best: Option<(&VenuePrice, &VenuePrice, f64)> = None;
+        for buy in prices.iter() {
+            for sell in prices.iter().filter(|sell| sell.pool != buy.pool) {
+                let cost = buy.buy_cost();
+                if cost <= 0.0 {
+                    continue;
+                }
+                let net_bps = (sell.sell_proceeds() / cost - 1.0) * BPS;
+                if best.is_none_or(|(_, _, best_bps)| net_bps > best_bps) {
+                    best = Some((buy, sell, net_bps));
+                }
+            }
+        }
+        let Some((buy, sell, net_bps)) = best.filter(|(_, _, net_bps)| *net_bps >= self.threshold_bps) else {
+            self.last.remove(mint);
+            return;
+        };
+        let opportunity = Opportunity {
+            mint: mint.to_string(),
+            buy: buy.clone(),
+            sell: sell.clone(),
+            net_bps,
+            slot,
+            tx: tx.to_string(),
+        };
+        // 两边的价格都没有变化时是同一个机会
+        if self.last.get(mint).is_some_and(|last| {
+            (&last.buy.pool, last.buy.price, &last.sell.pool, last.sell.price)
+                == (&opportunity.buy.pool, opportunity.buy.price, &opportunity.sell.pool, opportunity.sell.price)
+        }) {
+            return;
+        }
+        self.opportunities += 1;
+        sink.publish(&opportunity);
+        self.last.insert(mint.to_string(), opportunity);
+    }
+
+    // mint在各池子的最新价格
+    #[allow(dead_code)]
+    pub fn prices(&self, mint: &str) -> &[VenuePrice] {
+        self.prices.get(mint).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    #[allow(dead_code)]
+    pub fn mints(&self) -> usize {
+        self.prices.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn opportunities(&self) -> u64 {
+        self.opportunities
+    }
+}
@@ -0,0 +1,424 @@
+// 按mint聚合的OHLCV K线：pump、boop绑定曲线与PumpSwap池子中以SOL计价的买卖按slot时间落入各周期的时间桶。
+// shreds中没有区块时间，slot时间按 基准时刻 + (slot - 基准slot) * slot时长 估算（与指标的slot时钟相同），
+// 基准默认取第一个见到的slot与当时的本地时间。指令参数只给出一侧的精确数量与另一侧的滑点限制，
+// 价格与成交量取自CurveTracker与PoolRegistry按估算状态模拟的成交（见Fill），没有跟踪状态的曲线与池子上的买卖不计入
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::analytics::{EventSink, Fill};
+use crate::config::CandlesConfig;
+use crate::export::writer::now_ms;
+use crate::labels;
+use crate::models::events::TradeSide;
+use crate::models::token_parser::WSOL_MINT;
+use crate::utils::account_layout::Program;
+
+// K线周期，配置中写作 "1s"、"1m"、"5m"、"1h"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub struct CandleInterval {
+    secs: u64,
+}
+
+impl CandleInterval {
+    #[allow(dead_code)]
+    pub const fn from_secs(secs: u64) -> Self {
+        Self { secs }
+    }
+
+    #[allow(dead_code)]
+    pub fn secs(&self) -> u64 {
+        self.secs
+    }
+
+    #[allow(dead_code)]
+    pub fn millis(&self) -> i64 {
+        self.secs as i64 * 1_000
+    }
+
+    // 时间所在桶的开始时间
+    #[allow(dead_code)]
+    pub fn bucket(&self, time_ms: i64) -> i64 {
+        time_ms.div_euclid(self.millis()) * self.millis()
+    }
+}
+
+impl FromStr for CandleInterval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unit = match s.chars().last() {
+            Some('s') => 1,
+            Some('m') => 60,
+            Some('h') => 3_600,
+            _ => return Err(format!("无效的K线周期: {}（例如 1s / 1m / 5m / 1h）", s)),
+        };
+        match s[..s.len() - 1].parse::<u64>() {
+            Ok(count) if count > 0 && count <= u32::MAX as u64 => Ok(Self::from_secs(count * unit)),
+            _ => Err(format!("无效的K线周期: {}（例如 1s / 1m / 5m / 1h）", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for CandleInterval {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for CandleInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.secs {
+            secs if secs % 3_600 == 0 => write!(f, "{}h", secs / 3_600),
+            secs if secs % 60 == 0 => write!(f, "{}m", secs / 60),
+            secs => write!(f, "{}s", secs),
+        }
+    }
+}
+
+// 一根K线；价格为每个代币最小单位的lamports，成交量为最小单位
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    pub mint: String,
+    pub interval: CandleInterval,
+    // 桶的开始时间（Unix毫秒，slot时间）
+    pub start_ms: i64,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    // 收盘成交所在的场所；迁移前后同一mint的K线是连续的
+    pub venue: Program,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub token_volume: u64,
+    pub quote_volume: u64,
+    pub trades: u64,
+    pub buys: u64,
+    pub sells: u64,
+}
+
+impl Candle {
+    #[allow(dead_code)]
+    pub fn end_ms(&self) -> i64 {
+        self.start_ms + self.interval.millis()
+    }
+
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "mint": self.mint,
+            "interval": self.interval.to_string(),
+            "start_ms": self.start_ms,
+            "end_ms": self.end_ms(),
+            "first_slot": self.first_slot,
+            "last_slot": self.last_slot,
+            "venue": self.venue.parser_name(),
+            "open": self.open,
+            "high": self.high,
+            "low": self.low,
+            "close": self.close,
+            "token_volume": self.token_volume,
+            "quote_volume": self.quote_volume,
+            "trades": self.trades,
+            "buys": self.buys,
+            "sells": self.sells,
+        })
+    }
+}
+
+impl fmt::Display for Candle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} {} {} open={} high={} low={} close={} token_volume={} quote_volume={} trades={}",
+            labels::text("candle"),
+            self.mint,
+            self.interval,
+            self.start_ms,
+            self.open,
+            self.high,
+            self.low,
+            self.close,
+            self.token_volume,
+            self.quote_volume,
+            self.trades
+        )
+    }
+}
+
+// 成交在slot内的先后：(slot, 消息到达序号, 交易在消息中的序号, 指令序号)
+type TradeKey = (u64, u64, usize, usize);
+
+// 未收盘的K线与开盘、收盘成交的位置
+struct Building {
+    candle: Candle,
+    open_key: TradeKey,
+    close_key: TradeKey,
+}
+
+// 一个mint在一个周期上的K线
+#[derive(Default)]
+struct Series {
+    // 未收盘的桶，键为开始时间
+    open: BTreeMap<i64, Building>,
+    // 已收盘的K线，最旧的在前，只保留最近history个桶内的
+    closed: VecDeque<Candle>,
+}
+
+impl Series {
+    // 丢弃早于 水位所在桶 - history 个桶的K线
+    fn trim(&mut self, interval: CandleInterval, history: usize, watermark: i64) {
+        let oldest = interval.bucket(watermark) - interval.millis() * history as i64;
+        while self.closed.front().is_some_and(|candle| candle.start_ms < oldest) {
+            self.closed.pop_front();
+        }
+    }
+}
+
+// K线聚合器：同一slot的成交按entry顺序排列，不依赖到达顺序；最新slot往前grace_slots个slot结束后，
+// 之前结束的桶收盘并交给sink，之后到达的属于已收盘桶的成交计入late_trades并丢弃
+pub struct CandleAggregator {
+    intervals: Vec<CandleInterval>,
+    history: usize,
+    grace_slots: u64,
+    slot_duration_ms: i64,
+    // (基准slot, 基准时刻Unix毫秒)
+    origin: Option<(u64, i64)>,
+    latest_slot: Option<u64>,
+    // 已收盘的时间，早于此时间结束的桶都已收盘
+    watermark: Option<i64>,
+    next_sweep: i64,
+    sequence: u64,
+    series: HashMap<String, Vec<Series>>,
+    // 未收盘的桶：(结束时间, mint, 周期序号)
+    pending: BTreeSet<(i64, String, usize)>,
+    late_trades: u64,
+}
+
+impl CandleAggregator {
+    #[allow(dead_code)]
+    pub fn new(config: &CandlesConfig) -> Self {
+        let mut intervals = config.intervals.clone();
+        intervals.sort();
+        intervals.dedup();
+        Self {
+            intervals,
+            history: config.history.max(1),
+            grace_slots: config.grace_slots,
+            slot_duration_ms: config.slot_duration_ms.max(1) as i64,
+            origin: None,
+            latest_slot: None,
+            watermark: None,
+            next_sweep: i64::MIN,
+            sequence: 0,
+            series: HashMap::new(),
+            pending: BTreeSet::new(),
+            late_trades: 0,
+        }
+    }
+
+    // 指定slot时间的基准；未指定时取第一个见到的slot与当时的本地时间
+    #[allow(dead_code)]
+    pub fn set_origin(&mut self, slot: u64, unix_ms: i64) {
+        self.origin = Some((slot, unix_ms));
+    }
+
+    // slot的估算开始时间（Unix毫秒）
+    #[allow(dead_code)]
+    pub fn slot_time(&self, slot: u64) -> Option<i64> {
+        let (origin_slot, origin_ms) = self.origin?;
+        Some(origin_ms + (slot as i64 - origin_slot as i64) * self.slot_duration_ms)
+    }
+
+    #[allow(dead_code)]
+    pub fn intervals(&self) -> &[CandleInterval] {
+        &self.intervals
+    }
+
+    // 因最短周期的桶已经收盘而丢弃的成交数
+    #[allow(dead_code)]
+    pub fn late_trades(&self) -> u64 {
+        self.late_trades
+    }
+
+    // 有K线（含未收盘）的mint数
+    #[allow(dead_code)]
+    pub fn mints(&self) -> usize {
+        self.series.len()
+    }
+
+    // 一个mint在某个周期上最近的n根已收盘K线，最旧的在前；没有成交的桶没有K线
+    #[allow(dead_code)]
+    pub fn last(&self, mint: &str, interval: CandleInterval, n: usize) -> Vec<Candle> {
+        let (Some(series), Some(index)) = (self.series.get(mint), self.interval_index(interval)) else {
+            return Vec::new();
+        };
+        let closed = &series[index].closed;
+        closed.iter().skip(closed.len().saturating_sub(n)).cloned().collect()
+    }
+
+    // 一个mint在某个周期上最新的未收盘K线
+    #[allow(dead_code)]
+    pub fn current(&self, mint: &str, interval: CandleInterval) -> Option<&Candle> {
+        let series = &self.series.get(mint)?[self.interval_index(interval)?];
+        series.open.values().next_back().map(|building| &building.candle)
+    }
+
+    // 输入一条消息（同一slot可能有多条）中模拟的成交，通常是curves.fills()与pools.fills()；
+    // 没有成交的slot也要调用以推进时间，把因此收盘的K线交给sink
+    #[allow(dead_code)]
+    pub fn record<'a, S: EventSink<Candle>>(&mut self, slot: u64, fills: impl IntoIterator<Item = &'a Fill>, sink: &mut S) {
+        if self.origin.is_none() {
+            self.set_origin(slot, now_ms());
+        }
+        self.sequence += 1;
+
+        let wsol = WSOL_MINT.to_string();
+        for fill in fills {
+            if fill.quote_mint != wsol {
+                continue;
+            }
+            self.record_trade(fill, (slot, self.sequence, fill.index, fill.position));
+        }
+
+        if self.latest_slot.is_some_and(|latest| latest >= slot) {
+            return;
+        }
+        self.latest_slot = Some(slot);
+        // 水位：slot latest - grace_slots 的结束时间
+        let Some(watermark) = self.slot_time(slot.saturating_sub(self.grace_slots)) else {
+            return;
+        };
+        self.close_until(watermark + self.slot_duration_ms, sink);
+    }
+
+    // 收盘全部未收盘的K线，回放结束时调用
+    #[allow(dead_code)]
+    pub fn flush<S: EventSink<Candle>>(&mut self, sink: &mut S) {
+        if let Some(end) = self.pending.iter().map(|(end, _, _)| *end).max() {
+            self.close_until(end, sink);
+        }
+    }
+
+    fn interval_index(&self, interval: CandleInterval) -> Option<usize> {
+        self.intervals.iter().position(|candidate| *candidate == interval)
+    }
+
+    fn record_trade(&mut self, trade: &Fill, key: TradeKey) {
+        let (Some(price), Some(time)) = (trade.price(), self.slot_time(trade.slot)) else {
+            return;
+        };
+
+        let intervals = self.intervals.len();
+        for (index, interval) in self.intervals.iter().enumerate() {
+            let start = interval.bucket(time);
+            let end = start + interval.millis();
+            if self.watermark.is_some_and(|watermark| end <= watermark) {
+                // 只按最短周期计数一次
+                if index == 0 {
+                    self.late_trades += 1;
+                }
+                continue;
+            }
+
+            let series = self
+                .series
+                .entry(trade.mint.clone())
+                .or_insert_with(|| (0..intervals).map(|_| Series::default()).collect());
+            let building = series[index].open.entry(start).or_insert_with(|| {
+                self.pending.insert((end, trade.mint.clone(), index));
+                Building {
+                    candle: Candle {
+                        mint: trade.mint.clone(),
+                        interval: *interval,
+                        start_ms: start,
+                        first_slot: trade.slot,
+                        last_slot: trade.slot,
+                        venue: trade.venue,
+                        open: price,
+                        high: price,
+                        low: price,
+                        close: price,
+                        token_volume: 0,
+                        quote_volume: 0,
+                        trades: 0,
+                        buys: 0,
+                        sells: 0,
+                    },
+                    open_key: key,
+                    close_key: key,
+                }
+            });
+
+            let candle = &mut building.candle;
+            if key < building.open_key {
+                building.open_key = key;
+                candle.open = price;
+            }
+            if key >= building.close_key {
+                building.close_key = key;
+                candle.close = price;
+                candle.venue = trade.venue;
+            }
+            candle.high = candle.high.max(price);
+            candle.low = candle.low.min(price);
+            candle.first_slot = candle.first_slot.min(trade.slot);
+            candle.last_slot = candle.last_slot.max(trade.slot);
+            candle.token_volume = candle.token_volume.saturating_add(trade.token_amount);
+            candle.quote_volume = candle.quote_volume.saturating_add(trade.quote_amount);
+            candle.trades += 1;
+            match trade.side {
+                TradeSide::Buy => candle.buys += 1,
+                TradeSide::Sell => candle.sells += 1,
+            }
+        }
+    }
+
+    // 收盘结束时间不晚于watermark的桶
+    fn close_until<S: EventSink<Candle>>(&mut self, watermark: i64, sink: &mut S) {
+        if self.watermark.is_some_and(|current| current >= watermark) {
+            return;
+        }
+        self.watermark = Some(watermark);
+
+        while let Some((end, mint, index)) = self.pending.first().cloned() {
+            if end > watermark {
+                break;
+            }
+            self.pending.pop_first();
+            let interval = self.intervals[index];
+            let Some(series) = self.series.get_mut(&mint).map(|series| &mut series[index]) else {
+                continue;
+            };
+            let Some(building) = series.open.remove(&(end - interval.millis())) else {
+                continue;
+            };
+            sink.publish(&building.candle);
+            series.closed.push_back(building.candle);
+            series.trim(interval, self.history, watermark);
+        }
+
+        if watermark >= self.next_sweep {
+            self.sweep(watermark);
+            self.next_sweep = watermark + self.intervals.last().map_or(1_000, CandleInterval::millis);
+        }
+    }
+
+    // 清理窗口外的K线，删除没有任何K线的mint
+    fn sweep(&mut self, watermark: i64) {
+        let (intervals, history) = (&self.intervals, self.history);
+        self.series.retain(|_, series| {
+            for (interval, series) in intervals.iter().zip(series.iter_mut()) {
+                series.trim(*interval, history, watermark);
+            }
+            series.iter().any(|series| !series.open.is_empty() || !series.closed.is_empty())
+        });
+    }
+}
@@ -0,0 +1,589 @@
+// pump与boop绑定曲线状态：pump的Create、boop的DeployBondingCurve以配置的初始储备建立曲线，
+// 买卖按恒定乘积（虚拟储备）推算储备、现价与进度。两个launchpad的曲线使用同一个CurveState，
+// 同一个策略可以同时交易两边；pump的SetParams只有由curves.pump_authority签名时才应用。
+// 曲线中可卖的代币（real_token_reserves）卖完即完成；pump曲线之后由Migrate迁移（见MigrationTracker），
+// boop曲线完成时直接输出毕业事件，迁移后的Raydium池子地址在指令中看不到
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::analytics::{EventSink, Fill, Graduated, Venue};
+use crate::config::{CurveParams, CurvesConfig};
+use crate::labels;
+use crate::models::boop_parser::BoopInstructionType;
+use crate::models::events::{account, arg_u64, TradeSide};
+use crate::models::pump_parser::PumpInstructionType;
+use crate::models::token_parser::WSOL_MINT;
+use crate::models::TransactionResults;
+use crate::utils::account_layout::Program;
+
+const BPS: u128 = 10_000;
+
+// 一条绑定曲线的估算状态；代币为最小单位，SOL为lamports
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveState {
+    pub venue: Program,
+    pub mint: String,
+    pub curve: String,
+    pub creator: Option<String>,
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    pub fee_bps: u64,
+    pub created_slot: u64,
+    // 最后一次改变状态的slot
+    pub last_slot: u64,
+    pub complete: bool,
+}
+
+// 一笔买卖的估算结果：sol_amount是用户支付（买，含手续费）或收到（卖，扣除手续费）的lamports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveQuote {
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub fee: u64,
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> u128 {
+    numerator.div_ceil(denominator)
+}
+
+impl CurveState {
+    fn new(venue: Program, mint: String, curve: String, creator: Option<String>, params: &CurveParams, slot: u64) -> Self {
+        Self {
+            venue,
+            mint,
+            curve,
+            creator,
+            virtual_token_reserves: params.initial_virtual_token_reserves,
+            virtual_sol_reserves: params.initial_virtual_sol_reserves,
+            real_token_reserves: params.initial_real_token_reserves,
+            real_sol_reserves: 0,
+            initial_real_token_reserves: params.initial_real_token_reserves,
+            fee_bps: params.fee_bps,
+            created_slot: slot,
+            last_slot: slot,
+            complete: false,
+        }
+    }
+
+    // 现价 = 虚拟SOL储备 / 虚拟代币储备（每个代币最小单位的lamports）
+    #[allow(dead_code)]
+    pub fn price(&self) -> Option<f64> {
+        (self.virtual_token_reserves > 0).then(|| self.virtual_sol_reserves as f64 / self.virtual_token_reserves as f64)
+    }
+
+    // 已卖出的可卖代币比例，0到1
+    #[allow(dead_code)]
+    pub fn progress(&self) -> f64 {
+        if self.initial_real_token_reserves == 0 {
+            return 0.0;
+        }
+        let sold = self.initial_real_token_reserves.saturating_sub(self.real_token_reserves);
+        sold as f64 / self.initial_real_token_reserves as f64
+    }
+
+    // 买入token_amount个代币需要支付的SOL；超过剩余可卖代币时按剩余数量成交（与pump程序相同）
+    #[allow(dead_code)]
+    pub fn quote_buy(&self, token_amount: u64) -> Option<CurveQuote> {
+        let amount = token_amount.min(self.real_token_reserves) as u128;
+        let (tokens, sol) = (self.virtual_token_reserves as u128, self.virtual_sol_reserves as u128);
+        if self.complete || amount == 0 || amount >= tokens {
+            return None;
+        }
+        let cost = ceil_div(sol * amount, tokens - amount);
+        let fee = ceil_div(cost * self.fee_bps as u128, BPS);
+        Some(CurveQuote {
+            token_amount: amount as u64,
+            sol_amount: u64::try_from(cost + fee).ok()?,
+            fee: u64::try_from(fee).ok()?,
+        })
+    }
+
+    // 支付sol_amount（含手续费）能买到的代币；超过剩余可卖代币时按剩余数量成交
+    #[allow(dead_code)]
+    pub fn quote_buy_with_sol(&self, sol_amount: u64) -> Option<CurveQuote> {
+        if self.complete {
+            return None;
+        }
+        let fee = ceil_div(sol_amount as u128 * self.fee_bps as u128, BPS);
+        let net = (sol_amount as u128).checked_sub(fee)?;
+        let (tokens, sol) = (self.virtual_token_reserves as u128, self.virtual_sol_reserves as u128);
+        if net == 0 {
+            return None;
+        }
+        let amount = tokens * net / (sol + net);
+        if amount > self.real_token_reserves as u128 {
+            return self.quote_buy(self.real_token_reserves);
+        }
+        Some(CurveQuote {
+            token_amount: amount as u64,
+            sol_amount,
+            fee: fee as u64,
+        })
+    }
+
+    // 卖出token_amount个代币收到的SOL（扣除手续费）；卖出的代币多于曲线卖出过的、所得为0、
+    // 曲线中的SOL不足或费用超过所得时返回None
+    #[allow(dead_code)]
+    pub fn quote_sell(&self, token_amount: u64) -> Option<CurveQuote> {
+        let amount = token_amount as u128;
+        let (tokens, sol) = (self.virtual_token_reserves as u128, self.virtual_sol_reserves as u128);
+        if self.complete || amount == 0 {
+            return None;
+        }
+        // 能卖回曲线的代币不会多于从曲线买走的
+        if self.real_token_reserves as u128 + amount > self.initial_real_token_reserves as u128 {
+            return None;
+        }
+        let gross = sol * amount / (tokens + amount);
+        if gross == 0 || gross > self.real_sol_reserves as u128 {
+            return None;
+        }
+        let fee = ceil_div(gross * self.fee_bps as u128, BPS);
+        Some(CurveQuote {
+            token_amount,
+            sol_amount: gross.checked_sub(fee)? as u64,
+            fee: fee as u64,
+        })
+    }
+
+    // 成交后的 (虚拟代币, 虚拟SOL, 可卖代币, 曲线中的SOL)：买入时代币离开曲线、扣除手续费后的SOL进入曲线，卖出相反
+    fn after(&self, side: TradeSide, quote: CurveQuote) -> Option<(u64, u64, u64, u64)> {
+        match side {
+            TradeSide::Buy => {
+                let net = quote.sol_amount.checked_sub(quote.fee)?;
+                Some((
+                    self.virtual_token_reserves.checked_sub(quote.token_amount)?,
+                    self.virtual_sol_reserves.checked_add(net)?,
+                    self.real_token_reserves.checked_sub(quote.token_amount)?,
+                    self.real_sol_reserves.checked_add(net)?,
+                ))
+            }
+            TradeSide::Sell => {
+                let gross = quote.sol_amount.checked_add(quote.fee)?;
+                Some((
+                    self.virtual_token_reserves.checked_add(quote.token_amount)?,
+                    self.virtual_sol_reserves.checked_sub(gross)?,
+                    self.real_token_reserves.checked_add(quote.token_amount)?,
+                    self.real_sol_reserves.checked_sub(gross)?,
+                ))
+            }
+        }
+    }
+
+    // 应用一笔估算的成交，返回是否应用；可卖代币卖完时曲线完成
+    fn apply(&mut self, side: TradeSide, quote: CurveQuote, slot: u64) -> bool {
+        let Some((virtual_token_reserves, virtual_sol_reserves, real_token_reserves, real_sol_reserves)) =
+            self.after(side, quote)
+        else {
+            return false;
+        };
+        self.virtual_token_reserves = virtual_token_reserves;
+        self.virtual_sol_reserves = virtual_sol_reserves;
+        self.real_token_reserves = real_token_reserves;
+        self.real_sol_reserves = real_sol_reserves;
+        self.complete = real_token_reserves == 0;
+        self.last_slot = slot;
+        true
+    }
+
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "venue": self.venue.parser_name(),
+            "mint": self.mint,
+            "curve": self.curve,
+            "creator": self.creator,
+            "virtual_token_reserves": self.virtual_token_reserves,
+            "virtual_sol_reserves": self.virtual_sol_reserves,
+            "real_token_reserves": self.real_token_reserves,
+            "real_sol_reserves": self.real_sol_reserves,
+            "price": self.price(),
+            "progress": self.progress(),
+            "complete": self.complete,
+            "created_slot": self.created_slot,
+            "last_slot": self.last_slot,
+        })
+    }
+}
+
+impl fmt::Display for CurveState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} {} curve={} price={} progress={:.4} real_sol={} real_token={} complete={} slot={}",
+            labels::text("curve"),
+            self.mint,
+            self.venue.parser_name(),
+            self.curve,
+            self.price().unwrap_or(0.0),
+            self.progress(),
+            self.real_sol_reserves,
+            self.real_token_reserves,
+            self.complete,
+            self.last_slot
+        )
+    }
+}
+
+// 一个slot中与曲线相关的指令
+enum Action {
+    // pump的SetParams：之后创建的pump曲线使用新的初始储备与费率
+    SetParams(CurveParams),
+    Create {
+        venue: Program,
+        mint: String,
+        curve: String,
+        creator: Option<String>,
+    },
+    // pump的Buy：精确的代币数量，SOL上限
+    BuyTokens { mint: String, amount: u64, max_sol: u64 },
+    // boop的BuyToken：精确的SOL数量，代币下限
+    BuyWithSol { mint: String, sol: u64, min_tokens: u64 },
+    Sell { mint: String, amount: u64, min_sol: u64 },
+}
+
+pub struct CurveTracker {
+    curves: HashMap<String, CurveState>,
+    pump: CurveParams,
+    boop: CurveParams,
+    // SetParams必须由它签名，None时忽略SetParams
+    pump_authority: Option<String>,
+    unknown_curve_updates: u64,
+    rejected_updates: u64,
+    ignored_set_params: u64,
+    graduated: u64,
+    // 曲线没有买卖的最长slot数
+    idle_curve_slots: u64,
+    // 下一次清理闲置曲线的slot；每idle_curve_slots / 8个slot清理一次，避免每个slot遍历全部mint
+    next_sweep: u64,
+    evicted: u64,
+    // 最近一次record中应用了的买卖
+    fills: Vec<Fill>,
+}
+
+impl CurveTracker {
+    #[allow(dead_code)]
+    pub fn new(config: &CurvesConfig) -> Self {
+        Self {
+            curves: HashMap::new(),
+            pump: config.pump.clone(),
+            boop: config.boop.clone(),
+            pump_authority: config.pump_authority.clone(),
+            unknown_curve_updates: 0,
+            rejected_updates: 0,
+            ignored_set_params: 0,
+            graduated: 0,
+            idle_curve_slots: config.idle_curve_slots,
+            next_sweep: 0,
+            evicted: 0,
+            fills: Vec::new(),
+        }
+    }
+
+    // 按entry中的顺序处理一个slot的pump与boop指令；本slot中变化的曲线各输出一次最新状态，boop曲线完成时输出毕业事件
+    #[allow(dead_code)]
+    pub fn record<S: EventSink<CurveState> + EventSink<Graduated>>(&mut self, results: &TransactionResults, sink: &mut S) {
+        let slot = results.current_slot;
+        self.fills.clear();
+        let mut actions: Vec<(usize, usize, &str, Action)> = Vec::new();
+
+        for tx in &results.pump_transactions {
+            let index = results.transaction_index(&tx.signature).unwrap_or(usize::MAX);
+            for (position, ix) in tx.instructions.iter().enumerate() {
+                let (layout, args) = (ix.account_layout(), ix.decoded_args());
+                let key = |name| account(layout, &ix.accounts, name);
+                let arg = |name| arg_u64(&args, name);
+                let action = match ix.instruction_type {
+                    Some(PumpInstructionType::SetParams) if !self.authority_signed(key("user"), &tx.signers) => {
+                        self.ignored_set_params += 1;
+                        continue;
+                    }
+                    Some(PumpInstructionType::SetParams) => {
+                        let (Some(virtual_tokens), Some(virtual_sol), Some(real_tokens), Some(fee_bps)) = (
+                            arg("initial_virtual_token_reserves"),
+                            arg("initial_virtual_sol_reserves"),
+                            arg("initial_real_token_reserves"),
+                            arg("fee_basis_points"),
+                        ) else {
+                            continue;
+                        };
+                        Action::SetParams(CurveParams {
+                            initial_virtual_token_reserves: virtual_tokens,
+                            initial_virtual_sol_reserves: virtual_sol,
+                            initial_real_token_reserves: real_tokens,
+                            fee_bps,
+                        })
+                    }
+                    Some(PumpInstructionType::Create) => {
+                        let (Some(mint), Some(curve)) = (key("mint"), key("bonding_curve")) else {
+                            continue;
+                        };
+                        let creator = args
+                            .as_ref()
+                            .and_then(|args| args.get("creator")?.as_str().map(str::to_string))
+                            .or_else(|| key("user"));
+                        Action::Create {
+                            venue: Program::Pump,
+                            mint,
+                            curve,
+                            creator,
+                        }
+                    }
+                    Some(PumpInstructionType::Buy) => {
+                        let (Some(mint), Some(amount), Some(max_sol)) = (key("mint"), arg("amount"), arg("max_sol_cost"))
+                        else {
+                            continue;
+                        };
+                        Action::BuyTokens { mint, amount, max_sol }
+                    }
+                    Some(PumpInstructionType::Sell) => {
+                        let (Some(mint), Some(amount), Some(min_sol)) = (key("mint"), arg("amount"), arg("min_sol_output"))
+                        else {
+                            continue;
+                        };
+                        Action::Sell { mint, amount, min_sol }
+                    }
+                    _ => continue,
+                };
+                actions.push((index, position, tx.signature.as_str(), action));
+            }
+        }
+
+        for tx in &results.boop_transactions {
+            let index = results.transaction_index(&tx.signature).unwrap_or(usize::MAX);
+            for (position, ix) in tx.instructions.iter().enumerate() {
+                let (layout, args) = (ix.account_layout(), ix.decoded_args());
+                let key = |name| account(layout, &ix.accounts, name);
+                let arg = |name| arg_u64(&args, name);
+                let Some(mint) = key("mint") else {
+                    continue;
+                };
+                let action = match ix.instruction_type {
+                    Some(BoopInstructionType::DeployBondingCurve | BoopInstructionType::DeployBondingCurveFallback) => {
+                        let Some(curve) = key("bonding_curve") else {
+                            continue;
+                        };
+                        let creator = args
+                            .as_ref()
+                            .and_then(|args| args.get("creator")?.as_str().map(str::to_string))
+                            .or_else(|| key("payer"));
+                        Action::Create {
+                            venue: Program::Boop,
+                            mint,
+                            curve,
+                            creator,
+                        }
+                    }
+                    Some(BoopInstructionType::BuyToken) => {
+                        let (Some(sol), Some(min_tokens)) = (arg("buy_amount"), arg("amount_out_min")) else {
+                            continue;
+                        };
+                        Action::BuyWithSol { mint, sol, min_tokens }
+                    }
+                    Some(BoopInstructionType::SellToken) => {
+                        let (Some(amount), Some(min_sol)) = (arg("sell_amount"), arg("amount_out_min")) else {
+                            continue;
+                        };
+                        Action::Sell { mint, amount, min_sol }
+                    }
+                    _ => continue,
+                };
+                actions.push((index, position, tx.signature.as_str(), action));
+            }
+        }
+
+        actions.sort_by_key(|(index, position, _, _)| (*index, *position));
+        let mut changed: Vec<String> = Vec::new();
+        for (index, position, tx, action) in actions {
+            if let Some(mint) = self.apply(action, (index, position, tx), slot, sink) {
+                if !changed.contains(&mint) {
+                    changed.push(mint);
+                }
+            }
+        }
+        for mint in changed {
+            if let Some(state) = self.curves.get(&mint) {
+                EventSink::<CurveState>::publish(sink, state);
+            }
+        }
+        self.evict(slot);
+    }
+
+    // 闲置超过idle_curve_slots的曲线不再跟踪；已完成的曲线不再有买卖，同样按闲置清理
+    fn evict(&mut self, slot: u64) {
+        if slot < self.next_sweep {
+            return;
+        }
+        self.next_sweep = slot.saturating_add((self.idle_curve_slots / 8).max(1));
+        let idle_curve_slots = self.idle_curve_slots;
+        let before = self.curves.len();
+        self.curves
+            .retain(|_, curve| curve.last_slot.saturating_add(idle_curve_slots) >= slot);
+        self.evicted += (before - self.curves.len()) as u64;
+    }
+
+    // 应用一条指令并记录成交，返回状态改变了的mint；at是 (交易序号, 指令序号, 签名)
+    fn apply<S: EventSink<Graduated>>(
+        &mut self,
+        action: Action,
+        (index, position, tx): (usize, usize, &str),
+        slot: u64,
+        sink: &mut S,
+    ) -> Option<String> {
+        let (mint, quote) = match action {
+            Action::SetParams(params) => {
+                self.pump = params;
+                return None;
+            }
+            Action::Create {
+                venue,
+                mint,
+                curve,
+                creator,
+            } => {
+                // 曲线账户已存在时链上会失败
+                if self.curves.contains_key(&mint) {
+                    self.rejected_updates += 1;
+                    return None;
+                }
+                let params = if venue == Program::Boop { &self.boop } else { &self.pump };
+                let state = CurveState::new(venue, mint.clone(), curve, creator, params, slot);
+                self.curves.insert(mint.clone(), state);
+                return Some(mint);
+            }
+            Action::BuyTokens { mint, amount, max_sol } => {
+                let quote = self.quote(&mint, |curve| curve.quote_buy(amount).filter(|quote| quote.sol_amount <= max_sol));
+                (mint, quote.map(|quote| (TradeSide::Buy, quote)))
+            }
+            Action::BuyWithSol { mint, sol, min_tokens } => {
+                let quote = self.quote(&mint, |curve| {
+                    curve.quote_buy_with_sol(sol).filter(|quote| quote.token_amount >= min_tokens)
+                });
+                (mint, quote.map(|quote| (TradeSide::Buy, quote)))
+            }
+            Action::Sell { mint, amount, min_sol } => {
+                let quote = self.quote(&mint, |curve| curve.quote_sell(amount).filter(|quote| quote.sol_amount >= min_sol));
+                (mint, quote.map(|quote| (TradeSide::Sell, quote)))
+            }
+        };
+        let (side, quote) = quote?;
+        let curve = self.curves.get_mut(&mint)?;
+        if !curve.apply(side, quote, slot) {
+            self.rejected_updates += 1;
+            return None;
+        }
+        // 买入时用户支付的SOL含手续费，卖出时收到的SOL已扣除手续费
+        let sol = match side {
+            TradeSide::Buy => quote.sol_amount - quote.fee,
+            TradeSide::Sell => quote.sol_amount + quote.fee,
+        };
+        self.fills.push(Fill {
+            slot,
+            signature: tx.to_string(),
+            index,
+            position,
+            venue: curve.venue,
+            pool: curve.curve.clone(),
+            side,
+            mint: mint.clone(),
+            quote_mint: WSOL_MINT.to_string(),
+            token_amount: quote.token_amount,
+            quote_amount: sol,
+        });
+        if curve.complete && curve.venue == Program::Boop {
+            self.graduated += 1;
+            sink.publish(&Graduated {
+                mint: mint.clone(),
+                curve: curve.curve.clone(),
+                pool: None,
+                venue: Venue::Raydium,
+                slot,
+                tx: tx.to_string(),
+            });
+        }
+        Some(mint)
+    }
+
+    // 指令中的user账户是配置的pump_authority，并且签名了交易
+    fn authority_signed(&self, user: Option<String>, signers: &[String]) -> bool {
+        match (&self.pump_authority, user) {
+            (Some(expected), Some(user)) => *expected == user && signers.contains(&user),
+            _ => false,
+        }
+    }
+
+    // 对已知曲线估算一笔买卖；未知曲线与估算会失败的指令分别计数
+    fn quote(&mut self, mint: &str, estimate: impl Fn(&CurveState) -> Option<CurveQuote>) -> Option<CurveQuote> {
+        let Some(curve) = self.curves.get(mint) else {
+            self.unknown_curve_updates += 1;
+            return None;
+        };
+        let quote = estimate(curve);
+        if quote.is_none() {
+            self.rejected_updates += 1;
+        }
+        quote
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, mint: &str) -> Option<&CurveState> {
+        self.curves.get(mint)
+    }
+
+    // 最近一次record中按估算成交的买卖，按entry中的顺序
+    #[allow(dead_code)]
+    pub fn fills(&self) -> &[Fill] {
+        &self.fills
+    }
+
+    // 当前的pump新曲线参数（由pump_authority签名的SetParams之后为指令中的值）
+    #[allow(dead_code)]
+    pub fn pump_params(&self) -> &CurveParams {
+        &self.pump
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.curves.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.curves.is_empty()
+    }
+
+    // 针对未知曲线（创建早于订阅）的买卖指令数
+    #[allow(dead_code)]
+    pub fn unknown_curve_updates(&self) -> u64 {
+        self.unknown_curve_updates
+    }
+
+    // 按估算会失败而没有应用的指令数
+    #[allow(dead_code)]
+    pub fn rejected_updates(&self) -> u64 {
+        self.rejected_updates
+    }
+
+    // 没有由pump_authority签名而忽略的SetParams数
+    #[allow(dead_code)]
+    pub fn ignored_set_params(&self) -> u64 {
+        self.ignored_set_params
+    }
+
+    // 完成的boop曲线数
+    #[allow(dead_code)]
+    pub fn graduated(&self) -> u64 {
+        self.graduated
+    }
+
+    // 闲置太久而不再跟踪的曲线数
+    #[allow(dead_code)]
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+}
@@ -0,0 +1,365 @@
+// 绑定曲线毕业追踪：把完成的pump绑定曲线与为该mint创建的池子对应起来，并维护 mint -> 当前交易场所 的索引。
+// Migrate在同一指令中给出曲线与PumpSwap池子，直接毕业；历史的Withdraw只是取出曲线中的流动性，
+// 之后由迁移账户另外建池，因此Withdraw的mint先挂起，等到以它为一侧的PumpSwap CreatePool（或外部解码器报告的Raydium池子）
+// 再毕业。shreds中没有执行结果，同一mint重复发送的Migrate只在池子变化时产生一次事件。
+// 大部分曲线永远不会完成，长时间没有买卖的曲线定期清理，索引不会无限增长
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::{json, Value};
+
+use crate::analytics::EventSink;
+use crate::config::MigrationsConfig;
+use crate::labels;
+use crate::models::events::account;
+use crate::models::pump_parser::PumpInstructionType;
+use crate::models::pumpamm_parser::PumpAmmInstructionType;
+use crate::models::token_parser::WSOL_MINT;
+use crate::models::TransactionResults;
+
+// 代币的交易场所
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Venue {
+    PumpCurve,
+    PumpSwap,
+    Raydium,
+    Meteora,
+}
+
+impl Venue {
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Venue::PumpCurve => "pump",
+            Venue::PumpSwap => "pumpswap",
+            Venue::Raydium => "raydium",
+            Venue::Meteora => "meteora",
+        }
+    }
+}
+
+impl fmt::Display for Venue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// mint当前所在的场所；address是绑定曲线或池子账户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Listing {
+    pub venue: Venue,
+    pub address: String,
+    pub since_slot: u64,
+    // 最后一次见到该场所上的指令
+    pub last_slot: u64,
+}
+
+// 绑定曲线完成并迁移到池子；池子地址在指令中看不到时（boop迁移到Raydium）为None
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graduated {
+    pub mint: String,
+    pub curve: String,
+    pub pool: Option<String>,
+    pub venue: Venue,
+    pub slot: u64,
+    pub tx: String,
+}
+
+impl Graduated {
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "mint": self.mint,
+            "curve": self.curve,
+            "pool": self.pool,
+            "venue": self.venue.as_str(),
+            "slot": self.slot,
+            "tx": self.tx,
+        })
+    }
+}
+
+impl fmt::Display for Graduated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} {} pool={} curve={} slot={} tx={}",
+            labels::text("graduated"),
+            self.mint,
+            self.venue,
+            self.pool.as_deref().unwrap_or("-"),
+            self.curve,
+            self.slot,
+            self.tx
+        )
+    }
+}
+
+// Withdraw之后等待建池的曲线
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pending {
+    curve: String,
+    slot: u64,
+}
+
+// 一个slot中与迁移相关的指令
+enum Action {
+    // pump的Create / Buy / Sell：mint在绑定曲线上
+    OnCurve { mint: String, curve: String },
+    Withdraw { mint: String, curve: String },
+    Migrate { mint: String, curve: String, pool: String },
+    CreatePool { pool: String, base_mint: String, quote_mint: String },
+    // PumpSwap的买卖：订阅前已经毕业的mint
+    OnPool { mint: String, pool: String },
+}
+
+pub struct MigrationTracker {
+    venues: HashMap<String, Listing>,
+    pending: HashMap<String, Pending>,
+    // Withdraw之后等待建池的最长slot数
+    pending_slots: u64,
+    // 绑定曲线上的mint没有指令的最长slot数
+    idle_curve_slots: u64,
+    // 下一次清理闲置曲线的slot；每idle_curve_slots / 8个slot清理一次，避免每个slot遍历全部mint
+    next_sweep: u64,
+    graduated: u64,
+    expired: u64,
+    evicted: u64,
+}
+
+impl MigrationTracker {
+    #[allow(dead_code)]
+    pub fn new(config: &MigrationsConfig) -> Self {
+        Self {
+            venues: HashMap::new(),
+            pending: HashMap::new(),
+            pending_slots: config.pending_slots,
+            idle_curve_slots: config.idle_curve_slots,
+            next_sweep: 0,
+            graduated: 0,
+            expired: 0,
+            evicted: 0,
+        }
+    }
+
+    // 按entry中的顺序处理一个slot的pump与PumpSwap指令，毕业事件交给sink
+    #[allow(dead_code)]
+    pub fn record<S: EventSink<Graduated>>(&mut self, results: &TransactionResults, sink: &mut S) {
+        let slot = results.current_slot;
+        let mut actions: Vec<(usize, usize, &str, Action)> = Vec::new();
+
+        for tx in &results.pump_transactions {
+            let index = results.transaction_index(&tx.signature).unwrap_or(usize::MAX);
+            for (position, ix) in tx.instructions.iter().enumerate() {
+                let layout = ix.account_layout();
+                let key = |name| account(layout, &ix.accounts, name);
+                let (Some(mint), Some(curve)) = (key("mint"), key("bonding_curve")) else {
+                    continue;
+                };
+                let action = match ix.instruction_type {
+                    Some(PumpInstructionType::Create | PumpInstructionType::Buy | PumpInstructionType::Sell) => {
+                        Action::OnCurve { mint, curve }
+                    }
+                    Some(PumpInstructionType::Withdraw) => Action::Withdraw { mint, curve },
+                    Some(PumpInstructionType::Migrate) => {
+                        let Some(pool) = key("pool") else {
+                            continue;
+                        };
+                        Action::Migrate { mint, curve, pool }
+                    }
+                    _ => continue,
+                };
+                actions.push((index, position, tx.signature.as_str(), action));
+            }
+        }
+
+        for tx in &results.pumpamm_transactions {
+            let index = results.transaction_index(&tx.signature).unwrap_or(usize::MAX);
+            for (position, ix) in tx.instructions.iter().enumerate() {
+                let layout = ix.account_layout();
+                let key = |name| account(layout, &ix.accounts, name);
+                let (Some(pool), Some(base_mint)) = (key("pool"), key("base_mint")) else {
+                    continue;
+                };
+                let action = match ix.instruction_type {
+                    Some(PumpAmmInstructionType::CreatePool) => {
+                        let Some(quote_mint) = key("quote_mint") else {
+                            continue;
+                        };
+                        Action::CreatePool { pool, base_mint, quote_mint }
+                    }
+                    Some(PumpAmmInstructionType::Buy | PumpAmmInstructionType::Sell) => Action::OnPool { mint: base_mint, pool },
+                    _ => continue,
+                };
+                actions.push((index, position, tx.signature.as_str(), action));
+            }
+        }
+
+        actions.sort_by_key(|(index, position, _, _)| (*index, *position));
+        for (_, _, tx, action) in actions {
+            self.apply(action, slot, tx, sink);
+        }
+        self.expire(slot);
+    }
+
+    fn apply<S: EventSink<Graduated>>(&mut self, action: Action, slot: u64, tx: &str, sink: &mut S) {
+        match action {
+            Action::OnCurve { mint, curve } => {
+                self.venues
+                    .entry(mint)
+                    .and_modify(|listing| listing.last_slot = slot)
+                    .or_insert(Listing {
+                        venue: Venue::PumpCurve,
+                        address: curve,
+                        since_slot: slot,
+                        last_slot: slot,
+                    });
+            }
+            Action::Withdraw { mint, curve } => {
+                self.pending.insert(mint, Pending { curve, slot });
+            }
+            Action::Migrate { mint, curve, pool } => {
+                self.pending.remove(&mint);
+                self.graduate(mint, curve, pool, Venue::PumpSwap, slot, tx, sink);
+            }
+            Action::CreatePool { pool, base_mint, quote_mint } => {
+                // 迁移的池子以曲线的mint为一侧、WSOL为另一侧
+                let wsol = WSOL_MINT.to_string();
+                let pending = [&base_mint, &quote_mint]
+                    .into_iter()
+                    .find(|mint| **mint != wsol && self.pending.contains_key(*mint))
+                    .cloned();
+                match pending {
+                    Some(mint) => self.pool_created(Venue::PumpSwap, &mint, &pool, slot, tx, sink),
+                    None => self.on_pool(base_mint, pool, slot),
+                }
+            }
+            Action::OnPool { mint, pool } => self.on_pool(mint, pool, slot),
+        }
+    }
+
+    // 订阅前已经毕业或直接在PumpSwap上发行的mint，以第一次见到的池子为当前场所
+    fn on_pool(&mut self, mint: String, pool: String, slot: u64) {
+        self.venues
+            .entry(mint)
+            .and_modify(|listing| listing.last_slot = slot)
+            .or_insert(Listing {
+                venue: Venue::PumpSwap,
+                address: pool,
+                since_slot: slot,
+                last_slot: slot,
+            });
+    }
+
+    // 报告为mint创建的池子；挂起的（已Withdraw的）mint因此毕业。供PumpSwap以外的解码器（例如Raydium）调用
+    #[allow(dead_code)]
+    pub fn pool_created<S: EventSink<Graduated>>(
+        &mut self,
+        venue: Venue,
+        mint: &str,
+        pool: &str,
+        slot: u64,
+        tx: &str,
+        sink: &mut S,
+    ) {
+        let Some(pending) = self.pending.remove(mint) else {
+            return;
+        };
+        self.graduate(mint.to_string(), pending.curve, pool.to_string(), venue, slot, tx, sink);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn graduate<S: EventSink<Graduated>>(
+        &mut self,
+        mint: String,
+        curve: String,
+        pool: String,
+        venue: Venue,
+        slot: u64,
+        tx: &str,
+        sink: &mut S,
+    ) {
+        // 同一迁移的重复指令
+        if self
+            .venues
+            .get(&mint)
+            .is_some_and(|current| current.venue == venue && current.address == pool)
+        {
+            return;
+        }
+        let listing = Listing {
+            venue,
+            address: pool.clone(),
+            since_slot: slot,
+            last_slot: slot,
+        };
+        self.venues.insert(mint.clone(), listing);
+        self.graduated += 1;
+        sink.publish(&Graduated {
+            mint,
+            curve,
+            pool: Some(pool),
+            venue,
+            slot,
+            tx: tx.to_string(),
+        });
+    }
+
+    // 超过pending_slots仍没有建池的Withdraw不再等待
+    fn expire(&mut self, slot: u64) {
+        let pending_slots = self.pending_slots;
+        let before = self.pending.len();
+        self.pending
+            .retain(|_, pending| pending.slot.saturating_add(pending_slots) >= slot);
+        self.expired += (before - self.pending.len()) as u64;
+
+        // 闲置超过idle_curve_slots的曲线不再跟踪；挂起的（已Withdraw的）曲线由上面的超时处理
+        if slot < self.next_sweep {
+            return;
+        }
+        self.next_sweep = slot.saturating_add((self.idle_curve_slots / 8).max(1));
+        let idle_curve_slots = self.idle_curve_slots;
+        let pending = &self.pending;
+        let before = self.venues.len();
+        self.venues.retain(|mint, listing| {
+            listing.venue != Venue::PumpCurve
+                || pending.contains_key(mint)
+                || listing.last_slot.saturating_add(idle_curve_slots) >= slot
+        });
+        self.evicted += (before - self.venues.len()) as u64;
+    }
+
+    // mint当前的交易场所
+    #[allow(dead_code)]
+    pub fn venue(&self, mint: &str) -> Option<&Listing> {
+        self.venues.get(mint)
+    }
+
+    // Withdraw之后仍在等待建池
+    #[allow(dead_code)]
+    pub fn is_pending(&self, mint: &str) -> bool {
+        self.pending.contains_key(mint)
+    }
+
+    #[allow(dead_code)]
+    pub fn mints(&self) -> usize {
+        self.venues.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn graduated(&self) -> u64 {
+        self.graduated
+    }
+
+    // 等待建池超时的Withdraw数
+    #[allow(dead_code)]
+    pub fn expired(&self) -> u64 {
+        self.expired
+    }
+
+    // 闲置太久而不再跟踪的曲线数
+    #[allow(dead_code)]
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+}
@@ -0,0 +1,63 @@
+// 基于解析结果的进程内分析：按slot输入TransactionResults，产生的事件交给任意EventSink。
+// shreds中没有执行结果，池子与曲线的状态都是估算：估算结果违反滑点限制的指令视为会失败，不应用；
+// 修改全局参数的管理指令任何人都能发送（链上会失败，但看不到），只有由配置的管理员签名时才应用
+pub mod arbitrage;
+pub mod candles;
+pub mod curves;
+pub mod migrations;
+pub mod pools;
+pub mod slots;
+
+pub use arbitrage::{ArbitrageDetector, Opportunity, VenuePrice};
+pub use candles::{Candle, CandleAggregator, CandleInterval};
+pub use curves::{CurveQuote, CurveState, CurveTracker};
+pub use migrations::{Graduated, Listing, MigrationTracker, Venue};
+pub use pools::{DisabledOperations, PoolFees, PoolRegistry, PoolSnapshot, PoolState, SwapQuote};
+pub use slots::SlotAnalytics;
+
+use crate::models::events::TradeSide;
+use crate::utils::account_layout::Program;
+
+// 按曲线或池子的估算状态模拟执行的一笔买卖；数量是实际成交的数量，不含手续费。
+// CurveTracker与PoolRegistry每个slot记录应用了的买卖，CandleAggregator据此计算成交价
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub slot: u64,
+    pub signature: String,
+    // 交易在消息中的序号与交易内同一解析器的指令序号
+    pub index: usize,
+    pub position: usize,
+    pub venue: Program,
+    // 绑定曲线或池子
+    pub pool: String,
+    pub side: TradeSide,
+    pub mint: String,
+    pub quote_mint: String,
+    pub token_amount: u64,
+    pub quote_amount: u64,
+}
+
+impl Fill {
+    // 成交均价（每个代币最小单位的quote最小单位数）
+    #[allow(dead_code)]
+    pub fn price(&self) -> Option<f64> {
+        (self.token_amount > 0).then(|| self.quote_amount as f64 / self.token_amount as f64)
+    }
+}
+
+// 分析事件的去向；闭包、Vec与OutputSink都可以作为sink
+pub trait EventSink<E> {
+    fn publish(&mut self, event: &E);
+}
+
+impl<E, F: FnMut(&E)> EventSink<E> for F {
+    fn publish(&mut self, event: &E) {
+        self(event)
+    }
+}
+
+impl<E: Clone> EventSink<E> for Vec<E> {
+    fn publish(&mut self, event: &E) {
+        self.push(event.clone())
+    }
+}
@@ -0,0 +1,714 @@
+// PumpSwap池子注册表：以Pool账户为键，CreatePool记录base/quote mint、LP mint与池子的代币账户，
+// Buy/Sell/Deposit/Withdraw按恒定乘积与global_config的费率推算储备、LP供应量与现价，Disable标记被关闭的操作；
+// 费率与Disable来自pools.admin签名的管理指令。pump的Migrate通过CPI建池，池子以配置的迁移储备登记。
+// 注册表可以从快照文件恢复并定期写回，重启后不丢状态；快照之后的slot才会应用
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::analytics::Fill;
+use crate::config::PoolsConfig;
+use crate::models::events::{account, arg_u64, TradeSide};
+use crate::models::pump_parser::{PumpInstruction, PumpInstructionType};
+use crate::models::pumpamm_parser::{PumpAmmInstruction, PumpAmmInstructionType};
+use crate::models::TransactionResults;
+use crate::utils::account_layout::Program;
+
+#[allow(dead_code)]
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+const BPS: u128 = 10_000;
+
+// 交易费率（基点），lp费留在池子中，协议费转给协议费接收账户
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolFees {
+    pub lp_fee_bps: u64,
+    pub protocol_fee_bps: u64,
+}
+
+impl PoolFees {
+    #[allow(dead_code)]
+    pub fn total_bps(&self) -> u64 {
+        self.lp_fee_bps + self.protocol_fee_bps
+    }
+}
+
+// Disable指令关闭的操作；Disable作用于global_config，其下的全部池子一起标记
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisabledOperations {
+    pub create_pool: bool,
+    pub deposit: bool,
+    pub withdraw: bool,
+    pub buy: bool,
+    pub sell: bool,
+}
+
+impl DisabledOperations {
+    #[allow(dead_code)]
+    pub fn any(&self) -> bool {
+        self.create_pool || self.deposit || self.withdraw || self.buy || self.sell
+    }
+}
+
+// 一个global_config的费率与禁用状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobalConfigState {
+    pub global_config: String,
+    pub fees: PoolFees,
+    #[serde(default)]
+    pub disabled: DisabledOperations,
+}
+
+// 一个池子的估算状态，数量均为原始单位
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolState {
+    pub pool: String,
+    pub global_config: String,
+    pub creator: Option<String>,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub lp_mint: String,
+    pub pool_base_token_account: String,
+    pub pool_quote_token_account: String,
+    pub base_reserve: u64,
+    pub quote_reserve: u64,
+    pub lp_supply: u64,
+    pub created_slot: u64,
+    // 最后一次改变状态的slot
+    pub last_slot: u64,
+    #[serde(default)]
+    pub disabled: DisabledOperations,
+}
+
+// 一笔买卖的估算结果：用户支付（买）或收到（卖）的quote数量与之后的储备
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub quote_amount: u64,
+    pub lp_fee: u64,
+    pub protocol_fee: u64,
+    pub base_reserve: u64,
+    pub quote_reserve: u64,
+}
+
+fn ceil_div(numerator: u128, denominator: u128) -> u128 {
+    numerator.div_ceil(denominator)
+}
+
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    // 浮点近似后修正到精确的整数平方根
+    let mut root = (value as f64).sqrt() as u128;
+    while root.checked_mul(root).is_none_or(|square| square > value) {
+        root -= 1;
+    }
+    while (root + 1).checked_mul(root + 1).is_some_and(|square| square <= value) {
+        root += 1;
+    }
+    root
+}
+
+impl PoolState {
+    // 现价 = quote储备 / base储备（原始单位）
+    #[allow(dead_code)]
+    pub fn spot_price(&self) -> Option<f64> {
+        (self.base_reserve > 0).then(|| self.quote_reserve as f64 / self.base_reserve as f64)
+    }
+
+    // 买入base_out个base需要支付的quote（含费用）；储备不足时返回None
+    #[allow(dead_code)]
+    pub fn quote_buy(&self, base_out: u64, fees: PoolFees) -> Option<SwapQuote> {
+        let (base, quote, out) = (self.base_reserve as u128, self.quote_reserve as u128, base_out as u128);
+        if out == 0 || out >= base {
+            return None;
+        }
+        let net = ceil_div(quote * out, base - out);
+        let lp_fee = ceil_div(net * fees.lp_fee_bps as u128, BPS);
+        let protocol_fee = ceil_div(net * fees.protocol_fee_bps as u128, BPS);
+        Some(SwapQuote {
+            quote_amount: u64::try_from(net + lp_fee + protocol_fee).ok()?,
+            lp_fee: u64::try_from(lp_fee).ok()?,
+            protocol_fee: u64::try_from(protocol_fee).ok()?,
+            base_reserve: (base - out) as u64,
+            quote_reserve: u64::try_from(quote + net + lp_fee).ok()?,
+        })
+    }
+
+    // 卖出base_in个base收到的quote（扣除费用）；费用超过所得时返回None
+    #[allow(dead_code)]
+    pub fn quote_sell(&self, base_in: u64, fees: PoolFees) -> Option<SwapQuote> {
+        let (base, quote, input) = (self.base_reserve as u128, self.quote_reserve as u128, base_in as u128);
+        if input == 0 || base == 0 {
+            return None;
+        }
+        let gross = quote * input / (base + input);
+        let lp_fee = ceil_div(gross * fees.lp_fee_bps as u128, BPS);
+        let protocol_fee = ceil_div(gross * fees.protocol_fee_bps as u128, BPS);
+        Some(SwapQuote {
+            quote_amount: gross.checked_sub(lp_fee + protocol_fee)? as u64,
+            lp_fee: lp_fee as u64,
+            protocol_fee: protocol_fee as u64,
+            base_reserve: u64::try_from(base + input).ok()?,
+            quote_reserve: (quote - gross + lp_fee) as u64,
+        })
+    }
+
+    // 按side估算一笔买卖，买入时amount是得到的base，卖出时是付出的base
+    #[allow(dead_code)]
+    pub fn quote_swap(&self, side: TradeSide, base_amount: u64, fees: PoolFees) -> Option<SwapQuote> {
+        match side {
+            TradeSide::Buy => self.quote_buy(base_amount, fees),
+            TradeSide::Sell => self.quote_sell(base_amount, fees),
+        }
+    }
+
+    // 存入换取lp_out个LP，需要的base与quote（向上取整）
+    fn deposit_amounts(&self, lp_out: u64) -> Option<(u64, u64)> {
+        if lp_out == 0 || self.lp_supply == 0 {
+            return None;
+        }
+        let supply = self.lp_supply as u128;
+        let base = ceil_div(lp_out as u128 * self.base_reserve as u128, supply);
+        let quote = ceil_div(lp_out as u128 * self.quote_reserve as u128, supply);
+        Some((u64::try_from(base).ok()?, u64::try_from(quote).ok()?))
+    }
+
+    // 赎回lp_in个LP取出的base与quote（向下取整）
+    fn withdraw_amounts(&self, lp_in: u64) -> Option<(u64, u64)> {
+        if lp_in == 0 || lp_in > self.lp_supply {
+            return None;
+        }
+        let supply = self.lp_supply as u128;
+        let base = lp_in as u128 * self.base_reserve as u128 / supply;
+        let quote = lp_in as u128 * self.quote_reserve as u128 / supply;
+        Some((base as u64, quote as u64))
+    }
+
+    // 指令执行后的 (base储备, quote储备, LP供应量)；None表示指令按估算会失败（被禁用、储备不足或违反滑点限制）
+    fn after(&self, kind: &PumpAmmInstructionType, args: &Option<Value>, fees: PoolFees) -> Option<(u64, u64, u64)> {
+        let arg = |name: &str| arg_u64(args, name);
+        match kind {
+            PumpAmmInstructionType::Buy if !self.disabled.buy => {
+                let quote = self.quote_buy(arg("base_amount_out")?, fees)?;
+                (quote.quote_amount <= arg("max_quote_amount_in")?)
+                    .then_some((quote.base_reserve, quote.quote_reserve, self.lp_supply))
+            }
+            PumpAmmInstructionType::Sell if !self.disabled.sell => {
+                let quote = self.quote_sell(arg("base_amount_in")?, fees)?;
+                (quote.quote_amount >= arg("min_quote_amount_out")?)
+                    .then_some((quote.base_reserve, quote.quote_reserve, self.lp_supply))
+            }
+            PumpAmmInstructionType::Deposit if !self.disabled.deposit => {
+                let lp_out = arg("lp_token_amount_out")?;
+                let (base, quote) = self.deposit_amounts(lp_out)?;
+                if base > arg("max_base_amount_in")? || quote > arg("max_quote_amount_in")? {
+                    return None;
+                }
+                Some((
+                    self.base_reserve.checked_add(base)?,
+                    self.quote_reserve.checked_add(quote)?,
+                    self.lp_supply.checked_add(lp_out)?,
+                ))
+            }
+            PumpAmmInstructionType::Withdraw if !self.disabled.withdraw => {
+                let lp_in = arg("lp_token_amount_in")?;
+                let (base, quote) = self.withdraw_amounts(lp_in)?;
+                if base < arg("min_base_amount_out")? || quote < arg("min_quote_amount_out")? {
+                    return None;
+                }
+                Some((self.base_reserve - base, self.quote_reserve - quote, self.lp_supply - lp_in))
+            }
+            _ => None,
+        }
+    }
+
+    // 买卖指令按当前储备成交的 (方向, base数量, quote数量)，quote不含费用；不检查滑点限制与禁用，应用前调用
+    fn fill(&self, kind: &PumpAmmInstructionType, args: &Option<Value>, fees: PoolFees) -> Option<(TradeSide, u64, u64)> {
+        let (side, base_amount) = match kind {
+            PumpAmmInstructionType::Buy => (TradeSide::Buy, arg_u64(args, "base_amount_out")?),
+            PumpAmmInstructionType::Sell => (TradeSide::Sell, arg_u64(args, "base_amount_in")?),
+            _ => return None,
+        };
+        let quote = self.quote_swap(side, base_amount, fees)?;
+        let fee = quote.lp_fee + quote.protocol_fee;
+        let quote_amount = match side {
+            TradeSide::Buy => quote.quote_amount - fee,
+            TradeSide::Sell => quote.quote_amount + fee,
+        };
+        Some((side, base_amount, quote_amount))
+    }
+
+    // 应用一条买卖或流动性指令，返回是否应用
+    fn apply(&mut self, kind: &PumpAmmInstructionType, args: &Option<Value>, fees: PoolFees, slot: u64) -> bool {
+        let Some((base_reserve, quote_reserve, lp_supply)) = self.after(kind, args, fees) else {
+            return false;
+        };
+        self.base_reserve = base_reserve;
+        self.quote_reserve = quote_reserve;
+        self.lp_supply = lp_supply;
+        self.last_slot = slot;
+        true
+    }
+}
+
+// 快照文件（JSON）的内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolSnapshot {
+    pub version: u32,
+    // 快照已包含到该slot为止的指令，恢复后只应用之后的slot
+    pub slot: u64,
+    #[serde(default)]
+    pub configs: Vec<GlobalConfigState>,
+    #[serde(default)]
+    pub pools: Vec<PoolState>,
+}
+
+impl PoolSnapshot {
+    #[allow(dead_code)]
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let bytes = fs::read(path)?;
+        let snapshot: Self = serde_json::from_slice(&bytes).map_err(|e| {
+            Error::new(ErrorKind::InvalidData, format!("无效的池子快照 {}: {}", path.display(), e))
+        })?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("池子快照 {} 的版本 {} 不受支持（当前 {}）", path.display(), snapshot.version, SNAPSHOT_VERSION),
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    // 先写临时文件再改名，中途退出不会留下不完整的快照
+    #[allow(dead_code)]
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let bytes = serde_json::to_vec(self).map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+        let temp = path.with_extension("tmp");
+        let mut file = BufWriter::new(File::create(&temp)?);
+        file.write_all(&bytes)?;
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&temp, path)
+    }
+}
+
+// 一个slot中按entry顺序应用的指令
+enum Step<'a> {
+    PumpSwap(&'a PumpAmmInstruction, &'a [String]),
+    // pump的Migrate，通过CPI创建PumpSwap池子
+    Migrate(&'a PumpInstruction),
+}
+
+pub struct PoolRegistry {
+    pools: HashMap<String, PoolState>,
+    // base mint -> 池子，按创建顺序
+    by_mint: HashMap<String, Vec<String>>,
+    configs: HashMap<String, GlobalConfigState>,
+    // 没见过CreateConfig / UpdateFeeConfig的global_config使用的费率
+    default_fees: PoolFees,
+    // 管理指令必须由它签名，None时忽略全部管理指令
+    admin: Option<String>,
+    // Migrate建立的池子的初始 (base, quote) 储备
+    migration_reserves: (u64, u64),
+    // 快照已包含的最后一个slot
+    seeded_slot: Option<u64>,
+    last_slot: u64,
+    unknown_pool_updates: u64,
+    rejected_updates: u64,
+    ignored_admin_updates: u64,
+    // 最近一次record中应用了的买卖
+    fills: Vec<Fill>,
+    snapshot_file: Option<PathBuf>,
+    snapshot_interval_ms: i64,
+    last_snapshot_ms: Option<i64>,
+}
+
+impl PoolRegistry {
+    #[allow(dead_code)]
+    pub fn new(config: &PoolsConfig) -> Self {
+        Self {
+            pools: HashMap::new(),
+            by_mint: HashMap::new(),
+            configs: HashMap::new(),
+            default_fees: PoolFees {
+                lp_fee_bps: config.lp_fee_bps,
+                protocol_fee_bps: config.protocol_fee_bps,
+            },
+            admin: config.admin.clone(),
+            migration_reserves: (config.migration_base_reserve, config.migration_quote_reserve),
+            seeded_slot: None,
+            last_slot: 0,
+            unknown_pool_updates: 0,
+            rejected_updates: 0,
+            ignored_admin_updates: 0,
+            fills: Vec::new(),
+            snapshot_file: config.snapshot_file.clone(),
+            snapshot_interval_ms: config.snapshot_interval_secs.saturating_mul(1_000) as i64,
+            last_snapshot_ms: None,
+        }
+    }
+
+    // 创建注册表，配置的快照文件存在时从中恢复；文件不存在视为第一次运行
+    #[allow(dead_code)]
+    pub fn open(config: &PoolsConfig) -> Result<Self, Error> {
+        let mut registry = Self::new(config);
+        if let Some(path) = config.snapshot_file.as_deref().filter(|path| path.exists()) {
+            registry.seed(PoolSnapshot::read(path)?);
+            log::info!("从 {} 恢复了 {} 个池子（slot {}）", path.display(), registry.len(), registry.last_slot);
+        }
+        Ok(registry)
+    }
+
+    // 用快照替换当前状态
+    #[allow(dead_code)]
+    pub fn seed(&mut self, snapshot: PoolSnapshot) {
+        self.pools.clear();
+        self.by_mint.clear();
+        self.configs = snapshot
+            .configs
+            .into_iter()
+            .map(|config| (config.global_config.clone(), config))
+            .collect();
+        for pool in snapshot.pools {
+            self.insert(pool);
+        }
+        self.seeded_slot = Some(snapshot.slot);
+        self.last_slot = snapshot.slot;
+    }
+
+    #[allow(dead_code)]
+    pub fn snapshot(&self) -> PoolSnapshot {
+        let mut configs: Vec<GlobalConfigState> = self.configs.values().cloned().collect();
+        configs.sort_by(|a, b| a.global_config.cmp(&b.global_config));
+        let mut pools: Vec<PoolState> = self.pools.values().cloned().collect();
+        pools.sort_by(|a, b| a.pool.cmp(&b.pool));
+        PoolSnapshot {
+            version: SNAPSHOT_VERSION,
+            slot: self.last_slot,
+            configs,
+            pools,
+        }
+    }
+
+    // 写出快照到配置的文件；没有配置快照文件时不做任何事
+    #[allow(dead_code)]
+    pub fn save(&mut self, now_ms: i64) -> Result<(), Error> {
+        let Some(path) = &self.snapshot_file else {
+            return Ok(());
+        };
+        self.snapshot()
+            .write(path)
+            .map_err(|e| Error::new(e.kind(), format!("无法写入池子快照 {}: {}", path.display(), e)))?;
+        self.last_snapshot_ms = Some(now_ms);
+        Ok(())
+    }
+
+    // 距上次写出超过snapshot_interval时写出快照，返回是否写出；第一次调用只开始计时
+    #[allow(dead_code)]
+    pub fn checkpoint(&mut self, now_ms: i64) -> Result<bool, Error> {
+        match self.last_snapshot_ms {
+            None => {
+                self.last_snapshot_ms = Some(now_ms);
+                Ok(false)
+            }
+            Some(last) if self.snapshot_file.is_some() && now_ms - last >= self.snapshot_interval_ms => {
+                self.save(now_ms)?;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+        }
+    }
+
+    // 按entry中的顺序应用一个slot的PumpSwap指令与pump的Migrate
+    #[allow(dead_code)]
+    pub fn record(&mut self, results: &TransactionResults) {
+        let slot = results.current_slot;
+        self.fills.clear();
+        if self.seeded_slot.is_some_and(|seeded| slot <= seeded) {
+            return;
+        }
+        let mut steps: Vec<(usize, usize, &str, Step)> = Vec::new();
+        for tx in &results.pumpamm_transactions {
+            let index = results.transaction_index(&tx.signature).unwrap_or(usize::MAX);
+            steps.extend(
+                tx.instructions
+                    .iter()
+                    .enumerate()
+                    .map(|(position, ix)| (index, position, tx.signature.as_str(), Step::PumpSwap(ix, &tx.signers))),
+            );
+        }
+        for tx in &results.pump_transactions {
+            let index = results.transaction_index(&tx.signature).unwrap_or(usize::MAX);
+            steps.extend(
+                tx.instructions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, ix)| ix.instruction_type == Some(PumpInstructionType::Migrate))
+                    .map(|(position, ix)| (index, position, tx.signature.as_str(), Step::Migrate(ix))),
+            );
+        }
+        steps.sort_by_key(|(index, position, _, _)| (*index, *position));
+        for (index, position, tx, step) in steps {
+            match step {
+                Step::PumpSwap(ix, signers) => self.apply(ix, signers, (index, position, tx), slot),
+                Step::Migrate(ix) => {
+                    let pool = migrated_pool(ix, self.migration_reserves, slot);
+                    self.create(pool);
+                }
+            }
+        }
+        self.last_slot = self.last_slot.max(slot);
+    }
+
+    // at是 (交易序号, 指令序号, 签名)，用于记录成交
+    fn apply(&mut self, ix: &PumpAmmInstruction, signers: &[String], (index, position, tx): (usize, usize, &str), slot: u64) {
+        let (layout, args) = (ix.account_layout(), ix.decoded_args());
+        let key = |name| account(layout, &ix.accounts, name);
+        let Some(kind) = ix.instruction_type else {
+            return;
+        };
+        match kind {
+            PumpAmmInstructionType::CreateConfig
+            | PumpAmmInstructionType::UpdateFeeConfig
+            | PumpAmmInstructionType::Disable
+                if !self.admin_signed(key("admin"), signers) =>
+            {
+                self.ignored_admin_updates += 1;
+            }
+            PumpAmmInstructionType::CreateConfig | PumpAmmInstructionType::UpdateFeeConfig => {
+                let (Some(global_config), Some(lp_fee_bps), Some(protocol_fee_bps)) = (
+                    key("global_config"),
+                    arg_u64(&args, "lp_fee_basis_points"),
+                    arg_u64(&args, "protocol_fee_basis_points"),
+                ) else {
+                    return;
+                };
+                self.config_entry(global_config).fees = PoolFees {
+                    lp_fee_bps,
+                    protocol_fee_bps,
+                };
+            }
+            PumpAmmInstructionType::Disable => {
+                let flag = |name: &str| args.as_ref()?.get(name)?.as_bool();
+                let (Some(global_config), Some(create_pool), Some(deposit), Some(withdraw), Some(buy), Some(sell)) = (
+                    key("global_config"),
+                    flag("disable_create_pool"),
+                    flag("disable_deposit"),
+                    flag("disable_withdraw"),
+                    flag("disable_buy"),
+                    flag("disable_sell"),
+                ) else {
+                    return;
+                };
+                let disabled = DisabledOperations {
+                    create_pool,
+                    deposit,
+                    withdraw,
+                    buy,
+                    sell,
+                };
+                for pool in self.pools.values_mut().filter(|pool| pool.global_config == global_config) {
+                    pool.disabled = disabled;
+                }
+                self.config_entry(global_config).disabled = disabled;
+            }
+            PumpAmmInstructionType::CreatePool => self.create(created_pool(ix, &args, slot)),
+            PumpAmmInstructionType::Buy
+            | PumpAmmInstructionType::Sell
+            | PumpAmmInstructionType::Deposit
+            | PumpAmmInstructionType::Withdraw => {
+                let Some(address) = key("pool") else {
+                    return;
+                };
+                let Some(pool) = self.pools.get_mut(&address) else {
+                    self.unknown_pool_updates += 1;
+                    return;
+                };
+                let fees = self.configs.get(&pool.global_config).map_or(self.default_fees, |config| config.fees);
+                let fill = pool.fill(&kind, &args, fees);
+                if !pool.apply(&kind, &args, fees, slot) {
+                    self.rejected_updates += 1;
+                    return;
+                }
+                if let Some((side, base_amount, quote_amount)) = fill {
+                    self.fills.push(Fill {
+                        slot,
+                        signature: tx.to_string(),
+                        index,
+                        position,
+                        venue: Program::PumpAmm,
+                        pool: address,
+                        side,
+                        mint: pool.base_mint.clone(),
+                        quote_mint: pool.quote_mint.clone(),
+                        token_amount: base_amount,
+                        quote_amount,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn create(&mut self, pool: Option<PoolState>) {
+        let Some(pool) = pool else {
+            return;
+        };
+        let disabled = self.configs.get(&pool.global_config).map(|config| config.disabled);
+        // 池子账户已存在或创建被禁用时链上会失败
+        if self.pools.contains_key(&pool.pool) || disabled.is_some_and(|disabled| disabled.create_pool) {
+            self.rejected_updates += 1;
+            return;
+        }
+        self.insert(PoolState {
+            disabled: disabled.unwrap_or_default(),
+            ..pool
+        });
+    }
+
+    // 指令中的admin账户是配置的管理员，并且签名了交易
+    fn admin_signed(&self, admin: Option<String>, signers: &[String]) -> bool {
+        match (&self.admin, admin) {
+            (Some(expected), Some(admin)) => *expected == admin && signers.contains(&admin),
+            _ => false,
+        }
+    }
+
+    fn config_entry(&mut self, global_config: String) -> &mut GlobalConfigState {
+        let fees = self.default_fees;
+        self.configs.entry(global_config.clone()).or_insert_with(|| GlobalConfigState {
+            global_config,
+            fees,
+            disabled: DisabledOperations::default(),
+        })
+    }
+
+    fn insert(&mut self, pool: PoolState) {
+        let pools = self.by_mint.entry(pool.base_mint.clone()).or_default();
+        if !pools.contains(&pool.pool) {
+            pools.push(pool.pool.clone());
+        }
+        self.pools.insert(pool.pool.clone(), pool);
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, pool: &str) -> Option<&PoolState> {
+        self.pools.get(pool)
+    }
+
+    // 最近一次record中按估算成交的买卖，按entry中的顺序
+    #[allow(dead_code)]
+    pub fn fills(&self) -> &[Fill] {
+        &self.fills
+    }
+
+    // 以mint为base的全部池子，按创建顺序
+    #[allow(dead_code)]
+    pub fn pools_for_mint(&self, mint: &str) -> Vec<&PoolState> {
+        self.by_mint
+            .get(mint)
+            .map(|pools| pools.iter().filter_map(|pool| self.pools.get(pool)).collect())
+            .unwrap_or_default()
+    }
+
+    // 池子所属global_config的费率
+    #[allow(dead_code)]
+    pub fn fees(&self, pool: &PoolState) -> PoolFees {
+        self.configs.get(&pool.global_config).map_or(self.default_fees, |config| config.fees)
+    }
+
+    #[allow(dead_code)]
+    pub fn config(&self, global_config: &str) -> Option<&GlobalConfigState> {
+        self.configs.get(global_config)
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn last_slot(&self) -> u64 {
+        self.last_slot
+    }
+
+    // 针对未知池子（创建早于订阅且没有快照）的买卖与流动性指令数
+    #[allow(dead_code)]
+    pub fn unknown_pool_updates(&self) -> u64 {
+        self.unknown_pool_updates
+    }
+
+    // 按估算会失败而没有应用的指令数
+    #[allow(dead_code)]
+    pub fn rejected_updates(&self) -> u64 {
+        self.rejected_updates
+    }
+
+    // 没有由配置的管理员签名而忽略的管理指令数
+    #[allow(dead_code)]
+    pub fn ignored_admin_updates(&self) -> u64 {
+        self.ignored_admin_updates
+    }
+}
+
+// pump的Migrate建立的池子：base为曲线的mint、quote为WSOL，储备取配置的迁移储备
+fn migrated_pool(ix: &PumpInstruction, (base_reserve, quote_reserve): (u64, u64), slot: u64) -> Option<PoolState> {
+    let layout = ix.account_layout();
+    let key = |name| account(layout, &ix.accounts, name);
+    Some(PoolState {
+        pool: key("pool")?,
+        global_config: key("amm_global_config")?,
+        creator: key("pool_authority"),
+        base_mint: key("mint")?,
+        quote_mint: key("wsol_mint")?,
+        lp_mint: key("lp_mint")?,
+        pool_base_token_account: key("pool_base_token_account")?,
+        pool_quote_token_account: key("pool_quote_token_account")?,
+        base_reserve,
+        quote_reserve,
+        lp_supply: isqrt(base_reserve as u128 * quote_reserve as u128) as u64,
+        created_slot: slot,
+        last_slot: slot,
+        disabled: DisabledOperations::default(),
+    })
+}
+
+// CreatePool建立的池子；LP供应量按 sqrt(base * quote) 估算
+fn created_pool(ix: &PumpAmmInstruction, args: &Option<Value>, slot: u64) -> Option<PoolState> {
+    let layout = ix.account_layout();
+    let key = |name| account(layout, &ix.accounts, name);
+    let base_reserve = arg_u64(args, "base_amount_in")?;
+    let quote_reserve = arg_u64(args, "quote_amount_in")?;
+    if base_reserve == 0 || quote_reserve == 0 {
+        return None;
+    }
+    Some(PoolState {
+        pool: key("pool")?,
+        global_config: key("global_config")?,
+        creator: key("creator"),
+        base_mint: key("base_mint")?,
+        quote_mint: key("quote_mint")?,
+        lp_mint: key("lp_mint")?,
+        pool_base_token_account: key("pool_base_token_account")?,
+        pool_quote_token_account: key("pool_quote_token_account")?,
+        base_reserve,
+        quote_reserve,
+        lp_supply: isqrt(base_reserve as u128 * quote_reserve as u128) as u64,
+        created_slot: slot,
+        last_slot: slot,
+        disabled: DisabledOperations::default(),
+    })
+}
@@ -0,0 +1,80 @@
+// 按配置启用的全部分析；实时订阅与回放都通过它按slot驱动，两边的输入（包括没有匹配交易的slot）与调用顺序一致
+use std::io::Error;
+
+use crate::analytics::{
+    ArbitrageDetector, Candle, CandleAggregator, CurveState, CurveTracker, EventSink, Graduated, MigrationTracker,
+    Opportunity, PoolRegistry,
+};
+use crate::config::Config;
+use crate::models::TransactionResults;
+
+pub struct SlotAnalytics {
+    pub candles: Option<CandleAggregator>,
+    pub pools: Option<PoolRegistry>,
+    pub migrations: Option<MigrationTracker>,
+    pub curves: Option<CurveTracker>,
+    pub arbitrage: Option<ArbitrageDetector>,
+}
+
+impl SlotAnalytics {
+    // 启用池子状态时从快照恢复
+    #[allow(dead_code)]
+    pub fn from_config(config: &Config) -> Result<Self, Error> {
+        Ok(Self {
+            candles: config.candles.enabled.then(|| CandleAggregator::new(&config.candles)),
+            pools: config.pools.enabled.then(|| PoolRegistry::open(&config.pools)).transpose()?,
+            migrations: config.migrations.enabled.then(|| MigrationTracker::new(&config.migrations)),
+            curves: config.curves.enabled.then(|| CurveTracker::new(&config.curves)),
+            arbitrage: config.arbitrage.enabled.then(|| ArbitrageDetector::new(&config.arbitrage)),
+        })
+    }
+
+    // 每条消息的解析结果调用一次，没有匹配交易的slot也要调用以推进K线的时间与过期清理
+    #[allow(dead_code)]
+    pub fn record<S>(&mut self, results: &TransactionResults, sink: &mut S)
+    where
+        S: EventSink<Candle> + EventSink<Graduated> + EventSink<CurveState> + EventSink<Opportunity>,
+    {
+        if let Some(migrations) = self.migrations.as_mut() {
+            migrations.record(results, sink);
+        }
+        if let Some(curves) = self.curves.as_mut() {
+            curves.record(results, sink);
+        }
+        if let Some(pools) = self.pools.as_mut() {
+            pools.record(results);
+        }
+        // 套利比较的PumpSwap价格来自池子注册表
+        if let (Some(arbitrage), Some(pools)) = (self.arbitrage.as_mut(), self.pools.as_ref()) {
+            arbitrage.record(results, pools, sink);
+        }
+        // K线按曲线与池子模拟的成交计价，在它们之后推进
+        if let Some(candles) = self.candles.as_mut() {
+            let curves = self.curves.iter().flat_map(CurveTracker::fills);
+            let pools = self.pools.iter().flat_map(PoolRegistry::fills);
+            candles.record(results.current_slot, curves.chain(pools), sink);
+        }
+    }
+
+    // 距上次快照超过snapshot_interval_secs时写回池子状态
+    #[allow(dead_code)]
+    pub fn checkpoint(&mut self, now_ms: i64) -> Result<(), Error> {
+        if let Some(pools) = self.pools.as_mut() {
+            pools.checkpoint(now_ms)?;
+        }
+        Ok(())
+    }
+
+    // 回放结束时收盘剩余的K线并写出池子快照
+    #[allow(dead_code)]
+    pub fn finish<S: EventSink<Candle>>(&mut self, now_ms: i64, sink: &mut S) -> Result<(), Error> {
+        if let Some(candles) = self.candles.as_mut() {
+            candles.flush(sink);
+        }
+        if let Some(pools) = self.pools.as_mut() {
+            pools.save(now_ms)?;
+            log::info!("池子状态: {} 个池子，截至slot {}", pools.len(), pools.last_slot());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+// 各子命令的实现，全部复用解析器与TransactionProcessor
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_entry::entry::Entry;
+use solana_sdk::hash::Hash;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::analytics::SlotAnalytics;
+use crate::config::{Config, OutputFormat};
+use crate::export::writer::{now_ms, SlotClock};
+use crate::export::EventExporter;
+use crate::labels;
+use crate::models::TransactionResults;
+use crate::services::{
+    threads, DiscoveryReport, JitoClient, OutputSink, ProcessorOptions, RecordingReader, SearcherClient, TransactionProcessor,
+};
+use crate::storage::StorageWriter;
+
+// 实时订阅
+pub fn stream(config: Config, record: Option<&Path>) -> Result<(), Error> {
+    JitoClient::start_with_config(config, record)
+}
+
+// 回放录制文件
+pub fn replay(config: &Config, file: &Path) -> Result<(), Error> {
+    let pool = threads::parser_pool(config.processing.threads, &config.runtime.parser_cores)?;
+    let options = ProcessorOptions::from_config(config)?.with_pool(pool);
+    let mut sink = OutputSink::from_config(config)?;
+    let storage = config
+        .storage
+        .enabled
+        .then(|| StorageWriter::start(&config.storage))
+        .transpose()?;
+    let exporter = config
+        .export
+        .enabled
+        .then(|| EventExporter::start(&config.export))
+        .transpose()?;
+    let mut clock = SlotClock::from_config(&config.export);
+    let mut analytics = SlotAnalytics::from_config(config)?;
+
+    let mut records = 0usize;
+    for record in RecordingReader::open(file)? {
+        let (slot, payload) = record?;
+        records += 1;
+
+        let entries = match bincode::deserialize::<Vec<Entry>>(&payload) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("slot {} 反序列化失败: {e}", slot);
+                continue;
+            }
+        };
+
+        let results = TransactionProcessor::process_entries_with(&entries, slot, &options);
+        if results.has_results() {
+            if let Some(storage) = &storage {
+                storage.submit(&results);
+            }
+            if let Some(exporter) = &exporter {
+                exporter.submit(&results, clock.time(slot));
+            }
+            sink.emit(&results);
+        }
+        analytics.record(&results, &mut sink);
+    }
+
+    // 收盘剩余的K线并写出池子快照，等待剩余的slot写入数据库与导出文件
+    analytics.finish(now_ms(), &mut sink)?;
+    if let Some(storage) = storage {
+        storage.shutdown();
+    }
+    if let Some(exporter) = exporter {
+        exporter.shutdown();
+    }
+    log::info!("回放完成: {} 条记录", records);
+    Ok(())
+}
+
+// 解码base58或base64编码的bincode交易；只由base58字符组成的base64也能识别
+pub fn decode_transaction(input: &str) -> Result<VersionedTransaction, Error> {
+    let input = input.trim();
+    let candidates = [
+        bs58::decode(input).into_vec().ok(),
+        base64::engine::general_purpose::STANDARD.decode(input).ok(),
+    ];
+    if candidates.iter().all(Option::is_none) {
+        return Err(Error::new(ErrorKind::InvalidInput, "交易既不是有效的base58也不是有效的base64"));
+    }
+
+    let mut last_error = None;
+    for bytes in candidates.into_iter().flatten() {
+        match bincode::deserialize::<VersionedTransaction>(&bytes) {
+            Ok(transaction) => return Ok(transaction),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(Error::new(
+        ErrorKind::InvalidData,
+        format!("无法反序列化交易: {}", last_error.map(|e| e.to_string()).unwrap_or_default()),
+    ))
+}
+
+// 解码单笔交易
+pub fn decode(config: &Config, input: &str) -> Result<(), Error> {
+    let transaction = decode_transaction(input)?;
+    let entries = vec![Entry {
+        num_hashes: 0,
+        hash: Hash::default(),
+        transactions: vec![transaction],
+    }];
+
+    emit_or_report(config, &entries, 0)
+}
+
+// 解码bincode编码的Vec<Entry>文件
+pub fn decode_entries(config: &Config, file: &Path, slot: u64) -> Result<(), Error> {
+    let bytes = fs::read(file)
+        .map_err(|e| Error::new(e.kind(), format!("无法读取 {}: {}", file.display(), e)))?;
+    let entries = bincode::deserialize::<Vec<Entry>>(&bytes)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, format!("无法反序列化Entry: {}", e)))?;
+
+    emit_or_report(config, &entries, slot)
+}
+
+fn emit_or_report(config: &Config, entries: &[Entry], slot: u64) -> Result<(), Error> {
+    let options = ProcessorOptions::from_config(config)?;
+    let results = TransactionProcessor::process_entries_with(entries, slot, &options);
+
+    if results.has_results() {
+        OutputSink::from_config(config)?.emit(&results);
+    } else {
+        let transactions: usize = entries.iter().map(|e| e.transactions.len()).sum();
+        log::warn!("{} 笔交易中没有已启用解析器可识别的指令", transactions);
+    }
+    Ok(())
+}
+
+// 汇总录制文件
+pub fn stats(config: &Config, file: &Path) -> Result<(), Error> {
+    let pool = threads::parser_pool(config.processing.threads, &config.runtime.parser_cores)?;
+    let options = ProcessorOptions::from_config(config)?.with_pool(pool);
+
+    let mut stats = RecordingStats {
+        unknown: DiscoveryReport::from_config(&config.discovery, options.registry.as_deref()),
+        ..RecordingStats::default()
+    };
+    for record in RecordingReader::open(file)? {
+        let (slot, payload) = record?;
+        stats.add(slot, &payload, &options);
+    }
+
+    match config.sinks.format {
+        OutputFormat::Text => print!("{}", stats),
+        OutputFormat::Json => println!("{}", stats.to_json()),
+    }
+    Ok(())
+}
+
+// 查询block engine当前的小费账户
+pub fn tip_accounts(config: &Config) -> Result<(), Error> {
+    let rt = threads::tokio_runtime(&config.runtime)?;
+    let accounts = rt.block_on(async { SearcherClient::from_config(config).await?.get_tip_accounts().await })?;
+
+    match config.sinks.format {
+        OutputFormat::Text => accounts.iter().for_each(|account| println!("{}", account)),
+        OutputFormat::Json => println!("{}", json!({ "tip_accounts": accounts })),
+    }
+    Ok(())
+}
+
+// 单个解析器的统计
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParserStats {
+    pub transactions: u64,
+    pub instructions: BTreeMap<String, u64>,
+}
+
+// 录制文件的统计信息
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RecordingStats {
+    pub records: u64,
+    pub malformed: u64,
+    pub payload_bytes: u64,
+    pub first_slot: Option<u64>,
+    pub last_slot: Option<u64>,
+    pub slots: HashSet<u64>,
+    pub entries: u64,
+    pub transactions: u64,
+    pub parsers: BTreeMap<&'static str, ParserStats>,
+    // 解析器无法识别的指令
+    pub unknown: DiscoveryReport,
+}
+
+impl RecordingStats {
+    // 累加一条录制记录
+    pub fn add(&mut self, slot: u64, payload: &[u8], options: &ProcessorOptions) {
+        self.records += 1;
+        self.payload_bytes += payload.len() as u64;
+        self.first_slot.get_or_insert(slot);
+        self.last_slot = Some(slot);
+        self.slots.insert(slot);
+
+        let entries = match bincode::deserialize::<Vec<Entry>>(payload) {
+            Ok(entries) => entries,
+            Err(_) => {
+                self.malformed += 1;
+                return;
+            }
+        };
+        self.entries += entries.len() as u64;
+        self.transactions += entries.iter().map(|e| e.transactions.len() as u64).sum::<u64>();
+
+        let results = TransactionProcessor::process_entries_with(&entries, slot, options);
+        self.add_results(&results);
+    }
+
+    fn add_results(&mut self, results: &TransactionResults) {
+        self.unknown.record_results(results);
+        for tx in &results.pump_transactions {
+            self.count("PUMP", tx.instructions.iter().map(|ix| ix.kind().to_string()));
+        }
+        for tx in &results.pumpamm_transactions {
+            self.count("PUMPAMM", tx.instructions.iter().map(|ix| ix.kind().to_string()));
+        }
+        for tx in &results.boop_transactions {
+            self.count("BOOP", tx.instructions.iter().map(|ix| ix.kind().to_string()));
+        }
+        for tx in &results.idl_transactions {
+            self.count("IDL", tx.instructions.iter().map(|ix| format!("{}::{}", ix.program, ix.name)));
+        }
+    }
+
+    fn count<I: Iterator<Item = String>>(&mut self, parser: &'static str, instructions: I) {
+        let stats = self.parsers.entry(parser).or_default();
+        stats.transactions += 1;
+        for name in instructions {
+            *stats.instructions.entry(name).or_default() += 1;
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "records": self.records,
+            "malformed": self.malformed,
+            "payload_bytes": self.payload_bytes,
+            "first_slot": self.first_slot,
+            "last_slot": self.last_slot,
+            "distinct_slots": self.slots.len(),
+            "entries": self.entries,
+            "transactions": self.transactions,
+            "parsers": self
+                .parsers
+                .iter()
+                .map(|(name, stats)| {
+                    (
+                        name.to_string(),
+                        json!({"transactions": stats.transactions, "instructions": stats.instructions}),
+                    )
+                })
+                .collect::<serde_json::Map<_, _>>(),
+            "unknown_discriminators": self.unknown.to_json(),
+        })
+    }
+}
+
+impl fmt::Display for RecordingStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        labels::field(f, "records", self.records)?;
+        labels::field(f, "malformed", self.malformed)?;
+        labels::field(f, "payload_bytes", self.payload_bytes)?;
+        match (self.first_slot, self.last_slot) {
+            (Some(first), Some(last)) => writeln!(
+                f,
+                "{}: {} - {} ({} {})",
+                labels::text("slots"),
+                first,
+                last,
+                self.slots.len(),
+                labels::text("distinct")
+            )?,
+            _ => labels::field(f, "slots", "-")?,
+        }
+        labels::field(f, "entries", self.entries)?;
+        labels::field(f, "transactions", self.transactions)?;
+
+        for (parser, stats) in &self.parsers {
+            writeln!(f, "{}:{} {}: {}", labels::text("parser"), parser, labels::text("transactions"), stats.transactions)?;
+            for (instruction, count) in &stats.instructions {
+                writeln!(f, "  {}: {}", instruction, count)?;
+            }
+        }
+        if !self.unknown.is_empty() {
+            labels::field(f, "unknown_discriminators", self.unknown.len())?;
+            write!(f, "{}", self.unknown)?;
+        }
+        Ok(())
+    }
+}
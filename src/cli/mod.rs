@@ -0,0 +1,193 @@
+// 命令行入口：解析子命令及其参数，加载分层配置后分发到各命令
+pub mod commands;
+
+use std::io::{Error, ErrorKind};
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::labels;
+use crate::services::logging;
+
+pub const USAGE: &str = "\
+用法: shreds-client [子命令] [参数] [配置覆盖]
+
+子命令:
+  stream [--record <文件>]            实时订阅并解析（默认）；--record 同时录制原始消息
+  replay <文件>                       将录制文件中的消息交给解析器处理
+  decode <交易>                       解码单笔交易（base58或base64编码的bincode VersionedTransaction）
+  decode-entries <文件> [--slot <N>]  解码bincode编码的Vec<Entry>文件
+  stats <文件>                        汇总录制文件的统计信息
+  tip-accounts                        查询block engine的小费账户（需要 --block-engine-url，可选 --keypair）
+
+通用参数:
+  --config <文件>                     配置文件路径（默认 config.toml）
+  --format <text|json>                输出格式
+  --locale <en|zh>                    文本输出的标签语言（默认 en）
+  --parsers <列表>                    只启用列出的解析器，例如 pump,boop（可选 pump / pumpamm / boop / idl）
+  --accounts <地址列表>               只处理引用了其中任一账户的交易
+  --url / --idl-dir / --output / --batch-size / --threads / --set key=value 等配置覆盖见README
+  -h, --help                          显示帮助
+";
+
+const PARSER_NAMES: &[&str] = &["pump", "pumpamm", "boop", "idl"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Stream { record: Option<PathBuf> },
+    Replay { file: PathBuf },
+    Decode { transaction: String },
+    DecodeEntries { file: PathBuf, slot: u64 },
+    Stats { file: PathBuf },
+    TipAccounts,
+    Help,
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    pub config: Config,
+}
+
+fn usage_error(message: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, format!("{}\n\n{}", message, USAGE))
+}
+
+impl Cli {
+    // 解析命令行参数（不含程序名），并按子命令校验配置
+    pub fn parse(args: &[String]) -> Result<Self, Error> {
+        let mut record = None;
+        let mut slot = None;
+        let mut parsers = None;
+        let mut config_args = Vec::new();
+
+        // 先取出子命令专用参数，其余交给配置层处理
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "-h" || arg == "--help" {
+                return Ok(Self {
+                    command: Command::Help,
+                    config: Config::default(),
+                });
+            }
+
+            let (name, inline) = match arg.strip_prefix("--").map(|flag| flag.split_once('=')) {
+                Some(Some((name, value))) => (name, Some(value.to_string())),
+                Some(None) => (&arg[2..], None),
+                None => ("", None),
+            };
+            let target = match name {
+                "record" => &mut record,
+                "slot" => &mut slot,
+                "parsers" => &mut parsers,
+                _ => {
+                    config_args.push(arg.clone());
+                    continue;
+                }
+            };
+            let value = inline
+                .or_else(|| iter.next().cloned())
+                .ok_or_else(|| usage_error(format!("--{} 缺少参数值", name)))?;
+            *target = Some(value);
+        }
+
+        let (mut config, positional) = Config::load_layers(&config_args)?;
+        if let Some(list) = parsers {
+            Self::apply_parsers(&mut config, &list)?;
+        }
+
+        let mut positional = positional.into_iter();
+        let subcommand = positional.next().unwrap_or_else(|| "stream".to_string());
+        let mut operand = |what: &str| {
+            positional
+                .next()
+                .ok_or_else(|| usage_error(format!("{} 缺少参数: {}", subcommand, what)))
+        };
+
+        let command = match subcommand.as_str() {
+            "stream" => Command::Stream {
+                record: record.take().map(PathBuf::from),
+            },
+            "replay" => Command::Replay {
+                file: PathBuf::from(operand("<文件>")?),
+            },
+            "decode" => Command::Decode {
+                transaction: operand("<交易>")?,
+            },
+            "decode-entries" => Command::DecodeEntries {
+                file: PathBuf::from(operand("<文件>")?),
+                slot: match slot.take() {
+                    Some(slot) => slot
+                        .parse()
+                        .map_err(|_| usage_error(format!("--slot 的值无效: {}", slot)))?,
+                    None => 0,
+                },
+            },
+            "stats" => Command::Stats {
+                file: PathBuf::from(operand("<文件>")?),
+            },
+            "tip-accounts" => Command::TipAccounts,
+            "help" => Command::Help,
+            other => return Err(usage_error(format!("未知的子命令: {}", other))),
+        };
+
+        if let Some(extra) = positional.next() {
+            return Err(usage_error(format!("多余的参数: {}", extra)));
+        }
+        if record.is_some() {
+            return Err(usage_error("--record 只能用于 stream".to_string()));
+        }
+        if slot.is_some() {
+            return Err(usage_error("--slot 只能用于 decode-entries".to_string()));
+        }
+
+        // 只有实时订阅需要连接shredstream端点
+        match command {
+            Command::Stream { .. } => config.validate()?,
+            Command::TipAccounts => config.validate_block_engine()?,
+            Command::Help => {}
+            _ => config.validate_offline()?,
+        }
+
+        Ok(Self { command, config })
+    }
+
+    // --parsers 只启用列出的解析器
+    fn apply_parsers(config: &mut Config, list: &str) -> Result<(), Error> {
+        let names: Vec<&str> = list.split(',').map(str::trim).filter(|name| !name.is_empty()).collect();
+        if let Some(unknown) = names.iter().find(|name| !PARSER_NAMES.contains(name)) {
+            return Err(usage_error(format!(
+                "未知的解析器: {}（可选 {}）",
+                unknown,
+                PARSER_NAMES.join(" / ")
+            )));
+        }
+
+        config.parsers.pump.enabled = names.contains(&"pump");
+        config.parsers.pumpamm.enabled = names.contains(&"pumpamm");
+        config.parsers.boop.enabled = names.contains(&"boop");
+        if !names.contains(&"idl") {
+            config.parsers.idl_dir = None;
+        }
+        Ok(())
+    }
+
+    pub fn run(self) -> Result<(), Error> {
+        if self.command != Command::Help {
+            logging::init(&self.config.logging)?;
+        }
+        labels::set_locale(self.config.sinks.locale);
+
+        match self.command {
+            Command::Stream { record } => commands::stream(self.config, record.as_deref()),
+            Command::Replay { file } => commands::replay(&self.config, &file),
+            Command::Decode { transaction } => commands::decode(&self.config, &transaction),
+            Command::DecodeEntries { file, slot } => commands::decode_entries(&self.config, &file, slot),
+            Command::Stats { file } => commands::stats(&self.config, &file),
+            Command::TipAccounts => commands::tip_accounts(&self.config),
+            Command::Help => {
+                print!("{}", USAGE);
+                Ok(())
+            }
+        }
+    }
+}
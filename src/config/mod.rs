@@ -1,30 +1,45 @@
 use std::fs;
 use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+pub mod settings;
+
+pub use settings::{
+    ArbitrageConfig, AttributionConfig, CandlesConfig, Config, CurveParams, CurvesConfig, DiscoveryConfig, ExportConfig,
+    LogFormat, LoggingConfig, MigrationsConfig, OutputFormat, PipelineConfig, PoolsConfig, QueueConfig, QueuePolicy,
+    RuntimeConfig, StorageConfig, DEFAULT_BATCH_SIZE,
+};
 
 // 定义要查找的程序ID (Base58格式)
 pub const PUMP_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 pub const PUMPAMM_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
 pub const BOOP_PROGRAM_ID: &str = "boop8hVGQGqehUK2iVEMEnMrL5RbjywRzHKBmBE7ry4";
 
-// 批处理大小 - 每批处理的交易数量
-pub const BATCH_SIZE: usize = 100;
-
 // 从env.toml文件中读取JITO服务器URL
 pub fn read_jito_url() -> Result<String, Error> {
-    let env_content = fs::read_to_string("env.toml")
-        .map_err(|_| Error::new(ErrorKind::NotFound, "无法读取env.toml文件"))?;
-    
+    read_env_file(Path::new(settings::LEGACY_ENV_PATH))?
+        .into_iter()
+        .find(|(key, _)| key == "JITO-SHRED-URL")
+        .map(|(_, value)| value)
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "未找到JITO-SHRED-URL配置"))
+}
+
+// 读取旧格式env.toml中的所有 KEY=VALUE 行
+pub(crate) fn read_env_file(path: &Path) -> Result<Vec<(String, String)>, Error> {
+    let env_content = fs::read_to_string(path)
+        .map_err(|_| Error::new(ErrorKind::NotFound, format!("无法读取{}文件", path.display())))?;
+
+    let mut values = Vec::new();
     for line in env_content.lines() {
         if line.starts_with('#') || line.trim().is_empty() {
             continue; // 跳过注释和空行
         }
-        
+
         if let Some((key, value)) = line.split_once('=') {
-            if key.trim() == "JITO-SHRED-URL" {
-                return Ok(value.trim().to_string());
-            }
+            // 兼容带引号的写法
+            values.push((key.trim().to_string(), value.trim().trim_matches('"').to_string()));
         }
     }
-    
-    Err(Error::new(ErrorKind::NotFound, "未找到JITO-SHRED-URL配置"))
-} 
\ No newline at end of file
+
+    Ok(values)
+}
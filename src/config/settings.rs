@@ -0,0 +1,1342 @@
+// 类型化配置：默认值 → TOML配置文件 → 环境变量 → 命令行参数 依次覆盖，启动时统一校验
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use log::LevelFilter;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::analytics::CandleInterval;
+use crate::config::{read_env_file, BOOP_PROGRAM_ID, PUMPAMM_PROGRAM_ID, PUMP_PROGRAM_ID};
+use crate::labels::Locale;
+use crate::models::sol_flow::JITO_TIP_ACCOUNTS;
+
+// 默认配置文件路径
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+// 旧格式配置文件，仅在没有config.toml时读取
+pub const LEGACY_ENV_PATH: &str = "env.toml";
+// 环境变量前缀，例如 SHREDS_ENDPOINTS_SHREDSTREAM_URL
+pub const ENV_PREFIX: &str = "SHREDS_";
+// 指定配置文件路径的环境变量
+pub const CONFIG_PATH_ENV: &str = "SHREDS_CONFIG";
+// 批处理大小 - 每个并行批次处理的交易数量
+pub const DEFAULT_BATCH_SIZE: usize = 200;
+// 主网slot目标时长
+pub const DEFAULT_SLOT_DURATION_MS: u64 = 400;
+// 流水线各阶段之间队列的默认容量（slot数）
+pub const DEFAULT_QUEUE_CAPACITY: usize = 128;
+
+// 可以通过环境变量或命令行覆盖的配置项
+pub const CONFIG_KEYS: &[&str] = &[
+    "endpoints.shredstream_url",
+    "endpoints.block_engine_url",
+    "auth.keypair_path",
+    "parsers.pump.enabled",
+    "parsers.pump.program_id",
+    "parsers.pumpamm.enabled",
+    "parsers.pumpamm.program_id",
+    "parsers.boop.enabled",
+    "parsers.boop.program_id",
+    "parsers.idl_dir",
+    "parsers.token_effects",
+    "parsers.sol_flows",
+    "parsers.tip_accounts",
+    "filters.accounts",
+    "sinks.stdout",
+    "sinks.file",
+    "sinks.format",
+    "sinks.locale",
+    "processing.batch_size",
+    "processing.threads",
+    "logging.level",
+    "logging.format",
+    "logging.rate_limit_secs",
+    "metrics.listen",
+    "metrics.slot_clock",
+    "metrics.slot_duration_ms",
+    "discovery.enabled",
+    "discovery.report_interval_secs",
+    "discovery.report_file",
+    "discovery.candidate_names",
+    "pipeline.decode_threads",
+    "pipeline.decode.capacity",
+    "pipeline.decode.policy",
+    "pipeline.parse.capacity",
+    "pipeline.parse.policy",
+    "pipeline.emit.capacity",
+    "pipeline.emit.policy",
+    "runtime.tokio_threads",
+    "runtime.receive_cores",
+    "runtime.tokio_cores",
+    "runtime.parser_cores",
+    "attribution.enabled",
+    "attribution.rules_file",
+    "storage.enabled",
+    "storage.path",
+    "storage.queue.capacity",
+    "storage.queue.policy",
+    "export.enabled",
+    "export.dir",
+    "export.rows_per_file",
+    "export.flush_interval_secs",
+    "export.origin_slot",
+    "export.origin_unix_ms",
+    "export.slot_duration_ms",
+    "export.queue.capacity",
+    "export.queue.policy",
+    "candles.enabled",
+    "candles.intervals",
+    "candles.history",
+    "candles.grace_slots",
+    "candles.slot_duration_ms",
+    "pools.enabled",
+    "pools.snapshot_file",
+    "pools.snapshot_interval_secs",
+    "pools.lp_fee_bps",
+    "pools.protocol_fee_bps",
+    "pools.admin",
+    "pools.migration_base_reserve",
+    "pools.migration_quote_reserve",
+    "migrations.enabled",
+    "migrations.pending_slots",
+    "migrations.idle_curve_slots",
+    "curves.enabled",
+    "curves.pump_authority",
+    "curves.idle_curve_slots",
+    "curves.pump.initial_virtual_token_reserves",
+    "curves.pump.initial_virtual_sol_reserves",
+    "curves.pump.initial_real_token_reserves",
+    "curves.pump.fee_bps",
+    "curves.boop.initial_virtual_token_reserves",
+    "curves.boop.initial_virtual_sol_reserves",
+    "curves.boop.initial_real_token_reserves",
+    "curves.boop.fee_bps",
+    "arbitrage.enabled",
+    "arbitrage.threshold_bps",
+    "arbitrage.max_age_slots",
+];
+
+// 命令行简写参数 -> 配置项
+const CLI_ALIASES: &[(&str, &str)] = &[
+    ("url", "endpoints.shredstream_url"),
+    ("block-engine-url", "endpoints.block_engine_url"),
+    ("keypair", "auth.keypair_path"),
+    ("idl-dir", "parsers.idl_dir"),
+    ("accounts", "filters.accounts"),
+    ("output", "sinks.file"),
+    ("format", "sinks.format"),
+    ("locale", "sinks.locale"),
+    ("batch-size", "processing.batch_size"),
+    ("threads", "processing.threads"),
+    ("log-level", "logging.level"),
+    ("log-format", "logging.format"),
+    ("metrics-listen", "metrics.listen"),
+    ("discovery-report", "discovery.report_file"),
+    ("decode-threads", "pipeline.decode_threads"),
+    ("tokio-threads", "runtime.tokio_threads"),
+    ("attribution-rules", "attribution.rules_file"),
+    ("db", "storage.path"),
+    ("export-dir", "export.dir"),
+    ("pool-snapshot", "pools.snapshot_file"),
+];
+
+const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub endpoints: EndpointsConfig,
+    pub auth: AuthConfig,
+    pub parsers: ParsersConfig,
+    pub filters: FiltersConfig,
+    pub sinks: SinksConfig,
+    pub processing: ProcessingConfig,
+    pub logging: LoggingConfig,
+    pub metrics: MetricsConfig,
+    pub discovery: DiscoveryConfig,
+    pub pipeline: PipelineConfig,
+    pub runtime: RuntimeConfig,
+    pub attribution: AttributionConfig,
+    pub storage: StorageConfig,
+    pub export: ExportConfig,
+    pub candles: CandlesConfig,
+    pub pools: PoolsConfig,
+    pub migrations: MigrationsConfig,
+    pub curves: CurvesConfig,
+    pub arbitrage: ArbitrageConfig,
+}
+
+// 服务端点
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EndpointsConfig {
+    pub shredstream_url: String,
+    pub block_engine_url: Option<String>,
+}
+
+// 认证
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    pub keypair_path: Option<PathBuf>,
+}
+
+// 单个内置解析器；program_id为空时使用内置程序ID
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ParserConfig {
+    pub enabled: bool,
+    pub program_id: Option<String>,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            program_id: None,
+        }
+    }
+}
+
+impl ParserConfig {
+    // 启用时返回生效的程序ID
+    fn resolve(&self, default: &str) -> Result<Option<Pubkey>, String> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        let id = self.program_id.as_deref().unwrap_or(default);
+        Pubkey::from_str(id)
+            .map(Some)
+            .map_err(|_| format!("无效的程序ID: {}", id))
+    }
+}
+
+// 解析器
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ParsersConfig {
+    pub pump: ParserConfig,
+    pub pumpamm: ParserConfig,
+    pub boop: ParserConfig,
+    // 运行时加载的Anchor IDL目录
+    pub idl_dir: Option<PathBuf>,
+    // 为匹配到的交易解码SPL Token / Token-2022 / ATA指令，汇总代币层面的副作用
+    pub token_effects: bool,
+    // 为匹配到的交易汇总System Program转账的SOL流向
+    pub sol_flows: bool,
+    // 内置Jito小费账户之外的小费账户（其他交易发送服务）
+    pub tip_accounts: Vec<String>,
+}
+
+impl Default for ParsersConfig {
+    fn default() -> Self {
+        Self {
+            pump: ParserConfig::default(),
+            pumpamm: ParserConfig::default(),
+            boop: ParserConfig::default(),
+            idl_dir: None,
+            token_effects: true,
+            sol_flows: true,
+            tip_accounts: Vec::new(),
+        }
+    }
+}
+
+impl ParsersConfig {
+    #[allow(dead_code)]
+    pub fn pump_program_id(&self) -> Option<Pubkey> {
+        self.pump.resolve(PUMP_PROGRAM_ID).ok().flatten()
+    }
+
+    #[allow(dead_code)]
+    pub fn pumpamm_program_id(&self) -> Option<Pubkey> {
+        self.pumpamm.resolve(PUMPAMM_PROGRAM_ID).ok().flatten()
+    }
+
+    #[allow(dead_code)]
+    pub fn boop_program_id(&self) -> Option<Pubkey> {
+        self.boop.resolve(BOOP_PROGRAM_ID).ok().flatten()
+    }
+
+    // 内置Jito小费账户加上配置的小费账户
+    #[allow(dead_code)]
+    pub fn tip_account_keys(&self) -> HashSet<Pubkey> {
+        JITO_TIP_ACCOUNTS
+            .into_iter()
+            .chain(self.tip_accounts.iter().filter_map(|account| Pubkey::from_str(account).ok()))
+            .collect()
+    }
+}
+
+// 过滤条件：accounts非空时只处理引用了其中任一账户的交易
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FiltersConfig {
+    pub accounts: Vec<String>,
+}
+
+impl FiltersConfig {
+    #[allow(dead_code)]
+    pub fn account_keys(&self) -> Vec<Pubkey> {
+        self.accounts
+            .iter()
+            .filter_map(|account| Pubkey::from_str(account).ok())
+            .collect()
+    }
+}
+
+// 解析结果的输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    // 每笔交易一行JSON
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("无效的输出格式: {}（可选 text / json）", s)),
+        }
+    }
+}
+
+// 输出
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SinksConfig {
+    pub stdout: bool,
+    // 追加写入解析结果的文件
+    pub file: Option<PathBuf>,
+    pub format: OutputFormat,
+    // 文本输出的标签语言，JSON字段名不受影响
+    pub locale: Locale,
+}
+
+impl Default for SinksConfig {
+    fn default() -> Self {
+        Self {
+            stdout: true,
+            file: None,
+            format: OutputFormat::Text,
+            locale: Locale::En,
+        }
+    }
+}
+
+// 处理参数
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProcessingConfig {
+    pub batch_size: usize,
+    // 解析线程池（rayon）线程数，未设置时使用CPU核心数
+    pub threads: Option<usize>,
+}
+
+impl Default for ProcessingConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+            threads: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("无效的日志格式: {}（可选 text / json）", s)),
+        }
+    }
+}
+
+// 日志；level支持按模块设置，例如 "warn,shreds_client::services::jito_client=debug"
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    pub level: String,
+    pub format: LogFormat,
+    // 同一条重复的运维日志（如未知鉴别器）两次输出之间的最短间隔
+    pub rate_limit_secs: u64,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            format: LogFormat::Text,
+            rate_limit_secs: 60,
+        }
+    }
+}
+
+impl LoggingConfig {
+    // 解析level为 (默认级别, [(模块前缀, 级别)])
+    pub fn level_filters(&self) -> Result<(LevelFilter, Vec<(String, LevelFilter)>), String> {
+        let mut default = LevelFilter::Info;
+        let mut targets = Vec::new();
+        for directive in self.level.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => targets.push((target.trim().to_string(), parse_log_level(level.trim())?)),
+                None => default = parse_log_level(directive)?,
+            }
+        }
+        Ok((default, targets))
+    }
+}
+
+fn parse_log_level(level: &str) -> Result<LevelFilter, String> {
+    if !LOG_LEVELS.contains(&level) {
+        return Err(format!("{}（可选 {}）", level, LOG_LEVELS.join(" / ")));
+    }
+    level.parse().map_err(|_| level.to_string())
+}
+
+// Prometheus指标端点，listen为空时不启动
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    pub listen: Option<String>,
+    // 按本地估算的slot开始时间统计slot首次到达的延迟
+    pub slot_clock: bool,
+    pub slot_duration_ms: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            listen: None,
+            slot_clock: false,
+            slot_duration_ms: DEFAULT_SLOT_DURATION_MS,
+        }
+    }
+}
+
+impl MetricsConfig {
+    #[allow(dead_code)]
+    pub fn listen_addr(&self) -> Option<SocketAddr> {
+        self.listen.as_deref()?.parse().ok()
+    }
+
+    // 启用slot时钟时返回slot时长
+    #[allow(dead_code)]
+    pub fn slot_duration(&self) -> Option<Duration> {
+        self.slot_clock.then(|| Duration::from_millis(self.slot_duration_ms))
+    }
+}
+
+// 未知鉴别器发现：按程序汇总解析器无法识别的指令并定期输出报告
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiscoveryConfig {
+    pub enabled: bool,
+    pub report_interval_secs: u64,
+    // 报告（JSON）写入的文件，每次覆盖；未设置时只写日志
+    pub report_file: Option<PathBuf>,
+    // 额外的候选指令名，用于猜测未知鉴别器对应的指令
+    pub candidate_names: Vec<String>,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            report_interval_secs: 300,
+            report_file: None,
+            candidate_names: Vec::new(),
+        }
+    }
+}
+
+// 交易机器人 / 前端归因：规则文件格式见 attribution.example.toml
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AttributionConfig {
+    pub enabled: bool,
+    pub rules_file: Option<PathBuf>,
+}
+
+// SQLite持久化：按slot批量写入交易、指令、买卖、代币发行与曲线快照（需要sqlite特性）
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub enabled: bool,
+    pub path: PathBuf,
+    // 写入线程前的队列（单位：slot）
+    pub queue: QueueConfig,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from("shreds.db"),
+            queue: QueueConfig::default(),
+        }
+    }
+}
+
+// 研究用的Parquet导出：买卖、代币发行与流动性事件按小时分区写出
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExportConfig {
+    pub enabled: bool,
+    pub dir: PathBuf,
+    // 单张表缓冲的行数达到该值时写出一个文件
+    pub rows_per_file: usize,
+    // 缓冲中最早的一行超过该时长时写出，限制进程退出时丢失的数据
+    pub flush_interval_secs: u64,
+    // 回放时observed_at按slot推算：origin_slot开始于origin_unix_ms，之后每个slot slot_duration_ms；
+    // 未设置时以第一个回放的slot与回放开始的时间为基准。实时订阅使用本地处理的时间
+    pub origin_slot: Option<u64>,
+    pub origin_unix_ms: Option<i64>,
+    pub slot_duration_ms: u64,
+    // 导出线程前的队列（单位：slot）
+    pub queue: QueueConfig,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: PathBuf::from("export"),
+            rows_per_file: 100_000,
+            flush_interval_secs: 300,
+            origin_slot: None,
+            origin_unix_ms: None,
+            slot_duration_ms: DEFAULT_SLOT_DURATION_MS,
+            queue: QueueConfig::default(),
+        }
+    }
+}
+
+// 按mint聚合的OHLCV K线（pump绑定曲线与PumpSwap池子），收盘的K线写入输出
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CandlesConfig {
+    pub enabled: bool,
+    pub intervals: Vec<CandleInterval>,
+    // 每个mint每个周期保留最近多少个桶内的已收盘K线
+    pub history: usize,
+    // 最新slot之前的多少个slot仍可能有entry到达；这些slot所在的桶暂不收盘
+    pub grace_slots: u64,
+    // 由slot估算时间使用的slot时长
+    pub slot_duration_ms: u64,
+}
+
+impl Default for CandlesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intervals: vec![
+                CandleInterval::from_secs(1),
+                CandleInterval::from_secs(60),
+                CandleInterval::from_secs(300),
+            ],
+            history: 500,
+            grace_slots: 2,
+            slot_duration_ms: DEFAULT_SLOT_DURATION_MS,
+        }
+    }
+}
+
+// PumpSwap池子状态：由CreatePool与买卖、流动性指令估算储备、LP供应量与现价，定期写出快照以便重启后恢复
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PoolsConfig {
+    pub enabled: bool,
+    // 启动时存在则从中恢复，运行中与退出时写回
+    pub snapshot_file: Option<PathBuf>,
+    pub snapshot_interval_secs: u64,
+    // 没见过CreateConfig / UpdateFeeConfig时使用的费率（基点）
+    pub lp_fee_bps: u64,
+    pub protocol_fee_bps: u64,
+    // global_config的管理员：CreateConfig / UpdateFeeConfig / Disable只有由它签名时才应用，
+    // 为空时忽略这些指令，费率一直使用上面的值
+    pub admin: Option<String>,
+    // pump的Migrate通过CPI建池，指令中看不到数量：池子的初始储备取这两个值，
+    // 默认是按默认曲线参数完成的pump曲线中剩下的代币与SOL
+    pub migration_base_reserve: u64,
+    pub migration_quote_reserve: u64,
+}
+
+impl Default for PoolsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snapshot_file: None,
+            snapshot_interval_secs: 60,
+            lp_fee_bps: 20,
+            protocol_fee_bps: 5,
+            admin: None,
+            migration_base_reserve: 206_900_000_000_000,
+            migration_quote_reserve: 85_005_359_057,
+        }
+    }
+}
+
+// 绑定曲线毕业追踪：Migrate / Withdraw与之后的建池对应起来，输出毕业事件并维护mint当前的交易场所
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MigrationsConfig {
+    pub enabled: bool,
+    // Withdraw之后最多等待多少个slot的建池
+    pub pending_slots: u64,
+    // 绑定曲线上的mint超过这么多个slot没有买卖时不再跟踪，大部分曲线永远不会完成
+    pub idle_curve_slots: u64,
+}
+
+impl Default for MigrationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pending_slots: 9_000,
+            idle_curve_slots: 216_000,
+        }
+    }
+}
+
+// pump与boop绑定曲线状态：由发行指令建立，按买卖估算储备、价格与进度，boop曲线完成时输出毕业事件
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CurvesConfig {
+    pub enabled: bool,
+    pub pump: CurveParams,
+    pub boop: CurveParams,
+    // pump全局配置的管理员：SetParams只有由它签名时才更新pump的参数，为空时忽略SetParams
+    pub pump_authority: Option<String>,
+    // 曲线超过这么多个slot没有买卖时不再跟踪（包括已完成的曲线），大部分曲线永远不会完成
+    pub idle_curve_slots: u64,
+}
+
+impl Default for CurvesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pump: CurveParams::default(),
+            boop: CurveParams::default(),
+            pump_authority: None,
+            idle_curve_slots: 216_000,
+        }
+    }
+}
+
+// 新曲线的初始储备与手续费。由pump_authority签名的SetParams会更新pump的参数；
+// boop的参数在config账户中，指令里看不到，需要按链上的config设置
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CurveParams {
+    pub initial_virtual_token_reserves: u64,
+    pub initial_virtual_sol_reserves: u64,
+    pub initial_real_token_reserves: u64,
+    // 按SOL数量收取（基点）
+    pub fee_bps: u64,
+}
+
+impl Default for CurveParams {
+    fn default() -> Self {
+        Self {
+            initial_virtual_token_reserves: 1_073_000_000_000_000,
+            initial_virtual_sol_reserves: 30_000_000_000,
+            initial_real_token_reserves: 793_100_000_000_000,
+            fee_bps: 100,
+        }
+    }
+}
+
+// 跨场所套利：同一mint在各池子中的现价扣除两边手续费后的价差超过threshold_bps时输出机会事件
+// 内置的价格只来自PumpSwap池子；Raydium / Meteora的价格由外部解码器通过ArbitrageDetector::price_updated报告
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ArbitrageConfig {
+    pub enabled: bool,
+    pub threshold_bps: u64,
+    // 超过这么多个slot没有更新的价格不参与比较
+    pub max_age_slots: u64,
+}
+
+impl Default for ArbitrageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_bps: 50,
+            max_age_slots: 150,
+        }
+    }
+}
+
+// 流水线队列满时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QueuePolicy {
+    // 等待下游腾出空位，不丢数据
+    #[default]
+    Block,
+    // 丢弃队列中最旧的一条，保留最新数据
+    DropOldest,
+    // 丢弃新到的这一条
+    DropNewest,
+}
+
+impl QueuePolicy {
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueuePolicy::Block => "block",
+            QueuePolicy::DropOldest => "drop-oldest",
+            QueuePolicy::DropNewest => "drop-newest",
+        }
+    }
+}
+
+impl FromStr for QueuePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(QueuePolicy::Block),
+            "drop-oldest" => Ok(QueuePolicy::DropOldest),
+            "drop-newest" => Ok(QueuePolicy::DropNewest),
+            _ => Err(format!("无效的队列策略: {}（可选 block / drop-oldest / drop-newest）", s)),
+        }
+    }
+}
+
+// 单个阶段的输入队列
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QueueConfig {
+    pub capacity: usize,
+    pub policy: QueuePolicy,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_QUEUE_CAPACITY,
+            policy: QueuePolicy::Block,
+        }
+    }
+}
+
+// 接收 → 反序列化 → 解析 → 输出 流水线；每个阶段前有一个有界队列
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PipelineConfig {
+    // 反序列化线程数；多个线程并行反序列化，输出仍保持接收顺序
+    pub decode_threads: usize,
+    pub decode: QueueConfig,
+    pub parse: QueueConfig,
+    pub emit: QueueConfig,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            decode_threads: 1,
+            decode: QueueConfig::default(),
+            parse: QueueConfig::default(),
+            emit: QueueConfig::default(),
+        }
+    }
+}
+
+// 线程与CPU核心绑定
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuntimeConfig {
+    // tokio工作线程数；流水线启用后tokio只负责接收、指标端点和定时任务
+    pub tokio_threads: usize,
+    // 运行接收循环的线程绑定的CPU核心（只使用第一个），为空时不绑定
+    pub receive_cores: Vec<usize>,
+    // tokio工作线程按启动顺序轮流绑定的CPU核心，为空时不绑定
+    pub tokio_cores: Vec<usize>,
+    // 解析线程按序号轮流绑定的CPU核心，为空时不绑定
+    pub parser_cores: Vec<usize>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            tokio_threads: 2,
+            receive_cores: Vec::new(),
+            tokio_cores: Vec::new(),
+            parser_cores: Vec::new(),
+        }
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| invalid(format!("{} 的值无效: {}", key, value)))
+}
+
+// 逗号分隔的列表，空字符串表示空列表
+fn parse_list<T: FromStr>(key: &str, value: &str) -> Result<Vec<T>, Error> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| parse_value(key, item))
+        .collect()
+}
+
+fn optional_path(value: &str) -> Option<PathBuf> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(value))
+    }
+}
+
+impl Config {
+    // 从TOML字符串解析
+    pub fn from_toml_str(content: &str) -> Result<Self, Error> {
+        toml::from_str(content).map_err(|e| Error::new(ErrorKind::InvalidData, format!("配置解析失败: {}", e)))
+    }
+
+    // 从TOML文件解析
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| Error::new(e.kind(), format!("无法读取配置文件 {}: {}", path.display(), e)))?;
+        Self::from_toml_str(&content).map_err(|e| Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+    }
+
+    // 兼容旧格式的env.toml（JITO-SHRED-URL / IDL-DIR）
+    pub fn from_legacy_env<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut config = Self::default();
+        for (key, value) in read_env_file(path.as_ref())? {
+            match key.as_str() {
+                "JITO-SHRED-URL" => config.endpoints.shredstream_url = value,
+                "IDL-DIR" => config.parsers.idl_dir = optional_path(&value),
+                _ => {}
+            }
+        }
+        Ok(config)
+    }
+
+    // 按 默认值 → 配置文件 → 环境变量 → 命令行参数 的顺序加载并校验，返回配置与剩余的位置参数
+    pub fn load(args: &[String]) -> Result<(Self, Vec<String>), Error> {
+        let (config, rest) = Self::load_layers(args)?;
+        config.validate()?;
+        Ok((config, rest))
+    }
+
+    // 同上但不校验，由调用方按用途选择 validate / validate_offline
+    pub fn load_layers(args: &[String]) -> Result<(Self, Vec<String>), Error> {
+        let mut config = match Self::config_path(args)? {
+            Some(path) => Self::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_PATH).exists() => Self::from_file(DEFAULT_CONFIG_PATH)?,
+            None if Path::new(LEGACY_ENV_PATH).exists() => Self::from_legacy_env(LEGACY_ENV_PATH)?,
+            None => Self::default(),
+        };
+        config.apply_env(env::vars())?;
+        let rest = config.apply_args(args)?;
+        Ok((config, rest))
+    }
+
+    // 配置文件路径：--config 优先于 SHREDS_CONFIG
+    fn config_path(args: &[String]) -> Result<Option<PathBuf>, Error> {
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(path) = arg.strip_prefix("--config=") {
+                return Ok(Some(PathBuf::from(path)));
+            }
+            if arg == "--config" {
+                return iter
+                    .next()
+                    .map(|path| Some(PathBuf::from(path)))
+                    .ok_or_else(|| invalid("--config 缺少文件路径".to_string()));
+            }
+        }
+        Ok(env::var(CONFIG_PATH_ENV).ok().map(PathBuf::from))
+    }
+
+    // 配置项对应的环境变量名
+    pub fn env_var_name(key: &str) -> String {
+        format!("{}{}", ENV_PREFIX, key.replace('.', "_").to_uppercase())
+    }
+
+    // 应用环境变量覆盖
+    pub fn apply_env<I>(&mut self, vars: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let vars: Vec<(String, String)> = vars.into_iter().collect();
+        for key in CONFIG_KEYS {
+            let name = Self::env_var_name(key);
+            if let Some((_, value)) = vars.iter().find(|(var, _)| *var == name) {
+                self.set(key, value)
+                    .map_err(|e| invalid(format!("环境变量 {}: {}", name, e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    // 应用命令行覆盖：--set key=value、--key value、--key=value 以及简写参数，返回剩余的位置参数
+    pub fn apply_args(&mut self, args: &[String]) -> Result<Vec<String>, Error> {
+        let mut rest = Vec::new();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                rest.push(arg.clone());
+                continue;
+            };
+
+            let (name, inline) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| iter.next().cloned())
+                    .ok_or_else(|| invalid(format!("--{} 缺少参数值", name)))
+            };
+
+            if name == "config" {
+                value()?;
+            } else if name == "set" {
+                let assignment = value()?;
+                let (key, value) = assignment
+                    .split_once('=')
+                    .ok_or_else(|| invalid(format!("--set 需要 key=value 格式: {}", assignment)))?;
+                self.set(key.trim(), value.trim())?;
+            } else if let Some((_, key)) = CLI_ALIASES.iter().find(|(alias, _)| *alias == name) {
+                self.set(key, &value()?)?;
+            } else if CONFIG_KEYS.contains(&name) {
+                self.set(name, &value()?)?;
+            } else {
+                return Err(invalid(format!("未知的命令行参数: --{}", name)));
+            }
+        }
+
+        Ok(rest)
+    }
+
+    // 按配置项名称设置值
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        match key {
+            "endpoints.shredstream_url" => self.endpoints.shredstream_url = value.to_string(),
+            "endpoints.block_engine_url" => {
+                self.endpoints.block_engine_url = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "auth.keypair_path" => self.auth.keypair_path = optional_path(value),
+            "parsers.pump.enabled" => self.parsers.pump.enabled = parse_value(key, value)?,
+            "parsers.pump.program_id" => self.parsers.pump.program_id = Some(value.to_string()),
+            "parsers.pumpamm.enabled" => self.parsers.pumpamm.enabled = parse_value(key, value)?,
+            "parsers.pumpamm.program_id" => self.parsers.pumpamm.program_id = Some(value.to_string()),
+            "parsers.boop.enabled" => self.parsers.boop.enabled = parse_value(key, value)?,
+            "parsers.boop.program_id" => self.parsers.boop.program_id = Some(value.to_string()),
+            "parsers.idl_dir" => self.parsers.idl_dir = optional_path(value),
+            "parsers.token_effects" => self.parsers.token_effects = parse_value(key, value)?,
+            "parsers.sol_flows" => self.parsers.sol_flows = parse_value(key, value)?,
+            "parsers.tip_accounts" => self.parsers.tip_accounts = parse_list(key, value)?,
+            "filters.accounts" => {
+                self.filters.accounts = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|account| !account.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }
+            "sinks.stdout" => self.sinks.stdout = parse_value(key, value)?,
+            "sinks.file" => self.sinks.file = optional_path(value),
+            "sinks.format" => self.sinks.format = value.parse().map_err(invalid)?,
+            "sinks.locale" => self.sinks.locale = value.parse().map_err(invalid)?,
+            "processing.batch_size" => self.processing.batch_size = parse_value(key, value)?,
+            "processing.threads" => self.processing.threads = Some(parse_value(key, value)?),
+            "logging.level" => self.logging.level = value.to_lowercase(),
+            "logging.format" => self.logging.format = value.parse().map_err(invalid)?,
+            "logging.rate_limit_secs" => self.logging.rate_limit_secs = parse_value(key, value)?,
+            "metrics.listen" => self.metrics.listen = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "metrics.slot_clock" => self.metrics.slot_clock = parse_value(key, value)?,
+            "metrics.slot_duration_ms" => self.metrics.slot_duration_ms = parse_value(key, value)?,
+            "discovery.enabled" => self.discovery.enabled = parse_value(key, value)?,
+            "discovery.report_interval_secs" => self.discovery.report_interval_secs = parse_value(key, value)?,
+            "discovery.report_file" => self.discovery.report_file = optional_path(value),
+            "discovery.candidate_names" => {
+                self.discovery.candidate_names = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            }
+            "pipeline.decode_threads" => self.pipeline.decode_threads = parse_value(key, value)?,
+            "pipeline.decode.capacity" => self.pipeline.decode.capacity = parse_value(key, value)?,
+            "pipeline.decode.policy" => self.pipeline.decode.policy = value.parse().map_err(invalid)?,
+            "pipeline.parse.capacity" => self.pipeline.parse.capacity = parse_value(key, value)?,
+            "pipeline.parse.policy" => self.pipeline.parse.policy = value.parse().map_err(invalid)?,
+            "pipeline.emit.capacity" => self.pipeline.emit.capacity = parse_value(key, value)?,
+            "pipeline.emit.policy" => self.pipeline.emit.policy = value.parse().map_err(invalid)?,
+            "runtime.tokio_threads" => self.runtime.tokio_threads = parse_value(key, value)?,
+            "runtime.receive_cores" => self.runtime.receive_cores = parse_list(key, value)?,
+            "runtime.tokio_cores" => self.runtime.tokio_cores = parse_list(key, value)?,
+            "runtime.parser_cores" => self.runtime.parser_cores = parse_list(key, value)?,
+            "attribution.enabled" => self.attribution.enabled = parse_value(key, value)?,
+            "attribution.rules_file" => self.attribution.rules_file = optional_path(value),
+            "storage.enabled" => self.storage.enabled = parse_value(key, value)?,
+            "storage.path" => self.storage.path = PathBuf::from(value),
+            "storage.queue.capacity" => self.storage.queue.capacity = parse_value(key, value)?,
+            "storage.queue.policy" => self.storage.queue.policy = value.parse().map_err(invalid)?,
+            "export.enabled" => self.export.enabled = parse_value(key, value)?,
+            "export.dir" => self.export.dir = PathBuf::from(value),
+            "export.rows_per_file" => self.export.rows_per_file = parse_value(key, value)?,
+            "export.flush_interval_secs" => self.export.flush_interval_secs = parse_value(key, value)?,
+            "export.origin_slot" => self.export.origin_slot = Some(parse_value(key, value)?),
+            "export.origin_unix_ms" => self.export.origin_unix_ms = Some(parse_value(key, value)?),
+            "export.slot_duration_ms" => self.export.slot_duration_ms = parse_value(key, value)?,
+            "export.queue.capacity" => self.export.queue.capacity = parse_value(key, value)?,
+            "export.queue.policy" => self.export.queue.policy = value.parse().map_err(invalid)?,
+            "candles.enabled" => self.candles.enabled = parse_value(key, value)?,
+            "candles.intervals" => self.candles.intervals = parse_list(key, value)?,
+            "candles.history" => self.candles.history = parse_value(key, value)?,
+            "candles.grace_slots" => self.candles.grace_slots = parse_value(key, value)?,
+            "candles.slot_duration_ms" => self.candles.slot_duration_ms = parse_value(key, value)?,
+            "pools.enabled" => self.pools.enabled = parse_value(key, value)?,
+            "pools.snapshot_file" => self.pools.snapshot_file = optional_path(value),
+            "pools.snapshot_interval_secs" => self.pools.snapshot_interval_secs = parse_value(key, value)?,
+            "pools.lp_fee_bps" => self.pools.lp_fee_bps = parse_value(key, value)?,
+            "pools.protocol_fee_bps" => self.pools.protocol_fee_bps = parse_value(key, value)?,
+            "pools.admin" => self.pools.admin = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "pools.migration_base_reserve" => self.pools.migration_base_reserve = parse_value(key, value)?,
+            "pools.migration_quote_reserve" => self.pools.migration_quote_reserve = parse_value(key, value)?,
+            "migrations.enabled" => self.migrations.enabled = parse_value(key, value)?,
+            "migrations.pending_slots" => self.migrations.pending_slots = parse_value(key, value)?,
+            "migrations.idle_curve_slots" => self.migrations.idle_curve_slots = parse_value(key, value)?,
+            "curves.enabled" => self.curves.enabled = parse_value(key, value)?,
+            "curves.pump_authority" => self.curves.pump_authority = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "curves.idle_curve_slots" => self.curves.idle_curve_slots = parse_value(key, value)?,
+            "curves.pump.initial_virtual_token_reserves" => {
+                self.curves.pump.initial_virtual_token_reserves = parse_value(key, value)?
+            }
+            "curves.pump.initial_virtual_sol_reserves" => {
+                self.curves.pump.initial_virtual_sol_reserves = parse_value(key, value)?
+            }
+            "curves.pump.initial_real_token_reserves" => {
+                self.curves.pump.initial_real_token_reserves = parse_value(key, value)?
+            }
+            "curves.pump.fee_bps" => self.curves.pump.fee_bps = parse_value(key, value)?,
+            "curves.boop.initial_virtual_token_reserves" => {
+                self.curves.boop.initial_virtual_token_reserves = parse_value(key, value)?
+            }
+            "curves.boop.initial_virtual_sol_reserves" => {
+                self.curves.boop.initial_virtual_sol_reserves = parse_value(key, value)?
+            }
+            "curves.boop.initial_real_token_reserves" => {
+                self.curves.boop.initial_real_token_reserves = parse_value(key, value)?
+            }
+            "curves.boop.fee_bps" => self.curves.boop.fee_bps = parse_value(key, value)?,
+            "arbitrage.enabled" => self.arbitrage.enabled = parse_value(key, value)?,
+            "arbitrage.threshold_bps" => self.arbitrage.threshold_bps = parse_value(key, value)?,
+            "arbitrage.max_age_slots" => self.arbitrage.max_age_slots = parse_value(key, value)?,
+            _ => return Err(invalid(format!("未知的配置项: {}", key))),
+        }
+        Ok(())
+    }
+
+    // 校验配置，一次性报告所有问题
+    pub fn validate(&self) -> Result<(), Error> {
+        Self::report(self.problems(true))
+    }
+
+    // 离线命令（回放、解码）不需要连接端点，跳过端点检查
+    pub fn validate_offline(&self) -> Result<(), Error> {
+        Self::report(self.problems(false))
+    }
+
+    // 连接block engine的命令（tip-accounts）不需要shredstream端点，但必须设置block engine地址
+    pub fn validate_block_engine(&self) -> Result<(), Error> {
+        let mut problems = self.problems(false);
+        match &self.endpoints.block_engine_url {
+            None => problems.push(format!(
+                "endpoints.block_engine_url 未设置（配置文件、{} 或 --block-engine-url）",
+                Self::env_var_name("endpoints.block_engine_url")
+            )),
+            Some(url) if !url.starts_with("http://") && !url.starts_with("https://") => {
+                problems.push(format!("endpoints.block_engine_url 必须以 http:// 或 https:// 开头: {}", url))
+            }
+            Some(_) => {}
+        }
+        Self::report(problems)
+    }
+
+    fn report(problems: Vec<String>) -> Result<(), Error> {
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid(format!("配置无效:\n  - {}", problems.join("\n  - "))))
+        }
+    }
+
+    fn problems(&self, online: bool) -> Vec<String> {
+        let mut problems = Vec::new();
+        if online {
+            self.endpoint_problems(&mut problems);
+        }
+
+        if let Some(path) = &self.auth.keypair_path {
+            if !path.is_file() {
+                problems.push(format!("auth.keypair_path 文件不存在: {}", path.display()));
+            }
+        }
+
+        for (name, parser, default) in [
+            ("pump", &self.parsers.pump, PUMP_PROGRAM_ID),
+            ("pumpamm", &self.parsers.pumpamm, PUMPAMM_PROGRAM_ID),
+            ("boop", &self.parsers.boop, BOOP_PROGRAM_ID),
+        ] {
+            if let Err(e) = parser.resolve(default) {
+                problems.push(format!("parsers.{}.program_id {}", name, e));
+            }
+        }
+        if let Some(dir) = &self.parsers.idl_dir {
+            if !dir.is_dir() {
+                problems.push(format!("parsers.idl_dir 目录不存在: {}", dir.display()));
+            }
+        }
+        if !self.parsers.pump.enabled
+            && !self.parsers.pumpamm.enabled
+            && !self.parsers.boop.enabled
+            && self.parsers.idl_dir.is_none()
+        {
+            problems.push("未启用任何解析器（parsers.*.enabled 或 parsers.idl_dir）".to_string());
+        }
+
+        for account in &self.parsers.tip_accounts {
+            if Pubkey::from_str(account).is_err() {
+                problems.push(format!("parsers.tip_accounts 包含无效地址: {}", account));
+            }
+        }
+
+        for account in &self.filters.accounts {
+            if Pubkey::from_str(account).is_err() {
+                problems.push(format!("filters.accounts 包含无效地址: {}", account));
+            }
+        }
+
+        if !self.sinks.stdout && self.sinks.file.is_none() {
+            problems.push("未启用任何输出（sinks.stdout 或 sinks.file）".to_string());
+        }
+
+        if self.processing.batch_size == 0 {
+            problems.push("processing.batch_size 必须大于0".to_string());
+        }
+        if self.processing.threads == Some(0) {
+            problems.push("processing.threads 必须大于0".to_string());
+        }
+
+        if let Err(e) = self.logging.level_filters() {
+            problems.push(format!("logging.level 无效: {}", e));
+        }
+
+        if let Some(listen) = &self.metrics.listen {
+            if listen.parse::<SocketAddr>().is_err() {
+                problems.push(format!("metrics.listen 不是有效的监听地址（例如 127.0.0.1:9100）: {}", listen));
+            }
+        }
+        if self.metrics.slot_duration_ms == 0 {
+            problems.push("metrics.slot_duration_ms 必须大于0".to_string());
+        }
+
+        if self.discovery.enabled && self.discovery.report_interval_secs == 0 {
+            problems.push("discovery.report_interval_secs 必须大于0".to_string());
+        }
+
+        if self.runtime.tokio_threads == 0 {
+            problems.push("runtime.tokio_threads 必须大于0".to_string());
+        }
+        let cores = num_cpus::get();
+        for (name, list) in [
+            ("receive_cores", &self.runtime.receive_cores),
+            ("tokio_cores", &self.runtime.tokio_cores),
+            ("parser_cores", &self.runtime.parser_cores),
+        ] {
+            if let Some(core) = list.iter().find(|core| **core >= cores) {
+                problems.push(format!("runtime.{} 中的核心 {} 超出范围（本机共 {} 个核心）", name, core, cores));
+            }
+        }
+
+        if self.pipeline.decode_threads == 0 {
+            problems.push("pipeline.decode_threads 必须大于0".to_string());
+        }
+        for (stage, queue) in [
+            ("decode", &self.pipeline.decode),
+            ("parse", &self.pipeline.parse),
+            ("emit", &self.pipeline.emit),
+        ] {
+            if queue.capacity == 0 {
+                problems.push(format!("pipeline.{}.capacity 必须大于0", stage));
+            }
+        }
+
+        match &self.attribution.rules_file {
+            Some(path) if !path.is_file() => {
+                problems.push(format!("attribution.rules_file 文件不存在: {}", path.display()));
+            }
+            None if self.attribution.enabled => {
+                problems.push("attribution.enabled 需要设置 attribution.rules_file".to_string());
+            }
+            _ => {}
+        }
+
+        if self.storage.enabled {
+            if !cfg!(feature = "sqlite") {
+                problems.push("storage.enabled 需要以 --features sqlite 编译".to_string());
+            }
+            if self.storage.path.as_os_str().is_empty() {
+                problems.push("storage.path 未设置".to_string());
+            }
+            if self.storage.queue.capacity == 0 {
+                problems.push("storage.queue.capacity 必须大于0".to_string());
+            }
+        }
+
+        if self.export.enabled {
+            if self.export.dir.as_os_str().is_empty() {
+                problems.push("export.dir 未设置".to_string());
+            } else if self.export.dir.is_file() {
+                problems.push(format!("export.dir 不是目录: {}", self.export.dir.display()));
+            }
+            if self.export.rows_per_file == 0 {
+                problems.push("export.rows_per_file 必须大于0".to_string());
+            }
+            if self.export.flush_interval_secs == 0 {
+                problems.push("export.flush_interval_secs 必须大于0".to_string());
+            }
+            if self.export.origin_slot.is_some() != self.export.origin_unix_ms.is_some() {
+                problems.push("export.origin_slot 与 export.origin_unix_ms 必须同时设置".to_string());
+            }
+            if self.export.slot_duration_ms == 0 {
+                problems.push("export.slot_duration_ms 必须大于0".to_string());
+            }
+            if self.export.queue.capacity == 0 {
+                problems.push("export.queue.capacity 必须大于0".to_string());
+            }
+        }
+
+        if self.candles.enabled {
+            if self.candles.intervals.is_empty() {
+                problems.push("candles.intervals 不能为空".to_string());
+            }
+            if self.candles.history == 0 {
+                problems.push("candles.history 必须大于0".to_string());
+            }
+            if self.candles.slot_duration_ms == 0 {
+                problems.push("candles.slot_duration_ms 必须大于0".to_string());
+            }
+            // 价格来自曲线与池子模拟的成交
+            if !self.curves.enabled && !self.pools.enabled {
+                problems.push("candles.enabled 需要启用 curves.enabled 或 pools.enabled".to_string());
+            }
+        }
+
+        if self.pools.enabled {
+            if let Some(path) = self.pools.snapshot_file.as_ref().filter(|path| path.is_dir()) {
+                problems.push(format!("pools.snapshot_file 是目录: {}", path.display()));
+            }
+            if self.pools.snapshot_interval_secs == 0 {
+                problems.push("pools.snapshot_interval_secs 必须大于0".to_string());
+            }
+            if self.pools.lp_fee_bps.saturating_add(self.pools.protocol_fee_bps) >= 10_000 {
+                problems.push("pools.lp_fee_bps 与 pools.protocol_fee_bps 之和必须小于10000".to_string());
+            }
+            if let Some(admin) = self.pools.admin.as_ref().filter(|admin| Pubkey::from_str(admin).is_err()) {
+                problems.push(format!("pools.admin 不是有效地址: {}", admin));
+            }
+            if self.pools.migration_base_reserve == 0 || self.pools.migration_quote_reserve == 0 {
+                problems.push("pools.migration_base_reserve 与 pools.migration_quote_reserve 必须大于0".to_string());
+            }
+        }
+
+        if self.migrations.enabled {
+            if self.migrations.pending_slots == 0 {
+                problems.push("migrations.pending_slots 必须大于0".to_string());
+            }
+            if self.migrations.idle_curve_slots == 0 {
+                problems.push("migrations.idle_curve_slots 必须大于0".to_string());
+            }
+        }
+
+        if self.curves.enabled {
+            for (name, params) in [("pump", &self.curves.pump), ("boop", &self.curves.boop)] {
+                if params.initial_virtual_sol_reserves == 0 {
+                    problems.push(format!("curves.{}.initial_virtual_sol_reserves 必须大于0", name));
+                }
+                if params.initial_real_token_reserves == 0
+                    || params.initial_real_token_reserves >= params.initial_virtual_token_reserves
+                {
+                    problems.push(format!(
+                        "curves.{}.initial_real_token_reserves 必须大于0且小于 initial_virtual_token_reserves",
+                        name
+                    ));
+                }
+                if params.fee_bps >= 10_000 {
+                    problems.push(format!("curves.{}.fee_bps 必须小于10000", name));
+                }
+            }
+            if let Some(authority) = self.curves.pump_authority.as_ref().filter(|key| Pubkey::from_str(key).is_err()) {
+                problems.push(format!("curves.pump_authority 不是有效地址: {}", authority));
+            }
+            if self.curves.idle_curve_slots == 0 {
+                problems.push("curves.idle_curve_slots 必须大于0".to_string());
+            }
+        }
+
+        if self.arbitrage.enabled {
+            // PumpSwap的现价来自池子注册表
+            if !self.pools.enabled {
+                problems.push("arbitrage.enabled 需要同时启用 pools.enabled".to_string());
+            }
+            if self.arbitrage.max_age_slots == 0 {
+                problems.push("arbitrage.max_age_slots 必须大于0".to_string());
+            }
+        }
+
+        problems
+    }
+
+    fn endpoint_problems(&self, problems: &mut Vec<String>) {
+        let url = &self.endpoints.shredstream_url;
+        if url.is_empty() {
+            problems.push(format!(
+                "endpoints.shredstream_url 未设置（配置文件、{} 或 --url）",
+                Self::env_var_name("endpoints.shredstream_url")
+            ));
+        } else if !url.starts_with("http://") && !url.starts_with("https://") {
+            problems.push(format!("endpoints.shredstream_url 必须以 http:// 或 https:// 开头: {}", url));
+        }
+    }
+}
@@ -0,0 +1,264 @@
+// 按列缓存的记录批次，布局与Arrow的RecordBatch相同：固定的模式加上每列一个可空的值数组
+use std::io::{Error, ErrorKind};
+
+// 逻辑类型；UInt64与TimestampMillis在缓存中按INT64保存，写出时转换为Arrow的UInt64与Timestamp(ms, UTC)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    Int64,
+    UInt64,
+    TimestampMillis,
+    Double,
+    Boolean,
+    Utf8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    pub kind: ColumnType,
+    pub nullable: bool,
+}
+
+impl Field {
+    #[allow(dead_code)]
+    pub const fn required(name: &'static str, kind: ColumnType) -> Self {
+        Self {
+            name,
+            kind,
+            nullable: false,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub const fn optional(name: &'static str, kind: ColumnType) -> Self {
+        Self {
+            name,
+            kind,
+            nullable: true,
+        }
+    }
+}
+
+// 一行中的一个值
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Null,
+    Int(i64),
+    UInt(u64),
+    Double(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl From<u64> for Cell {
+    fn from(value: u64) -> Self {
+        Cell::UInt(value)
+    }
+}
+
+impl From<usize> for Cell {
+    fn from(value: usize) -> Self {
+        Cell::Int(value as i64)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Cell::Double(value)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(value: bool) -> Self {
+        Cell::Bool(value)
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(value: &str) -> Self {
+        Cell::Text(value.to_string())
+    }
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Cell::Text(value)
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Cell::Null, Into::into)
+    }
+}
+
+// 一列的值，None为空值
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Int64(Vec<Option<i64>>),
+    Double(Vec<Option<f64>>),
+    Boolean(Vec<Option<bool>>),
+    Utf8(Vec<Option<String>>),
+}
+
+impl Column {
+    fn new(kind: ColumnType) -> Self {
+        match kind {
+            ColumnType::Int64 | ColumnType::UInt64 | ColumnType::TimestampMillis => Column::Int64(Vec::new()),
+            ColumnType::Double => Column::Double(Vec::new()),
+            ColumnType::Boolean => Column::Boolean(Vec::new()),
+            ColumnType::Utf8 => Column::Utf8(Vec::new()),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Int64(values) => values.len(),
+            Column::Double(values) => values.len(),
+            Column::Boolean(values) => values.len(),
+            Column::Utf8(values) => values.len(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 每一行是否有值
+    #[allow(dead_code)]
+    pub fn presence(&self) -> Box<dyn Iterator<Item = bool> + '_> {
+        match self {
+            Column::Int64(values) => Box::new(values.iter().map(Option::is_some)),
+            Column::Double(values) => Box::new(values.iter().map(Option::is_some)),
+            Column::Boolean(values) => Box::new(values.iter().map(Option::is_some)),
+            Column::Utf8(values) => Box::new(values.iter().map(Option::is_some)),
+        }
+    }
+
+    fn extend(&mut self, other: Column) {
+        match (self, other) {
+            (Column::Int64(values), Column::Int64(other)) => values.extend(other),
+            (Column::Double(values), Column::Double(other)) => values.extend(other),
+            (Column::Boolean(values), Column::Boolean(other)) => values.extend(other),
+            (Column::Utf8(values), Column::Utf8(other)) => values.extend(other),
+            (column, other) => unreachable!("{:?} 不能合并到 {:?}", other, column),
+        }
+    }
+
+    // 追加一个已校验类型的值；UInt64按位保存为i64，读取方按转换类型还原
+    fn push(&mut self, cell: Cell) {
+        match (self, cell) {
+            (Column::Int64(values), Cell::Int(value)) => values.push(Some(value)),
+            (Column::Int64(values), Cell::UInt(value)) => values.push(Some(value as i64)),
+            (Column::Int64(values), Cell::Null) => values.push(None),
+            (Column::Double(values), Cell::Double(value)) => values.push(Some(value)),
+            (Column::Double(values), Cell::Null) => values.push(None),
+            (Column::Boolean(values), Cell::Bool(value)) => values.push(Some(value)),
+            (Column::Boolean(values), Cell::Null) => values.push(None),
+            (Column::Utf8(values), Cell::Text(value)) => values.push(Some(value)),
+            (Column::Utf8(values), Cell::Null) => values.push(None),
+            (column, cell) => unreachable!("{:?} 不能写入 {:?}", cell, column),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordBatch {
+    schema: &'static [Field],
+    columns: Vec<Column>,
+    rows: usize,
+}
+
+impl RecordBatch {
+    #[allow(dead_code)]
+    pub fn new(schema: &'static [Field]) -> Self {
+        Self {
+            schema,
+            columns: schema.iter().map(|field| Column::new(field.kind)).collect(),
+            rows: 0,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn schema(&self) -> &'static [Field] {
+        self.schema
+    }
+
+    #[allow(dead_code)]
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+
+    // 追加一行；值的个数、类型或非空约束不符时整行不写入
+    #[allow(dead_code)]
+    pub fn push_row(&mut self, row: Vec<Cell>) -> Result<(), Error> {
+        if row.len() != self.schema.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("行有 {} 个值，模式有 {} 列", row.len(), self.schema.len()),
+            ));
+        }
+        for (field, cell) in self.schema.iter().zip(&row) {
+            let matches = match (field.kind, cell) {
+                (_, Cell::Null) => field.nullable,
+                (ColumnType::Int64 | ColumnType::TimestampMillis, Cell::Int(_)) => true,
+                (ColumnType::UInt64, Cell::UInt(_)) => true,
+                (ColumnType::Double, Cell::Double(_)) => true,
+                (ColumnType::Boolean, Cell::Bool(_)) => true,
+                (ColumnType::Utf8, Cell::Text(_)) => true,
+                _ => false,
+            };
+            if !matches {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("列 {} 的值无效: {:?}", field.name, cell),
+                ));
+            }
+        }
+        for (column, cell) in self.columns.iter_mut().zip(row) {
+            column.push(cell);
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    // 追加同一模式的另一个批次
+    #[allow(dead_code)]
+    pub fn extend(&mut self, other: RecordBatch) -> Result<(), Error> {
+        if other.schema != self.schema {
+            return Err(Error::new(ErrorKind::InvalidInput, "批次的模式不一致"));
+        }
+        for (column, other) in self.columns.iter_mut().zip(other.columns) {
+            column.extend(other);
+        }
+        self.rows += other.rows;
+        Ok(())
+    }
+
+    // 第一行与最后一行的slot（第一列）
+    #[allow(dead_code)]
+    pub fn slot_range(&self) -> Option<(u64, u64)> {
+        match self.columns.first()? {
+            Column::Int64(values) => Some((values.first()?.unwrap_or(0) as u64, values.last()?.unwrap_or(0) as u64)),
+            _ => None,
+        }
+    }
+
+    // 取出当前的全部行，批次清空后继续使用
+    #[allow(dead_code)]
+    pub fn take(&mut self) -> Self {
+        std::mem::replace(self, Self::new(self.schema))
+    }
+}
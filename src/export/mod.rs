@@ -0,0 +1,10 @@
+// 研究用的列式导出：把买卖、代币发行与流动性事件按列缓存（与Arrow RecordBatch相同的布局），
+// 按小时分区写成Parquet文件，供Polars / DuckDB直接读取
+pub mod columns;
+pub mod parquet;
+pub mod tables;
+pub mod writer;
+
+pub use columns::{Cell, Column, ColumnType, Field, RecordBatch};
+pub use tables::EventTables;
+pub use writer::{EventExporter, PartitionedWriter, SlotClock};
@@ -0,0 +1,107 @@
+// Parquet写入：把按列缓存的批次转换为Arrow的RecordBatch，由parquet的ArrowWriter编码（每个文件一个行组、不压缩），
+// 文件元数据中带上调用方给出的键值
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::sync::Arc;
+
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::file::metadata::KeyValue;
+use ::parquet::file::properties::WriterProperties;
+use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray, TimestampMillisecondArray, UInt64Array};
+use arrow_schema::{DataType, Field as ArrowField, Schema, SchemaRef, TimeUnit};
+
+use crate::export::columns::{Column, ColumnType, Field, RecordBatch};
+
+const CREATED_BY: &str = "shreds-client";
+
+fn data_type(kind: ColumnType) -> DataType {
+    match kind {
+        ColumnType::Int64 => DataType::Int64,
+        ColumnType::UInt64 => DataType::UInt64,
+        ColumnType::TimestampMillis => DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        ColumnType::Double => DataType::Float64,
+        ColumnType::Boolean => DataType::Boolean,
+        ColumnType::Utf8 => DataType::Utf8,
+    }
+}
+
+// 导出模式对应的Arrow模式
+#[allow(dead_code)]
+pub fn arrow_schema(schema: &[Field]) -> SchemaRef {
+    Arc::new(Schema::new(
+        schema
+            .iter()
+            .map(|field| ArrowField::new(field.name, data_type(field.kind), field.nullable))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+// 一列转换为Arrow数组；UInt64在缓存中按位保存为i64，这里还原
+fn array(field: &Field, column: &Column) -> ArrayRef {
+    match (field.kind, column) {
+        (ColumnType::UInt64, Column::Int64(values)) => {
+            Arc::new(values.iter().map(|value| value.map(|value| value as u64)).collect::<UInt64Array>())
+        }
+        (ColumnType::TimestampMillis, Column::Int64(values)) => {
+            Arc::new(values.iter().copied().collect::<TimestampMillisecondArray>().with_timezone("UTC"))
+        }
+        (_, Column::Int64(values)) => Arc::new(values.iter().copied().collect::<Int64Array>()),
+        (_, Column::Double(values)) => Arc::new(values.iter().copied().collect::<Float64Array>()),
+        (_, Column::Boolean(values)) => Arc::new(values.iter().copied().collect::<BooleanArray>()),
+        (_, Column::Utf8(values)) => Arc::new(values.iter().map(Option::as_deref).collect::<StringArray>()),
+    }
+}
+
+// 转换为Arrow的RecordBatch
+#[allow(dead_code)]
+pub fn to_arrow(batch: &RecordBatch) -> Result<arrow_array::RecordBatch, Error> {
+    let columns = batch
+        .schema()
+        .iter()
+        .zip(batch.columns())
+        .map(|(field, column)| array(field, column))
+        .collect();
+    arrow_array::RecordBatch::try_new(arrow_schema(batch.schema()), columns)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))
+}
+
+fn properties(metadata: &[(&str, &str)]) -> WriterProperties {
+    let metadata = metadata
+        .iter()
+        .map(|(key, value)| KeyValue::new(key.to_string(), value.to_string()))
+        .collect();
+    WriterProperties::builder()
+        .set_created_by(CREATED_BY.to_string())
+        .set_key_value_metadata(Some(metadata))
+        .build()
+}
+
+// 把一个批次编码为完整的Parquet文件内容
+#[allow(dead_code)]
+pub fn encode(batch: &RecordBatch, metadata: &[(&str, &str)]) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    write(&mut out, batch, metadata)?;
+    Ok(out)
+}
+
+fn write<W: std::io::Write + Send>(out: W, batch: &RecordBatch, metadata: &[(&str, &str)]) -> Result<W, Error> {
+    let batch = to_arrow(batch)?;
+    let mut writer = ArrowWriter::try_new(out, batch.schema(), Some(properties(metadata))).map_err(Error::other)?;
+    writer.write(&batch).map_err(Error::other)?;
+    writer.into_inner().map_err(Error::other)
+}
+
+// 写入文件：先写临时文件再改名，读取方不会看到写了一半的文件
+#[allow(dead_code)]
+pub fn write_file(path: &Path, batch: &RecordBatch, metadata: &[(&str, &str)]) -> Result<(), Error> {
+    if batch.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidInput, "不写入空批次"));
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temp = path.with_extension("parquet.tmp");
+    write(File::create(&temp)?, batch, metadata)?.sync_all()?;
+    fs::rename(&temp, path)
+}
@@ -0,0 +1,177 @@
+// 导出的三张表：买卖、代币发行与流动性变化。列的名称、顺序与类型是对外约定，
+// 只能在末尾追加可空列，修改时同时提高SCHEMA_VERSION
+use std::io::Error;
+
+use crate::export::columns::ColumnType::{Boolean, Double, Int64, TimestampMillis, UInt64, Utf8};
+use crate::export::columns::{Cell, Field, RecordBatch};
+use crate::models::events;
+use crate::models::TransactionResults;
+
+// 写入每个文件的键值元数据 shreds.schema_version
+pub const SCHEMA_VERSION: &str = "1";
+
+pub const TRADES_SCHEMA: &[Field] = &[
+    Field::required("slot", UInt64),
+    // 本地处理该slot的时间；shreds中没有区块时间
+    Field::required("observed_at", TimestampMillis),
+    Field::required("signature", Utf8),
+    Field::required("position", Int64),
+    Field::required("venue", Utf8),
+    Field::required("side", Utf8),
+    Field::required("mint", Utf8),
+    Field::optional("pool", Utf8),
+    Field::optional("user", Utf8),
+    Field::required("quote_mint", Utf8),
+    Field::required("token_amount", UInt64),
+    Field::required("quote_amount", UInt64),
+    Field::required("exact_quote", Boolean),
+    Field::optional("price", Double),
+    Field::optional("attribution", Utf8),
+];
+
+pub const LAUNCHES_SCHEMA: &[Field] = &[
+    Field::required("slot", UInt64),
+    Field::required("observed_at", TimestampMillis),
+    Field::required("signature", Utf8),
+    Field::required("venue", Utf8),
+    Field::required("mint", Utf8),
+    Field::optional("name", Utf8),
+    Field::optional("symbol", Utf8),
+    Field::optional("uri", Utf8),
+    Field::optional("creator", Utf8),
+    Field::optional("bonding_curve", Utf8),
+];
+
+pub const LIQUIDITY_SCHEMA: &[Field] = &[
+    Field::required("slot", UInt64),
+    Field::required("observed_at", TimestampMillis),
+    Field::required("signature", Utf8),
+    Field::required("position", Int64),
+    Field::required("venue", Utf8),
+    Field::required("kind", Utf8),
+    Field::optional("pool", Utf8),
+    Field::optional("user", Utf8),
+    Field::required("base_mint", Utf8),
+    Field::optional("quote_mint", Utf8),
+    Field::optional("lp_amount", UInt64),
+    Field::optional("base_amount", UInt64),
+    Field::optional("quote_amount", UInt64),
+];
+
+// 表名（也是输出目录名）与模式
+pub const TABLES: &[(&str, &[Field])] = &[
+    ("trades", TRADES_SCHEMA),
+    ("launches", LAUNCHES_SCHEMA),
+    ("liquidity", LIQUIDITY_SCHEMA),
+];
+
+// 三张表各自的缓冲批次，顺序与TABLES一致
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventTables {
+    pub batches: Vec<RecordBatch>,
+}
+
+impl Default for EventTables {
+    fn default() -> Self {
+        Self {
+            batches: TABLES.iter().map(|(_, schema)| RecordBatch::new(schema)).collect(),
+        }
+    }
+}
+
+impl EventTables {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn trades(&self) -> &RecordBatch {
+        &self.batches[0]
+    }
+
+    #[allow(dead_code)]
+    pub fn launches(&self) -> &RecordBatch {
+        &self.batches[1]
+    }
+
+    #[allow(dead_code)]
+    pub fn liquidity(&self) -> &RecordBatch {
+        &self.batches[2]
+    }
+
+    // 追加一个slot的全部事件
+    #[allow(dead_code)]
+    pub fn append(&mut self, results: &TransactionResults, observed_at_ms: i64) -> Result<(), Error> {
+        let observed_at = Cell::Int(observed_at_ms);
+
+        for trade in events::trades(results) {
+            let attribution = results.attributions.get(&trade.signature).cloned();
+            self.batches[0].push_row(vec![
+                trade.slot.into(),
+                observed_at.clone(),
+                trade.signature.as_str().into(),
+                trade.position.into(),
+                trade.venue.parser_name().into(),
+                trade.side.as_str().into(),
+                trade.mint.as_str().into(),
+                trade.pool.clone().into(),
+                trade.user.clone().into(),
+                trade.quote_mint.as_str().into(),
+                trade.token_amount.into(),
+                trade.quote_amount.into(),
+                trade.exact_quote.into(),
+                trade.limit_price().into(),
+                attribution.into(),
+            ])?;
+        }
+
+        for launch in events::launches(results) {
+            self.batches[1].push_row(vec![
+                launch.slot.into(),
+                observed_at.clone(),
+                launch.signature.into(),
+                launch.venue.parser_name().into(),
+                launch.mint.into(),
+                launch.name.into(),
+                launch.symbol.into(),
+                launch.uri.into(),
+                launch.creator.into(),
+                launch.bonding_curve.into(),
+            ])?;
+        }
+
+        for event in events::liquidity(results) {
+            self.batches[2].push_row(vec![
+                event.slot.into(),
+                observed_at.clone(),
+                event.signature.into(),
+                event.position.into(),
+                event.venue.parser_name().into(),
+                event.kind.as_str().into(),
+                event.pool.into(),
+                event.user.into(),
+                event.base_mint.into(),
+                event.quote_mint.into(),
+                event.lp_amount.into(),
+                event.base_amount.into(),
+                event.quote_amount.into(),
+            ])?;
+        }
+        Ok(())
+    }
+
+    // 合并另一个slot的事件
+    #[allow(dead_code)]
+    pub fn extend(&mut self, other: EventTables) -> Result<(), Error> {
+        for (batch, other) in self.batches.iter_mut().zip(other.batches) {
+            batch.extend(other)?;
+        }
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn rows(&self) -> usize {
+        self.batches.iter().map(RecordBatch::len).sum()
+    }
+}
@@ -0,0 +1,196 @@
+// 按小时分区写出Parquet：<dir>/<表>/date=YYYY-MM-DD/hour=HH/part-<首slot>-<末slot>.parquet（UTC），
+// Polars / DuckDB可以按hive分区直接读取整个目录。缓冲的行数达到rows_per_file、距第一行超过flush_interval
+// 或跨过整点时写出一个文件；后台线程前有一个有界队列（阶段名 export）
+use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::ExportConfig;
+use crate::export::parquet;
+use crate::export::tables::{EventTables, SCHEMA_VERSION, TABLES};
+use crate::models::TransactionResults;
+use crate::services::pipeline::{BoundedQueue, Pushed};
+
+const HOUR_MS: i64 = 3_600_000;
+
+// 当前的Unix时间（毫秒）
+#[allow(dead_code)]
+pub fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+// Unix时间所在小时的分区目录 date=YYYY-MM-DD/hour=HH
+#[allow(dead_code)]
+pub fn partition(observed_at_ms: i64) -> String {
+    let hours = observed_at_ms.div_euclid(HOUR_MS);
+    let days = hours.div_euclid(24);
+    // 公历日期换算（Howard Hinnant的civil_from_days）
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("date={:04}-{:02}-{:02}/hour={:02}", year, month, day, hours.rem_euclid(24))
+}
+
+// 回放时按slot推算的observed_at：基准slot开始于基准时刻，之后每个slot slot_duration_ms
+pub struct SlotClock {
+    origin: Option<(u64, i64)>,
+    slot_duration_ms: i64,
+}
+
+impl SlotClock {
+    #[allow(dead_code)]
+    pub fn from_config(config: &ExportConfig) -> Self {
+        Self {
+            origin: config.origin_slot.zip(config.origin_unix_ms),
+            slot_duration_ms: config.slot_duration_ms.max(1) as i64,
+        }
+    }
+
+    // slot的开始时间（Unix毫秒）；没有配置基准时以第一次调用的slot与当时的本地时间为基准
+    #[allow(dead_code)]
+    pub fn time(&mut self, slot: u64) -> i64 {
+        let (origin_slot, origin_ms) = *self.origin.get_or_insert_with(|| (slot, now_ms()));
+        origin_ms + (slot as i64 - origin_slot as i64) * self.slot_duration_ms
+    }
+}
+
+// 同步的分区写入，回放与后台线程共用
+pub struct PartitionedWriter {
+    dir: PathBuf,
+    rows_per_file: usize,
+    flush_interval_ms: i64,
+    tables: EventTables,
+    // 缓冲中第一行的时间
+    first_observed_ms: Option<i64>,
+}
+
+impl PartitionedWriter {
+    #[allow(dead_code)]
+    pub fn new(config: &ExportConfig) -> Self {
+        Self {
+            dir: config.dir.clone(),
+            rows_per_file: config.rows_per_file.max(1),
+            flush_interval_ms: config.flush_interval_secs.saturating_mul(1_000) as i64,
+            tables: EventTables::new(),
+            first_observed_ms: None,
+        }
+    }
+
+    // 追加一个slot的事件，返回因此写出的文件
+    #[allow(dead_code)]
+    pub fn write_slot(&mut self, slot: EventTables, observed_at_ms: i64) -> Result<Vec<PathBuf>, Error> {
+        let mut written = Vec::new();
+        if let Some(first) = self.first_observed_ms {
+            let new_hour = first.div_euclid(HOUR_MS) != observed_at_ms.div_euclid(HOUR_MS);
+            if new_hour || observed_at_ms - first >= self.flush_interval_ms {
+                written.extend(self.flush()?);
+            }
+        }
+        if slot.rows() == 0 {
+            return Ok(written);
+        }
+        self.first_observed_ms.get_or_insert(observed_at_ms);
+        self.tables.extend(slot)?;
+
+        if self.tables.batches.iter().any(|batch| batch.len() >= self.rows_per_file) {
+            written.extend(self.flush()?);
+        }
+        Ok(written)
+    }
+
+    #[allow(dead_code)]
+    pub fn append(&mut self, results: &TransactionResults, observed_at_ms: i64) -> Result<Vec<PathBuf>, Error> {
+        let mut slot = EventTables::new();
+        slot.append(results, observed_at_ms)?;
+        self.write_slot(slot, observed_at_ms)
+    }
+
+    // 写出全部缓冲的行，每张非空的表一个文件
+    #[allow(dead_code)]
+    pub fn flush(&mut self) -> Result<Vec<PathBuf>, Error> {
+        let Some(observed_at_ms) = self.first_observed_ms.take() else {
+            return Ok(Vec::new());
+        };
+        let partition = partition(observed_at_ms);
+        let mut written = Vec::new();
+        for ((table, _), batch) in TABLES.iter().zip(self.tables.batches.iter_mut()) {
+            let batch = batch.take();
+            let Some((first, last)) = batch.slot_range() else {
+                continue;
+            };
+            let path = self
+                .dir
+                .join(table)
+                .join(&partition)
+                .join(format!("part-{}-{}.parquet", first, last));
+            parquet::write_file(&path, &batch, &[("shreds.schema_version", SCHEMA_VERSION)])
+                .map_err(|e| Error::new(e.kind(), format!("无法写入 {}: {}", path.display(), e)))?;
+            log::debug!("导出 {} 行到 {}", batch.len(), path.display());
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    #[allow(dead_code)]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+// 后台导出线程
+pub struct EventExporter {
+    queue: Arc<BoundedQueue<(EventTables, i64)>>,
+    worker: JoinHandle<()>,
+}
+
+impl EventExporter {
+    #[allow(dead_code)]
+    pub fn start(config: &ExportConfig) -> Result<Self, Error> {
+        std::fs::create_dir_all(&config.dir)
+            .map_err(|e| Error::new(e.kind(), format!("无法创建导出目录 {}: {}", config.dir.display(), e)))?;
+        let queue = Arc::new(BoundedQueue::new("export", &config.queue));
+        let mut writer = PartitionedWriter::new(config);
+        let input = queue.clone();
+        let worker = thread::Builder::new().name("shreds-export".to_string()).spawn(move || {
+            while let Some((slot, observed_at_ms)) = input.pop() {
+                if let Err(e) = writer.write_slot(slot, observed_at_ms) {
+                    log::error!("导出失败: {e}");
+                }
+            }
+            if let Err(e) = writer.flush() {
+                log::error!("导出失败: {e}");
+            }
+        })?;
+        log::info!("事件导出到: {}", config.dir.display());
+        Ok(Self { queue, worker })
+    }
+
+    // 提交一个slot的结果与它的时间（实时为本地处理的时间，回放由SlotClock按slot推算），没有事件的slot只用于推进时间
+    #[allow(dead_code)]
+    pub fn submit(&self, results: &TransactionResults, observed_at_ms: i64) -> Pushed {
+        let mut slot = EventTables::new();
+        if let Err(e) = slot.append(results, observed_at_ms) {
+            log::error!("slot {} 的事件无法导出: {e}", results.current_slot);
+            return Pushed::DroppedNewest;
+        }
+        self.queue.push((slot, observed_at_ms))
+    }
+
+    // 关闭队列，写出剩余的缓冲行
+    #[allow(dead_code)]
+    pub fn shutdown(self) {
+        self.queue.close();
+        let _ = self.worker.join();
+    }
+}
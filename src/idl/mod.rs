@@ -0,0 +1,97 @@
+// IDL驱动的指令解码：build.rs根据 idl/*.json 生成各程序的指令表，此处提供通用的解码逻辑
+pub mod types;
+pub mod runtime;
+
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+pub use types::{decode_fields, decode_value, IdlType};
+pub use runtime::IdlRegistry;
+
+// 由build.rs生成的程序模块（pump / pump_amm / boop ...）与PROGRAMS列表
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/idl_programs.rs"));
+}
+pub use generated::*;
+
+// 编译期生成的单条指令定义
+#[derive(Debug)]
+pub struct IdlInstructionDef {
+    pub name: &'static str,
+    pub discriminator: [u8; 8],
+    pub accounts: &'static [&'static str],
+    pub args: &'static [(&'static str, &'static str)],
+}
+
+impl IdlInstructionDef {
+    // 按参数布局解码指令数据（跳过8字节鉴别器）
+    #[allow(dead_code)]
+    pub fn decode_args(&self, data: &[u8]) -> Option<Value> {
+        let mut rest = data.get(8..)?;
+        let fields = self
+            .args
+            .iter()
+            .map(|(name, ty)| ty.parse::<IdlType>().map(|ty| (*name, ty)))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        Some(decode_fields(fields, &mut rest, &|_, _| None))
+    }
+
+    // 账户索引对应的名称
+    #[allow(dead_code)]
+    pub fn account_name(&self, index: usize) -> Option<&'static str> {
+        self.accounts.get(index).copied()
+    }
+}
+
+// 编译期生成的程序定义
+#[derive(Debug)]
+pub struct IdlProgramDef {
+    pub name: &'static str,
+    pub address: &'static str,
+    pub instructions: &'static [IdlInstructionDef],
+}
+
+impl IdlProgramDef {
+    // 根据指令数据查找指令定义
+    #[allow(dead_code)]
+    pub fn find_instruction(&self, data: &[u8]) -> Option<&'static IdlInstructionDef> {
+        self.instructions
+            .iter()
+            .find(|ix| data.starts_with(&ix.discriminator))
+    }
+}
+
+// 通用的指令解码结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedInstruction {
+    pub program: String,
+    pub name: String,
+    pub args: Value,
+    pub accounts: Vec<(String, Pubkey)>,
+}
+
+// 使用编译期生成的IDL表解码任意已知程序的指令
+#[allow(dead_code)]
+pub fn decode_instruction(program_id: &Pubkey, data: &[u8], accounts: &[Pubkey]) -> Option<DecodedInstruction> {
+    let program_id = program_id.to_string();
+    let program = PROGRAMS.iter().find(|p| p.address == program_id)?;
+    let instruction = program.find_instruction(data)?;
+
+    Some(DecodedInstruction {
+        program: program.name.to_string(),
+        name: instruction.name.to_string(),
+        args: instruction.decode_args(data)?,
+        accounts: accounts
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                let name = instruction
+                    .account_name(i)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("remaining_{}", i));
+                (name, *key)
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,329 @@
+// 运行时加载的IDL注册表：从目录读取任意Anchor IDL，无需重新编译即可解码对应程序的指令
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::idl::types::{decode_fields, decode_value, IdlType};
+use crate::idl::DecodedInstruction;
+
+// 运行时指令定义
+#[derive(Debug, Clone)]
+pub struct RuntimeInstruction {
+    pub name: String,
+    pub discriminator: Vec<u8>,
+    pub accounts: Vec<String>,
+    pub args: Vec<(String, IdlType)>,
+}
+
+// 自定义类型定义
+#[derive(Debug, Clone)]
+pub enum RuntimeTypeDef {
+    Struct(Vec<(String, IdlType)>),
+    Enum(Vec<(String, Vec<(String, IdlType)>)>),
+}
+
+// 单个程序的运行时IDL
+#[derive(Debug, Clone)]
+pub struct RuntimeProgram {
+    pub name: String,
+    pub address: Pubkey,
+    pub instructions: Vec<RuntimeInstruction>,
+    pub types: HashMap<String, RuntimeTypeDef>,
+}
+
+impl RuntimeProgram {
+    // 从IDL JSON解析，同时兼容新版(0.30+)与旧版Anchor IDL格式
+    pub fn from_json(idl: &Value) -> Result<Self, Error> {
+        let address = idl
+            .get("address")
+            .or_else(|| idl.get("metadata").and_then(|m| m.get("address")))
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "IDL缺少程序地址"))?;
+        let address = Pubkey::from_str(address)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("无效的程序地址 {}: {}", address, e)))?;
+
+        let name = idl
+            .get("metadata")
+            .and_then(|m| m.get("name"))
+            .or_else(|| idl.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string();
+
+        let instructions = idl
+            .get("instructions")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("{} 缺少instructions", name)))?
+            .iter()
+            .map(|ix| Self::parse_instruction(&name, ix))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut types = HashMap::new();
+        for ty in idl.get("types").and_then(Value::as_array).into_iter().flatten() {
+            if let (Some(type_name), Some(def)) = (ty.get("name").and_then(Value::as_str), ty.get("type")) {
+                if let Some(def) = Self::parse_type_def(def) {
+                    types.insert(type_name.to_string(), def);
+                }
+            }
+        }
+
+        Ok(Self { name, address, instructions, types })
+    }
+
+    fn parse_instruction(program: &str, ix: &Value) -> Result<RuntimeInstruction, Error> {
+        let name = ix
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("{} 存在无名指令", program)))?
+            .to_string();
+
+        // IDL中给出鉴别器时直接使用，否则按Anchor规则推导
+        let discriminator = match ix.get("discriminator") {
+            Some(value) => parse_discriminator(value)
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("{}.{} 的鉴别器必须是8个0-255的整数", program, name)))?,
+            None => anchor_discriminator(&to_snake_case(&name)).to_vec(),
+        };
+
+        let mut accounts = Vec::new();
+        if let Some(list) = ix.get("accounts").and_then(Value::as_array) {
+            flatten_accounts(list, &mut accounts);
+        }
+
+        let args = ix
+            .get("args")
+            .and_then(Value::as_array)
+            .map(|list| parse_fields(list))
+            .transpose()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}.{}: {}", program, name, e)))?
+            .unwrap_or_default();
+
+        Ok(RuntimeInstruction { name, discriminator, accounts, args })
+    }
+
+    fn parse_type_def(def: &Value) -> Option<RuntimeTypeDef> {
+        match def.get("kind")?.as_str()? {
+            "struct" => {
+                let fields = def.get("fields").and_then(Value::as_array).cloned().unwrap_or_default();
+                Some(RuntimeTypeDef::Struct(parse_fields(&fields).ok()?))
+            }
+            "enum" => {
+                let variants = def
+                    .get("variants")?
+                    .as_array()?
+                    .iter()
+                    .map(|variant| {
+                        let name = variant.get("name")?.as_str()?.to_string();
+                        let fields = variant.get("fields").and_then(Value::as_array).cloned().unwrap_or_default();
+                        Some((name, parse_fields(&fields).ok()?))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(RuntimeTypeDef::Enum(variants))
+            }
+            _ => None,
+        }
+    }
+
+    // 根据指令数据查找指令定义
+    pub fn find_instruction(&self, data: &[u8]) -> Option<&RuntimeInstruction> {
+        self.instructions
+            .iter()
+            .find(|ix| !ix.discriminator.is_empty() && data.starts_with(&ix.discriminator))
+    }
+
+    // 解码自定义类型
+    fn resolve(&self, name: &str, data: &mut &[u8], depth: usize) -> Option<Value> {
+        // 防止递归类型导致栈溢出
+        if depth > 16 {
+            return None;
+        }
+        let resolver = |inner: &str, rest: &mut &[u8]| self.resolve(inner, rest, depth + 1);
+
+        match self.types.get(name)? {
+            RuntimeTypeDef::Struct(fields) => {
+                let mut object = Map::new();
+                for (field, ty) in fields {
+                    object.insert(field.clone(), decode_value(ty, data, &resolver)?);
+                }
+                Some(Value::Object(object))
+            }
+            RuntimeTypeDef::Enum(variants) => {
+                let index = *data.first()? as usize;
+                *data = &data[1..];
+                let (variant, fields) = variants.get(index)?;
+                if fields.is_empty() {
+                    return Some(Value::String(variant.clone()));
+                }
+                let mut object = Map::new();
+                for (field, ty) in fields {
+                    object.insert(field.clone(), decode_value(ty, data, &resolver)?);
+                }
+                let mut wrapper = Map::new();
+                wrapper.insert(variant.clone(), Value::Object(object));
+                Some(Value::Object(wrapper))
+            }
+        }
+    }
+
+    // 解码一条指令
+    pub fn decode(&self, data: &[u8], accounts: &[Pubkey]) -> Option<DecodedInstruction> {
+        let instruction = self.find_instruction(data)?;
+        let mut rest = &data[instruction.discriminator.len()..];
+        let resolver = |name: &str, data: &mut &[u8]| self.resolve(name, data, 0);
+        let args = decode_fields(
+            instruction.args.iter().map(|(name, ty)| (name.as_str(), ty.clone())),
+            &mut rest,
+            &resolver,
+        );
+
+        Some(DecodedInstruction {
+            program: self.name.clone(),
+            name: instruction.name.clone(),
+            args,
+            accounts: accounts
+                .iter()
+                .enumerate()
+                .map(|(i, key)| {
+                    let name = instruction
+                        .accounts
+                        .get(i)
+                        .cloned()
+                        .unwrap_or_else(|| format!("remaining_{}", i));
+                    (name, *key)
+                })
+                .collect(),
+        })
+    }
+}
+
+// 按程序ID索引的运行时IDL集合
+#[derive(Debug, Clone, Default)]
+pub struct IdlRegistry {
+    programs: HashMap<Pubkey, RuntimeProgram>,
+}
+
+impl IdlRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 加载目录下所有 *.json IDL 文件
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Self, Error> {
+        let dir = dir.as_ref();
+        let mut registry = Self::new();
+
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .map_err(|e| Error::new(e.kind(), format!("无法读取IDL目录 {}: {}", dir.display(), e)))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            registry.load_file(&path)?;
+        }
+
+        Ok(registry)
+    }
+
+    // 加载单个IDL文件
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let idl: Value = serde_json::from_str(&content)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("无法解析IDL {}: {}", path.display(), e)))?;
+        self.insert(RuntimeProgram::from_json(&idl)?);
+        Ok(())
+    }
+
+    pub fn insert(&mut self, program: RuntimeProgram) {
+        self.programs.insert(program.address, program);
+    }
+
+    pub fn get(&self, program_id: &Pubkey) -> Option<&RuntimeProgram> {
+        self.programs.get(program_id)
+    }
+
+    pub fn contains(&self, program_id: &Pubkey) -> bool {
+        self.programs.contains_key(program_id)
+    }
+
+    pub fn programs(&self) -> impl Iterator<Item = &RuntimeProgram> {
+        self.programs.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    // 解码指定程序的指令
+    pub fn decode(&self, program_id: &Pubkey, data: &[u8], accounts: &[Pubkey]) -> Option<DecodedInstruction> {
+        self.programs.get(program_id)?.decode(data, accounts)
+    }
+}
+
+// Anchor指令鉴别器：sha256("global:<name>")的前8字节
+pub fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let hash = hashv(&[b"global:".as_slice(), name.as_bytes()]);
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+// 显式给出的鉴别器必须恰好8个字节，与build.rs对生成表的检查一致
+fn parse_discriminator(value: &Value) -> Option<Vec<u8>> {
+    let bytes = value
+        .as_array()?
+        .iter()
+        .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+        .collect::<Option<Vec<u8>>>()?;
+    (bytes.len() == 8).then_some(bytes)
+}
+
+// 旧版IDL使用camelCase指令名，鉴别器按snake_case计算
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// 展开嵌套的账户组
+fn flatten_accounts(list: &[Value], out: &mut Vec<String>) {
+    for account in list {
+        match account.get("accounts").and_then(Value::as_array) {
+            Some(nested) => flatten_accounts(nested, out),
+            None => out.push(account.get("name").and_then(Value::as_str).unwrap_or("unknown").to_string()),
+        }
+    }
+}
+
+fn parse_fields(list: &[Value]) -> Result<Vec<(String, IdlType)>, String> {
+    list.iter()
+        .map(|field| {
+            let name = field.get("name").and_then(Value::as_str).ok_or("字段缺少name")?;
+            let ty = field
+                .get("type")
+                .and_then(IdlType::from_json)
+                .ok_or_else(|| format!("字段 {} 的类型不受支持", name))?;
+            Ok((name.to_string(), ty))
+        })
+        .collect()
+}
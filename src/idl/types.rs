@@ -0,0 +1,220 @@
+// IDL参数类型与Borsh解码
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{Map, Number, Value};
+
+// IDL参数类型
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdlType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    String,
+    Bytes,
+    Pubkey,
+    Option(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined(String),
+}
+
+impl IdlType {
+    // 从IDL JSON中的type字段解析
+    #[allow(dead_code)]
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(s) => s.parse().ok(),
+            Value::Object(map) => {
+                if let Some(inner) = map.get("option") {
+                    Some(IdlType::Option(Box::new(Self::from_json(inner)?)))
+                } else if let Some(inner) = map.get("vec") {
+                    Some(IdlType::Vec(Box::new(Self::from_json(inner)?)))
+                } else if let Some(Value::Array(array)) = map.get("array") {
+                    let inner = Self::from_json(array.first()?)?;
+                    let len = array.get(1)?.as_u64()? as usize;
+                    Some(IdlType::Array(Box::new(inner), len))
+                } else if let Some(defined) = map.get("defined") {
+                    let name = defined.get("name").unwrap_or(defined).as_str()?;
+                    Some(IdlType::Defined(name.to_string()))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+// 规范字符串格式: u64 / pubkey / option<T> / vec<T> / [T;N] / defined:Name
+impl FromStr for IdlType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(inner) = s.strip_prefix("option<").and_then(|r| r.strip_suffix('>')) {
+            return Ok(IdlType::Option(Box::new(inner.parse()?)));
+        }
+        if let Some(inner) = s.strip_prefix("vec<").and_then(|r| r.strip_suffix('>')) {
+            return Ok(IdlType::Vec(Box::new(inner.parse()?)));
+        }
+        if let Some(inner) = s.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+            let (ty, len) = inner.rsplit_once(';').ok_or_else(|| format!("无效的数组类型: {}", s))?;
+            let len = len.trim().parse().map_err(|_| format!("无效的数组长度: {}", s))?;
+            return Ok(IdlType::Array(Box::new(ty.parse()?), len));
+        }
+        if let Some(name) = s.strip_prefix("defined:") {
+            return Ok(IdlType::Defined(name.to_string()));
+        }
+
+        Ok(match s {
+            "bool" => IdlType::Bool,
+            "u8" => IdlType::U8,
+            "u16" => IdlType::U16,
+            "u32" => IdlType::U32,
+            "u64" => IdlType::U64,
+            "u128" => IdlType::U128,
+            "i8" => IdlType::I8,
+            "i16" => IdlType::I16,
+            "i32" => IdlType::I32,
+            "i64" => IdlType::I64,
+            "i128" => IdlType::I128,
+            "f32" => IdlType::F32,
+            "f64" => IdlType::F64,
+            "string" => IdlType::String,
+            "bytes" => IdlType::Bytes,
+            "pubkey" | "publicKey" => IdlType::Pubkey,
+            _ => return Err(format!("不支持的IDL类型: {}", s)),
+        })
+    }
+}
+
+impl fmt::Display for IdlType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdlType::Option(inner) => write!(f, "option<{}>", inner),
+            IdlType::Vec(inner) => write!(f, "vec<{}>", inner),
+            IdlType::Array(inner, len) => write!(f, "[{};{}]", inner, len),
+            IdlType::Defined(name) => write!(f, "defined:{}", name),
+            IdlType::Bool => write!(f, "bool"),
+            IdlType::U8 => write!(f, "u8"),
+            IdlType::U16 => write!(f, "u16"),
+            IdlType::U32 => write!(f, "u32"),
+            IdlType::U64 => write!(f, "u64"),
+            IdlType::U128 => write!(f, "u128"),
+            IdlType::I8 => write!(f, "i8"),
+            IdlType::I16 => write!(f, "i16"),
+            IdlType::I32 => write!(f, "i32"),
+            IdlType::I64 => write!(f, "i64"),
+            IdlType::I128 => write!(f, "i128"),
+            IdlType::F32 => write!(f, "f32"),
+            IdlType::F64 => write!(f, "f64"),
+            IdlType::String => write!(f, "string"),
+            IdlType::Bytes => write!(f, "bytes"),
+            IdlType::Pubkey => write!(f, "pubkey"),
+        }
+    }
+}
+
+// 从数据头部读取固定长度字节
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if data.len() < len {
+        return None;
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Some(head)
+}
+
+fn take_array<const N: usize>(data: &mut &[u8]) -> Option<[u8; N]> {
+    take(data, N).map(|bytes| bytes.try_into().unwrap())
+}
+
+// 解码单个值；自定义类型通过resolver查找，找不到时返回None
+pub fn decode_value(
+    ty: &IdlType,
+    data: &mut &[u8],
+    resolver: &dyn Fn(&str, &mut &[u8]) -> Option<Value>,
+) -> Option<Value> {
+    Some(match ty {
+        IdlType::Bool => Value::Bool(take(data, 1)?[0] != 0),
+        IdlType::U8 => Value::from(take(data, 1)?[0]),
+        IdlType::U16 => Value::from(u16::from_le_bytes(take_array(data)?)),
+        IdlType::U32 => Value::from(u32::from_le_bytes(take_array(data)?)),
+        IdlType::U64 => Value::from(u64::from_le_bytes(take_array(data)?)),
+        IdlType::I8 => Value::from(take(data, 1)?[0] as i8),
+        IdlType::I16 => Value::from(i16::from_le_bytes(take_array(data)?)),
+        IdlType::I32 => Value::from(i32::from_le_bytes(take_array(data)?)),
+        IdlType::I64 => Value::from(i64::from_le_bytes(take_array(data)?)),
+        // 128位整数超出JSON数字精度，以字符串表示
+        IdlType::U128 => Value::String(u128::from_le_bytes(take_array(data)?).to_string()),
+        IdlType::I128 => Value::String(i128::from_le_bytes(take_array(data)?).to_string()),
+        IdlType::F32 => Number::from_f64(f32::from_le_bytes(take_array(data)?) as f64)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        IdlType::F64 => Number::from_f64(f64::from_le_bytes(take_array(data)?))
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        IdlType::String => {
+            let len = u32::from_le_bytes(take_array(data)?) as usize;
+            Value::String(String::from_utf8_lossy(take(data, len)?).into_owned())
+        }
+        IdlType::Bytes => {
+            let len = u32::from_le_bytes(take_array(data)?) as usize;
+            Value::String(hex::encode(take(data, len)?))
+        }
+        IdlType::Pubkey => Value::String(bs58::encode(take(data, 32)?).into_string()),
+        IdlType::Option(inner) => match take(data, 1)?[0] {
+            0 => Value::Null,
+            _ => decode_value(inner, data, resolver)?,
+        },
+        IdlType::Vec(inner) => {
+            let len = u32::from_le_bytes(take_array(data)?) as usize;
+            // 防止畸形数据导致过量分配
+            let mut items = Vec::with_capacity(len.min(data.len()));
+            for _ in 0..len {
+                items.push(decode_value(inner, data, resolver)?);
+            }
+            Value::Array(items)
+        }
+        IdlType::Array(inner, len) => {
+            let mut items = Vec::with_capacity(*len);
+            for _ in 0..*len {
+                items.push(decode_value(inner, data, resolver)?);
+            }
+            Value::Array(items)
+        }
+        IdlType::Defined(name) => resolver(name, data)?,
+    })
+}
+
+// 按字段顺序解码为JSON对象；数据不足时保留已解码的字段并停止
+pub fn decode_fields<'a, I>(
+    fields: I,
+    data: &mut &[u8],
+    resolver: &dyn Fn(&str, &mut &[u8]) -> Option<Value>,
+) -> Value
+where
+    I: IntoIterator<Item = (&'a str, IdlType)>,
+{
+    let mut object = Map::new();
+    for (name, ty) in fields {
+        match decode_value(&ty, data, resolver) {
+            Some(value) => {
+                object.insert(name.to_string(), value);
+            }
+            None => break,
+        }
+    }
+    Value::Object(object)
+}
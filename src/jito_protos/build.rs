@@ -13,6 +13,9 @@ fn main() {
                 "protos/auth.proto",
                 "protos/shared.proto",
                 "protos/shredstream.proto",
+                "protos/packet.proto",
+                "protos/bundle.proto",
+                "protos/searcher.proto",
             ],
             &["protos"],
         )
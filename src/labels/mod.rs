@@ -0,0 +1,256 @@
+// 输出标签目录：每个字段一个规范键（snake_case，账户名与IDL一致），按当前语言渲染为英文或中文。
+// 文本输出全部经由这里取标签，JSON输出直接使用规范键，不随语言变化
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Zh,
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "en" => Ok(Locale::En),
+            "zh" => Ok(Locale::Zh),
+            _ => Err(format!("无效的语言: {}（可选 en / zh）", s)),
+        }
+    }
+}
+
+// (规范键, 英文, 中文)
+pub const CATALOG: &[(&str, &str, &str)] = &[
+    // 通用
+    ("parser", "Parser", "解析器"),
+    ("slot", "Slot", "Slot"),
+    ("signature", "Signature", "签名"),
+    ("latency_us", "Latency_us", "延迟(微秒)"),
+    ("decode", "decode", "解码"),
+    ("parse", "parse", "解析"),
+    ("instructions_count", "Instructions_Count", "指令数"),
+    ("instruction", "Instruction", "指令"),
+    ("type", "Type", "类型"),
+    ("args", "Args", "参数"),
+    ("unknown", "Unknown", "未知"),
+    // 指令参数
+    ("token_amount", "Token_Amount", "代币数量"),
+    ("max_sol_cost", "Max_SOL_Cost", "最大SOL花费"),
+    ("min_sol_output", "Min_SOL_Output", "最小SOL输出"),
+    ("buy_amount", "Buy_Amount", "买入SOL数量"),
+    ("amount_out_min", "Amount_Out_Min", "最小输出数量"),
+    ("name", "Name", "名称"),
+    ("symbol", "Symbol", "符号"),
+    ("uri", "URI", "URI"),
+    ("creator", "Creator", "创建者"),
+    ("salt", "Salt", "盐值"),
+    ("index", "Index", "索引"),
+    ("base_amount_out", "Base_Amount_Out", "基础代币输出数量"),
+    ("base_amount_in", "Base_Amount_In", "基础代币输入数量"),
+    ("quote_amount_in", "Quote_Amount_In", "报价代币输入数量"),
+    ("max_quote_amount_in", "Max_Quote_Amount_In", "最大报价代币输入数量"),
+    ("min_quote_amount_out", "Min_Quote_Amount_Out", "最小报价代币输出数量"),
+    ("max_base_amount_in", "Max_Base_Amount_In", "最大基础代币输入数量"),
+    ("min_base_amount_out", "Min_Base_Amount_Out", "最小基础代币输出数量"),
+    ("lp_token_amount_out", "LP_Token_Amount_Out", "LP代币输出数量"),
+    ("lp_token_amount_in", "LP_Token_Amount_In", "LP代币输入数量"),
+    ("lp_fee_basis_points", "LP_Fee_Basis_Points", "LP手续费基点"),
+    ("protocol_fee_basis_points", "Protocol_Fee_Basis_Points", "协议手续费基点"),
+    // ComputeBudget
+    ("request_heap_frame_size", "Request_Heap_Frame_Size", "请求堆帧大小"),
+    ("set_compute_unit_limit", "Set_Compute_Unit_Limit", "计算单元上限"),
+    ("set_compute_unit_price", "Set_Compute_Unit_Price", "计算单元价格"),
+    ("estimated_fee", "Estimated_Fee", "预估费用"),
+    ("based_on", "Based_On", "基于"),
+    ("instruction_data_format", "Instruction_Data_Format", "指令数据格式"),
+    ("position", "Position", "位置"),
+    ("opcode", "opcode", "指令码"),
+    ("price_param", "price (u64, little-endian)", "价格参数 (u64, 小端序)"),
+    ("units_param", "units/size (u32, little-endian)", "单元/大小参数 (u32, 小端序)"),
+    ("raw_data", "Raw_Data", "原始数据"),
+    ("unit_bytes", "bytes", "字节"),
+    ("unit_micro_lamports_per_cu", "micro-lamports/CU", "微lamports/计算单元"),
+    // 无参数指令的说明
+    ("desc_set_params", "Set global parameters", "设置全局参数"),
+    ("desc_initialize", "Initialize global state, no arguments", "初始化全局状态，无参数"),
+    ("desc_withdraw", "Withdraw liquidity (admin), no arguments", "提取流动性（管理员操作），无参数"),
+    ("desc_migrate", "Migrate liquidity to pump_amm", "迁移流动性到pump_amm"),
+    ("desc_extend_account", "Extend a program-owned account", "扩展程序拥有的账户大小"),
+    ("desc_update_global_authority", "Update the global authority", "更新全局权限"),
+    ("desc_unknown_instruction", "Unknown instruction", "未知指令"),
+    ("desc_unknown_compute_budget", "Unknown ComputeBudget instruction", "未知的ComputeBudget指令"),
+    // 账户
+    ("global", "Global", "全局状态"),
+    ("fee_recipient", "Fee_Recipient", "手续费接收者"),
+    ("mint", "Mint", "代币Mint"),
+    ("bonding_curve", "Bonding_Curve", "绑定曲线"),
+    ("associated_bonding_curve", "Associated_Bonding_Curve", "绑定曲线代币账户"),
+    ("associated_user", "Associated_User", "用户代币账户"),
+    ("user", "User", "用户"),
+    ("system_program", "System_Program", "系统程序"),
+    ("token_program", "Token_Program", "代币程序"),
+    ("token_2022_program", "Token_2022_Program", "Token-2022程序"),
+    ("associated_token_program", "Associated_Token_Program", "关联代币程序"),
+    ("rent", "Rent", "租金"),
+    ("event_authority", "Event_Authority", "事件权限"),
+    ("program", "Program", "程序"),
+    ("mint_authority", "Mint_Authority", "Mint权限"),
+    ("mpl_token_metadata", "Mpl_Token_Metadata", "Metaplex元数据程序"),
+    ("metadata", "Metadata", "元数据"),
+    ("admin", "Admin", "管理员"),
+    ("new_admin", "New_Admin", "新管理员"),
+    ("pool", "Pool", "流动性池"),
+    ("global_config", "Global_Config", "全局配置"),
+    ("base_mint", "Base_Mint", "基础代币Mint"),
+    ("quote_mint", "Quote_Mint", "报价代币Mint"),
+    ("user_base_token_account", "User_Base_Token_Account", "用户基础代币账户"),
+    ("user_quote_token_account", "User_Quote_Token_Account", "用户报价代币账户"),
+    ("user_pool_token_account", "User_Pool_Token_Account", "用户LP代币账户"),
+    ("pool_base_token_account", "Pool_Base_Token_Account", "池基础代币账户"),
+    ("pool_quote_token_account", "Pool_Quote_Token_Account", "池报价代币账户"),
+    ("protocol_fee_recipient", "Protocol_Fee_Recipient", "协议手续费接收者"),
+    ("protocol_fee_recipient_token_account", "Protocol_Fee_Recipient_Token_Account", "协议手续费接收代币账户"),
+    ("base_token_program", "Base_Token_Program", "基础代币程序"),
+    ("quote_token_program", "Quote_Token_Program", "报价代币程序"),
+    ("lp_mint", "LP_Mint", "LP代币Mint"),
+    ("trading_fees_vault", "Trading_Fees_Vault", "交易手续费金库"),
+    ("bonding_curve_vault", "Bonding_Curve_Vault", "绑定曲线代币金库"),
+    ("bonding_curve_sol_vault", "Bonding_Curve_Sol_Vault", "绑定曲线SOL金库"),
+    ("recipient_token_account", "Recipient_Token_Account", "接收方代币账户"),
+    ("buyer", "Buyer", "买方"),
+    ("config", "Config", "配置"),
+    ("vault_authority", "Vault_Authority", "金库权限"),
+    ("wsol", "Wsol", "WSOL"),
+    ("seller_token_account", "Seller_Token_Account", "卖方代币账户"),
+    ("seller", "Seller", "卖方"),
+    ("recipient", "Recipient", "接收方"),
+    ("payer", "Payer", "付款人"),
+    ("token_metadata_program", "Token_Metadata_Program", "代币元数据程序"),
+    ("account", "Account", "账户"),
+    ("authority", "Authority", "权限"),
+    ("new_authority", "New_Authority", "新权限"),
+    ("withdraw_authority", "Withdraw_Authority", "提取权限"),
+    ("last_withdraw", "Last_Withdraw", "最近提取记录"),
+    ("pump_amm", "Pump_Amm", "pump_amm程序"),
+    ("pool_authority", "Pool_Authority", "池权限"),
+    ("pool_authority_mint_account", "Pool_Authority_Mint_Account", "池权限代币账户"),
+    ("pool_authority_wsol_account", "Pool_Authority_Wsol_Account", "池权限WSOL账户"),
+    ("amm_global_config", "Amm_Global_Config", "AMM全局配置"),
+    ("wsol_mint", "Wsol_Mint", "WSOL Mint"),
+    ("pump_amm_event_authority", "Pump_Amm_Event_Authority", "pump_amm事件权限"),
+    // 账户数异常
+    ("account_anomaly", "Account_Anomaly", "账户数异常"),
+    ("missing", "missing", "缺少账户"),
+    ("extra", "extra", "多出账户"),
+    ("expected", "expected", "预期"),
+    ("actual", "actual", "实际"),
+    // 代币副作用
+    ("token_effects", "Token_Effects", "代币副作用"),
+    ("ata_created", "ATA_Created", "创建ATA"),
+    ("wallet", "wallet", "钱包"),
+    ("idempotent", "idempotent", "幂等"),
+    ("wsol_wrapped", "WSOL_Wrapped", "包装WSOL"),
+    ("wsol_unwrapped", "WSOL_Unwrapped", "解包WSOL"),
+    ("lamports", "lamports", "lamports"),
+    ("account_closed", "Account_Closed", "关闭账户"),
+    ("rent_reclaimed", "rent_reclaimed", "回收租金"),
+    ("token_transfer", "Token_Transfer", "代币转账"),
+    ("amount", "amount", "数量"),
+    // SOL流向
+    ("sol_flow", "SOL_Flow", "SOL流向"),
+    ("fee_payer", "fee_payer", "手续费付款人"),
+    ("payer_outflow", "payer_outflow", "付款人流出"),
+    ("tip", "Tip", "小费"),
+    ("account_creation", "Account_Creation", "创建账户"),
+    ("third_party", "Third_Party", "第三方"),
+    // 来源归因
+    ("attribution", "Attribution", "来源"),
+    ("attribution_counts", "Attribution_Counts", "来源统计"),
+    // K线
+    ("candle", "Candle", "K线"),
+    // 绑定曲线毕业
+    ("graduated", "Graduated", "毕业"),
+    // 绑定曲线状态
+    ("curve", "Curve", "曲线"),
+    // 跨场所套利
+    ("arbitrage", "Arbitrage", "套利"),
+    // 录制统计与发现报告
+    ("records", "Records", "记录数"),
+    ("malformed", "Malformed", "无法解析"),
+    ("payload_bytes", "Payload_Bytes", "载荷字节数"),
+    ("slots", "Slots", "Slot范围"),
+    ("distinct", "distinct", "个不同"),
+    ("entries", "Entries", "Entry数"),
+    ("transactions", "Transactions", "交易数"),
+    ("unknown_discriminators", "Unknown_Discriminators", "未知鉴别器"),
+    ("discriminator", "Discriminator", "鉴别器"),
+    ("count", "Count", "次数"),
+    ("guess", "Guess", "推测"),
+    ("data_lengths", "Data_Lengths", "数据长度"),
+    ("account_counts", "Account_Counts", "账户数"),
+    ("example", "Example", "示例"),
+];
+
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+static INDEX: OnceLock<HashMap<&'static str, (&'static str, &'static str)>> = OnceLock::new();
+
+// 设置文本输出使用的语言
+#[allow(dead_code)]
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+#[allow(dead_code)]
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::Zh,
+        _ => Locale::En,
+    }
+}
+
+// 规范键是否在目录中
+#[allow(dead_code)]
+pub fn contains(key: &str) -> bool {
+    index().contains_key(key)
+}
+
+fn index() -> &'static HashMap<&'static str, (&'static str, &'static str)> {
+    INDEX.get_or_init(|| CATALOG.iter().map(|(key, en, zh)| (*key, (*en, *zh))).collect())
+}
+
+// 指定语言下的标签；目录中没有的键原样返回
+#[allow(dead_code)]
+pub fn text_in(key: &str, locale: Locale) -> &str {
+    match (index().get(key), locale) {
+        (Some((en, _)), Locale::En) => en,
+        (Some((_, zh)), Locale::Zh) => zh,
+        (None, _) => key,
+    }
+}
+
+// 当前语言下的标签
+#[allow(dead_code)]
+pub fn text(key: &str) -> &str {
+    text_in(key, locale())
+}
+
+// 输出一行 "标签: 值"
+#[allow(dead_code)]
+pub fn field<T: fmt::Display>(f: &mut fmt::Formatter<'_>, key: &str, value: T) -> fmt::Result {
+    writeln!(f, "{}: {}", text(key), value)
+}
+
+// 输出一行 "[索引]账户标签: 地址"，没有标签的账户显示为Unknown
+#[allow(dead_code)]
+pub fn account(f: &mut fmt::Formatter<'_>, index: usize, key: Option<&str>, address: &str) -> fmt::Result {
+    writeln!(f, "[{}]{}: {}", index, text(key.unwrap_or("unknown")), address)
+}
@@ -2,8 +2,17 @@ pub mod config;
 pub mod models;
 pub mod services;
 pub mod utils;
+pub mod idl;
+// 测试支持（合成交易与mock服务），只在测试或启用testing特性时编译
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod cli;
+pub mod labels;
+pub mod storage;
+pub mod export;
+pub mod analytics;
 
 // 重新导出重要的类型，方便调用
-pub use services::{JitoClient, TransactionProcessor};
+pub use services::{JitoClient, TransactionProcessor, SearcherClient};
 pub use models::TransactionResults;
-pub use config::{PUMP_PROGRAM_ID, PUMPAMM_PROGRAM_ID, read_jito_url}; 
\ No newline at end of file
+pub use config::{Config, PUMP_PROGRAM_ID, PUMPAMM_PROGRAM_ID, read_jito_url}; 
\ No newline at end of file
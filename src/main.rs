@@ -2,13 +2,20 @@
 // 现在只负责程序的启动和调用模块，不再包含具体业务逻辑
 
 // 引入所需的库
+use std::env;
 use std::io::Error;
-use shreds_client::{JitoClient, read_jito_url};
+use shreds_client::cli::Cli;
 
 fn main() -> Result<(), Error> {
+    // 解析子命令并加载配置：config.toml（或旧格式env.toml）→ SHREDS_* 环境变量 → 命令行参数
+    let args: Vec<String> = env::args().skip(1).collect();
+    let cli = match Cli::parse(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
 
-    
-    // 读取配置并启动客户端
-    let jito_url = read_jito_url()?;
-    JitoClient::start(jito_url)
-}
\ No newline at end of file
+    cli.run()
+}
@@ -0,0 +1,222 @@
+// 交易机器人 / 前端归因：按规则集（手续费钱包、路由程序、计算预算指纹、memo与指令数据特征）
+// 为pump、PumpSwap和boop交易标注最可能的来源工具
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::models::pump_parser::COMPUTE_BUDGET_PROGRAM_ID;
+use crate::models::sol_flow::decode_system_transfer;
+use crate::models::token_parser::SYSTEM_PROGRAM_ID;
+
+// 没有规则命中时的归因
+pub const UNATTRIBUTED: &str = "unknown";
+
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TYDNBHLCxRoZtSbw6QAPz5");
+pub const MEMO_V1_PROGRAM_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+
+// 规则文件中的一条规则；配置了的条件必须全部满足，同一列表内任一命中即可
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AttributionRule {
+    pub name: String,
+    // SOL转账的收款人，通常是平台的手续费钱包
+    pub fee_recipients: Vec<String>,
+    // 交易中调用的程序，通常是平台的路由/代理程序
+    pub programs: Vec<String>,
+    // SetComputeUnitLimit / SetComputeUnitPrice 的取值
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+    // memo内容包含的字符串
+    pub memo: Vec<String>,
+    // 任一指令数据的十六进制前缀
+    pub instruction_prefixes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RulesFile {
+    rules: Vec<AttributionRule>,
+}
+
+// 预先解析好地址与前缀的规则
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    name: String,
+    fee_recipients: HashSet<Pubkey>,
+    programs: HashSet<Pubkey>,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+    memo: Vec<String>,
+    instruction_prefixes: Vec<Vec<u8>>,
+}
+
+impl CompiledRule {
+    fn compile(rule: &AttributionRule) -> Result<Self, Error> {
+        let invalid = |message: String| Error::new(ErrorKind::InvalidData, format!("归因规则 {}: {}", rule.name, message));
+        if rule.name.is_empty() {
+            return Err(invalid("缺少name".to_string()));
+        }
+        let keys = |values: &[String]| {
+            values
+                .iter()
+                .map(|value| Pubkey::from_str(value).map_err(|_| invalid(format!("无效地址: {}", value))))
+                .collect::<Result<HashSet<_>, _>>()
+        };
+        let compiled = Self {
+            name: rule.name.clone(),
+            fee_recipients: keys(&rule.fee_recipients)?,
+            programs: keys(&rule.programs)?,
+            compute_unit_limit: rule.compute_unit_limit,
+            compute_unit_price: rule.compute_unit_price,
+            memo: rule.memo.clone(),
+            instruction_prefixes: rule
+                .instruction_prefixes
+                .iter()
+                .map(|prefix| hex::decode(prefix).map_err(|_| invalid(format!("无效的十六进制前缀: {}", prefix))))
+                .collect::<Result<_, _>>()?,
+        };
+        if compiled.fee_recipients.is_empty()
+            && compiled.programs.is_empty()
+            && compiled.compute_unit_limit.is_none()
+            && compiled.compute_unit_price.is_none()
+            && compiled.memo.is_empty()
+            && compiled.instruction_prefixes.is_empty()
+        {
+            return Err(invalid("至少需要一个条件".to_string()));
+        }
+        Ok(compiled)
+    }
+
+    fn matches(&self, fingerprint: &TransactionFingerprint) -> bool {
+        (self.fee_recipients.is_empty() || !self.fee_recipients.is_disjoint(&fingerprint.sol_recipients))
+            && (self.programs.is_empty() || !self.programs.is_disjoint(&fingerprint.programs))
+            && self.compute_unit_limit.is_none_or(|limit| fingerprint.compute_unit_limit == Some(limit))
+            && self.compute_unit_price.is_none_or(|price| fingerprint.compute_unit_price == Some(price))
+            && (self.memo.is_empty()
+                || fingerprint
+                    .memos
+                    .iter()
+                    .any(|memo| self.memo.iter().any(|pattern| memo.contains(pattern.as_str()))))
+            && (self.instruction_prefixes.is_empty()
+                || fingerprint
+                    .instruction_data
+                    .iter()
+                    .any(|data| self.instruction_prefixes.iter().any(|prefix| data.starts_with(prefix))))
+    }
+}
+
+// 归因用到的交易特征
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionFingerprint {
+    pub programs: HashSet<Pubkey>,
+    pub sol_recipients: HashSet<Pubkey>,
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+    pub memos: Vec<String>,
+    pub instruction_data: Vec<Vec<u8>>,
+}
+
+impl TransactionFingerprint {
+    // 程序ID只能来自静态账户；带地址查找表的V0交易中引用了查找表账户的转账无法解析，跳过
+    #[allow(dead_code)]
+    pub fn from_transaction(transaction: &VersionedTransaction) -> Self {
+        let account_keys = transaction.message.static_account_keys();
+        let compute_budget = Pubkey::from_str(COMPUTE_BUDGET_PROGRAM_ID).unwrap();
+        let mut fingerprint = Self::default();
+
+        for ix in transaction.message.instructions() {
+            let Some(program_id) = account_keys.get(ix.program_id_index as usize) else {
+                continue;
+            };
+            fingerprint.programs.insert(*program_id);
+            fingerprint.instruction_data.push(ix.data.clone());
+
+            if *program_id == compute_budget {
+                match ix.data.first() {
+                    Some(2) => {
+                        fingerprint.compute_unit_limit =
+                            ix.data.get(1..5).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+                    }
+                    Some(3) => {
+                        fingerprint.compute_unit_price =
+                            ix.data.get(1..9).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()));
+                    }
+                    _ => {}
+                }
+            } else if *program_id == MEMO_PROGRAM_ID || *program_id == MEMO_V1_PROGRAM_ID {
+                fingerprint.memos.push(String::from_utf8_lossy(&ix.data).into_owned());
+            } else if *program_id == SYSTEM_PROGRAM_ID {
+                let accounts: Option<Vec<Pubkey>> = ix
+                    .accounts
+                    .iter()
+                    .map(|&idx| account_keys.get(idx as usize).copied())
+                    .collect();
+                if let Some(transfer) = accounts.and_then(|accounts| decode_system_transfer(&ix.data, &accounts)) {
+                    fingerprint.sol_recipients.insert(transfer.to);
+                }
+            }
+        }
+        fingerprint
+    }
+}
+
+// 按顺序匹配的规则集，第一条命中的规则给出归因
+#[derive(Debug, Clone, Default)]
+pub struct AttributionRules {
+    rules: Vec<CompiledRule>,
+}
+
+impl AttributionRules {
+    #[allow(dead_code)]
+    pub fn new(rules: &[AttributionRule]) -> Result<Self, Error> {
+        Ok(Self {
+            rules: rules.iter().map(CompiledRule::compile).collect::<Result<_, _>>()?,
+        })
+    }
+
+    // 从TOML规则文件加载（顶层为 [[rules]] 列表）
+    #[allow(dead_code)]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .map_err(|e| Error::new(e.kind(), format!("无法读取归因规则文件 {}: {}", path.display(), e)))?;
+        Self::from_toml_str(&content)
+    }
+
+    #[allow(dead_code)]
+    pub fn from_toml_str(content: &str) -> Result<Self, Error> {
+        let file: RulesFile = toml::from_str(content)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("归因规则解析失败: {}", e)))?;
+        Self::new(&file.rules)
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn classify(&self, fingerprint: &TransactionFingerprint) -> &str {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(fingerprint))
+            .map_or(UNATTRIBUTED, |rule| rule.name.as_str())
+    }
+
+    #[allow(dead_code)]
+    pub fn classify_transaction(&self, transaction: &VersionedTransaction) -> &str {
+        self.classify(&TransactionFingerprint::from_transaction(transaction))
+    }
+}
@@ -6,73 +6,77 @@ use solana_sdk::transaction::VersionedTransaction;
 use solana_sdk::message::legacy::Message as LegacyMessage;
 use solana_sdk::message::v0::Message as V0Message;
 use solana_sdk::message::VersionedMessage;
-use crate::utils::get_boop_account_labels_by_instruction;
+use crate::utils::account_layout::{self, AccountAnomaly, AccountLayout};
+use crate::idl;
+use crate::labels;
+use crate::utils;
 
 // 添加allow注解来消除警告
 #[allow(dead_code)]
 pub const BOOP_PROGRAM_ID: &str = "boop8hVGQGqehUK2iVEMEnMrL5RbjywRzHKBmBE7ry4";
 
-// BOOP指令类型（根据IDL定义）
-#[derive(Debug, PartialEq, Clone)]
-#[allow(dead_code)]
-pub enum BoopInstructionType {
-    Unknown,
-    // 新指令类型
-    BuyToken,      // 购买代币
-    SellToken,     // 卖出代币
-    CreateToken,   // 创建代币
-    DeployBondingCurve, // 部署绑定曲线
-    
-    // 原始指令类型
-    Create,        // 创建新币种
-    Sell,          // 卖出代币
-    Initialize,    // 初始化全局状态
-    SetParams,     // 设置全局参数
-    UpdateAuthority, // 更新权限
-}
+// BOOP指令类型：由build.rs根据IDL生成
+pub use idl::boop::Instruction as BoopInstructionType;
 
 // BOOP指令的详细信息
 #[derive(Debug, Clone)]
 pub struct BoopInstruction {
-    pub instruction_type: BoopInstructionType,
+    // 按IDL鉴别器（含历史鉴别器）识别的指令，未知鉴别器为None
+    pub instruction_type: Option<BoopInstructionType>,
     pub accounts: Vec<String>,
     pub data: Vec<u8>,
 }
 
+impl BoopInstruction {
+    // 指令类型名，未知指令为Unknown
+    #[allow(dead_code)]
+    pub fn kind(&self) -> &'static str {
+        self.instruction_type.map_or("Unknown", |ix| ix.name())
+    }
+
+    // 按IDL参数布局解码指令参数，历史鉴别器或未知指令返回None
+    #[allow(dead_code)]
+    pub fn decoded_args(&self) -> Option<serde_json::Value> {
+        idl::boop::PROGRAM.find_instruction(&self.data)?.decode_args(&self.data)
+    }
+    
+    // IDL中该指令对应索引的账户名
+    #[allow(dead_code)]
+    pub fn idl_account_name(&self, index: usize) -> Option<&'static str> {
+        idl::boop::PROGRAM.find_instruction(&self.data)?.account_name(index)
+    }
+
+    // 账户布局注册表中该指令的布局
+    #[allow(dead_code)]
+    pub fn account_layout(&self) -> Option<&'static AccountLayout> {
+        self.instruction_type.and_then(account_layout::find)
+    }
+
+    // 账户数与布局不一致时返回异常
+    #[allow(dead_code)]
+    pub fn account_anomaly(&self) -> Option<AccountAnomaly> {
+        self.account_layout()?.check(self.accounts.len())
+    }
+}
+
 impl fmt::Display for BoopInstruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // 首先打印指令参数
-        match &self.instruction_type {
-            BoopInstructionType::BuyToken => {
-                if self.data.len() >= 16 {
-                    // 解析max_sol_cost参数（如果存在）
-                    if self.data.len() >= 24 {
-                        let amount_in_max = u64::from_le_bytes([
-                            self.data[8], self.data[9], self.data[10], self.data[11],
-                            self.data[12], self.data[13], self.data[14], self.data[15]
-                        ]);
-                        writeln!(f, "Max_SOL_Cost: {} ", amount_in_max)?;
+        match self.instruction_type {
+            Some(BoopInstructionType::BuyToken) => {
+                // 按IDL解码参数：buy_amount为支付的SOL，amount_out_min为代币下限；数据截断时只打印完整的参数
+                if let Some(args) = self.decoded_args() {
+                    for name in ["buy_amount", "amount_out_min"] {
+                        if let Some(value) = args.get(name).and_then(|value| value.as_u64()) {
+                            labels::field(f, name, value)?;
+                        }
                     }
-                    
-                    // 解析amount参数
-                    let buy_amount = u64::from_le_bytes([
-                        self.data[16], self.data[17], self.data[18], self.data[19],
-                        self.data[20], self.data[21], self.data[22], self.data[23]
-                    ]);
-                    
-                    writeln!(f, "Token_Amount: {}", buy_amount)?;
                 }
                 
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("BuyToken");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::SellToken => {
+            Some(BoopInstructionType::SellToken) => {
                 if self.data.len() >= 16 {
                     // 解析sell_amount参数
                     let sell_amount = u64::from_le_bytes([
@@ -80,7 +84,7 @@ impl fmt::Display for BoopInstruction {
                         self.data[12], self.data[13], self.data[14], self.data[15]
                     ]);
                     
-                    writeln!(f, "Token_Amount: {}", sell_amount)?;
+                    labels::field(f, "token_amount", sell_amount)?;
                     
                     // 解析min_sol_out参数（如果存在）
                     if self.data.len() >= 24 {
@@ -88,20 +92,14 @@ impl fmt::Display for BoopInstruction {
                             self.data[16], self.data[17], self.data[18], self.data[19],
                             self.data[20], self.data[21], self.data[22], self.data[23]
                         ]);
-                        writeln!(f, "Min_SOL_Output: {} ", min_sol_out)?;
+                        labels::field(f, "min_sol_output", min_sol_out)?;
                     }
                 }
                 
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("SellToken");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::CreateToken => {
+            Some(BoopInstructionType::CreateToken | BoopInstructionType::CreateTokenFallback) => {
                 // 解析CreateToken指令的参数数据
                 if self.data.len() >= 16 { // 至少包含鉴别器(8字节)和部分数据
                     // 跳过前8字节的鉴别器
@@ -113,7 +111,7 @@ impl fmt::Display for BoopInstruction {
                             self.data[offset], self.data[offset+1], self.data[offset+2], self.data[offset+3],
                             self.data[offset+4], self.data[offset+5], self.data[offset+6], self.data[offset+7]
                         ]);
-                        writeln!(f, "Salt: {}", salt)?;
+                        labels::field(f, "salt", salt)?;
                         offset += 8;
                     }
                     
@@ -128,7 +126,7 @@ impl fmt::Display for BoopInstruction {
                         // 读取name字符串内容
                         if offset + name_len <= self.data.len() {
                             let name = String::from_utf8_lossy(&self.data[offset..offset+name_len]);
-                            writeln!(f, "Name: {}", name)?;
+                            labels::field(f, "name", name)?;
                             offset += name_len;
                             
                             // 解析symbol字段
@@ -142,7 +140,7 @@ impl fmt::Display for BoopInstruction {
                                 // 读取symbol字符串内容
                                 if offset + symbol_len <= self.data.len() {
                                     let symbol = String::from_utf8_lossy(&self.data[offset..offset+symbol_len]);
-                                    writeln!(f, "Symbol: {}", symbol)?;
+                                    labels::field(f, "symbol", symbol)?;
                                     offset += symbol_len;
                                     
                                     // 解析uri字段
@@ -156,7 +154,7 @@ impl fmt::Display for BoopInstruction {
                                         // 读取uri字符串内容
                                         if offset + uri_len <= self.data.len() {
                                             let uri = String::from_utf8_lossy(&self.data[offset..offset+uri_len]);
-                                            writeln!(f, "URI: {}", uri)?;
+                                            labels::field(f, "uri", uri)?;
                                         }
                                     }
                                 }
@@ -165,16 +163,10 @@ impl fmt::Display for BoopInstruction {
                     }
                 }
                 
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("CreateToken");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::DeployBondingCurve => {
+            Some(BoopInstructionType::DeployBondingCurve | BoopInstructionType::DeployBondingCurveFallback) => {
                 // 解析DeployBondingCurve指令的参数数据
                 if self.data.len() >= 16 { // 鉴别器(8字节) + 至少8字节数据
                     // 跳过前8字节的鉴别器
@@ -185,7 +177,7 @@ impl fmt::Display for BoopInstruction {
                     if self.data.len() >= 8 + 32 + 8 { // 鉴别器 + creator(32字节) + salt(8字节)
                         // 尝试读取creator (32字节的公钥)
                         let creator = bs58::encode(&self.data[offset..offset+32]).into_string();
-                        writeln!(f, "Creator: {}", creator)?;
+                        labels::field(f, "creator", creator)?;
                         offset += 32;
                         
                         // 读取salt
@@ -194,7 +186,7 @@ impl fmt::Display for BoopInstruction {
                                 self.data[offset], self.data[offset+1], self.data[offset+2], self.data[offset+3],
                                 self.data[offset+4], self.data[offset+5], self.data[offset+6], self.data[offset+7]
                             ]);
-                            writeln!(f, "Salt: {}", salt)?;
+                            labels::field(f, "salt", salt)?;
                         }
                     } else {
                         // 假设第一个参数是salt (8字节)
@@ -202,27 +194,21 @@ impl fmt::Display for BoopInstruction {
                             self.data[offset], self.data[offset+1], self.data[offset+2], self.data[offset+3],
                             self.data[offset+4], self.data[offset+5], self.data[offset+6], self.data[offset+7]
                         ]);
-                        writeln!(f, "Salt: {}", salt)?;
+                        labels::field(f, "salt", salt)?;
                         offset += 8;
                         
                         // 如果还有更多数据，尝试读取creator
                         if offset + 32 <= self.data.len() {
                             let creator = bs58::encode(&self.data[offset..offset+32]).into_string();
-                            writeln!(f, "Creator: {}", creator)?;
+                            labels::field(f, "creator", creator)?;
                         }
                     }
                 }
                 
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("DeployBondingCurve");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::Create => {
+            Some(BoopInstructionType::Create) => {
                 // Create指令的字符串参数在data[8..]之后
                 if self.data.len() > 8 {
                     // 前8字节是discriminator，后面是参数数据
@@ -234,7 +220,7 @@ impl fmt::Display for BoopInstruction {
                             self.data[offset], self.data[offset+1], self.data[offset+2], self.data[offset+3],
                             self.data[offset+4], self.data[offset+5], self.data[offset+6], self.data[offset+7]
                         ]);
-                        writeln!(f, "Salt: {}", salt)?;
+                        labels::field(f, "salt", salt)?;
                         offset += 8;
                     }
                     
@@ -249,7 +235,7 @@ impl fmt::Display for BoopInstruction {
                         // 读取name字符串内容
                         if offset + name_len <= self.data.len() {
                             let name = String::from_utf8_lossy(&self.data[offset..offset+name_len]);
-                            writeln!(f, "Name: {}", name)?;
+                            labels::field(f, "name", name)?;
                             offset += name_len;
                             
                             // 解析symbol字段
@@ -263,7 +249,7 @@ impl fmt::Display for BoopInstruction {
                                 // 读取symbol字符串内容
                                 if offset + symbol_len <= self.data.len() {
                                     let symbol = String::from_utf8_lossy(&self.data[offset..offset+symbol_len]);
-                                    writeln!(f, "Symbol: {}", symbol)?;
+                                    labels::field(f, "symbol", symbol)?;
                                     offset += symbol_len;
                                     
                                     // 解析URI字段
@@ -277,13 +263,13 @@ impl fmt::Display for BoopInstruction {
                                         // 读取URI字符串内容
                                         if offset + uri_len <= self.data.len() {
                                             let uri = String::from_utf8_lossy(&self.data[offset..offset+uri_len]);
-                                            writeln!(f, "URI: {}", uri)?;
+                                            labels::field(f, "uri", uri)?;
                                             offset += uri_len;
                                             
                                             // 解析creator字段 (Pubkey是32字节)
                                             if offset + 32 <= self.data.len() {
                                                 let creator = bs58::encode(&self.data[offset..offset+32]).into_string();
-                                                writeln!(f, "Creator: {}", creator)?;
+                                                labels::field(f, "creator", creator)?;
                                             }
                                         }
                                     }
@@ -293,16 +279,10 @@ impl fmt::Display for BoopInstruction {
                     }
                 }
                 
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("Create");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::Sell => {
+            Some(BoopInstructionType::Sell) => {
                 if self.data.len() >= 16 {
                     // 解析sell_amount参数
                     let sell_amount = u64::from_le_bytes([
@@ -310,7 +290,7 @@ impl fmt::Display for BoopInstruction {
                         self.data[12], self.data[13], self.data[14], self.data[15]
                     ]);
                     
-                    writeln!(f, "Token_Amount: {}", sell_amount)?;
+                    labels::field(f, "token_amount", sell_amount)?;
                     
                     // 解析min_sol_out参数（如果存在）
                     if self.data.len() >= 24 {
@@ -318,50 +298,26 @@ impl fmt::Display for BoopInstruction {
                             self.data[16], self.data[17], self.data[18], self.data[19],
                             self.data[20], self.data[21], self.data[22], self.data[23]
                         ]);
-                        writeln!(f, "Min_SOL_Output: {} ", min_sol_out)?;
+                        labels::field(f, "min_sol_output", min_sol_out)?;
                     }
                 }
                 
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("Sell");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::Initialize => {
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("Initialize");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+            Some(BoopInstructionType::Initialize) => {
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::SetParams => {
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("SetParams");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+            Some(BoopInstructionType::SetParams) => {
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::UpdateAuthority => {
-                // 获取账户标签
-                let account_labels = get_boop_account_labels_by_instruction("UpdateAuthority");
-                
-                // 打印账户信息
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]{}: {}", i, label, account)?;
-                }
+            Some(BoopInstructionType::UpdateAuthority) => {
+                // 按账户布局打印账户信息
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            BoopInstructionType::Unknown => {
+            None => {
                 // 对于未知指令，不打印任何信息
                 return Ok(());
             }
@@ -375,15 +331,17 @@ impl fmt::Display for BoopInstruction {
 #[derive(Debug)]
 pub struct BoopTransaction {
     pub signature: String,
+    // 交易的签名账户，用于判断管理指令是否由管理员签名
+    pub signers: Vec<String>,
     pub instructions: Vec<BoopInstruction>,
 }
 
 impl fmt::Display for BoopTransaction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Instruction_Count: {}", self.instructions.len())?;
+        labels::field(f, "instructions_count", self.instructions.len())?;
         
         for (i, instruction) in self.instructions.iter().enumerate() {
-            writeln!(f, "Instruction #{}: {:?}", i + 1, instruction.instruction_type)?;
+            writeln!(f, "{}[{}]{}: {}", labels::text("instruction"), i, labels::text("type"), instruction.kind())?;
             write!(f, "{}", instruction)?;
         }
         
@@ -395,11 +353,15 @@ pub struct BoopParser;
 
 impl BoopParser {
     pub fn parse_transaction(transaction: &VersionedTransaction) -> Option<BoopTransaction> {
-        let boop_program_id = Pubkey::from_str(BOOP_PROGRAM_ID).unwrap();
-        
+        let boop_program_id = Pubkey::from_str(BOOP_PROGRAM_ID).ok()?;
+        Self::parse_transaction_with_program_id(transaction, &boop_program_id)
+    }
+
+    // 使用指定的程序ID解析交易（程序ID可由配置覆盖）
+    pub fn parse_transaction_with_program_id(transaction: &VersionedTransaction, boop_program_id: &Pubkey) -> Option<BoopTransaction> {
         let (instructions, signature) = match &transaction.message {
             VersionedMessage::Legacy(message) => {
-                let instructions = Self::extract_boop_instructions_from_legacy(message, &boop_program_id);
+                let instructions = Self::extract_boop_instructions_from_legacy(message, boop_program_id);
                 
                 if instructions.is_empty() {
                     return None;
@@ -409,7 +371,7 @@ impl BoopParser {
                 (instructions, signature)
             }
             VersionedMessage::V0(message) => {
-                let instructions = Self::extract_boop_instructions_from_v0(message, &boop_program_id);
+                let instructions = Self::extract_boop_instructions_from_v0(message, boop_program_id);
                 
                 if instructions.is_empty() {
                     return None;
@@ -422,6 +384,7 @@ impl BoopParser {
         
         Some(BoopTransaction {
             signature,
+            signers: utils::transaction_signers(transaction),
             instructions,
         })
     }
@@ -447,11 +410,7 @@ impl BoopParser {
         message: &V0Message,
         boop_program_id: &Pubkey,
     ) -> Vec<BoopInstruction> {
-        let account_keys: Vec<Pubkey> = message
-            .account_keys
-            .iter()
-            .map(|key| key.clone())
-            .collect();
+        let account_keys: Vec<Pubkey> = message.account_keys.to_vec();
             
         let mut boop_instructions = Vec::new();
         
@@ -482,33 +441,8 @@ impl BoopParser {
             })
             .collect();
             
-        // 确定指令类型
-        let instruction_type = if ix.data.len() >= 8 {
-            // 检查指令数据的前8个字节来确定指令类型
-            match &ix.data[0..8] {
-                // 根据IDL定义的指令鉴别器
-                [138, 127, 14, 91, 38, 87, 115, 105] => BoopInstructionType::BuyToken,  // buy_token指令
-                [109, 61, 40, 187, 230, 176, 135, 174] => BoopInstructionType::SellToken, // sell_token指令
-                [253, 184, 126, 199, 235, 232, 172, 162] => BoopInstructionType::CreateToken, // create_token指令
-                [53, 230, 172, 84, 77, 174, 22, 61] => BoopInstructionType::DeployBondingCurve, // deploy_bonding_curve指令
-                [84, 52, 204, 228, 24, 140, 234, 75] => BoopInstructionType::CreateToken, // 新发现的create_token指令鉴别器
-                [180, 89, 199, 76, 168, 236, 217, 138] => BoopInstructionType::DeployBondingCurve, // 新发现的deploy_bonding_curve指令鉴别器
-                
-                // 原始指令的鉴别器
-                [191, 19, 103, 26, 245, 85, 112, 105] => BoopInstructionType::Create, // create指令
-                [25, 169, 76, 76, 84, 153, 195, 216] => BoopInstructionType::Sell,    // sell指令
-                [175, 175, 109, 31, 13, 152, 155, 237] => BoopInstructionType::Initialize, // initialize指令
-                [235, 129, 153, 118, 219, 194, 131, 246] => BoopInstructionType::SetParams, // setParams指令
-                [167, 17, 172, 137, 241, 116, 201, 161] => BoopInstructionType::UpdateAuthority, // updateAuthority指令
-                
-                _ => {
-                    println!("未知鉴别器: {:?}", &ix.data[0..8]);
-                    BoopInstructionType::Unknown
-                },
-            }
-        } else {
-            BoopInstructionType::Unknown
-        };
+        // 按生成的IDL指令表识别指令类型；未知鉴别器留在结果中（instruction_type为None），由services层报告
+        let instruction_type = BoopInstructionType::from_discriminator(&ix.data);
         
         BoopInstruction {
             instruction_type,
@@ -0,0 +1,2 @@
+// Anchor指令鉴别器，由build.rs根据 sha256("global:<name>") 在编译期生成
+include!(concat!(env!("OUT_DIR"), "/discriminators.rs"));
@@ -0,0 +1,377 @@
+// 从各解析器的结果中提取统一的交易事件：买卖成交、代币发行与流动性变化，供存储、导出与后续统计使用。
+// shreds中只有指令参数，没有执行结果：一侧数量是精确值，另一侧是滑点上限/下限
+use serde_json::Value;
+
+use crate::models::boop_parser::BoopInstructionType;
+use crate::models::pump_parser::PumpInstructionType;
+use crate::models::pumpamm_parser::PumpAmmInstructionType;
+use crate::models::token_parser::WSOL_MINT;
+use crate::models::TransactionResults;
+use crate::utils::account_layout::{AccountLayout, Program};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+impl TradeSide {
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeSide::Buy => "buy",
+            TradeSide::Sell => "sell",
+        }
+    }
+}
+
+// 一条买卖指令；数量为最小单位（代币按mint精度，SOL为lamports）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub slot: u64,
+    pub signature: String,
+    // 交易内同一解析器的指令序号
+    pub position: usize,
+    pub venue: Program,
+    pub side: TradeSide,
+    pub mint: String,
+    // 绑定曲线或池子
+    pub pool: Option<String>,
+    pub user: Option<String>,
+    pub quote_mint: String,
+    pub token_amount: u64,
+    pub quote_amount: u64,
+    // true表示quote_amount是精确值、token_amount是滑点限制；false相反
+    pub exact_quote: bool,
+}
+
+impl Trade {
+    // 精确数量与滑点限制之比（每个代币最小单位的quote最小单位数），是用户能接受的最差价格，不是成交价；
+    // 成交价需要按曲线或池子的状态模拟，见analytics::Fill
+    #[allow(dead_code)]
+    pub fn limit_price(&self) -> Option<f64> {
+        (self.token_amount > 0).then(|| self.quote_amount as f64 / self.token_amount as f64)
+    }
+}
+
+// 代币发行
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenLaunch {
+    pub slot: u64,
+    pub signature: String,
+    pub venue: Program,
+    pub mint: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    pub creator: Option<String>,
+    pub bonding_curve: Option<String>,
+}
+
+// 流动性变化的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LiquidityKind {
+    CreatePool,
+    Deposit,
+    Withdraw,
+    // pump绑定曲线完成后迁移到PumpSwap
+    Migrate,
+}
+
+impl LiquidityKind {
+    #[allow(dead_code)]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LiquidityKind::CreatePool => "create_pool",
+            LiquidityKind::Deposit => "deposit",
+            LiquidityKind::Withdraw => "withdraw",
+            LiquidityKind::Migrate => "migrate",
+        }
+    }
+}
+
+// 一条流动性指令；Deposit / Withdraw的代币数量是滑点限制，CreatePool是精确值，Migrate没有参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityEvent {
+    pub slot: u64,
+    pub signature: String,
+    pub position: usize,
+    pub venue: Program,
+    pub kind: LiquidityKind,
+    pub pool: Option<String>,
+    pub user: Option<String>,
+    pub base_mint: String,
+    pub quote_mint: Option<String>,
+    pub lp_amount: Option<u64>,
+    pub base_amount: Option<u64>,
+    pub quote_amount: Option<u64>,
+}
+
+// 按布局的规范键取账户（分析模块也复用）
+pub(crate) fn account(layout: Option<&AccountLayout>, accounts: &[String], key: &str) -> Option<String> {
+    accounts.get(layout?.index_of(key)?).cloned()
+}
+
+pub(crate) fn arg_u64(args: &Option<Value>, name: &str) -> Option<u64> {
+    args.as_ref()?.get(name)?.as_u64()
+}
+
+fn arg_str(args: &Option<Value>, name: &str) -> Option<String> {
+    args.as_ref()?.get(name)?.as_str().map(str::to_string)
+}
+
+// 一个slot中pump、PumpSwap与boop的全部买卖
+#[allow(dead_code)]
+pub fn trades(results: &TransactionResults) -> Vec<Trade> {
+    let slot = results.current_slot;
+    let wsol = WSOL_MINT.to_string();
+    let mut trades = Vec::new();
+
+    for tx in &results.pump_transactions {
+        for (position, ix) in tx.instructions.iter().enumerate() {
+            let (side, quote_arg) = match ix.instruction_type {
+                Some(PumpInstructionType::Buy) => (TradeSide::Buy, "max_sol_cost"),
+                Some(PumpInstructionType::Sell) => (TradeSide::Sell, "min_sol_output"),
+                _ => continue,
+            };
+            let (layout, args) = (ix.account_layout(), ix.decoded_args());
+            let (Some(mint), Some(token_amount), Some(quote_amount)) = (
+                account(layout, &ix.accounts, "mint"),
+                arg_u64(&args, "amount"),
+                arg_u64(&args, quote_arg),
+            ) else {
+                continue;
+            };
+            trades.push(Trade {
+                slot,
+                signature: tx.signature.clone(),
+                position,
+                venue: Program::Pump,
+                side,
+                mint,
+                pool: account(layout, &ix.accounts, "bonding_curve"),
+                user: account(layout, &ix.accounts, "user"),
+                quote_mint: wsol.clone(),
+                token_amount,
+                quote_amount,
+                exact_quote: false,
+            });
+        }
+    }
+
+    for tx in &results.pumpamm_transactions {
+        for (position, ix) in tx.instructions.iter().enumerate() {
+            let (side, token_arg, quote_arg) = match ix.instruction_type {
+                Some(PumpAmmInstructionType::Buy) => (TradeSide::Buy, "base_amount_out", "max_quote_amount_in"),
+                Some(PumpAmmInstructionType::Sell) => (TradeSide::Sell, "base_amount_in", "min_quote_amount_out"),
+                _ => continue,
+            };
+            let (layout, args) = (ix.account_layout(), ix.decoded_args());
+            let (Some(mint), Some(token_amount), Some(quote_amount)) = (
+                account(layout, &ix.accounts, "base_mint"),
+                arg_u64(&args, token_arg),
+                arg_u64(&args, quote_arg),
+            ) else {
+                continue;
+            };
+            trades.push(Trade {
+                slot,
+                signature: tx.signature.clone(),
+                position,
+                venue: Program::PumpAmm,
+                side,
+                mint,
+                pool: account(layout, &ix.accounts, "pool"),
+                user: account(layout, &ix.accounts, "user"),
+                quote_mint: account(layout, &ix.accounts, "quote_mint").unwrap_or_else(|| wsol.clone()),
+                token_amount,
+                quote_amount,
+                exact_quote: false,
+            });
+        }
+    }
+
+    for tx in &results.boop_transactions {
+        for (position, ix) in tx.instructions.iter().enumerate() {
+            // boop买入时给出精确的SOL数量，卖出时给出精确的代币数量
+            let (side, user_key) = match ix.instruction_type {
+                Some(BoopInstructionType::BuyToken) => (TradeSide::Buy, "buyer"),
+                Some(BoopInstructionType::SellToken) => (TradeSide::Sell, "seller"),
+                _ => continue,
+            };
+            let (layout, args) = (ix.account_layout(), ix.decoded_args());
+            let exact = arg_u64(&args, if side == TradeSide::Buy { "buy_amount" } else { "sell_amount" });
+            let limit = arg_u64(&args, "amount_out_min");
+            let (Some(mint), Some(exact), Some(limit)) = (account(layout, &ix.accounts, "mint"), exact, limit) else {
+                continue;
+            };
+            let (token_amount, quote_amount) = match side {
+                TradeSide::Buy => (limit, exact),
+                TradeSide::Sell => (exact, limit),
+            };
+            trades.push(Trade {
+                slot,
+                signature: tx.signature.clone(),
+                position,
+                venue: Program::Boop,
+                side,
+                mint,
+                pool: account(layout, &ix.accounts, "bonding_curve"),
+                user: account(layout, &ix.accounts, user_key),
+                quote_mint: wsol.clone(),
+                token_amount,
+                quote_amount,
+                exact_quote: side == TradeSide::Buy,
+            });
+        }
+    }
+
+    trades
+}
+
+// 一个slot中pump与boop的代币发行
+#[allow(dead_code)]
+pub fn launches(results: &TransactionResults) -> Vec<TokenLaunch> {
+    let slot = results.current_slot;
+    let mut launches = Vec::new();
+
+    for tx in &results.pump_transactions {
+        for ix in &tx.instructions {
+            if ix.instruction_type != Some(PumpInstructionType::Create) {
+                continue;
+            }
+            let (layout, args) = (ix.account_layout(), ix.decoded_args());
+            let Some(mint) = account(layout, &ix.accounts, "mint") else {
+                continue;
+            };
+            launches.push(TokenLaunch {
+                slot,
+                signature: tx.signature.clone(),
+                venue: Program::Pump,
+                mint,
+                name: arg_str(&args, "name"),
+                symbol: arg_str(&args, "symbol"),
+                uri: arg_str(&args, "uri"),
+                creator: arg_str(&args, "creator").or_else(|| account(layout, &ix.accounts, "user")),
+                bonding_curve: account(layout, &ix.accounts, "bonding_curve"),
+            });
+        }
+    }
+
+    for tx in &results.boop_transactions {
+        // boop的CreateToken与DeployBondingCurve通常在同一笔交易中，后者给出创建者与曲线
+        let deploy = tx
+            .instructions
+            .iter()
+            .find(|ix| {
+                matches!(
+                    ix.instruction_type,
+                    Some(BoopInstructionType::DeployBondingCurve | BoopInstructionType::DeployBondingCurveFallback)
+                )
+            });
+        for ix in &tx.instructions {
+            if !matches!(
+                ix.instruction_type,
+                Some(BoopInstructionType::CreateToken | BoopInstructionType::CreateTokenFallback)
+            ) {
+                continue;
+            }
+            let (layout, args) = (ix.account_layout(), ix.decoded_args());
+            let Some(mint) = account(layout, &ix.accounts, "mint") else {
+                continue;
+            };
+            let deploy_args = deploy.and_then(|deploy| deploy.decoded_args());
+            launches.push(TokenLaunch {
+                slot,
+                signature: tx.signature.clone(),
+                venue: Program::Boop,
+                mint,
+                name: arg_str(&args, "name"),
+                symbol: arg_str(&args, "symbol"),
+                uri: arg_str(&args, "uri"),
+                creator: arg_str(&deploy_args, "creator").or_else(|| account(layout, &ix.accounts, "payer")),
+                bonding_curve: deploy.and_then(|deploy| account(deploy.account_layout(), &deploy.accounts, "bonding_curve")),
+            });
+        }
+    }
+
+    launches
+}
+
+// 一个slot中PumpSwap的建池、存入、取出，以及pump的迁移
+#[allow(dead_code)]
+pub fn liquidity(results: &TransactionResults) -> Vec<LiquidityEvent> {
+    let slot = results.current_slot;
+    let mut events = Vec::new();
+
+    for tx in &results.pumpamm_transactions {
+        for (position, ix) in tx.instructions.iter().enumerate() {
+            let (kind, user_key, lp_arg, base_arg, quote_arg) = match ix.instruction_type {
+                Some(PumpAmmInstructionType::CreatePool) => {
+                    (LiquidityKind::CreatePool, "creator", None, "base_amount_in", "quote_amount_in")
+                }
+                Some(PumpAmmInstructionType::Deposit) => (
+                    LiquidityKind::Deposit,
+                    "user",
+                    Some("lp_token_amount_out"),
+                    "max_base_amount_in",
+                    "max_quote_amount_in",
+                ),
+                Some(PumpAmmInstructionType::Withdraw) => (
+                    LiquidityKind::Withdraw,
+                    "user",
+                    Some("lp_token_amount_in"),
+                    "min_base_amount_out",
+                    "min_quote_amount_out",
+                ),
+                _ => continue,
+            };
+            let (layout, args) = (ix.account_layout(), ix.decoded_args());
+            let Some(base_mint) = account(layout, &ix.accounts, "base_mint") else {
+                continue;
+            };
+            events.push(LiquidityEvent {
+                slot,
+                signature: tx.signature.clone(),
+                position,
+                venue: Program::PumpAmm,
+                kind,
+                pool: account(layout, &ix.accounts, "pool"),
+                user: account(layout, &ix.accounts, user_key),
+                base_mint,
+                quote_mint: account(layout, &ix.accounts, "quote_mint"),
+                lp_amount: lp_arg.and_then(|name| arg_u64(&args, name)),
+                base_amount: arg_u64(&args, base_arg),
+                quote_amount: arg_u64(&args, quote_arg),
+            });
+        }
+    }
+
+    for tx in &results.pump_transactions {
+        for (position, ix) in tx.instructions.iter().enumerate() {
+            if ix.instruction_type != Some(PumpInstructionType::Migrate) {
+                continue;
+            }
+            let layout = ix.account_layout();
+            let Some(base_mint) = account(layout, &ix.accounts, "mint") else {
+                continue;
+            };
+            events.push(LiquidityEvent {
+                slot,
+                signature: tx.signature.clone(),
+                position,
+                venue: Program::Pump,
+                kind: LiquidityKind::Migrate,
+                pool: account(layout, &ix.accounts, "pool"),
+                user: account(layout, &ix.accounts, "user"),
+                base_mint,
+                quote_mint: account(layout, &ix.accounts, "wsol_mint"),
+                lp_amount: None,
+                base_amount: None,
+                quote_amount: None,
+            });
+        }
+    }
+
+    events
+}
@@ -0,0 +1,85 @@
+use std::fmt;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_sdk::message::VersionedMessage;
+use crate::idl::{DecodedInstruction, IdlRegistry};
+use crate::labels;
+
+// 由运行时IDL解码的交易
+#[derive(Debug, Clone)]
+pub struct IdlTransaction {
+    pub signature: String,
+    pub instructions: Vec<DecodedInstruction>,
+}
+
+impl fmt::Display for IdlTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        labels::field(f, "instructions_count", self.instructions.len())?;
+
+        for (i, instruction) in self.instructions.iter().enumerate() {
+            writeln!(f, "{}[{}]{}: {}::{}", labels::text("instruction"), i, labels::text("type"), instruction.program, instruction.name)?;
+            labels::field(f, "args", &instruction.args)?;
+            // IDL账户名即规范键，目录中有的按语言翻译
+            for (index, (name, key)) in instruction.accounts.iter().enumerate() {
+                labels::account(f, index, Some(name), &key.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// 通用IDL解析器：解码注册表中任意程序的指令
+#[allow(dead_code)]
+pub struct IdlParser;
+
+impl IdlParser {
+    // 解析交易，提取注册表中已知程序的指令
+    #[allow(dead_code)]
+    pub fn parse_transaction(transaction: &VersionedTransaction, registry: &IdlRegistry) -> Option<IdlTransaction> {
+        if registry.is_empty() {
+            return None;
+        }
+
+        // 与其他解析器一致，带地址查找表的V0交易只有静态账户可用，暂不处理
+        if let VersionedMessage::V0(message) = &transaction.message {
+            if !message.address_table_lookups.is_empty() {
+                return None;
+            }
+        }
+
+        let account_keys = transaction.message.static_account_keys();
+        let instructions: Vec<_> = transaction
+            .message
+            .instructions()
+            .iter()
+            .filter_map(|ix| {
+                let program_id = account_keys.get(ix.program_id_index as usize)?;
+                if !registry.contains(program_id) {
+                    return None;
+                }
+                let accounts: Vec<Pubkey> = ix
+                    .accounts
+                    .iter()
+                    .filter_map(|&idx| account_keys.get(idx as usize).copied())
+                    .collect();
+                registry.decode(program_id, &ix.data, &accounts)
+            })
+            .collect();
+
+        if instructions.is_empty() {
+            return None;
+        }
+
+        let signature = if !transaction.signatures.is_empty() {
+            transaction.signatures[0].to_string()
+        } else {
+            "No_Signature".to_string()
+        };
+
+        Some(IdlTransaction {
+            signature,
+            instructions,
+        })
+    }
+}
@@ -2,8 +2,21 @@ pub mod transaction;
 pub mod pump_parser;
 pub mod pumpamm_parser;
 pub mod boop_parser;
+pub mod discriminators;
+pub mod idl_parser;
+pub mod timing;
+pub mod token_parser;
+pub mod sol_flow;
+pub mod attribution;
+pub mod events;
 
-pub use transaction::TransactionResults;
+pub use transaction::{TransactionResults, UnknownInstruction};
 pub use pump_parser::{PumpParser, PumpTransaction};
 pub use pumpamm_parser::{PumpAmmParser, PumpAmmTransaction};
-pub use boop_parser::{BoopParser, BoopTransaction}; 
\ No newline at end of file
+pub use boop_parser::{BoopParser, BoopTransaction};
+pub use idl_parser::{IdlParser, IdlTransaction};
+pub use timing::SlotTiming;
+pub use token_parser::{TokenEffects, TokenParser};
+pub use sol_flow::{SolFlow, SolFlowParser};
+pub use attribution::AttributionRules;
+pub use events::{LiquidityEvent, TokenLaunch, Trade};
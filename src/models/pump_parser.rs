@@ -6,42 +6,66 @@ use solana_sdk::transaction::VersionedTransaction;
 use solana_sdk::message::legacy::Message as LegacyMessage;
 use solana_sdk::message::v0::Message as V0Message;
 use solana_sdk::message::VersionedMessage;
+use crate::idl;
+use crate::labels;
+use crate::utils;
+use crate::utils::account_layout::{self, AccountAnomaly, AccountLayout};
 
 // 添加allow注解来消除警告
 #[allow(dead_code)]
 pub const PUMP_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
 
-// PUMP指令类型（根据IDL定义）
-#[derive(Debug, PartialEq, Clone)]
-#[allow(dead_code)]
-pub enum PumpInstructionType {
-    Unknown,
-    Buy,          // 从绑定曲线购买代币
-    Create,       // 创建新币种和绑定曲线
-    ExtendAccount,// 扩展程序拥有的账户
-    Initialize,   // 初始化全局状态
-    Migrate,      // 如果绑定曲线完成，则将流动性迁移到pump_amm
-    Sell,         // 卖出代币到绑定曲线
-    SetParams,    // 设置全局参数
-    UpdateGlobalAuthority, // 更新全局权限
-    Withdraw,     // 如果绑定曲线完成，允许管理员提取流动性用于迁移
-}
+// PUMP指令类型：由build.rs根据IDL生成
+pub use idl::pump::Instruction as PumpInstructionType;
 
 // PUMP指令的详细信息
 #[derive(Debug, Clone)]
 pub struct PumpInstruction {
-    pub instruction_type: PumpInstructionType,
+    // 按IDL鉴别器（含历史鉴别器）识别的指令，未知鉴别器为None
+    pub instruction_type: Option<PumpInstructionType>,
     pub accounts: Vec<String>,
     pub data: Vec<u8>,
 }
 
+impl PumpInstruction {
+    // 指令类型名，未知指令为Unknown
+    #[allow(dead_code)]
+    pub fn kind(&self) -> &'static str {
+        self.instruction_type.map_or("Unknown", |ix| ix.name())
+    }
+
+    // 按IDL参数布局解码指令参数，历史鉴别器或未知指令返回None
+    #[allow(dead_code)]
+    pub fn decoded_args(&self) -> Option<serde_json::Value> {
+        idl::pump::PROGRAM.find_instruction(&self.data)?.decode_args(&self.data)
+    }
+    
+    // IDL中该指令对应索引的账户名
+    #[allow(dead_code)]
+    pub fn idl_account_name(&self, index: usize) -> Option<&'static str> {
+        idl::pump::PROGRAM.find_instruction(&self.data)?.account_name(index)
+    }
+
+    // 账户布局注册表中该指令的布局
+    #[allow(dead_code)]
+    pub fn account_layout(&self) -> Option<&'static AccountLayout> {
+        self.instruction_type.and_then(account_layout::find)
+    }
+
+    // 账户数与布局不一致时返回异常
+    #[allow(dead_code)]
+    pub fn account_anomaly(&self) -> Option<AccountAnomaly> {
+        self.account_layout()?.check(self.accounts.len())
+    }
+}
+
 impl fmt::Display for PumpInstruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 
         
         // 首先打印指令参数
-        match &self.instruction_type {
-            PumpInstructionType::Buy => {
+        match self.instruction_type {
+            Some(PumpInstructionType::Buy) => {
                 if self.data.len() >= 16 {
                     // 解析amount和max_sol_cost参数
                     let amount = u64::from_le_bytes([
@@ -49,52 +73,20 @@ impl fmt::Display for PumpInstruction {
                         self.data[12], self.data[13], self.data[14], self.data[15]
                     ]);
                     
-                    writeln!(f, "Token_Amount: {}", amount)?;
+                    labels::field(f, "token_amount", amount)?;
                     
                     if self.data.len() >= 24 {
                         let max_sol_cost = u64::from_le_bytes([
                             self.data[16], self.data[17], self.data[18], self.data[19],
                             self.data[20], self.data[21], self.data[22], self.data[23]
                         ]);
-                        writeln!(f, "Max_SOL_Cost: {} ", max_sol_cost)?;
+                        labels::field(f, "max_sol_cost", max_sol_cost)?;
                     }
                 }
                 
-                if self.accounts.len() >= 3 {
-                    writeln!(f, "[0]Global: {}", self.accounts[0])?;
-                    writeln!(f, "[1]Fee_Recipient: {}", self.accounts[1])?;
-                    writeln!(f, "[2]Mint: {}", self.accounts[2])?;
-                    
-                    if self.accounts.len() >= 4 {
-                        writeln!(f, "[3]Bonding_Curve: {}", self.accounts[3])?;
-                    }
-                    if self.accounts.len() >= 5 {
-                        writeln!(f, "[4]Associated_Bonding_Curve: {}", self.accounts[4])?;
-                    }
-                    if self.accounts.len() >= 6 {
-                        writeln!(f, "[5]Associated_User: {}", self.accounts[5])?;
-                    }
-                    if self.accounts.len() >= 7 {
-                        writeln!(f, "[6]User: {}", self.accounts[6])?;
-                    }
-                    if self.accounts.len() >= 8 {
-                        writeln!(f, "[7]System_Program: {}", self.accounts[7])?;
-                    }
-                    if self.accounts.len() >= 9 {
-                        writeln!(f, "[8]Token_Program: {}", self.accounts[8])?;
-                    }
-                    if self.accounts.len() >= 10 {
-                        writeln!(f, "[9]Rent: {}", self.accounts[9])?;
-                    }
-                    if self.accounts.len() >= 11 {
-                        writeln!(f, "[10]Event_Authority: {}", self.accounts[10])?;
-                    }
-                    if self.accounts.len() >= 12 {
-                        writeln!(f, "[11]Program: {}", self.accounts[11])?;
-                    }
-                }
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::Create => {
+            Some(PumpInstructionType::Create) => {
                 // Create指令的字符串参数在data[8..]之后
                 if self.data.len() > 8 {
                     // 前8字节是discriminator，后面是参数数据
@@ -111,7 +103,7 @@ impl fmt::Display for PumpInstruction {
                         // 读取name字符串内容
                         if offset + name_len <= self.data.len() {
                             let name = String::from_utf8_lossy(&self.data[offset..offset+name_len]);
-                            writeln!(f, "name: {}", name)?;
+                            labels::field(f, "name", name)?;
                             offset += name_len;
                             
                             // 解析symbol字段
@@ -125,7 +117,7 @@ impl fmt::Display for PumpInstruction {
                                 // 读取symbol字符串内容
                                 if offset + symbol_len <= self.data.len() {
                                     let symbol = String::from_utf8_lossy(&self.data[offset..offset+symbol_len]);
-                                    writeln!(f, "symbol: {}", symbol)?;
+                                    labels::field(f, "symbol", symbol)?;
                                     offset += symbol_len;
                                     
                                     // 解析URI字段
@@ -139,13 +131,13 @@ impl fmt::Display for PumpInstruction {
                                         // 读取URI字符串内容
                                         if offset + uri_len <= self.data.len() {
                                             let uri = String::from_utf8_lossy(&self.data[offset..offset+uri_len]);
-                                            writeln!(f, "uri: {}", uri)?;
+                                            labels::field(f, "uri", uri)?;
                                             offset += uri_len;
                                             
                                             // 解析creator字段 (Pubkey是32字节)
                                             if offset + 32 <= self.data.len() {
                                                 let creator = bs58::encode(&self.data[offset..offset+32]).into_string();
-                                                writeln!(f, "creator: {}", creator)?;
+                                                labels::field(f, "creator", creator)?;
                                             }
                                         }
                                     }
@@ -155,47 +147,9 @@ impl fmt::Display for PumpInstruction {
                     }
                 }
                 
-                if self.accounts.len() >= 3 {
-                    writeln!(f, "[0]Mint: {}", self.accounts[0])?;
-                    writeln!(f, "[1]Mint_Authority: {}", self.accounts[1])?;
-                    writeln!(f, "[2]Bonding_Curve: {}", self.accounts[2])?;
-                    
-                    if self.accounts.len() >= 4 {
-                        writeln!(f, "[3]Associated_Bonding_Curve: {}", self.accounts[3])?;
-                    }
-                    if self.accounts.len() >= 5 {
-                        writeln!(f, "[4]Global: {}", self.accounts[4])?;
-                    }
-                    if self.accounts.len() >= 6 {
-                        writeln!(f, "[5]Mpl_Token_Metadata: {}", self.accounts[5])?;
-                    }
-                    if self.accounts.len() >= 7 {
-                        writeln!(f, "[6]Metadata: {}", self.accounts[6])?;
-                    }
-                    if self.accounts.len() >= 8 {
-                        writeln!(f, "[7]User: {}", self.accounts[7])?;
-                    }
-                    if self.accounts.len() >= 9 {
-                        writeln!(f, "[8]System_Program: {}", self.accounts[8])?;
-                    }
-                    if self.accounts.len() >= 10 {
-                        writeln!(f, "[9]Token_Program: {}", self.accounts[9])?;
-                    }
-                    if self.accounts.len() >= 11 {
-                        writeln!(f, "[10]Associated_Token_Program: {}", self.accounts[10])?;
-                    }
-                    if self.accounts.len() >= 12 {
-                        writeln!(f, "[11]Rent: {}", self.accounts[11])?;
-                    }
-                    if self.accounts.len() >= 13 {
-                        writeln!(f, "[12]Event_Authority: {}", self.accounts[12])?;
-                    }
-                    if self.accounts.len() >= 14 {
-                        writeln!(f, "[13]Program: {}", self.accounts[13])?;
-                    }
-                }
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::Sell => {
+            Some(PumpInstructionType::Sell) => {
                 if self.data.len() >= 16 {
                     // 解析amount和min_sol_output参数
                     let amount = u64::from_le_bytes([
@@ -203,72 +157,46 @@ impl fmt::Display for PumpInstruction {
                         self.data[12], self.data[13], self.data[14], self.data[15]
                     ]);
                     
-                    writeln!(f, "Token_Amount: {}", amount)?;
+                    labels::field(f, "token_amount", amount)?;
                     
                     if self.data.len() >= 24 {
                         let min_sol_output = u64::from_le_bytes([
                             self.data[16], self.data[17], self.data[18], self.data[19],
                             self.data[20], self.data[21], self.data[22], self.data[23]
                         ]);
-                        writeln!(f, "Min_SOL_Output: {} ", min_sol_output)?;
+                        labels::field(f, "min_sol_output", min_sol_output)?;
                     }
                 }
                 
-                if self.accounts.len() >= 3 {
-                    writeln!(f, "[0]Global: {}", self.accounts[0])?;
-                    writeln!(f, "[1]Fee_Recipient: {}", self.accounts[1])?;
-                    writeln!(f, "[2]Mint: {}", self.accounts[2])?;
-                    
-                    if self.accounts.len() >= 4 {
-                        writeln!(f, "[3]Bonding_Curve: {}", self.accounts[3])?;
-                    }
-                    if self.accounts.len() >= 5 {
-                        writeln!(f, "[4]Associated_Bonding_Curve: {}", self.accounts[4])?;
-                    }
-                    if self.accounts.len() >= 6 {
-                        writeln!(f, "[5]Associated_User: {}", self.accounts[5])?;
-                    }
-                    if self.accounts.len() >= 7 {
-                        writeln!(f, "[6]User: {}", self.accounts[6])?;
-                    }
-                    if self.accounts.len() >= 8 {
-                        writeln!(f, "[7]System_Program: {}", self.accounts[7])?;
-                    }
-                    if self.accounts.len() >= 9 {
-                        writeln!(f, "[8]Token_Program: {}", self.accounts[8])?;
-                    }
-                    if self.accounts.len() >= 10 {
-                        writeln!(f, "[9]Rent: {}", self.accounts[9])?;
-                    }
-                    if self.accounts.len() >= 11 {
-                        writeln!(f, "[10]Event_Authority: {}", self.accounts[10])?;
-                    }
-                    if self.accounts.len() >= 12 {
-                        writeln!(f, "[11]Program: {}", self.accounts[11])?;
-                    }
-                }
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::SetParams => {
+            Some(PumpInstructionType::SetParams) => {
                 // SetParams有多个参数
-                writeln!(f, "SetParams: 设置全局参数")?;
+                writeln!(f, "SetParams: {}", labels::text("desc_set_params"))?;
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::Initialize => {
-                writeln!(f, "Initialize: 初始化全局状态，无参数")?;
+            Some(PumpInstructionType::Initialize) => {
+                writeln!(f, "Initialize: {}", labels::text("desc_initialize"))?;
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::Withdraw => {
-                writeln!(f, "Withdraw: 提取流动性（管理员操作），无参数")?;
+            Some(PumpInstructionType::Withdraw) => {
+                writeln!(f, "Withdraw: {}", labels::text("desc_withdraw"))?;
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::Migrate => {
-                writeln!(f, "Migrate: 迁移流动性到pump_amm")?;
+            Some(PumpInstructionType::Migrate) => {
+                writeln!(f, "Migrate: {}", labels::text("desc_migrate"))?;
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::ExtendAccount => {
-                writeln!(f, "ExtendAccount: 扩展程序拥有的账户大小")?;
+            Some(PumpInstructionType::ExtendAccount) => {
+                writeln!(f, "ExtendAccount: {}", labels::text("desc_extend_account"))?;
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::UpdateGlobalAuthority => {
-                writeln!(f, "UpdateGlobalAuthority: 更新全局权限")?;
+            Some(PumpInstructionType::UpdateGlobalAuthority) => {
+                writeln!(f, "UpdateGlobalAuthority: {}", labels::text("desc_update_global_authority"))?;
+                account_layout::write_accounts(f, self.account_layout(), &self.accounts)?;
             },
-            PumpInstructionType::Unknown => {
-                writeln!(f, "Unknown: 未知指令")?;
+            None => {
+                writeln!(f, "Unknown: {}", labels::text("desc_unknown_instruction"))?;
             },
         }
         
@@ -281,15 +209,17 @@ impl fmt::Display for PumpInstruction {
 #[derive(Debug, Clone)]
 pub struct PumpTransaction {
     pub signature: String,
+    // 交易的签名账户，用于判断管理指令是否由管理员签名
+    pub signers: Vec<String>,
     pub instructions: Vec<PumpInstruction>,
 }
 
 impl fmt::Display for PumpTransaction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Instructions_Count: {}", self.instructions.len())?;
+        labels::field(f, "instructions_count", self.instructions.len())?;
         
         for (i, instruction) in self.instructions.iter().enumerate() {
-            writeln!(f, "Instruction[{}]Type: {:?}", i, instruction.instruction_type)?;
+            writeln!(f, "{}[{}]{}: {}", labels::text("instruction"), i, labels::text("type"), instruction.kind())?;
             write!(f, "{}", instruction)?;
         }
         
@@ -307,14 +237,19 @@ impl PumpParser {
     pub fn parse_transaction(transaction: &VersionedTransaction) -> Option<PumpTransaction> {
         // 获取PUMP程序的Pubkey
         let pump_program_id = Pubkey::from_str(PUMP_PROGRAM_ID).ok()?;
-        
+        Self::parse_transaction_with_program_id(transaction, &pump_program_id)
+    }
+
+    // 使用指定的程序ID解析交易（程序ID可由配置覆盖）
+    #[allow(dead_code)]
+    pub fn parse_transaction_with_program_id(transaction: &VersionedTransaction, pump_program_id: &Pubkey) -> Option<PumpTransaction> {
         // 提取PUMP相关指令
         let pump_instructions = match &transaction.message {
             VersionedMessage::Legacy(message) => {
-                Self::extract_pump_instructions_from_legacy(message, &pump_program_id)
+                Self::extract_pump_instructions_from_legacy(message, pump_program_id)
             }
             VersionedMessage::V0(message) => {
-                Self::extract_pump_instructions_from_v0(message, &pump_program_id)
+                Self::extract_pump_instructions_from_v0(message, pump_program_id)
             }
         };
         
@@ -333,6 +268,7 @@ impl PumpParser {
         // 返回解析结果
         Some(PumpTransaction {
             signature,
+            signers: utils::transaction_signers(transaction),
             instructions: pump_instructions,
         })
     }
@@ -401,50 +337,8 @@ impl PumpParser {
         ix: &CompiledInstruction,
         account_keys: &[Pubkey],
     ) -> PumpInstruction {
-        // 解析指令类型
-        let instruction_type = if !ix.data.is_empty() && ix.data.len() >= 8 {
-            // 根据IDL中的discriminator识别指令类型
-            let discriminator = &ix.data[0..8];
-            match discriminator {
-                // Buy指令 - 从IDL中复制的discriminator
-                [102, 6, 61, 18, 1, 218, 235, 234] => PumpInstructionType::Buy,
-                [242, 35, 198, 137, 82, 225, 242, 182] => PumpInstructionType::Buy,
-                
-                // Create指令
-                [24, 30, 200, 40, 5, 28, 7, 119] => PumpInstructionType::Create,
-                [54, 49, 138, 255, 162, 99, 87, 199] => PumpInstructionType::Create,
-                
-                // ExtendAccount指令
-                [234, 102, 194, 203, 150, 72, 62, 229] => PumpInstructionType::ExtendAccount,
-                
-                // Initialize指令
-                [175, 175, 109, 31, 13, 152, 155, 237] => PumpInstructionType::Initialize,
-                [103, 232, 80, 22, 46, 244, 138, 11] => PumpInstructionType::Initialize,
-                
-                // Migrate指令
-                [155, 234, 231, 146, 236, 158, 162, 30] => PumpInstructionType::Migrate,
-                
-                // Sell指令
-                [51, 230, 133, 164, 1, 127, 131, 173] => PumpInstructionType::Sell,
-                [157, 141, 99, 91, 56, 32, 241, 199] => PumpInstructionType::Sell,
-                
-                // SetParams指令
-                [27, 234, 178, 52, 147, 2, 187, 141] => PumpInstructionType::SetParams,
-                [186, 127, 135, 21, 36, 67, 77, 55] => PumpInstructionType::SetParams,
-                
-                // UpdateGlobalAuthority指令
-                [227, 181, 74, 196, 208, 21, 97, 213] => PumpInstructionType::UpdateGlobalAuthority,
-                
-                // Withdraw指令
-                [183, 18, 70, 156, 148, 109, 161, 34] => PumpInstructionType::Withdraw,
-                [16, 9, 233, 100, 246, 18, 249, 253] => PumpInstructionType::Withdraw,
-                
-                // 未知指令
-                _ => PumpInstructionType::Unknown,
-            }
-        } else {
-            PumpInstructionType::Unknown
-        };
+        // 按生成的IDL指令表识别指令类型；未知鉴别器留在结果中（instruction_type为None），由services层报告
+        let instruction_type = PumpInstructionType::from_discriminator(&ix.data);
         
         // 获取账户地址
         let accounts = ix
@@ -495,7 +389,7 @@ impl fmt::Display for ComputeBudgetInstruction {
                     let size = u32::from_le_bytes([
                         self.data[1], self.data[2], self.data[3], self.data[4]
                     ]);
-                    writeln!(f, "Request_Heap_Frame_Size: {} 字节", size)?;
+                    writeln!(f, "{}: {} {}", labels::text("request_heap_frame_size"), size, labels::text("unit_bytes"))?;
                 }
             },
             ComputeBudgetInstructionType::RequestComputeUnits |
@@ -504,11 +398,11 @@ impl fmt::Display for ComputeBudgetInstruction {
                     let units = u32::from_le_bytes([
                         self.data[1], self.data[2], self.data[3], self.data[4]
                     ]);
-                    writeln!(f, "Set_Compute_Unit_Limit: {}", units)?;
+                    labels::field(f, "set_compute_unit_limit", units)?;
                     
                     // 估算费用（按照默认没有优先费的情况）
                     let estimated_fee = (units as f64) * 0.0000005; // 0.0000005 lamports per CU
-                    writeln!(f, "Estimated_Fee: {:.9} SOL (Based_on {} CU)", estimated_fee, units)?;
+                    writeln!(f, "{}: {:.9} SOL ({} {} CU)", labels::text("estimated_fee"), estimated_fee, labels::text("based_on"), units)?;
                 }
             },
             ComputeBudgetInstructionType::SetComputeUnitPrice => {
@@ -517,41 +411,41 @@ impl fmt::Display for ComputeBudgetInstruction {
                         self.data[1], self.data[2], self.data[3], self.data[4],
                         self.data[5], self.data[6], self.data[7], self.data[8]
                     ]);
-                    writeln!(f, "Set_Compute_Unit_Price: {} 微lamports/计算单元", price)?;
+                    writeln!(f, "{}: {} {}", labels::text("set_compute_unit_price"), price, labels::text("unit_micro_lamports_per_cu"))?;
                     
                     // 估算200K CU的费用
                     let estimated_cu = 200_000;
                     let estimated_fee = (price as f64) * (estimated_cu as f64) / 1_000_000.0 / 1_000_000_000.0;
-                    writeln!(f, "Estimated_Fee: {:.9} SOL (Based_on {}K CU)", estimated_fee, estimated_cu/1000)?;
+                    writeln!(f, "{}: {:.9} SOL ({} {}K CU)", labels::text("estimated_fee"), estimated_fee, labels::text("based_on"), estimated_cu/1000)?;
                 }
             },
             ComputeBudgetInstructionType::Unknown => {
-                writeln!(f, "Unknown: 未知的ComputeBudget指令")?;
+                writeln!(f, "Unknown: {}", labels::text("desc_unknown_compute_budget"))?;
             },
         }
         
         // 显示完整的指令数据格式
-        writeln!(f, "Instruction_Data_Format:")?;
+        writeln!(f, "{}:", labels::text("instruction_data_format"))?;
         for (i, byte) in self.data.iter().enumerate() {
             if i == 0 {
-                writeln!(f, "  位置 {}: {} (指令码)", i, byte)?;
+                writeln!(f, "  {} {}: {} ({})", labels::text("position"), i, byte, labels::text("opcode"))?;
             } else if self.instruction_type == ComputeBudgetInstructionType::SetComputeUnitPrice && i <= 8 {
                 if i == 1 {
-                    writeln!(f, "  位置 {}-8: 价格参数 (u64, 小端序)", i)?;
+                    writeln!(f, "  {} {}-8: {}", labels::text("position"), i, labels::text("price_param"))?;
                 }
             } else if (self.instruction_type == ComputeBudgetInstructionType::RequestComputeUnits || 
                        self.instruction_type == ComputeBudgetInstructionType::SetComputeUnitLimit ||
                        self.instruction_type == ComputeBudgetInstructionType::RequestHeapFrame) && i <= 4 {
                 if i == 1 {
-                    writeln!(f, "  位置 {}-4: 单元/大小参数 (u32, 小端序)", i)?;
+                    writeln!(f, "  {} {}-4: {}", labels::text("position"), i, labels::text("units_param"))?;
                 }
             } else {
-                writeln!(f, "  位置 {}: {}", i, byte)?;
+                writeln!(f, "  {} {}: {}", labels::text("position"), i, byte)?;
             }
         }
         
         // 显示原始数据
-        writeln!(f, "Raw_Data: {}", hex::encode(&self.data))?;
+        labels::field(f, "raw_data", hex::encode(&self.data))?;
         
         Ok(())
     }
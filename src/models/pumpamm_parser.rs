@@ -6,47 +6,69 @@ use solana_sdk::transaction::VersionedTransaction;
 use solana_sdk::message::legacy::Message as LegacyMessage;
 use solana_sdk::message::v0::Message as V0Message;
 use solana_sdk::message::VersionedMessage;
-use crate::utils::get_pumpamm_account_labels;
+use crate::utils::account_layout::{self, AccountAnomaly, AccountLayout};
+use crate::idl;
+use crate::labels;
+use crate::utils;
 
 // 添加allow注解来消除警告
 #[allow(dead_code)]
 pub const PUMP_AMM_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
 
-// PUMP_AMM指令类型（根据IDL定义）
-#[derive(Debug, PartialEq, Clone)]
-#[allow(dead_code)]
-pub enum PumpAmmInstructionType {
-    Unknown,
-    Buy,             // 从流动性池买入代币
-    CreateConfig,    // 创建全局配置
-    CreatePool,      // 创建新的流动性池
-    Deposit,         // 向流动性池存入代币
-    Disable,         // 禁用某些功能
-    ExtendAccount,   // 扩展账户
-    Sell,            // 向流动性池卖出代币
-    UpdateAdmin,     // 更新管理员
-    UpdateFeeConfig, // 更新费用配置
-    Withdraw,        // 从流动性池取出代币
-}
+// PUMP_AMM指令类型：由build.rs根据IDL生成
+pub use idl::pump_amm::Instruction as PumpAmmInstructionType;
 
 // PUMP_AMM指令的详细信息
 #[derive(Debug, Clone)]
 pub struct PumpAmmInstruction {
-    pub instruction_type: PumpAmmInstructionType,
+    // 按IDL鉴别器（含历史鉴别器）识别的指令，未知鉴别器为None
+    pub instruction_type: Option<PumpAmmInstructionType>,
     pub accounts: Vec<String>,
     pub data: Vec<u8>,
 }
 
+impl PumpAmmInstruction {
+    // 指令类型名，未知指令为Unknown
+    #[allow(dead_code)]
+    pub fn kind(&self) -> &'static str {
+        self.instruction_type.map_or("Unknown", |ix| ix.name())
+    }
+
+    // 按IDL参数布局解码指令参数，历史鉴别器或未知指令返回None
+    #[allow(dead_code)]
+    pub fn decoded_args(&self) -> Option<serde_json::Value> {
+        idl::pump_amm::PROGRAM.find_instruction(&self.data)?.decode_args(&self.data)
+    }
+    
+    // IDL中该指令对应索引的账户名
+    #[allow(dead_code)]
+    pub fn idl_account_name(&self, index: usize) -> Option<&'static str> {
+        idl::pump_amm::PROGRAM.find_instruction(&self.data)?.account_name(index)
+    }
+
+    // 账户布局注册表中该指令的布局
+    #[allow(dead_code)]
+    pub fn account_layout(&self) -> Option<&'static AccountLayout> {
+        self.instruction_type.and_then(account_layout::find)
+    }
+
+    // 账户数与布局不一致时返回异常
+    #[allow(dead_code)]
+    pub fn account_anomaly(&self) -> Option<AccountAnomaly> {
+        self.account_layout()?.check(self.accounts.len())
+    }
+}
+
 impl fmt::Display for PumpAmmInstruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 
         
-        // 获取账户标签
-        let account_labels = get_pumpamm_account_labels();
+        // 按指令类型获取账户布局
+        let layout = self.account_layout();
         
         // 根据指令类型显示相关信息
-        match &self.instruction_type {
-            PumpAmmInstructionType::Buy => {
+        match self.instruction_type {
+            Some(PumpAmmInstructionType::Buy) => {
                 if self.data.len() >= 24 {
                     // 解析base_amount_out和max_quote_amount_in参数
                     let base_amount_out = u64::from_le_bytes([
@@ -59,17 +81,14 @@ impl fmt::Display for PumpAmmInstruction {
                         self.data[20], self.data[21], self.data[22], self.data[23]
                     ]);
                     
-                    writeln!(f, "Base Amount Out: {}", base_amount_out)?;
-                    writeln!(f, "Max Quote Amount In: {}", max_quote_amount_in)?;
+                    labels::field(f, "base_amount_out", base_amount_out)?;
+                    labels::field(f, "max_quote_amount_in", max_quote_amount_in)?;
                 }
                 
                 // 显示账户
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]:{}: {}", i, label, account)?;
-                }
+                account_layout::write_accounts(f, layout, &self.accounts)?;
             },
-            PumpAmmInstructionType::CreateConfig => {
+            Some(PumpAmmInstructionType::CreateConfig) => {
                 if self.data.len() >= 24 {
                     // 解析lp_fee_basis_points和protocol_fee_basis_points参数
                     let lp_fee_basis_points = u64::from_le_bytes([
@@ -82,17 +101,14 @@ impl fmt::Display for PumpAmmInstruction {
                         self.data[20], self.data[21], self.data[22], self.data[23]
                     ]);
                     
-                    writeln!(f, "LP Fee Basis Points: {}", lp_fee_basis_points)?;
-                    writeln!(f, "Protocol Fee Basis Points: {}", protocol_fee_basis_points)?;
+                    labels::field(f, "lp_fee_basis_points", lp_fee_basis_points)?;
+                    labels::field(f, "protocol_fee_basis_points", protocol_fee_basis_points)?;
                 }
                 
                 // 显示账户
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]:{}: {}", i, label, account)?;
-                }
+                account_layout::write_accounts(f, layout, &self.accounts)?;
             },
-            PumpAmmInstructionType::CreatePool => {
+            Some(PumpAmmInstructionType::CreatePool) => {
                 if self.data.len() >= 24 {
                     // 解析index, base_amount_in和quote_amount_in参数
                     let index = u16::from_le_bytes([
@@ -109,18 +125,15 @@ impl fmt::Display for PumpAmmInstruction {
                         self.data[22], self.data[23], self.data[24], self.data[25]
                     ]);
                     
-                    writeln!(f, "Index: {}", index)?;
-                    writeln!(f, "Base Amount In: {}", base_amount_in)?;
-                    writeln!(f, "Quote Amount In: {}", quote_amount_in)?;
+                    labels::field(f, "index", index)?;
+                    labels::field(f, "base_amount_in", base_amount_in)?;
+                    labels::field(f, "quote_amount_in", quote_amount_in)?;
                 }
                 
                 // 显示账户
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]:{}: {}", i, label, account)?;
-                }
+                account_layout::write_accounts(f, layout, &self.accounts)?;
             },
-            PumpAmmInstructionType::Deposit => {
+            Some(PumpAmmInstructionType::Deposit) => {
                 if self.data.len() >= 32 {
                     // 解析lp_token_amount_out, max_base_amount_in和max_quote_amount_in参数
                     let lp_token_amount_out = u64::from_le_bytes([
@@ -138,18 +151,15 @@ impl fmt::Display for PumpAmmInstruction {
                         self.data[28], self.data[29], self.data[30], self.data[31]
                     ]);
                     
-                    writeln!(f, "LP Token Amount Out: {}", lp_token_amount_out)?;
-                    writeln!(f, "Max Base Amount In: {}", max_base_amount_in)?;
-                    writeln!(f, "Max Quote Amount In: {}", max_quote_amount_in)?;
+                    labels::field(f, "lp_token_amount_out", lp_token_amount_out)?;
+                    labels::field(f, "max_base_amount_in", max_base_amount_in)?;
+                    labels::field(f, "max_quote_amount_in", max_quote_amount_in)?;
                 }
                 
                 // 显示账户
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "账户[{}]:{}: {}", i, label, account)?;
-                }
+                account_layout::write_accounts(f, layout, &self.accounts)?;
             },
-            PumpAmmInstructionType::Sell => {
+            Some(PumpAmmInstructionType::Sell) => {
                 if self.data.len() >= 24 {
                     // 解析base_amount_in和min_quote_amount_out参数
                     let base_amount_in = u64::from_le_bytes([
@@ -162,17 +172,14 @@ impl fmt::Display for PumpAmmInstruction {
                         self.data[20], self.data[21], self.data[22], self.data[23]
                     ]);
                     
-                    writeln!(f, "Base Amount In: {}", base_amount_in)?;
-                    writeln!(f, "Min Quote Amount Out: {}", min_quote_amount_out)?;
+                    labels::field(f, "base_amount_in", base_amount_in)?;
+                    labels::field(f, "min_quote_amount_out", min_quote_amount_out)?;
                 }
                 
                 // 显示账户
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "[{}]:{}: {}", i, label, account)?;
-                }
+                account_layout::write_accounts(f, layout, &self.accounts)?;
             },
-            PumpAmmInstructionType::Withdraw => {
+            Some(PumpAmmInstructionType::Withdraw) => {
                 if self.data.len() >= 32 {
                     // 解析lp_token_amount_in, min_base_amount_out和min_quote_amount_out参数
                     let lp_token_amount_in = u64::from_le_bytes([
@@ -190,23 +197,17 @@ impl fmt::Display for PumpAmmInstruction {
                         self.data[28], self.data[29], self.data[30], self.data[31]
                     ]);
                     
-                    writeln!(f, "LP Token Amount In: {}", lp_token_amount_in)?;
-                    writeln!(f, "Min Base Amount Out: {}", min_base_amount_out)?;
-                    writeln!(f, "Min Quote Amount Out: {}", min_quote_amount_out)?;
+                    labels::field(f, "lp_token_amount_in", lp_token_amount_in)?;
+                    labels::field(f, "min_base_amount_out", min_base_amount_out)?;
+                    labels::field(f, "min_quote_amount_out", min_quote_amount_out)?;
                 }
                 
                 // 显示账户
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "账户[{}]:{}: {}", i, label, account)?;
-                }
+                account_layout::write_accounts(f, layout, &self.accounts)?;
             },
             _ => {
-                // 对于其他指令类型，只显示账户列表
-                for (i, account) in self.accounts.iter().enumerate() {
-                    let label = account_labels.get(&i).unwrap_or(&"Unknown");
-                    writeln!(f, "账户[{}]:{}: {}", i, label, account)?;
-                }
+                // 显示账户
+                account_layout::write_accounts(f, layout, &self.accounts)?;
             }
         }
         
@@ -218,15 +219,17 @@ impl fmt::Display for PumpAmmInstruction {
 #[derive(Debug, Clone)]
 pub struct PumpAmmTransaction {
     pub signature: String,
+    // 交易的签名账户，用于判断管理指令是否由管理员签名
+    pub signers: Vec<String>,
     pub instructions: Vec<PumpAmmInstruction>,
 }
 
 impl fmt::Display for PumpAmmTransaction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Instructions_Count: {}", self.instructions.len())?;
+        labels::field(f, "instructions_count", self.instructions.len())?;
         
         for (i, instruction) in self.instructions.iter().enumerate() {
-            writeln!(f, "Instruction[{}]Type: {:?}", i, instruction.instruction_type)?;
+            writeln!(f, "{}[{}]{}: {}", labels::text("instruction"), i, labels::text("type"), instruction.kind())?;
             write!(f, "{}", instruction)?;
         }
         
@@ -244,14 +247,19 @@ impl PumpAmmParser {
     pub fn parse_transaction(transaction: &VersionedTransaction) -> Option<PumpAmmTransaction> {
         // 获取PUMP_AMM程序的Pubkey
         let pump_amm_program_id = Pubkey::from_str(PUMP_AMM_PROGRAM_ID).ok()?;
-        
+        Self::parse_transaction_with_program_id(transaction, &pump_amm_program_id)
+    }
+
+    // 使用指定的程序ID解析交易（程序ID可由配置覆盖）
+    #[allow(dead_code)]
+    pub fn parse_transaction_with_program_id(transaction: &VersionedTransaction, pump_amm_program_id: &Pubkey) -> Option<PumpAmmTransaction> {
         // 提取PUMP_AMM相关指令
         let pump_amm_instructions = match &transaction.message {
             VersionedMessage::Legacy(message) => {
-                Self::extract_pump_amm_instructions_from_legacy(message, &pump_amm_program_id)
+                Self::extract_pump_amm_instructions_from_legacy(message, pump_amm_program_id)
             }
             VersionedMessage::V0(message) => {
-                Self::extract_pump_amm_instructions_from_v0(message, &pump_amm_program_id)
+                Self::extract_pump_amm_instructions_from_v0(message, pump_amm_program_id)
             }
         };
         
@@ -270,6 +278,7 @@ impl PumpAmmParser {
         // 返回解析结果
         Some(PumpAmmTransaction {
             signature,
+            signers: utils::transaction_signers(transaction),
             instructions: pump_amm_instructions,
         })
     }
@@ -338,47 +347,8 @@ impl PumpAmmParser {
         ix: &CompiledInstruction,
         account_keys: &[Pubkey],
     ) -> PumpAmmInstruction {
-        // 解析指令类型
-        let instruction_type = if !ix.data.is_empty() && ix.data.len() >= 8 {
-            // 根据IDL中的discriminator识别指令类型
-            let discriminator = &ix.data[0..8];
-            match discriminator {
-                // Buy指令
-                [102, 6, 61, 18, 1, 218, 235, 234] => PumpAmmInstructionType::Buy,
-                
-                // CreateConfig指令
-                [201, 207, 243, 114, 75, 111, 47, 189] => PumpAmmInstructionType::CreateConfig,
-                
-                // CreatePool指令
-                [233, 146, 209, 142, 207, 104, 64, 188] => PumpAmmInstructionType::CreatePool,
-                
-                // Deposit指令
-                [242, 35, 198, 137, 82, 225, 242, 182] => PumpAmmInstructionType::Deposit,
-                
-                // Disable指令
-                [185, 173, 187, 90, 216, 15, 238, 233] => PumpAmmInstructionType::Disable,
-                
-                // ExtendAccount指令
-                [234, 102, 194, 203, 150, 72, 62, 229] => PumpAmmInstructionType::ExtendAccount,
-                
-                // Sell指令
-                [51, 230, 133, 164, 1, 127, 131, 173] => PumpAmmInstructionType::Sell,
-                
-                // UpdateAdmin指令
-                [161, 176, 40, 213, 60, 184, 179, 228] => PumpAmmInstructionType::UpdateAdmin,
-                
-                // UpdateFeeConfig指令
-                [104, 184, 103, 242, 88, 151, 107, 20] => PumpAmmInstructionType::UpdateFeeConfig,
-                
-                // Withdraw指令
-                [183, 18, 70, 156, 148, 109, 161, 34] => PumpAmmInstructionType::Withdraw,
-                
-                // 未知指令
-                _ => PumpAmmInstructionType::Unknown,
-            }
-        } else {
-            PumpAmmInstructionType::Unknown
-        };
+        // 按生成的IDL指令表识别指令类型；未知鉴别器留在结果中（instruction_type为None），由services层报告
+        let instruction_type = PumpAmmInstructionType::from_discriminator(&ix.data);
         
         // 获取账户地址
         let accounts = ix
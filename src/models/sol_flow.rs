@@ -0,0 +1,273 @@
+// System Program层面的SOL流向：按目标账户把每笔转账归类为小费、已知手续费接收者、WSOL包装、
+// 创建账户或第三方。交易机器人平台（Photon、BullX等）通常以第三方转账的形式收取手续费
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{json, Value};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::labels;
+use crate::models::token_parser::{TokenEffects, SYSTEM_PROGRAM_ID};
+use crate::utils::account_layout::AccountLayout;
+
+// Jito小费账户（主网）
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+// 账户布局中表示手续费接收者的规范键（pump的fee_recipient、PumpSwap的protocol_fee_recipient）
+pub const FEE_RECIPIENT_KEYS: &[&str] = &["fee_recipient", "protocol_fee_recipient"];
+
+// 按布局取出指令中的手续费接收者账户
+#[allow(dead_code)]
+pub fn fee_recipients<'a>(
+    layout: Option<&'a AccountLayout>,
+    accounts: &'a [String],
+) -> impl Iterator<Item = Pubkey> + 'a {
+    accounts
+        .iter()
+        .enumerate()
+        .filter(move |(i, _)| {
+            layout
+                .and_then(|layout| layout.label(*i))
+                .is_some_and(|key| FEE_RECIPIENT_KEYS.contains(&key))
+        })
+        .filter_map(|(_, account)| Pubkey::from_str(account).ok())
+}
+
+// 一条转移lamports的System Program指令；space为Some表示创建账户
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemTransfer {
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub lamports: u64,
+    pub space: Option<u64>,
+}
+
+// 解码CreateAccount(0) / CreateAccountWithSeed(3) / Transfer(2) / TransferWithSeed(11)，其他指令返回None
+#[allow(dead_code)]
+pub fn decode_system_transfer(data: &[u8], accounts: &[Pubkey]) -> Option<SystemTransfer> {
+    let tag = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    let (from, to, lamports, space) = match tag {
+        0 => (0, 1, read_u64(data, 4)?, Some(read_u64(data, 12)?)),
+        2 => (0, 1, read_u64(data, 4)?, None),
+        3 => {
+            // base(32) + seed(u64长度 + 字节) 之后是lamports与space
+            let seed_len = usize::try_from(read_u64(data, 36)?).ok()?;
+            let offset = 44usize.checked_add(seed_len)?;
+            (0, 1, read_u64(data, offset)?, Some(read_u64(data, offset + 8)?))
+        }
+        11 => (0, 2, read_u64(data, 4)?, None),
+        _ => return None,
+    };
+    Some(SystemTransfer {
+        from: *accounts.get(from)?,
+        to: *accounts.get(to)?,
+        lamports,
+        space,
+    })
+}
+
+// SOL去向分类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlowKind {
+    Tip,
+    FeeRecipient,
+    WsolWrap,
+    AccountCreation,
+    ThirdParty,
+}
+
+impl FlowKind {
+    // 规范键，同时用于JSON与标签目录
+    #[allow(dead_code)]
+    pub fn key(&self) -> &'static str {
+        match self {
+            FlowKind::Tip => "tip",
+            FlowKind::FeeRecipient => "fee_recipient",
+            FlowKind::WsolWrap => "wsol_wrapped",
+            FlowKind::AccountCreation => "account_creation",
+            FlowKind::ThirdParty => "third_party",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolTransfer {
+    pub kind: FlowKind,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub lamports: u64,
+}
+
+// 一笔交易中System Program转移的SOL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolFlow {
+    pub fee_payer: Pubkey,
+    pub transfers: Vec<SolTransfer>,
+}
+
+impl SolFlow {
+    // 从手续费付款人转出的SOL合计（不含交易手续费与CPI内的转账）
+    #[allow(dead_code)]
+    pub fn payer_outflow(&self) -> u64 {
+        self.transfers
+            .iter()
+            .filter(|transfer| transfer.from == self.fee_payer)
+            .map(|transfer| transfer.lamports)
+            .sum()
+    }
+
+    // 某一类去向的合计
+    #[allow(dead_code)]
+    pub fn total(&self, kind: FlowKind) -> u64 {
+        self.transfers
+            .iter()
+            .filter(|transfer| transfer.kind == kind)
+            .map(|transfer| transfer.lamports)
+            .sum()
+    }
+
+    // 转给第三方的SOL，平台手续费通常在这里
+    #[allow(dead_code)]
+    pub fn third_parties(&self) -> impl Iterator<Item = &SolTransfer> {
+        self.transfers
+            .iter()
+            .filter(|transfer| transfer.kind == FlowKind::ThirdParty)
+    }
+
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "fee_payer": self.fee_payer.to_string(),
+            "payer_outflow": self.payer_outflow(),
+            "tip": self.total(FlowKind::Tip),
+            "fee_recipient": self.total(FlowKind::FeeRecipient),
+            "wsol_wrapped": self.total(FlowKind::WsolWrap),
+            "account_creation": self.total(FlowKind::AccountCreation),
+            "third_party": self.total(FlowKind::ThirdParty),
+            "transfers": self.transfers.iter().map(|transfer| json!({
+                "kind": transfer.kind.key(),
+                "from": transfer.from.to_string(),
+                "to": transfer.to.to_string(),
+                "lamports": transfer.lamports,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl fmt::Display for SolFlow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {}={} {}={}",
+            labels::text("sol_flow"),
+            labels::text("fee_payer"),
+            self.fee_payer,
+            labels::text("payer_outflow"),
+            self.payer_outflow()
+        )?;
+        for transfer in &self.transfers {
+            writeln!(
+                f,
+                "  {}: {} -> {} {}={}",
+                labels::text(transfer.kind.key()),
+                transfer.from,
+                transfer.to,
+                labels::text("lamports"),
+                transfer.lamports
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// SOL流向解析器
+#[allow(dead_code)]
+pub struct SolFlowParser;
+
+impl SolFlowParser {
+    // 汇总交易中的System Program转账；没有转账时返回None。
+    // fee_recipients来自被匹配指令的账户，token_effects用于识别WSOL账户
+    #[allow(dead_code)]
+    pub fn parse_transaction(
+        transaction: &VersionedTransaction,
+        tip_accounts: &HashSet<Pubkey>,
+        fee_recipients: &[Pubkey],
+        token_effects: Option<&TokenEffects>,
+    ) -> Option<SolFlow> {
+        // 与其他解析器一致，带地址查找表的V0交易只有静态账户可用，暂不处理
+        if let VersionedMessage::V0(message) = &transaction.message {
+            if !message.address_table_lookups.is_empty() {
+                return None;
+            }
+        }
+
+        let account_keys = transaction.message.static_account_keys();
+        let fee_payer = *account_keys.first()?;
+        let wsol_accounts: HashSet<Pubkey> = token_effects
+            .map(|effects| {
+                effects
+                    .wsol_wrapped
+                    .iter()
+                    .map(|wrap| wrap.account)
+                    .chain(effects.wsol_unwrapped().map(|closed| closed.account))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut transfers = Vec::new();
+        for ix in transaction.message.instructions() {
+            if account_keys.get(ix.program_id_index as usize) != Some(&SYSTEM_PROGRAM_ID) {
+                continue;
+            }
+            let accounts: Vec<Pubkey> = ix
+                .accounts
+                .iter()
+                .filter_map(|&idx| account_keys.get(idx as usize).copied())
+                .collect();
+            let Some(transfer) = decode_system_transfer(&ix.data, &accounts) else {
+                continue;
+            };
+            if transfer.lamports == 0 || transfer.from == transfer.to {
+                continue;
+            }
+
+            let kind = if tip_accounts.contains(&transfer.to) {
+                FlowKind::Tip
+            } else if fee_recipients.contains(&transfer.to) {
+                FlowKind::FeeRecipient
+            } else if wsol_accounts.contains(&transfer.to) {
+                FlowKind::WsolWrap
+            } else if transfer.space.is_some() {
+                FlowKind::AccountCreation
+            } else {
+                FlowKind::ThirdParty
+            };
+            transfers.push(SolTransfer {
+                kind,
+                from: transfer.from,
+                to: transfer.to,
+                lamports: transfer.lamports,
+            });
+        }
+
+        (!transfers.is_empty()).then_some(SolFlow { fee_payer, transfers })
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset.checked_add(8)?)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
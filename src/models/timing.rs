@@ -0,0 +1,29 @@
+use std::time::{Duration, Instant};
+
+// 单个slot消息在流水线中的单调时间戳：gRPC边界收到 → 反序列化完成 → 解析完成
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotTiming {
+    pub received_at: Instant,
+    pub decoded_at: Instant,
+    pub parsed_at: Instant,
+}
+
+impl SlotTiming {
+    // 反序列化耗时
+    #[allow(dead_code)]
+    pub fn decode_latency(&self) -> Duration {
+        self.decoded_at.saturating_duration_since(self.received_at)
+    }
+
+    // 解析耗时
+    #[allow(dead_code)]
+    pub fn parse_latency(&self) -> Duration {
+        self.parsed_at.saturating_duration_since(self.decoded_at)
+    }
+
+    // 从收到消息到指定时刻的总耗时
+    #[allow(dead_code)]
+    pub fn since_received(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.received_at)
+    }
+}
@@ -0,0 +1,505 @@
+// SPL Token / Token-2022 / 关联代币账户（ATA）指令解码，汇总匹配交易在代币层面的副作用：
+// 创建的ATA、WSOL包装与解包、关闭的账户及回收的租金、代币转账
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde_json::{json, Value};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::labels;
+use crate::models::sol_flow::{decode_system_transfer, SystemTransfer};
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+pub const WSOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+// SPL Token账户大小（字节），不含Token-2022扩展
+pub const TOKEN_ACCOUNT_SIZE: u64 = 165;
+
+// 按当前租金参数计算免租金最低余额：(数据长度 + 128) × 3480 × 2
+#[allow(dead_code)]
+pub const fn rent_exempt_minimum(space: u64) -> u64 {
+    (space + 128) * 3480 * 2
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenProgram {
+    Token,
+    Token2022,
+    AssociatedToken,
+}
+
+impl TokenProgram {
+    #[allow(dead_code)]
+    pub fn from_program_id(program_id: &Pubkey) -> Option<Self> {
+        if *program_id == TOKEN_PROGRAM_ID {
+            Some(TokenProgram::Token)
+        } else if *program_id == TOKEN_2022_PROGRAM_ID {
+            Some(TokenProgram::Token2022)
+        } else if *program_id == ASSOCIATED_TOKEN_PROGRAM_ID {
+            Some(TokenProgram::AssociatedToken)
+        } else {
+            None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenProgram::Token => "SPL_TOKEN",
+            TokenProgram::Token2022 => "TOKEN_2022",
+            TokenProgram::AssociatedToken => "ATA",
+        }
+    }
+}
+
+// 代币指令类型；SPL Token与Token-2022共用前25个指令标签
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[allow(dead_code)]
+pub enum TokenInstructionType {
+    Unknown,
+    InitializeMint,
+    InitializeAccount,
+    InitializeMultisig,
+    Transfer,
+    Approve,
+    Revoke,
+    SetAuthority,
+    MintTo,
+    Burn,
+    CloseAccount,
+    FreezeAccount,
+    ThawAccount,
+    TransferChecked,
+    ApproveChecked,
+    MintToChecked,
+    BurnChecked,
+    InitializeAccount2,
+    SyncNative,
+    InitializeAccount3,
+    InitializeMultisig2,
+    InitializeMint2,
+    GetAccountDataSize,
+    InitializeImmutableOwner,
+    AmountToUiAmount,
+    UiAmountToAmount,
+    Token2022Extension, // Token-2022扩展指令（标签25及以上）
+    CreateAssociatedAccount,
+    CreateAssociatedAccountIdempotent,
+    RecoverNested,
+}
+
+const TOKEN_INSTRUCTIONS: [TokenInstructionType; 25] = [
+    TokenInstructionType::InitializeMint,
+    TokenInstructionType::InitializeAccount,
+    TokenInstructionType::InitializeMultisig,
+    TokenInstructionType::Transfer,
+    TokenInstructionType::Approve,
+    TokenInstructionType::Revoke,
+    TokenInstructionType::SetAuthority,
+    TokenInstructionType::MintTo,
+    TokenInstructionType::Burn,
+    TokenInstructionType::CloseAccount,
+    TokenInstructionType::FreezeAccount,
+    TokenInstructionType::ThawAccount,
+    TokenInstructionType::TransferChecked,
+    TokenInstructionType::ApproveChecked,
+    TokenInstructionType::MintToChecked,
+    TokenInstructionType::BurnChecked,
+    TokenInstructionType::InitializeAccount2,
+    TokenInstructionType::SyncNative,
+    TokenInstructionType::InitializeAccount3,
+    TokenInstructionType::InitializeMultisig2,
+    TokenInstructionType::InitializeMint2,
+    TokenInstructionType::GetAccountDataSize,
+    TokenInstructionType::InitializeImmutableOwner,
+    TokenInstructionType::AmountToUiAmount,
+    TokenInstructionType::UiAmountToAmount,
+];
+
+// 解码后的单条代币指令，账户已按交易的账户表解析
+#[derive(Debug, Clone)]
+pub struct TokenInstruction {
+    pub program: TokenProgram,
+    pub instruction_type: TokenInstructionType,
+    pub accounts: Vec<Pubkey>,
+    pub amount: Option<u64>,
+    pub decimals: Option<u8>,
+}
+
+impl TokenInstruction {
+    fn account(&self, index: usize) -> Option<Pubkey> {
+        self.accounts.get(index).copied()
+    }
+}
+
+// 新建的关联代币账户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedAta {
+    pub account: Pubkey,
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    // CreateIdempotent：账户已存在时不报错，无法判断是否真的新建
+    pub idempotent: bool,
+}
+
+// SOL包装为WSOL：lamports为本交易中转入该账户、超出免租金部分的SOL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsolWrap {
+    pub account: Pubkey,
+    pub lamports: u64,
+}
+
+// 关闭的代币账户；WSOL账户关闭即解包，余额连同租金一起转给destination
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedAccount {
+    pub program: TokenProgram,
+    pub account: Pubkey,
+    pub destination: Pubkey,
+    pub owner: Option<Pubkey>,
+    pub wsol: bool,
+    // 回收的租金；Token-2022账户大小取决于扩展，无法从指令推断
+    pub rent_lamports: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenTransfer {
+    pub program: TokenProgram,
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Option<Pubkey>,
+    // 只有TransferChecked带mint和精度
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub decimals: Option<u8>,
+}
+
+// 一笔交易在代币层面的副作用
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenEffects {
+    pub atas_created: Vec<CreatedAta>,
+    pub wsol_wrapped: Vec<WsolWrap>,
+    pub accounts_closed: Vec<ClosedAccount>,
+    pub transfers: Vec<TokenTransfer>,
+}
+
+impl TokenEffects {
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.atas_created.is_empty()
+            && self.wsol_wrapped.is_empty()
+            && self.accounts_closed.is_empty()
+            && self.transfers.is_empty()
+    }
+
+    // 解包的WSOL账户（关闭的WSOL账户）
+    #[allow(dead_code)]
+    pub fn wsol_unwrapped(&self) -> impl Iterator<Item = &ClosedAccount> {
+        self.accounts_closed.iter().filter(|closed| closed.wsol)
+    }
+
+    // 可确定的回收租金合计
+    #[allow(dead_code)]
+    pub fn rent_reclaimed(&self) -> u64 {
+        self.accounts_closed.iter().filter_map(|closed| closed.rent_lamports).sum()
+    }
+
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "atas_created": self.atas_created.iter().map(|ata| json!({
+                "account": ata.account.to_string(),
+                "wallet": ata.wallet.to_string(),
+                "mint": ata.mint.to_string(),
+                "token_program": ata.token_program.to_string(),
+                "idempotent": ata.idempotent,
+            })).collect::<Vec<_>>(),
+            "wsol_wrapped": self.wsol_wrapped.iter().map(|wrap| json!({
+                "account": wrap.account.to_string(),
+                "lamports": wrap.lamports,
+            })).collect::<Vec<_>>(),
+            "wsol_unwrapped": self.wsol_unwrapped().map(|closed| json!({
+                "account": closed.account.to_string(),
+                "destination": closed.destination.to_string(),
+            })).collect::<Vec<_>>(),
+            "accounts_closed": self.accounts_closed.iter().map(|closed| json!({
+                "program": closed.program.name(),
+                "account": closed.account.to_string(),
+                "destination": closed.destination.to_string(),
+                "owner": closed.owner.map(|owner| owner.to_string()),
+                "wsol": closed.wsol,
+                "rent_lamports": closed.rent_lamports,
+            })).collect::<Vec<_>>(),
+            "rent_reclaimed": self.rent_reclaimed(),
+            "transfers": self.transfers.iter().map(|transfer| json!({
+                "program": transfer.program.name(),
+                "source": transfer.source.to_string(),
+                "destination": transfer.destination.to_string(),
+                "authority": transfer.authority.map(|authority| authority.to_string()),
+                "mint": transfer.mint.map(|mint| mint.to_string()),
+                "amount": transfer.amount,
+                "decimals": transfer.decimals,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+impl fmt::Display for TokenEffects {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", labels::text("token_effects"))?;
+        for ata in &self.atas_created {
+            writeln!(
+                f,
+                "  {}: {} {}={} {}={}{}",
+                labels::text("ata_created"),
+                ata.account,
+                labels::text("wallet"),
+                ata.wallet,
+                labels::text("mint"),
+                ata.mint,
+                if ata.idempotent { format!(" ({})", labels::text("idempotent")) } else { String::new() }
+            )?;
+        }
+        for wrap in &self.wsol_wrapped {
+            writeln!(f, "  {}: {} {}={}", labels::text("wsol_wrapped"), wrap.account, labels::text("lamports"), wrap.lamports)?;
+        }
+        for closed in &self.accounts_closed {
+            let key = if closed.wsol { "wsol_unwrapped" } else { "account_closed" };
+            write!(f, "  {}: {} -> {}", labels::text(key), closed.account, closed.destination)?;
+            if let Some(rent) = closed.rent_lamports {
+                write!(f, " {}={}", labels::text("rent_reclaimed"), rent)?;
+            }
+            writeln!(f)?;
+        }
+        for transfer in &self.transfers {
+            write!(
+                f,
+                "  {}: {} -> {} {}={}",
+                labels::text("token_transfer"),
+                transfer.source,
+                transfer.destination,
+                labels::text("amount"),
+                transfer.amount
+            )?;
+            if let Some(mint) = transfer.mint {
+                write!(f, " {}={}", labels::text("mint"), mint)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// 代币指令解析器
+#[allow(dead_code)]
+pub struct TokenParser;
+
+impl TokenParser {
+    // 解析交易中的代币指令并汇总副作用；没有代币活动时返回None
+    #[allow(dead_code)]
+    pub fn parse_transaction(transaction: &VersionedTransaction) -> Option<TokenEffects> {
+        // 与其他解析器一致，带地址查找表的V0交易只有静态账户可用，暂不处理
+        if let VersionedMessage::V0(message) = &transaction.message {
+            if !message.address_table_lookups.is_empty() {
+                return None;
+            }
+        }
+
+        let account_keys = transaction.message.static_account_keys();
+        let mut instructions = Vec::new();
+        // 本交易中System Program转入各账户的lamports，以及CreateAccount的 (lamports, space)
+        let mut funded: HashMap<Pubkey, u64> = HashMap::new();
+        let mut created: HashMap<Pubkey, (u64, u64)> = HashMap::new();
+
+        for ix in transaction.message.instructions() {
+            let Some(program_id) = account_keys.get(ix.program_id_index as usize) else {
+                continue;
+            };
+            let accounts: Vec<Pubkey> = ix
+                .accounts
+                .iter()
+                .filter_map(|&idx| account_keys.get(idx as usize).copied())
+                .collect();
+
+            if *program_id == SYSTEM_PROGRAM_ID {
+                match decode_system_transfer(&ix.data, &accounts) {
+                    Some(SystemTransfer { to, lamports, space: Some(space), .. }) => {
+                        created.insert(to, (lamports, space));
+                    }
+                    Some(SystemTransfer { to, lamports, space: None, .. }) => *funded.entry(to).or_default() += lamports,
+                    None => {}
+                }
+            } else if let Some(program) = TokenProgram::from_program_id(program_id) {
+                instructions.push(Self::decode(program, &ix.data, accounts));
+            }
+        }
+
+        if instructions.is_empty() {
+            return None;
+        }
+        let effects = Self::summarize(&instructions, &funded, &created);
+        (!effects.is_empty()).then_some(effects)
+    }
+
+    // 解码单条代币程序指令
+    #[allow(dead_code)]
+    pub fn decode(program: TokenProgram, data: &[u8], accounts: Vec<Pubkey>) -> TokenInstruction {
+        let (instruction_type, amount, decimals) = match program {
+            TokenProgram::AssociatedToken => {
+                // 早期的Create指令没有数据
+                let instruction_type = match data.first() {
+                    None | Some(0) => TokenInstructionType::CreateAssociatedAccount,
+                    Some(1) => TokenInstructionType::CreateAssociatedAccountIdempotent,
+                    Some(2) => TokenInstructionType::RecoverNested,
+                    Some(_) => TokenInstructionType::Unknown,
+                };
+                (instruction_type, None, None)
+            }
+            TokenProgram::Token | TokenProgram::Token2022 => {
+                let instruction_type = match data.first() {
+                    Some(&tag) if (tag as usize) < TOKEN_INSTRUCTIONS.len() => TOKEN_INSTRUCTIONS[tag as usize],
+                    Some(_) if program == TokenProgram::Token2022 => TokenInstructionType::Token2022Extension,
+                    _ => TokenInstructionType::Unknown,
+                };
+                let amount = match instruction_type {
+                    TokenInstructionType::Transfer
+                    | TokenInstructionType::Approve
+                    | TokenInstructionType::MintTo
+                    | TokenInstructionType::Burn
+                    | TokenInstructionType::TransferChecked
+                    | TokenInstructionType::ApproveChecked
+                    | TokenInstructionType::MintToChecked
+                    | TokenInstructionType::BurnChecked => read_u64(data, 1),
+                    _ => None,
+                };
+                let decimals = match instruction_type {
+                    TokenInstructionType::TransferChecked
+                    | TokenInstructionType::ApproveChecked
+                    | TokenInstructionType::MintToChecked
+                    | TokenInstructionType::BurnChecked => data.get(9).copied(),
+                    _ => None,
+                };
+                (instruction_type, amount, decimals)
+            }
+        };
+
+        TokenInstruction {
+            program,
+            instruction_type,
+            accounts,
+            amount,
+            decimals,
+        }
+    }
+
+    fn summarize(
+        instructions: &[TokenInstruction],
+        funded: &HashMap<Pubkey, u64>,
+        created: &HashMap<Pubkey, (u64, u64)>,
+    ) -> TokenEffects {
+        use TokenInstructionType as T;
+
+        // 先找出本交易中可确定为WSOL的账户
+        let mut wsol_accounts = HashSet::new();
+        for ix in instructions {
+            let wsol = match ix.instruction_type {
+                T::CreateAssociatedAccount | T::CreateAssociatedAccountIdempotent => {
+                    ix.account(1).filter(|_| ix.account(3) == Some(WSOL_MINT))
+                }
+                T::InitializeAccount | T::InitializeAccount2 | T::InitializeAccount3 => {
+                    ix.account(0).filter(|_| ix.account(1) == Some(WSOL_MINT))
+                }
+                T::SyncNative => ix.account(0),
+                _ => None,
+            };
+            wsol_accounts.extend(wsol);
+            if ix.instruction_type == T::TransferChecked && ix.account(1) == Some(WSOL_MINT) {
+                wsol_accounts.extend(ix.account(0));
+                wsol_accounts.extend(ix.account(2));
+            }
+        }
+
+        let mut effects = TokenEffects::default();
+        let mut wrapped = HashSet::new();
+        for ix in instructions {
+            match ix.instruction_type {
+                T::CreateAssociatedAccount | T::CreateAssociatedAccountIdempotent => {
+                    if let (Some(account), Some(wallet), Some(mint)) = (ix.account(1), ix.account(2), ix.account(3)) {
+                        effects.atas_created.push(CreatedAta {
+                            account,
+                            wallet,
+                            mint,
+                            token_program: ix.account(5).unwrap_or(TOKEN_PROGRAM_ID),
+                            idempotent: ix.instruction_type == T::CreateAssociatedAccountIdempotent,
+                        });
+                    }
+                }
+                // 初始化时按当前余额设置WSOL数量，之后的SyncNative同步新转入的SOL
+                T::InitializeAccount | T::InitializeAccount2 | T::InitializeAccount3 | T::SyncNative => {
+                    if let Some(account) = ix.account(0).filter(|account| wsol_accounts.contains(account)) {
+                        if wrapped.insert(account) {
+                            let deposited = created
+                                .get(&account)
+                                .map(|(lamports, space)| lamports.saturating_sub(rent_exempt_minimum(*space)))
+                                .unwrap_or_default();
+                            let lamports = deposited + funded.get(&account).copied().unwrap_or_default();
+                            effects.wsol_wrapped.push(WsolWrap { account, lamports });
+                        }
+                    }
+                }
+                T::CloseAccount => {
+                    if let (Some(account), Some(destination)) = (ix.account(0), ix.account(1)) {
+                        effects.accounts_closed.push(ClosedAccount {
+                            program: ix.program,
+                            account,
+                            destination,
+                            owner: ix.account(2),
+                            wsol: wsol_accounts.contains(&account),
+                            rent_lamports: (ix.program == TokenProgram::Token)
+                                .then_some(rent_exempt_minimum(TOKEN_ACCOUNT_SIZE)),
+                        });
+                    }
+                }
+                T::Transfer => {
+                    if let (Some(source), Some(destination), Some(amount)) = (ix.account(0), ix.account(1), ix.amount) {
+                        effects.transfers.push(TokenTransfer {
+                            program: ix.program,
+                            source,
+                            destination,
+                            authority: ix.account(2),
+                            mint: None,
+                            amount,
+                            decimals: None,
+                        });
+                    }
+                }
+                T::TransferChecked => {
+                    if let (Some(source), Some(destination), Some(amount)) = (ix.account(0), ix.account(2), ix.amount) {
+                        effects.transfers.push(TokenTransfer {
+                            program: ix.program,
+                            source,
+                            destination,
+                            authority: ix.account(3),
+                            mint: ix.account(1),
+                            amount,
+                            decimals: ix.decimals,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        effects
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
@@ -1,7 +1,25 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{self, Write};
+use std::time::Instant;
+use serde_json::{json, Value};
 use crate::models::pump_parser::PumpTransaction;
 use crate::models::pumpamm_parser::PumpAmmTransaction;
 use crate::models::boop_parser::BoopTransaction;
+use crate::models::idl_parser::IdlTransaction;
+use crate::models::timing::SlotTiming;
+use crate::models::sol_flow::SolFlow;
+use crate::models::token_parser::TokenEffects;
+use crate::labels;
+use crate::utils::account_layout::AccountAnomaly;
+
+// 解析器无法识别鉴别器的一条指令；解析器只把它留在结果中，日志与汇总由services层处理
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnknownInstruction<'a> {
+    pub parser: &'static str,
+    pub signature: &'a str,
+    pub data: &'a [u8],
+    pub accounts: usize,
+}
 
 // 交易结果容器，性能优化版本
 #[derive(Default, Debug)]
@@ -13,13 +31,27 @@ pub struct TransactionResults {
     #[allow(dead_code)]
     pub boop_signatures: HashSet<String>,
     #[allow(dead_code)]
+    pub idl_signatures: HashSet<String>,
+    #[allow(dead_code)]
     pub pump_transactions: Vec<PumpTransaction>, // 存储PUMP交易的详细信息
     #[allow(dead_code)]
     pub pumpamm_transactions: Vec<PumpAmmTransaction>, // 存储PUMP_AMM交易的详细信息
     #[allow(dead_code)]
     pub boop_transactions: Vec<BoopTransaction>, // 存储boop交易的详细信息
     #[allow(dead_code)]
+    pub idl_transactions: Vec<IdlTransaction>, // 存储运行时IDL解码的交易
+    #[allow(dead_code)]
     pub current_slot: u64, // 存储当前处理的slot
+    #[allow(dead_code)]
+    pub timing: Option<SlotTiming>, // 实时订阅时的流水线时间戳
+    #[allow(dead_code)]
+    pub token_effects: HashMap<String, TokenEffects>, // 按签名存储匹配交易的代币副作用
+    #[allow(dead_code)]
+    pub sol_flows: HashMap<String, SolFlow>, // 按签名存储匹配交易的SOL流向
+    #[allow(dead_code)]
+    pub attributions: HashMap<String, String>, // 按签名存储pump、PumpSwap和boop交易的来源工具
+    #[allow(dead_code)]
+    pub transaction_indices: HashMap<String, usize>, // 按签名存储匹配交易在本条消息中的序号（entry顺序）
 }
 
 impl TransactionResults {
@@ -30,10 +62,17 @@ impl TransactionResults {
             pump_signatures: HashSet::with_capacity(128),
             pumpamm_signatures: HashSet::with_capacity(128),
             boop_signatures: HashSet::with_capacity(128),
+            idl_signatures: HashSet::new(),
             pump_transactions: Vec::with_capacity(128),
             pumpamm_transactions: Vec::with_capacity(128),
             boop_transactions: Vec::with_capacity(128),
+            idl_transactions: Vec::new(),
             current_slot: 0,
+            timing: None,
+            token_effects: HashMap::new(),
+            sol_flows: HashMap::new(),
+            attributions: HashMap::new(),
+            transaction_indices: HashMap::new(),
         }
     }
 
@@ -41,6 +80,7 @@ impl TransactionResults {
     #[inline]
     pub fn has_results(&self) -> bool {
         !self.pump_signatures.is_empty() || !self.pumpamm_signatures.is_empty() || !self.boop_signatures.is_empty()
+            || !self.idl_signatures.is_empty()
     }
     
     // 设置当前slot
@@ -80,6 +120,95 @@ impl TransactionResults {
         self.boop_transactions.push(transaction);
     }
 
+    // 添加运行时IDL解码的交易
+    #[allow(dead_code)]
+    #[inline]
+    pub fn add_idl_transaction(&mut self, transaction: IdlTransaction) {
+        self.idl_signatures.insert(transaction.signature.clone());
+        self.idl_transactions.push(transaction);
+    }
+
+    // 记录匹配交易的代币副作用
+    #[allow(dead_code)]
+    #[inline]
+    pub fn add_token_effects(&mut self, signature: String, effects: TokenEffects) {
+        self.token_effects.insert(signature, effects);
+    }
+
+    // 记录匹配交易的SOL流向
+    #[allow(dead_code)]
+    #[inline]
+    pub fn add_sol_flow(&mut self, signature: String, flow: SolFlow) {
+        self.sol_flows.insert(signature, flow);
+    }
+
+    // 记录交易的来源工具
+    #[allow(dead_code)]
+    #[inline]
+    pub fn add_attribution(&mut self, signature: String, tool: String) {
+        self.attributions.insert(signature, tool);
+    }
+
+    // 记录匹配交易在消息中的序号
+    #[allow(dead_code)]
+    #[inline]
+    pub fn add_transaction_index(&mut self, signature: String, index: usize) {
+        self.transaction_indices.insert(signature, index);
+    }
+
+    // 各解析器未识别鉴别器的指令，按 PUMP / PUMPAMM / BOOP 的顺序
+    #[allow(dead_code)]
+    pub fn unknown_instructions(&self) -> Vec<UnknownInstruction<'_>> {
+        let mut unknown = Vec::new();
+        for tx in &self.pump_transactions {
+            for ix in tx.instructions.iter().filter(|ix| ix.instruction_type.is_none()) {
+                unknown.push(UnknownInstruction {
+                    parser: "PUMP",
+                    signature: &tx.signature,
+                    data: &ix.data,
+                    accounts: ix.accounts.len(),
+                });
+            }
+        }
+        for tx in &self.pumpamm_transactions {
+            for ix in tx.instructions.iter().filter(|ix| ix.instruction_type.is_none()) {
+                unknown.push(UnknownInstruction {
+                    parser: "PUMPAMM",
+                    signature: &tx.signature,
+                    data: &ix.data,
+                    accounts: ix.accounts.len(),
+                });
+            }
+        }
+        for tx in &self.boop_transactions {
+            for ix in tx.instructions.iter().filter(|ix| ix.instruction_type.is_none()) {
+                unknown.push(UnknownInstruction {
+                    parser: "BOOP",
+                    signature: &tx.signature,
+                    data: &ix.data,
+                    accounts: ix.accounts.len(),
+                });
+            }
+        }
+        unknown
+    }
+
+    // 匹配交易在消息中的序号；并行解析的结果按批次完成顺序合并，需要entry顺序时按此排序
+    #[allow(dead_code)]
+    pub fn transaction_index(&self, signature: &str) -> Option<usize> {
+        self.transaction_indices.get(signature).copied()
+    }
+
+    // 本slot内各来源工具的交易数
+    #[allow(dead_code)]
+    pub fn attribution_counts(&self) -> BTreeMap<&str, u64> {
+        let mut counts = BTreeMap::new();
+        for tool in self.attributions.values() {
+            *counts.entry(tool.as_str()).or_default() += 1;
+        }
+        counts
+    }
+
     // 批量添加PUMP交易
     #[allow(dead_code)]
     #[inline]
@@ -107,42 +236,220 @@ impl TransactionResults {
         }
     }
 
+    // 批量添加运行时IDL解码的交易
+    #[allow(dead_code)]
+    #[inline]
+    pub fn add_idl_transactions(&mut self, transactions: Vec<IdlTransaction>) {
+        for tx in transactions {
+            self.add_idl_transaction(tx);
+        }
+    }
+
     #[allow(dead_code)]
     pub fn print(&self) {
+        let stdout = io::stdout();
+        let _ = self.write_to(&mut stdout.lock());
+    }
+
+    // 将解析结果按打印格式写入任意输出
+    #[allow(dead_code)]
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
         if !self.has_results() {
-            return;
+            return Ok(());
         }
         
 
         // 打印PUMP交易
         for tx in &self.pump_transactions {
-            println!("--------------------------------------------------------");
-            println!("Parser:PUMP");
-            println!("Slot:{}", self.current_slot);
-            println!("Signature:{}", tx.signature);
-            println!("{}", tx);
-            println!("--------------------------------------------------------");
+            writeln!(out, "--------------------------------------------------------")?;
+            self.write_header(out, "PUMP", &tx.signature)?;
+            writeln!(out, "{}", tx)?;
+            self.write_side_effects(out, &tx.signature)?;
+            writeln!(out, "--------------------------------------------------------")?;
         }
         
         // 打印PUMP_AMM交易
         for tx in &self.pumpamm_transactions {
-            println!("--------------------------------------------------------");
-            println!("Parser:PUMPAMM");
-            println!("Slot:{}", self.current_slot);
-            println!("Signature:{}", tx.signature);
-            println!("{}", tx);
-            println!("--------------------------------------------------------");
+            writeln!(out, "--------------------------------------------------------")?;
+            self.write_header(out, "PUMPAMM", &tx.signature)?;
+            writeln!(out, "{}", tx)?;
+            self.write_side_effects(out, &tx.signature)?;
+            writeln!(out, "--------------------------------------------------------")?;
         }
 
         
         // 打印boop交易
         for tx in &self.boop_transactions {
-            println!("--------------------------------------------------------");
-            println!("Parser:BOOP");
-            println!("Slot:{}", self.current_slot);
-            println!("Signature:{}", tx.signature);
-            println!("{}", tx);
-            println!("--------------------------------------------------------");
+            writeln!(out, "--------------------------------------------------------")?;
+            self.write_header(out, "BOOP", &tx.signature)?;
+            writeln!(out, "{}", tx)?;
+            self.write_side_effects(out, &tx.signature)?;
+            writeln!(out, "--------------------------------------------------------")?;
+        }
+
+        // 打印运行时IDL解码的交易
+        for tx in &self.idl_transactions {
+            writeln!(out, "--------------------------------------------------------")?;
+            self.write_header(out, "IDL", &tx.signature)?;
+            writeln!(out, "{}", tx)?;
+            self.write_side_effects(out, &tx.signature)?;
+            writeln!(out, "--------------------------------------------------------")?;
+        }
+
+        // 每个slot末尾输出归因计数
+        let counts = self.attribution_counts();
+        if !counts.is_empty() {
+            let counts: Vec<String> = counts.iter().map(|(tool, count)| format!("{}={}", tool, count)).collect();
+            writeln!(out, "{}:{} {}", labels::text("attribution_counts"), self.current_slot, counts.join(" "))?;
+        }
+
+        Ok(())
+    }
+
+    // 交易有代币副作用或SOL流向时追加在指令之后
+    fn write_side_effects<W: Write>(&self, out: &mut W, signature: &str) -> io::Result<()> {
+        if let Some(effects) = self.token_effects.get(signature) {
+            write!(out, "{}", effects)?;
+        }
+        if let Some(flow) = self.sol_flows.get(signature) {
+            write!(out, "{}", flow)?;
+        }
+        Ok(())
+    }
+
+    // 每笔交易的头部：解析器、slot、各阶段耗时（微秒，有时间戳时）与签名
+    fn write_header<W: Write>(&self, out: &mut W, parser: &str, signature: &str) -> io::Result<()> {
+        writeln!(out, "{}:{}", labels::text("parser"), parser)?;
+        writeln!(out, "{}:{}", labels::text("slot"), self.current_slot)?;
+        if let Some(timing) = &self.timing {
+            writeln!(
+                out,
+                "{}: {}={} {}={}",
+                labels::text("latency_us"),
+                labels::text("decode"),
+                timing.decode_latency().as_micros(),
+                labels::text("parse"),
+                timing.parse_latency().as_micros()
+            )?;
+        }
+        if let Some(tool) = self.attributions.get(signature) {
+            writeln!(out, "{}:{}", labels::text("attribution"), tool)?;
+        }
+        writeln!(out, "{}:{}", labels::text("signature"), signature)
+    }
+
+    // 转换为JSON记录，每笔交易一条
+    #[allow(dead_code)]
+    pub fn to_json_records(&self) -> Vec<Value> {
+        let mut records = Vec::new();
+
+        for tx in &self.pump_transactions {
+            let instructions = tx
+                .instructions
+                .iter()
+                .map(|ix| instruction_json(ix.kind().to_string(), ix.decoded_args(), &ix.accounts, &ix.data, ix.account_anomaly()))
+                .collect();
+            records.push(self.record_json("PUMP", &tx.signature, instructions));
+        }
+
+        for tx in &self.pumpamm_transactions {
+            let instructions = tx
+                .instructions
+                .iter()
+                .map(|ix| instruction_json(ix.kind().to_string(), ix.decoded_args(), &ix.accounts, &ix.data, ix.account_anomaly()))
+                .collect();
+            records.push(self.record_json("PUMPAMM", &tx.signature, instructions));
+        }
+
+        for tx in &self.boop_transactions {
+            let instructions = tx
+                .instructions
+                .iter()
+                .map(|ix| instruction_json(ix.kind().to_string(), ix.decoded_args(), &ix.accounts, &ix.data, ix.account_anomaly()))
+                .collect();
+            records.push(self.record_json("BOOP", &tx.signature, instructions));
+        }
+
+        for tx in &self.idl_transactions {
+            let instructions = tx
+                .instructions
+                .iter()
+                .map(|ix| {
+                    json!({
+                        "program": ix.program,
+                        "type": ix.name,
+                        "args": ix.args,
+                        "accounts": ix
+                            .accounts
+                            .iter()
+                            .map(|(name, key)| json!({"name": name, "pubkey": key.to_string()}))
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            records.push(self.record_json("IDL", &tx.signature, instructions));
+        }
+
+        records
+    }
+
+    // 以每行一条JSON记录的格式写入任意输出
+    #[allow(dead_code)]
+    pub fn write_json_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for record in self.to_json_records() {
+            writeln!(out, "{}", record)?;
+        }
+        // 每个slot末尾一条归因计数记录（没有signature字段）
+        let counts = self.attribution_counts();
+        if !counts.is_empty() {
+            writeln!(out, "{}", json!({"slot": self.current_slot, "attribution_counts": counts}))?;
+        }
+        Ok(())
+    }
+
+    fn record_json(&self, parser: &str, signature: &str, instructions: Vec<Value>) -> Value {
+        let mut record = json!({
+            "parser": parser,
+            "slot": self.current_slot,
+            "signature": signature,
+            "instructions": instructions,
+        });
+        if let Some(effects) = self.token_effects.get(signature) {
+            record["token_effects"] = effects.to_json();
+        }
+        if let Some(flow) = self.sol_flows.get(signature) {
+            record["sol_flow"] = flow.to_json();
         }
+        if let Some(tool) = self.attributions.get(signature) {
+            record["attribution"] = json!(tool);
+        }
+        if let Some(timing) = &self.timing {
+            record["latency_us"] = json!({
+                "decode": timing.decode_latency().as_micros() as u64,
+                "parse": timing.parse_latency().as_micros() as u64,
+                "total": timing.since_received(Instant::now()).as_micros() as u64,
+            });
+        }
+        record
+    }
+}
+
+fn instruction_json(
+    instruction_type: String,
+    args: Option<Value>,
+    accounts: &[String],
+    data: &[u8],
+    anomaly: Option<AccountAnomaly>,
+) -> Value {
+    let mut instruction = json!({
+        "type": instruction_type,
+        "args": args,
+        "accounts": accounts,
+        "data": hex::encode(data),
+    });
+    // 账户数与布局不一致时才输出
+    if let Some(anomaly) = anomaly {
+        instruction["account_anomaly"] = anomaly.to_json();
     }
-} 
\ No newline at end of file
+    instruction
+}
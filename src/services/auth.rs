@@ -0,0 +1,195 @@
+use jito_protos::auth::{
+    auth_service_client::AuthServiceClient, GenerateAuthChallengeRequest,
+    GenerateAuthTokensRequest, RefreshAccessTokenRequest, Role,
+};
+use solana_sdk::signature::{Keypair, Signer};
+use std::io;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+use tonic::metadata::MetadataValue;
+use tonic::service::Interceptor;
+use tonic::transport::Channel;
+use tonic::{Request, Status};
+
+// 在令牌过期前多久刷新
+const REFRESH_MARGIN_SECS: u64 = 60;
+// 服务端没有给出access_token过期时间时假定的有效期
+const DEFAULT_ACCESS_LIFETIME_SECS: u64 = 5 * 60;
+
+// 向请求注入Bearer令牌的拦截器，令牌为空时不做处理（未启用认证）
+#[derive(Clone, Default)]
+pub struct AuthInterceptor {
+    access_token: Arc<RwLock<String>>,
+}
+
+impl AuthInterceptor {
+    #[allow(dead_code)]
+    pub fn new(access_token: Arc<RwLock<String>>) -> Self {
+        Self { access_token }
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let token = self.access_token.read().unwrap();
+        if !token.is_empty() {
+            let value = MetadataValue::try_from(format!("Bearer {}", token))
+                .map_err(|e| Status::internal(e.to_string()))?;
+            request.metadata_mut().insert("authorization", value);
+        }
+        Ok(request)
+    }
+}
+
+// 认证流程得到的令牌
+#[derive(Debug, Clone)]
+pub struct AuthTokens {
+    pub access_token: String,
+    pub access_expires_at: u64, // unix秒
+    pub refresh_token: String,
+    pub refresh_expires_at: u64, // unix秒
+}
+
+// 通过挑战-签名流程获取访问令牌
+#[allow(dead_code)]
+pub async fn authenticate(
+    channel: Channel,
+    keypair: &Keypair,
+    role: Role,
+) -> Result<AuthTokens, io::Error> {
+    let mut client = AuthServiceClient::new(channel);
+
+    let challenge_resp = client
+        .generate_auth_challenge(GenerateAuthChallengeRequest {
+            role: role as i32,
+            pubkey: keypair.pubkey().as_ref().to_vec(),
+        })
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e.to_string()))?
+        .into_inner();
+
+    // 服务端要求签名内容为 "pubkey-challenge"
+    let challenge = format!("{}-{}", keypair.pubkey(), challenge_resp.challenge);
+    let signed_challenge = keypair.sign_message(challenge.as_bytes()).as_ref().to_vec();
+
+    let tokens = client
+        .generate_auth_tokens(GenerateAuthTokensRequest {
+            challenge,
+            client_pubkey: keypair.pubkey().as_ref().to_vec(),
+            signed_challenge,
+        })
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e.to_string()))?
+        .into_inner();
+
+    let access_token = tokens
+        .access_token
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "缺少access_token"))?;
+    let refresh_token = tokens
+        .refresh_token
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "缺少refresh_token"))?;
+
+    Ok(AuthTokens {
+        access_expires_at: access_expires_at(access_token.expires_at_utc.map(|t| t.seconds)),
+        access_token: access_token.value,
+        refresh_expires_at: refresh_token.expires_at_utc.map(|t| t.seconds.max(0) as u64).unwrap_or(0),
+        refresh_token: refresh_token.value,
+    })
+}
+
+// 已认证的会话：持有供拦截器使用的共享令牌与后台刷新任务，drop时停止刷新
+pub struct AuthSession {
+    access_token: Arc<RwLock<String>>,
+    refresh_task: JoinHandle<()>,
+}
+
+impl AuthSession {
+    #[allow(dead_code)]
+    pub fn access_token(&self) -> Arc<RwLock<String>> {
+        self.access_token.clone()
+    }
+}
+
+impl Drop for AuthSession {
+    fn drop(&mut self) {
+        self.refresh_task.abort();
+    }
+}
+
+// 认证并启动后台刷新任务
+#[allow(dead_code)]
+pub async fn authenticate_and_refresh(
+    channel: Channel,
+    keypair: Arc<Keypair>,
+    role: Role,
+) -> Result<AuthSession, io::Error> {
+    let tokens = authenticate(channel.clone(), &keypair, role).await?;
+    let shared = Arc::new(RwLock::new(tokens.access_token.clone()));
+
+    let shared_clone = shared.clone();
+    let refresh_task = tokio::spawn(async move {
+        let mut tokens = tokens;
+        loop {
+            tokio::time::sleep(until_refresh(tokens.access_expires_at)).await;
+
+            let now = unix_now();
+            // refresh_token仍有效则刷新，否则重新走完整认证
+            let result = if tokens.refresh_expires_at > now + REFRESH_MARGIN_SECS {
+                refresh(channel.clone(), &tokens.refresh_token).await.map(|(token, expires_at)| {
+                    tokens.access_token = token;
+                    tokens.access_expires_at = expires_at;
+                })
+            } else {
+                authenticate(channel.clone(), &keypair, role).await.map(|t| tokens = t)
+            };
+
+            match result {
+                Ok(()) => *shared_clone.write().unwrap() = tokens.access_token.clone(),
+                Err(e) => {
+                    log::error!("刷新认证令牌失败: {e}");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        }
+    });
+
+    Ok(AuthSession {
+        access_token: shared,
+        refresh_task,
+    })
+}
+
+// 使用refresh_token获取新的access_token
+async fn refresh(channel: Channel, refresh_token: &str) -> Result<(String, u64), io::Error> {
+    let resp = AuthServiceClient::new(channel)
+        .refresh_access_token(RefreshAccessTokenRequest {
+            refresh_token: refresh_token.to_string(),
+        })
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e.to_string()))?
+        .into_inner();
+
+    let token = resp
+        .access_token
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "缺少access_token"))?;
+    Ok((token.value, access_expires_at(token.expires_at_utc.map(|t| t.seconds))))
+}
+
+// 没有过期时间时按默认有效期计算，避免把0当作已过期而不停刷新
+fn access_expires_at(expires_at_secs: Option<i64>) -> u64 {
+    expires_at_secs
+        .map(|seconds| seconds.max(0) as u64)
+        .unwrap_or_else(|| unix_now() + DEFAULT_ACCESS_LIFETIME_SECS)
+}
+
+fn until_refresh(expires_at: u64) -> Duration {
+    Duration::from_secs(expires_at.saturating_sub(unix_now() + REFRESH_MARGIN_SECS).max(1))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,275 @@
+// 未知鉴别器发现：按解析器汇总无法识别的指令（次数、slot范围、示例签名、数据长度与账户数分布），
+// 定期输出报告，程序升级后能在几分钟内发现新指令
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::config::DiscoveryConfig;
+use crate::idl::runtime::anchor_discriminator;
+use crate::idl::{IdlRegistry, PROGRAMS};
+use crate::labels;
+use crate::models::TransactionResults;
+
+// 每个鉴别器保留的示例签名数
+pub const MAX_EXAMPLES: usize = 3;
+
+// 单个未知鉴别器的汇总
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownDiscriminator {
+    pub count: u64,
+    pub first_slot: u64,
+    pub last_slot: u64,
+    pub examples: Vec<String>,
+    // 数据长度 -> 次数
+    pub data_lengths: BTreeMap<usize, u64>,
+    // 账户数 -> 次数
+    pub account_counts: BTreeMap<usize, u64>,
+    // 根据已知指令名推测的指令
+    pub guess: Option<String>,
+}
+
+impl UnknownDiscriminator {
+    fn new(slot: u64) -> Self {
+        Self {
+            count: 0,
+            first_slot: slot,
+            last_slot: slot,
+            examples: Vec::new(),
+            data_lengths: BTreeMap::new(),
+            account_counts: BTreeMap::new(),
+            guess: None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "count": self.count,
+            "first_slot": self.first_slot,
+            "last_slot": self.last_slot,
+            "examples": self.examples,
+            "data_lengths": counts_json(&self.data_lengths),
+            "account_counts": counts_json(&self.account_counts),
+            "guess": self.guess,
+        })
+    }
+}
+
+fn counts_json(counts: &BTreeMap<usize, u64>) -> Value {
+    Value::Object(counts.iter().map(|(k, v)| (k.to_string(), json!(v))).collect())
+}
+
+fn counts_text(counts: &BTreeMap<usize, u64>) -> String {
+    counts
+        .iter()
+        .map(|(k, v)| format!("{}×{}", k, v))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 解析器 -> 鉴别器（十六进制；数据不足8字节时为全部数据） -> 汇总
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiscoveryReport {
+    pub parsers: BTreeMap<&'static str, BTreeMap<String, UnknownDiscriminator>>,
+    // 候选指令名：鉴别器 -> 名称
+    candidates: BTreeMap<[u8; 8], String>,
+}
+
+impl DiscoveryReport {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 以编译期IDL中所有程序的指令名作为候选，用于猜测未知鉴别器
+    #[allow(dead_code)]
+    pub fn with_builtin_candidates() -> Self {
+        let mut report = Self::new();
+        for program in PROGRAMS {
+            for ix in program.instructions {
+                report
+                    .candidates
+                    .entry(ix.discriminator)
+                    .or_insert_with(|| format!("{}::{}", program.name, ix.name));
+            }
+        }
+        report
+    }
+
+    // 运行时IDL中的指令名也作为候选
+    #[allow(dead_code)]
+    pub fn add_registry_candidates(&mut self, registry: &IdlRegistry) {
+        for program in registry.programs() {
+            for ix in &program.instructions {
+                if let Ok(discriminator) = <[u8; 8]>::try_from(ix.discriminator.as_slice()) {
+                    self.candidates
+                        .entry(discriminator)
+                        .or_insert_with(|| format!("{}::{}", program.name, ix.name));
+                }
+            }
+        }
+    }
+
+    // 按Anchor规则计算候选指令名（snake_case）的鉴别器
+    #[allow(dead_code)]
+    pub fn add_candidate_names<I: IntoIterator<Item = S>, S: AsRef<str>>(&mut self, names: I) {
+        for name in names {
+            let name = name.as_ref();
+            self.candidates
+                .entry(anchor_discriminator(name))
+                .or_insert_with(|| name.to_string());
+        }
+    }
+
+    // 根据配置生成报告：内置IDL、运行时IDL与配置的候选名
+    #[allow(dead_code)]
+    pub fn from_config(config: &DiscoveryConfig, registry: Option<&IdlRegistry>) -> Self {
+        let mut report = Self::with_builtin_candidates();
+        if let Some(registry) = registry {
+            report.add_registry_candidates(registry);
+        }
+        report.add_candidate_names(&config.candidate_names);
+        report
+    }
+
+    // 记录一条未识别的指令
+    #[allow(dead_code)]
+    pub fn record(
+        &mut self,
+        parser: &'static str,
+        data: &[u8],
+        slot: u64,
+        signature: &str,
+        account_count: usize,
+    ) {
+        let prefix = &data[..data.len().min(8)];
+        let guess = <[u8; 8]>::try_from(prefix)
+            .ok()
+            .and_then(|discriminator| self.candidates.get(&discriminator).cloned());
+
+        let entry = self
+            .parsers
+            .entry(parser)
+            .or_default()
+            .entry(hex::encode(prefix))
+            .or_insert_with(|| UnknownDiscriminator::new(slot));
+
+        entry.count += 1;
+        entry.first_slot = entry.first_slot.min(slot);
+        entry.last_slot = entry.last_slot.max(slot);
+        if entry.examples.len() < MAX_EXAMPLES && !entry.examples.iter().any(|s| s == signature) {
+            entry.examples.push(signature.to_string());
+        }
+        *entry.data_lengths.entry(data.len()).or_default() += 1;
+        *entry.account_counts.entry(account_count).or_default() += 1;
+        entry.guess = guess;
+    }
+
+    // 从解析结果中收集各解析器的Unknown指令
+    #[allow(dead_code)]
+    pub fn record_results(&mut self, results: &TransactionResults) {
+        for unknown in results.unknown_instructions() {
+            self.record(unknown.parser, unknown.data, results.current_slot, unknown.signature, unknown.accounts);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, parser: &str, discriminator: &str) -> Option<&UnknownDiscriminator> {
+        self.parsers.get(parser)?.get(discriminator)
+    }
+
+    // 未知鉴别器的种数
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.parsers.values().map(BTreeMap::len).sum()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Value {
+        Value::Object(
+            self.parsers
+                .iter()
+                .map(|(parser, unknowns)| {
+                    let unknowns = unknowns
+                        .iter()
+                        .map(|(discriminator, unknown)| (discriminator.clone(), unknown.to_json()))
+                        .collect();
+                    (parser.to_string(), Value::Object(unknowns))
+                })
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for DiscoveryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (parser, unknowns) in &self.parsers {
+            for (discriminator, unknown) in unknowns {
+                write!(
+                    f,
+                    "{}:{} {}:{} {}:{} {}:{}-{}",
+                    labels::text("parser"),
+                    parser,
+                    labels::text("discriminator"),
+                    discriminator,
+                    labels::text("count"),
+                    unknown.count,
+                    labels::text("slots"),
+                    unknown.first_slot,
+                    unknown.last_slot
+                )?;
+                match &unknown.guess {
+                    Some(guess) => writeln!(f, " {}:{}", labels::text("guess"), guess)?,
+                    None => writeln!(f)?,
+                }
+                writeln!(f, "  {}: {}", labels::text("data_lengths"), counts_text(&unknown.data_lengths))?;
+                writeln!(f, "  {}: {}", labels::text("account_counts"), counts_text(&unknown.account_counts))?;
+                for signature in &unknown.examples {
+                    writeln!(f, "  {}: {}", labels::text("example"), signature)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// 输出一次报告：日志中列出汇总，配置了文件时覆盖写入JSON
+#[allow(dead_code)]
+pub fn write_report(report: &DiscoveryReport, file: Option<&Path>) -> io::Result<()> {
+    if report.is_empty() {
+        log::info!("未发现未知鉴别器");
+    } else {
+        log::warn!("发现 {} 种未知鉴别器:\n{}", report.len(), report.to_string().trim_end());
+    }
+    if let Some(path) = file {
+        let json = serde_json::to_string_pretty(&report.to_json()).map_err(|e| io::Error::other(e.to_string()))?;
+        fs::write(path, json)
+            .map_err(|e| io::Error::new(e.kind(), format!("无法写入发现报告 {}: {}", path.display(), e)))?;
+    }
+    Ok(())
+}
+
+// 定期输出报告，直到进程退出
+#[allow(dead_code)]
+pub async fn report_periodically(report: Arc<Mutex<DiscoveryReport>>, interval: Duration, file: Option<PathBuf>) {
+    let mut ticker = tokio::time::interval(interval);
+    // 第一次tick立即完成，跳过
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let snapshot = report.lock().unwrap().clone();
+        if let Err(e) = write_report(&snapshot, file.as_deref()) {
+            log::error!("{e}");
+        }
+    }
+}
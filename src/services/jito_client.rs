@@ -1,12 +1,29 @@
 use jito_protos::shredstream::{
     shredstream_proxy_client::ShredstreamProxyClient, SubscribeEntriesRequest,
 };
-use tokio::runtime::Runtime;
-use bincode;
 use std::io;
-use std::time::Instant;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
-use crate::services::transaction_processor::TransactionProcessor;
+use crate::analytics::SlotAnalytics;
+use crate::config::{Config, PipelineConfig, RuntimeConfig};
+use crate::export::writer::now_ms;
+use crate::export::EventExporter;
+use crate::models::TransactionResults;
+use crate::services::discovery::{self, DiscoveryReport};
+use crate::services::metrics;
+use crate::services::output::OutputSink;
+use crate::services::pipeline::{Pipeline, RawSlot};
+use crate::services::recording::RecordingWriter;
+use crate::services::threads;
+use crate::services::transaction_processor::ProcessorOptions;
+use crate::storage::StorageWriter;
+
+// 重连退避时间范围
+const RECONNECT_MIN_BACKOFF: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
 
 #[allow(dead_code)]
 pub struct JitoClient;
@@ -15,63 +32,222 @@ impl JitoClient {
     // 连接到Jito服务器并开始处理数据流
     #[allow(dead_code)]
     pub async fn connect_and_process(jito_url: String) -> Result<(), io::Error> {
-        // 创建更高级别的client配置
+        Self::connect_and_process_with(jito_url, |results| results.print()).await
+    }
+
+    // 连接到Jito服务器，将每个slot的解析结果交给回调处理
+    #[allow(dead_code)]
+    pub async fn connect_and_process_with<F>(jito_url: String, on_results: F) -> Result<(), io::Error>
+    where
+        F: FnMut(TransactionResults),
+    {
+        Self::connect_and_process_with_options(jito_url, ProcessorOptions::default(), on_results).await
+    }
+
+    // 同上，按配置生成的处理参数解析交易。反序列化与解析在流水线中进行，
+    // 有匹配交易的结果经有界通道回到当前任务交给回调，回调不需要Send
+    #[allow(dead_code)]
+    pub async fn connect_and_process_with_options<F>(
+        jito_url: String,
+        options: ProcessorOptions,
+        mut on_results: F,
+    ) -> Result<(), io::Error>
+    where
+        F: FnMut(TransactionResults),
+    {
+        let config = PipelineConfig::default();
+        let (tx, mut rx) = mpsc::channel(config.emit.capacity.max(1));
+        let pipeline = Pipeline::start(&config, options, move |results| {
+            // 接收方已退出时丢弃结果
+            if results.has_results() {
+                let _ = tx.blocking_send(results);
+            }
+        })?;
+
+        // 连接结束后关闭流水线，输出线程退出时通道随之关闭
+        let receive = async move {
+            let result = Self::connect_and_submit(jito_url, &pipeline).await;
+            tokio::task::spawn_blocking(move || pipeline.shutdown())
+                .await
+                .map_err(io::Error::other)?;
+            result
+        };
+        let deliver = async {
+            while let Some(results) = rx.recv().await {
+                on_results(results);
+            }
+        };
+        let (result, ()) = tokio::join!(receive, deliver);
+        result
+    }
+
+    // 接收阶段：只做时间戳与指标，原始载荷交给流水线录制、反序列化和解析
+    #[allow(dead_code)]
+    pub async fn connect_and_submit(jito_url: String, pipeline: &Pipeline) -> Result<(), io::Error> {
+        let metrics = metrics::global();
+        let endpoint = jito_url.clone();
+
         let mut client = ShredstreamProxyClient::connect(jito_url)
             .await
             .map_err(|e| io::Error::new(io::ErrorKind::ConnectionRefused, e.to_string()))?;
-            
+
         let mut stream = client
             .subscribe_entries(SubscribeEntriesRequest {})
             .await
             .map_err(|e| io::Error::new(io::ErrorKind::ConnectionAborted, e.to_string()))?
             .into_inner();
 
-        // 预先创建一个字符串缓冲区，避免重复分配
-        let mut tx_message_buf = String::with_capacity(1024);
-        
         while let Some(slot_entry) = stream.message().await.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))? {
-            let start_time = Instant::now();
-            
-            let entries =
-                match bincode::deserialize::<Vec<solana_entry::entry::Entry>>(&slot_entry.entries) {
-                    Ok(e) => e,
-                    Err(e) => {
-                        eprintln!("反序列化失败: {e}");
-                        continue;
-                    }
-                };
-                
-            // 处理该slot中的所有交易，并传递slot信息
-            let results = TransactionProcessor::process_entries(&entries, slot_entry.slot);
-            
-            // 只在有交易结果时才打印信息
-            if results.has_results() {
-                let _processing_time = start_time.elapsed();
-                
-                // 打印结果
-                results.print();
-                
-            }
-            
-            // 清空主缓冲区以便下次循环重用
-            tx_message_buf.clear();
+            // gRPC边界的单调时间戳，随结果一直传到输出
+            let received_at = Instant::now();
+            metrics.record_arrival(slot_entry.slot, received_at);
+            metrics.record_message(&endpoint, slot_entry.slot, slot_entry.entries.len());
+
+            pipeline
+                .submit(RawSlot {
+                    slot: slot_entry.slot,
+                    payload: slot_entry.entries,
+                    received_at,
+                })
+                .await;
         }
-        
+
         Ok(())
     }
+
+    // 持续订阅：连接失败或断开后按指数退避重连；收到过数据的会话结束后退避时间重置。
+    // 流水线跨连接保留，重连期间下游继续处理已入队的数据
+    #[allow(dead_code)]
+    pub async fn connect_with_reconnect(jito_url: String, pipeline: &Pipeline) -> Result<(), io::Error> {
+        let metrics = metrics::global();
+        let mut backoff = RECONNECT_MIN_BACKOFF;
+
+        loop {
+            let received = metrics.slots_received.get();
+            match Self::connect_and_submit(jito_url.clone(), pipeline).await {
+                Ok(()) => log::warn!("Shredstream连接已关闭"),
+                Err(e) => log::error!("Shredstream连接失败: {e}"),
+            }
+
+            if metrics.slots_received.get() > received {
+                backoff = RECONNECT_MIN_BACKOFF;
+            }
+            log::info!("{:?} 后重新连接", backoff);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+            metrics.reconnects.inc();
+        }
+    }
     
     // 创建一个同步方法启动客户端，方便在main中调用
     #[allow(dead_code)]
     pub fn start(jito_url: String) -> Result<(), io::Error> {
-        // 配置tokio运行时以获得最佳性能
-        let rt = Runtime::new()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
-        
-        // 设置最大工作线程数
-        let thread_count = num_cpus::get();
-        println!("使用 {} 线程处理交易", thread_count);
-        
+        let runtime = RuntimeConfig::default();
+        let rt = threads::tokio_runtime(&runtime)?;
+        log::info!(
+            "tokio工作线程 {}，解析使用rayon全局线程池（{} 线程）",
+            runtime.tokio_threads,
+            rayon::current_num_threads()
+        );
+
         // 启动处理循环
         rt.block_on(Self::connect_and_process(jito_url))
     }
-} 
\ No newline at end of file
+
+    // 按已校验的配置启动客户端：线程数、解析器、过滤条件与输出均来自配置；指定record时同时录制原始消息
+    #[allow(dead_code)]
+    pub fn start_with_config(config: Config, record: Option<&Path>) -> Result<(), io::Error> {
+        let pool = threads::parser_pool(config.processing.threads, &config.runtime.parser_cores)?;
+        let options = ProcessorOptions::from_config(&config)?.with_pool(pool);
+        let rt = threads::tokio_runtime(&config.runtime)?;
+        log::info!(
+            "解析线程 {}，tokio工作线程 {}",
+            options.parser_threads(),
+            config.runtime.tokio_threads
+        );
+
+        let mut sink = OutputSink::from_config(&config)?;
+
+        // 记录批次与线程设置，便于对照延迟直方图调优
+        let metrics = metrics::global();
+        metrics.batch_size.set(options.batch_size as u64);
+        metrics.worker_threads.set(options.parser_threads() as u64);
+        metrics.tokio_threads.set(config.runtime.tokio_threads as u64);
+        if let Some(slot_duration) = config.metrics.slot_duration() {
+            metrics.enable_slot_clock(slot_duration);
+        }
+        let recorder = match record {
+            Some(path) => Some(RecordingWriter::create(path)?),
+            None => None,
+        };
+
+        // 配置了监听地址时启动 /metrics 端点
+        if let Some(addr) = config.metrics.listen_addr() {
+            let listener = rt.block_on(metrics::bind(addr))?;
+            rt.spawn(metrics::serve(listener, metrics::global()));
+            log::info!("指标端点: http://{}/metrics", addr);
+        }
+
+        // 启用发现模式时汇总未知鉴别器并定期输出报告
+        let discovery = config.discovery.enabled.then(|| {
+            let report = Arc::new(Mutex::new(DiscoveryReport::from_config(
+                &config.discovery,
+                options.registry.as_deref(),
+            )));
+            rt.spawn(discovery::report_periodically(
+                report.clone(),
+                Duration::from_secs(config.discovery.report_interval_secs),
+                config.discovery.report_file.clone(),
+            ));
+            report
+        });
+
+        // 启用存储时在后台线程中按slot写入SQLite
+        let storage = config
+            .storage
+            .enabled
+            .then(|| StorageWriter::start(&config.storage))
+            .transpose()?;
+        let exporter = config
+            .export
+            .enabled
+            .then(|| EventExporter::start(&config.export))
+            .transpose()?;
+        // 启用的分析与回放相同，每个slot推进一次；池子状态从快照恢复并定期写回
+        let mut analytics = SlotAnalytics::from_config(&config)?;
+
+        // 反序列化、解析与输出在流水线的专用线程中进行，tokio运行时只负责接收
+        let pipeline = Pipeline::start_with_recorder(&config.pipeline, options, recorder, move |results| {
+            if results.has_results() {
+                if let Some(report) = &discovery {
+                    report.lock().unwrap().record_results(&results);
+                }
+                if let Some(storage) = &storage {
+                    storage.submit(&results);
+                }
+                if let Some(exporter) = &exporter {
+                    exporter.submit(&results, now_ms());
+                }
+                sink.emit(&results);
+            }
+            analytics.record(&results, &mut sink);
+            if let Err(e) = analytics.checkpoint(now_ms()) {
+                log::error!("{e}");
+            }
+        })?;
+        log::info!(
+            "流水线: {} 个反序列化线程，队列容量 decode={} parse={} emit={}",
+            config.pipeline.decode_threads,
+            config.pipeline.decode.capacity,
+            config.pipeline.parse.capacity,
+            config.pipeline.emit.capacity
+        );
+
+        // 接收循环在当前线程上运行；最后才绑定，避免之前创建的线程继承这一绑定
+        if let Some(core) = config.runtime.receive_cores.first() {
+            threads::pin_or_warn("接收", *core);
+        }
+
+        rt.block_on(Self::connect_with_reconnect(config.endpoints.shredstream_url, &pipeline))
+    }
+}
@@ -0,0 +1,199 @@
+// 日志：log门面的实现，输出到标准错误（标准输出只留给解析结果），支持按模块设置级别和JSON格式
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde_json::json;
+
+use crate::config::{LogFormat, LoggingConfig};
+use crate::models::TransactionResults;
+
+// 未知鉴别器日志的target，可单独设置级别，例如 "info,shreds_client::unknown_discriminator=off"
+pub const UNKNOWN_DISCRIMINATOR_TARGET: &str = "shreds_client::unknown_discriminator";
+
+// 未安装日志（例如库被直接调用）时的默认限流间隔
+const DEFAULT_RATE_LIMIT: Duration = Duration::from_secs(60);
+
+#[allow(dead_code)]
+pub struct Logger {
+    default: LevelFilter,
+    // 按前缀长度降序排列，最长匹配优先
+    targets: Vec<(String, LevelFilter)>,
+    format: LogFormat,
+}
+
+impl Logger {
+    #[allow(dead_code)]
+    pub fn new(config: &LoggingConfig) -> Result<Self, io::Error> {
+        let (default, mut targets) = config
+            .level_filters()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("logging.level 无效: {}", e)))?;
+        targets.sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+        Ok(Self {
+            default,
+            targets,
+            format: config.format,
+        })
+    }
+
+    // target对应的级别：取最长的匹配模块前缀，没有匹配时使用默认级别
+    #[allow(dead_code)]
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .find(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    // 所有target中最详细的级别，供log::set_max_level提前过滤
+    #[allow(dead_code)]
+    pub fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+
+    // 格式化一条日志（不含换行）
+    #[allow(dead_code)]
+    pub fn format_record(&self, record: &Record) -> String {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        match self.format {
+            LogFormat::Text => format!(
+                "{}.{:03} {:<5} {} {}",
+                now.as_secs(),
+                now.subsec_millis(),
+                record.level(),
+                record.target(),
+                record.args()
+            ),
+            LogFormat::Json => json!({
+                "ts": now.as_secs_f64(),
+                "level": record.level().as_str(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            })
+            .to_string(),
+        }
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let line = self.format_record(record);
+            let _ = writeln!(io::stderr().lock(), "{}", line);
+        }
+    }
+
+    fn flush(&self) {
+        let _ = io::stderr().flush();
+    }
+}
+
+// 安装全局日志，进程内只能安装一次
+#[allow(dead_code)]
+pub fn init(config: &LoggingConfig) -> Result<(), io::Error> {
+    let logger = Logger::new(config)?;
+    let max_level = logger.max_level();
+    log::set_boxed_logger(Box::new(logger))
+        .map_err(|e| io::Error::new(io::ErrorKind::AlreadyExists, format!("日志已初始化: {}", e)))?;
+    log::set_max_level(max_level);
+    let _ = UNKNOWN_DISCRIMINATORS.set(RateLimitedLog::new(Duration::from_secs(config.rate_limit_secs)));
+    Ok(())
+}
+
+struct Seen {
+    last_logged: Instant,
+    suppressed: u64,
+}
+
+// 去重限流的日志通道：同一个键首次出现时立即输出，之后在间隔内只计数，间隔过后的下一次出现连同累计次数一起输出
+#[allow(dead_code)]
+pub struct RateLimitedLog {
+    interval: Duration,
+    seen: Mutex<HashMap<String, Seen>>,
+}
+
+impl RateLimitedLog {
+    #[allow(dead_code)]
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 需要输出时返回上次输出后被抑制的次数，否则返回None
+    #[allow(dead_code)]
+    pub fn check(&self, key: &str, now: Instant) -> Option<u64> {
+        let mut seen = self.seen.lock().unwrap();
+        match seen.get_mut(key) {
+            None => {
+                seen.insert(
+                    key.to_string(),
+                    Seen {
+                        last_logged: now,
+                        suppressed: 0,
+                    },
+                );
+                Some(0)
+            }
+            Some(entry) if now.saturating_duration_since(entry.last_logged) >= self.interval => {
+                let suppressed = entry.suppressed;
+                entry.last_logged = now;
+                entry.suppressed = 0;
+                Some(suppressed)
+            }
+            Some(entry) => {
+                entry.suppressed += 1;
+                None
+            }
+        }
+    }
+}
+
+static UNKNOWN_DISCRIMINATORS: OnceLock<RateLimitedLog> = OnceLock::new();
+
+// 报告一个slot的解析结果中全部未知鉴别器；数据不足8字节的指令没有鉴别器，不报告
+#[allow(dead_code)]
+pub fn report_unknown_discriminators(results: &TransactionResults) {
+    for unknown in results.unknown_instructions() {
+        if let Some(discriminator) = unknown.data.get(..8) {
+            report_unknown_discriminator(unknown.parser, discriminator);
+        }
+    }
+}
+
+// 报告解析器遇到的未知鉴别器，按 解析器 + 鉴别器 去重限流
+#[allow(dead_code)]
+pub fn report_unknown_discriminator(parser: &str, discriminator: &[u8]) {
+    if !log::log_enabled!(target: UNKNOWN_DISCRIMINATOR_TARGET, Level::Warn) {
+        return;
+    }
+
+    let discriminator = hex::encode(discriminator);
+    let limiter = UNKNOWN_DISCRIMINATORS.get_or_init(|| RateLimitedLog::new(DEFAULT_RATE_LIMIT));
+    match limiter.check(&format!("{}:{}", parser, discriminator), Instant::now()) {
+        Some(0) => log::warn!(target: UNKNOWN_DISCRIMINATOR_TARGET, "{} 未知鉴别器: {}", parser, discriminator),
+        Some(suppressed) => log::warn!(
+            target: UNKNOWN_DISCRIMINATOR_TARGET,
+            "{} 未知鉴别器: {}（上次输出后又出现 {} 次）",
+            parser,
+            discriminator,
+            suppressed
+        ),
+        None => {}
+    }
+}
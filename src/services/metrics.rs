@@ -0,0 +1,510 @@
+// Prometheus指标：进程内全局注册表 + 本地HTTP /metrics 端点（文本格式 0.0.4）
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::models::{SlotTiming, TransactionResults};
+use crate::utils::account_layout::AccountAnomaly;
+
+// 处理耗时直方图的桶上限（秒）
+const LATENCY_BUCKETS: &[f64] = &[
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0,
+];
+
+// 各阶段耗时直方图的桶上限（秒），从10微秒开始
+const STAGE_BUCKETS: &[f64] = &[
+    0.00001, 0.000025, 0.00005, 0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1,
+];
+
+// slot首次到达延迟直方图的桶上限（秒）
+const SLOT_DELAY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.2, 0.4, 0.8, 1.6, 3.2];
+
+// slot时钟校准窗口（slot数），约一分钟
+const SLOT_CLOCK_WINDOW: u64 = 150;
+// 接受连接失败（例如文件描述符耗尽）后的等待时间，连续失败时加倍
+const ACCEPT_BACKOFF_MIN: Duration = Duration::from_millis(10);
+const ACCEPT_BACKOFF_MAX: Duration = Duration::from_secs(1);
+// 读取请求头的最长时间，超时即关闭连接，避免空闲或过慢的客户端一直占用任务和套接字
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Default)]
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    #[inline]
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    #[inline]
+    pub fn add(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Gauge(AtomicU64);
+
+impl Gauge {
+    #[inline]
+    pub fn set(&self, value: u64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    // 只在新值更大时更新，返回更新后的值
+    #[inline]
+    pub fn max(&self, value: u64) -> u64 {
+        self.0.fetch_max(value, Ordering::Relaxed).max(value)
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// 带标签的计数器，标签组合较少，使用互斥锁保护即可
+#[derive(Debug)]
+pub struct LabeledCounter {
+    labels: &'static [&'static str],
+    values: Mutex<BTreeMap<Vec<String>, u64>>,
+}
+
+impl LabeledCounter {
+    pub fn new(labels: &'static [&'static str]) -> Self {
+        Self {
+            labels,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn add(&self, label_values: &[&str], value: u64) {
+        debug_assert_eq!(label_values.len(), self.labels.len());
+        let key = label_values.iter().map(|v| v.to_string()).collect();
+        *self.values.lock().unwrap().entry(key).or_default() += value;
+    }
+
+    // 作为带标签的仪表盘使用时直接设置值
+    pub fn set(&self, label_values: &[&str], value: u64) {
+        debug_assert_eq!(label_values.len(), self.labels.len());
+        let key = label_values.iter().map(|v| v.to_string()).collect();
+        self.values.lock().unwrap().insert(key, value);
+    }
+
+    pub fn get(&self, label_values: &[&str]) -> u64 {
+        let key: Vec<String> = label_values.iter().map(|v| v.to_string()).collect();
+        self.values.lock().unwrap().get(&key).copied().unwrap_or_default()
+    }
+}
+
+// 固定桶的直方图；总和以纳秒累计，输出时换算为秒
+#[derive(Debug)]
+pub struct Histogram {
+    buckets: &'static [f64],
+    counts: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_nanos: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(buckets: &'static [f64]) -> Self {
+        Self {
+            buckets,
+            counts: buckets.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_nanos: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(i) = self.buckets.iter().position(|bound| seconds <= *bound) {
+            self.counts[i].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_nanos
+            .fetch_add(duration.as_nanos().min(u64::MAX as u128) as u64, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+// 本地slot时钟：按 基准时刻 + (slot - 基准slot) * slot时长 估算slot开始时间。
+// 基准取校准窗口内最早到达的slot，因此延迟是相对于近期最快到达的slot计算的，并能跟随实际slot时长的漂移
+#[derive(Debug)]
+pub struct SlotClock {
+    slot_duration: Duration,
+    origin: Option<(u64, Instant)>,
+    // (slot, 到达时刻相对于按origin推算的开始时刻的偏移，纳秒)
+    offsets: VecDeque<(u64, i128)>,
+    last_slot: Option<u64>,
+}
+
+impl SlotClock {
+    pub fn new(slot_duration: Duration) -> Self {
+        Self {
+            slot_duration,
+            origin: None,
+            offsets: VecDeque::new(),
+            last_slot: None,
+        }
+    }
+
+    // 记录一条消息的到达；只有slot首次出现（大于已见的所有slot）时返回相对估算开始时间的延迟
+    pub fn first_seen(&mut self, slot: u64, seen_at: Instant) -> Option<Duration> {
+        if self.last_slot.is_some_and(|last| slot <= last) {
+            return None;
+        }
+        self.last_slot = Some(slot);
+
+        let (origin_slot, origin_at) = *self.origin.get_or_insert((slot, seen_at));
+        let elapsed = if seen_at >= origin_at {
+            seen_at.duration_since(origin_at).as_nanos() as i128
+        } else {
+            -(origin_at.duration_since(seen_at).as_nanos() as i128)
+        };
+        let expected = (slot as i128 - origin_slot as i128) * self.slot_duration.as_nanos() as i128;
+        let offset = elapsed - expected;
+
+        self.offsets.push_back((slot, offset));
+        while self.offsets.front().is_some_and(|(front, _)| front + SLOT_CLOCK_WINDOW < slot) {
+            self.offsets.pop_front();
+        }
+
+        let earliest = self.offsets.iter().map(|(_, offset)| *offset).min().unwrap_or(offset);
+        Some(Duration::from_nanos((offset - earliest).min(u64::MAX as i128) as u64))
+    }
+}
+
+// 客户端的全部指标
+#[derive(Debug)]
+pub struct Metrics {
+    pub slots_received: Counter,
+    pub entries_received: Counter,
+    pub transactions_received: Counter,
+    pub deserialize_failures: Counter,
+    pub reconnects: Counter,
+    pub bytes_received: LabeledCounter,
+    pub parser_matches: LabeledCounter,
+    pub account_anomalies: LabeledCounter,
+    pub attributed_transactions: LabeledCounter,
+    pub pipeline_dropped: LabeledCounter,
+    pub pipeline_queue_depth: LabeledCounter,
+    pub pipeline_queue_capacity: LabeledCounter,
+    pub processing_seconds: Histogram,
+    pub decode_seconds: Histogram,
+    pub parse_seconds: Histogram,
+    pub sink_seconds: Histogram,
+    pub end_to_end_seconds: Histogram,
+    pub slot_first_seen_delay_seconds: Histogram,
+    pub highest_slot: Gauge,
+    pub slot_lag: Gauge,
+    pub batch_size: Gauge,
+    pub worker_threads: Gauge,
+    pub tokio_threads: Gauge,
+    slot_clock: Mutex<Option<SlotClock>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            slots_received: Counter::default(),
+            entries_received: Counter::default(),
+            transactions_received: Counter::default(),
+            deserialize_failures: Counter::default(),
+            reconnects: Counter::default(),
+            bytes_received: LabeledCounter::new(&["endpoint"]),
+            parser_matches: LabeledCounter::new(&["parser", "instruction"]),
+            account_anomalies: LabeledCounter::new(&["parser", "instruction", "kind"]),
+            attributed_transactions: LabeledCounter::new(&["tool"]),
+            pipeline_dropped: LabeledCounter::new(&["stage", "policy"]),
+            pipeline_queue_depth: LabeledCounter::new(&["stage"]),
+            pipeline_queue_capacity: LabeledCounter::new(&["stage"]),
+            processing_seconds: Histogram::new(LATENCY_BUCKETS),
+            decode_seconds: Histogram::new(STAGE_BUCKETS),
+            parse_seconds: Histogram::new(STAGE_BUCKETS),
+            sink_seconds: Histogram::new(STAGE_BUCKETS),
+            end_to_end_seconds: Histogram::new(STAGE_BUCKETS),
+            slot_first_seen_delay_seconds: Histogram::new(SLOT_DELAY_BUCKETS),
+            highest_slot: Gauge::default(),
+            slot_lag: Gauge::default(),
+            batch_size: Gauge::default(),
+            worker_threads: Gauge::default(),
+            tokio_threads: Gauge::default(),
+            slot_clock: Mutex::new(None),
+        }
+    }
+
+    // 启用slot时钟，统计slot首次到达相对于估算开始时间的延迟
+    pub fn enable_slot_clock(&self, slot_duration: Duration) {
+        *self.slot_clock.lock().unwrap() = Some(SlotClock::new(slot_duration));
+    }
+
+    // 在gRPC边界记录消息到达时刻（未启用slot时钟时不做任何事）
+    pub fn record_arrival(&self, slot: u64, received_at: Instant) {
+        if let Some(clock) = self.slot_clock.lock().unwrap().as_mut() {
+            if let Some(delay) = clock.first_seen(slot, received_at) {
+                self.slot_first_seen_delay_seconds.observe(delay);
+            }
+        }
+    }
+
+    // 反序列化与解析阶段耗时
+    pub fn record_timing(&self, timing: &SlotTiming) {
+        self.decode_seconds.observe(timing.decode_latency());
+        self.parse_seconds.observe(timing.parse_latency());
+        self.processing_seconds.observe(timing.since_received(timing.parsed_at));
+    }
+
+    // 输出完成后记录输出阶段与端到端耗时
+    pub fn record_emitted(&self, timing: &SlotTiming, emitted_at: Instant) {
+        self.sink_seconds.observe(emitted_at.saturating_duration_since(timing.parsed_at));
+        self.end_to_end_seconds.observe(timing.since_received(emitted_at));
+    }
+
+    // 记录收到的一条SubscribeEntries消息
+    pub fn record_message(&self, endpoint: &str, slot: u64, bytes: usize) {
+        self.slots_received.inc();
+        self.bytes_received.add(&[endpoint], bytes as u64);
+        // slot滞后：当前消息落后于已见最高slot的数量（乱序或补发时大于0）
+        let highest = self.highest_slot.max(slot);
+        self.slot_lag.set(highest - slot);
+    }
+
+    // 按解析器和指令类型累计匹配数与账户数异常
+    pub fn record_results(&self, results: &TransactionResults) {
+        for tx in &results.pump_transactions {
+            for ix in &tx.instructions {
+                self.record_instruction("PUMP", ix.kind(), ix.account_anomaly());
+            }
+        }
+        for tx in &results.pumpamm_transactions {
+            for ix in &tx.instructions {
+                self.record_instruction("PUMPAMM", ix.kind(), ix.account_anomaly());
+            }
+        }
+        for tx in &results.boop_transactions {
+            for ix in &tx.instructions {
+                self.record_instruction("BOOP", ix.kind(), ix.account_anomaly());
+            }
+        }
+        for tx in &results.idl_transactions {
+            for ix in &tx.instructions {
+                self.parser_matches.add(&["IDL", &format!("{}::{}", ix.program, ix.name)], 1);
+            }
+        }
+        for (tool, count) in results.attribution_counts() {
+            self.attributed_transactions.add(&[tool], count);
+        }
+    }
+
+    fn record_instruction(&self, parser: &str, instruction: &str, anomaly: Option<AccountAnomaly>) {
+        self.parser_matches.add(&[parser, instruction], 1);
+        if let Some(anomaly) = anomaly {
+            self.account_anomalies.add(&[parser, instruction, anomaly.kind()], 1);
+        }
+    }
+
+    // 按Prometheus文本格式输出
+    pub fn render(&self) -> String {
+        let mut out = String::with_capacity(4096);
+
+        render_counter(&mut out, "shreds_slots_received_total", "收到的SubscribeEntries消息（slot）数", self.slots_received.get());
+        render_counter(&mut out, "shreds_entries_received_total", "收到的Entry数", self.entries_received.get());
+        render_counter(&mut out, "shreds_transactions_received_total", "收到的交易数", self.transactions_received.get());
+        render_counter(&mut out, "shreds_deserialize_failures_total", "bincode反序列化失败次数", self.deserialize_failures.get());
+        render_counter(&mut out, "shreds_reconnects_total", "重新连接Shredstream的次数", self.reconnects.get());
+        render_labeled(&mut out, "shreds_bytes_received_total", "按端点统计的接收字节数", &self.bytes_received);
+        render_labeled(&mut out, "shreds_parser_matches_total", "按解析器和指令类型统计的匹配指令数", &self.parser_matches);
+        render_labeled(
+            &mut out,
+            "shreds_account_anomalies_total",
+            "账户数与账户布局不一致的指令数（kind为missing或extra）",
+            &self.account_anomalies,
+        );
+        render_labeled(
+            &mut out,
+            "shreds_attributed_transactions_total",
+            "按来源工具（交易机器人 / 前端）统计的pump、PumpSwap和boop交易数",
+            &self.attributed_transactions,
+        );
+        render_labeled(
+            &mut out,
+            "shreds_pipeline_dropped_total",
+            "流水线队列满时按策略丢弃的slot数",
+            &self.pipeline_dropped,
+        );
+        render_labeled_gauge(&mut out, "shreds_pipeline_queue_depth", "流水线各阶段输入队列当前长度", &self.pipeline_queue_depth);
+        render_labeled_gauge(&mut out, "shreds_pipeline_queue_capacity", "流水线各阶段输入队列容量", &self.pipeline_queue_capacity);
+        render_histogram(&mut out, "shreds_processing_duration_seconds", "每个slot的反序列化与解析耗时", &self.processing_seconds);
+        render_histogram(&mut out, "shreds_decode_duration_seconds", "从gRPC收到消息到bincode反序列化完成的耗时", &self.decode_seconds);
+        render_histogram(&mut out, "shreds_parse_duration_seconds", "反序列化完成到解析完成的耗时", &self.parse_seconds);
+        render_histogram(&mut out, "shreds_sink_duration_seconds", "解析完成到输出完成的耗时", &self.sink_seconds);
+        render_histogram(&mut out, "shreds_end_to_end_duration_seconds", "从gRPC收到消息到输出完成的耗时", &self.end_to_end_seconds);
+        render_histogram(
+            &mut out,
+            "shreds_slot_first_seen_delay_seconds",
+            "slot首次到达相对于本地估算的slot开始时间的延迟",
+            &self.slot_first_seen_delay_seconds,
+        );
+        render_gauge(&mut out, "shreds_highest_slot", "已收到的最高slot", self.highest_slot.get());
+        render_gauge(&mut out, "shreds_slot_lag", "最近一条消息落后于最高slot的数量", self.slot_lag.get());
+        render_gauge(&mut out, "shreds_batch_size", "并行解析的批次大小（processing.batch_size）", self.batch_size.get());
+        render_gauge(&mut out, "shreds_worker_threads", "解析线程池（rayon）线程数", self.worker_threads.get());
+        render_gauge(&mut out, "shreds_tokio_threads", "tokio工作线程数", self.tokio_threads.get());
+
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn render_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn render_counter(out: &mut String, name: &str, help: &str, value: u64) {
+    render_header(out, name, help, "counter");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn render_gauge(out: &mut String, name: &str, help: &str, value: u64) {
+    render_header(out, name, help, "gauge");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn render_labeled(out: &mut String, name: &str, help: &str, counter: &LabeledCounter) {
+    render_header(out, name, help, "counter");
+    render_labeled_values(out, name, counter);
+}
+
+fn render_labeled_gauge(out: &mut String, name: &str, help: &str, gauge: &LabeledCounter) {
+    render_header(out, name, help, "gauge");
+    render_labeled_values(out, name, gauge);
+}
+
+fn render_labeled_values(out: &mut String, name: &str, counter: &LabeledCounter) {
+    for (values, count) in counter.values.lock().unwrap().iter() {
+        let labels: Vec<String> = counter
+            .labels
+            .iter()
+            .zip(values)
+            .map(|(label, value)| format!("{}=\"{}\"", label, escape_label(value)))
+            .collect();
+        let _ = writeln!(out, "{}{{{}}} {}", name, labels.join(","), count);
+    }
+}
+
+fn render_histogram(out: &mut String, name: &str, help: &str, histogram: &Histogram) {
+    render_header(out, name, help, "histogram");
+    let mut cumulative = 0;
+    for (bound, count) in histogram.buckets.iter().zip(&histogram.counts) {
+        cumulative += count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative);
+    }
+    let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, histogram.count());
+    let sum = histogram.sum_nanos.load(Ordering::Relaxed) as f64 / 1e9;
+    let _ = writeln!(out, "{}_sum {}", name, sum);
+    let _ = writeln!(out, "{}_count {}", name, histogram.count());
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+// 进程内全局指标
+pub fn global() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
+
+// 绑定 /metrics 监听地址；绑定失败在启动时直接报错
+pub async fn bind(addr: SocketAddr) -> Result<TcpListener, io::Error> {
+    TcpListener::bind(addr)
+        .await
+        .map_err(|e| io::Error::new(e.kind(), format!("无法监听指标端口 {}: {}", addr, e)))
+}
+
+// 处理 /metrics 请求，直到监听器出错
+pub async fn serve(listener: TcpListener, metrics: &'static Metrics) {
+    let mut backoff = ACCEPT_BACKOFF_MIN;
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(accepted) => {
+                backoff = ACCEPT_BACKOFF_MIN;
+                accepted
+            }
+            Err(e) => {
+                log::warn!("指标端口接受连接失败，{}ms后重试: {e}", backoff.as_millis());
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(ACCEPT_BACKOFF_MAX);
+                continue;
+            }
+        };
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, metrics).await {
+                log::debug!("指标请求处理失败: {e}");
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, metrics: &Metrics) -> Result<(), io::Error> {
+    // 只需要请求行，读到头部结束或缓冲区满即可
+    let mut buf = [0u8; 4096];
+    let mut len = 0;
+    let read_request = async {
+        while len < buf.len() {
+            let n = stream.read(&mut buf[len..]).await?;
+            if n == 0 {
+                break;
+            }
+            len += n;
+            if buf[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+                break;
+            }
+        }
+        Ok::<(), io::Error>(())
+    };
+    tokio::time::timeout(REQUEST_READ_TIMEOUT, read_request)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "读取指标请求超时"))??;
+
+    let request = String::from_utf8_lossy(&buf[..len]);
+    let mut parts = request.lines().next().unwrap_or_default().split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+
+    let (status, content_type, body) = if method == "GET" && (path == "/metrics" || path.starts_with("/metrics?")) {
+        ("200 OK", "text/plain; version=0.0.4; charset=utf-8", metrics.render())
+    } else {
+        ("404 Not Found", "text/plain; charset=utf-8", "not found\n".to_string())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
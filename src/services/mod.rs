@@ -1,8 +1,27 @@
 pub mod transaction_processor;
 pub mod jito_client;
+pub mod auth;
+pub mod searcher_client;
+pub mod metrics;
+pub mod output;
+pub mod recording;
+pub mod logging;
+pub mod discovery;
+pub mod pipeline;
+pub mod threads;
 
 // 虽然这些导出在当前bin中未使用，但在lib.rs中被使用，所以需要保留
 #[allow(unused_imports)]
 pub use jito_client::JitoClient;
 #[allow(unused_imports)]
-pub use transaction_processor::TransactionProcessor; 
\ No newline at end of file
+pub use transaction_processor::{ProcessorOptions, TransactionProcessor};
+#[allow(unused_imports)]
+pub use searcher_client::SearcherClient;
+#[allow(unused_imports)]
+pub use output::OutputSink;
+#[allow(unused_imports)]
+pub use recording::{read_recording, RecordingReader, RecordingWriter};
+#[allow(unused_imports)]
+pub use discovery::DiscoveryReport;
+#[allow(unused_imports)]
+pub use pipeline::Pipeline;
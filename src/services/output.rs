@@ -0,0 +1,135 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::time::Instant;
+
+use serde_json::json;
+
+use crate::analytics::{Candle, CurveState, EventSink, Graduated, Opportunity};
+use crate::config::{Config, OutputFormat};
+use crate::models::TransactionResults;
+use crate::services::metrics;
+
+// 解析结果输出：标准输出和/或追加写入文件，格式由配置决定
+#[allow(dead_code)]
+pub struct OutputSink {
+    format: OutputFormat,
+    stdout: bool,
+    file: Option<File>,
+}
+
+impl OutputSink {
+    #[allow(dead_code)]
+    pub fn stdout(format: OutputFormat) -> Self {
+        Self {
+            format,
+            stdout: true,
+            file: None,
+        }
+    }
+
+    // 根据配置中的sinks创建输出
+    #[allow(dead_code)]
+    pub fn from_config(config: &Config) -> Result<Self, io::Error> {
+        let file = match &config.sinks.file {
+            Some(path) => Some(OpenOptions::new().create(true).append(true).open(path).map_err(|e| {
+                io::Error::new(e.kind(), format!("无法打开输出文件 {}: {}", path.display(), e))
+            })?),
+            None => None,
+        };
+
+        Ok(Self {
+            format: config.sinks.format,
+            stdout: config.sinks.stdout,
+            file,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    // 输出一个slot的解析结果，写入失败只打印错误，不中断处理
+    #[allow(dead_code)]
+    pub fn emit(&mut self, results: &TransactionResults) {
+        if self.stdout {
+            let stdout = io::stdout();
+            if let Err(e) = Self::write(self.format, results, &mut stdout.lock()) {
+                log::error!("写入标准输出失败: {e}");
+            }
+        }
+        if let Some(file) = self.file.as_mut() {
+            if let Err(e) = Self::write(self.format, results, file).and_then(|_| file.flush()) {
+                log::error!("写入输出文件失败: {e}");
+            }
+        }
+        if let Some(timing) = &results.timing {
+            metrics::global().record_emitted(timing, Instant::now());
+        }
+    }
+
+    fn write<W: Write>(format: OutputFormat, results: &TransactionResults, out: &mut W) -> io::Result<()> {
+        match format {
+            OutputFormat::Text => results.write_to(out),
+            OutputFormat::Json => results.write_json_to(out),
+        }
+    }
+
+    // 输出一行分析事件
+    fn write_line(&mut self, line: &str) {
+        if self.stdout {
+            if let Err(e) = writeln!(io::stdout().lock(), "{}", line) {
+                log::error!("写入标准输出失败: {e}");
+            }
+        }
+        if let Some(file) = self.file.as_mut() {
+            if let Err(e) = writeln!(file, "{}", line).and_then(|_| file.flush()) {
+                log::error!("写入输出文件失败: {e}");
+            }
+        }
+    }
+}
+
+// 收盘的K线：文本一行 Candle:<mint> ..，JSON一条没有signature字段的 {"candle": {..}} 记录
+impl EventSink<Candle> for OutputSink {
+    fn publish(&mut self, candle: &Candle) {
+        let line = match self.format {
+            OutputFormat::Text => candle.to_string(),
+            OutputFormat::Json => json!({"candle": candle.to_json()}).to_string(),
+        };
+        self.write_line(&line);
+    }
+}
+
+// 绑定曲线毕业：文本一行 Graduated:<mint> ..，JSON一条 {"graduated": {..}} 记录
+impl EventSink<Graduated> for OutputSink {
+    fn publish(&mut self, graduated: &Graduated) {
+        let line = match self.format {
+            OutputFormat::Text => graduated.to_string(),
+            OutputFormat::Json => json!({"graduated": graduated.to_json()}).to_string(),
+        };
+        self.write_line(&line);
+    }
+}
+
+// 绑定曲线状态：文本一行 Curve:<mint> ..，JSON一条 {"curve": {..}} 记录
+impl EventSink<CurveState> for OutputSink {
+    fn publish(&mut self, curve: &CurveState) {
+        let line = match self.format {
+            OutputFormat::Text => curve.to_string(),
+            OutputFormat::Json => json!({"curve": curve.to_json()}).to_string(),
+        };
+        self.write_line(&line);
+    }
+}
+
+// 套利机会：文本一行 Arbitrage:<mint> ..，JSON一条 {"arbitrage": {..}} 记录
+impl EventSink<Opportunity> for OutputSink {
+    fn publish(&mut self, opportunity: &Opportunity) {
+        let line = match self.format {
+            OutputFormat::Text => opportunity.to_string(),
+            OutputFormat::Json => json!({"arbitrage": opportunity.to_json()}).to_string(),
+        };
+        self.write_line(&line);
+    }
+}
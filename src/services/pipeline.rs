@@ -0,0 +1,404 @@
+// 分阶段处理流水线：接收(tokio) → 反序列化(专用线程) → 解析(rayon) → 输出(专用线程)。
+// 每个阶段前有一个有界队列，队列满时按配置的策略阻塞或丢弃，使慢速输出不会拖住gRPC接收
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use solana_entry::entry::Entry;
+use tokio::sync::Notify;
+
+use crate::config::{PipelineConfig, QueueConfig, QueuePolicy};
+use crate::models::TransactionResults;
+use crate::services::metrics;
+use crate::services::recording::RecordingWriter;
+use crate::services::transaction_processor::{ProcessorOptions, TransactionProcessor};
+
+// 一次入队的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pushed {
+    Queued,
+    // 队列已满，丢弃了队首最旧的一条
+    DroppedOldest,
+    // 队列已满，丢弃了新到的这一条
+    DroppedNewest,
+    // 队列已关闭，数据被丢弃
+    Closed,
+}
+
+struct QueueState<T> {
+    items: VecDeque<T>,
+    closed: bool,
+    // 已出队的数量，作为下一个出队数据的序号
+    popped: u64,
+}
+
+// 有界队列：同步线程用Condvar等待，tokio任务用Notify异步等待，不占用reactor线程
+pub struct BoundedQueue<T> {
+    stage: &'static str,
+    capacity: usize,
+    policy: QueuePolicy,
+    state: Mutex<QueueState<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    space: Notify,
+}
+
+impl<T> BoundedQueue<T> {
+    #[allow(dead_code)]
+    pub fn new(stage: &'static str, config: &QueueConfig) -> Self {
+        metrics::global()
+            .pipeline_queue_capacity
+            .set(&[stage], config.capacity as u64);
+        metrics::global().pipeline_queue_depth.set(&[stage], 0);
+        Self {
+            stage,
+            capacity: config.capacity.max(1),
+            policy: config.policy,
+            state: Mutex::new(QueueState {
+                items: VecDeque::with_capacity(config.capacity),
+                closed: false,
+                popped: 0,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            space: Notify::new(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().items.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 入队；Block策略下队列满时阻塞当前线程
+    #[allow(dead_code)]
+    pub fn push(&self, mut item: T) -> Pushed {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            match self.try_push(&mut state, item) {
+                Ok(pushed) => {
+                    // 持锁更新队列长度，避免与出队交错后留下过时的值
+                    self.observe(pushed, state.items.len());
+                    return pushed;
+                }
+                Err(back) => {
+                    item = back;
+                    state = self.not_full.wait(state).unwrap();
+                }
+            }
+        }
+    }
+
+    // 异步入队；Block策略下队列满时让出tokio任务，直到下游取走数据
+    #[allow(dead_code)]
+    pub async fn push_async(&self, mut item: T) -> Pushed {
+        loop {
+            // 先登记等待再检查队列，避免错过检查与等待之间的唤醒
+            let notified = self.space.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let attempt = {
+                let mut state = self.state.lock().unwrap();
+                let attempt = self.try_push(&mut state, item);
+                if let Ok(pushed) = attempt {
+                    self.observe(pushed, state.items.len());
+                }
+                attempt
+            };
+            match attempt {
+                Ok(pushed) => return pushed,
+                Err(back) => {
+                    item = back;
+                    notified.await;
+                }
+            }
+        }
+    }
+
+    // 出队；队列为空时阻塞，队列关闭且取空后返回None
+    #[allow(dead_code)]
+    pub fn pop(&self) -> Option<T> {
+        self.pop_numbered().map(|(_, item)| item)
+    }
+
+    // 出队并返回出队序号（从0连续递增），多个消费者可按序号恢复出队顺序
+    #[allow(dead_code)]
+    pub fn pop_numbered(&self) -> Option<(u64, T)> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(item) = state.items.pop_front() {
+                let number = state.popped;
+                state.popped += 1;
+                metrics::global().pipeline_queue_depth.set(&[self.stage], state.items.len() as u64);
+                drop(state);
+                self.not_full.notify_one();
+                self.space.notify_waiters();
+                return Some((number, item));
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.not_empty.wait(state).unwrap();
+        }
+    }
+
+    // 关闭队列：不再接受新数据，已入队的数据仍可取出
+    #[allow(dead_code)]
+    pub fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+        self.space.notify_waiters();
+    }
+
+    // 队列未满或策略允许丢弃时完成入队；Block策略下队列满时把数据交还给调用方等待
+    fn try_push(&self, state: &mut QueueState<T>, item: T) -> Result<Pushed, T> {
+        if state.closed {
+            return Ok(Pushed::Closed);
+        }
+        if state.items.len() < self.capacity {
+            state.items.push_back(item);
+            self.not_empty.notify_one();
+            return Ok(Pushed::Queued);
+        }
+        match self.policy {
+            QueuePolicy::Block => Err(item),
+            QueuePolicy::DropNewest => Ok(Pushed::DroppedNewest),
+            QueuePolicy::DropOldest => {
+                state.items.pop_front();
+                state.items.push_back(item);
+                self.not_empty.notify_one();
+                Ok(Pushed::DroppedOldest)
+            }
+        }
+    }
+
+    fn observe(&self, pushed: Pushed, depth: usize) {
+        let metrics = metrics::global();
+        metrics.pipeline_queue_depth.set(&[self.stage], depth as u64);
+        if matches!(pushed, Pushed::DroppedOldest | Pushed::DroppedNewest) {
+            metrics.pipeline_dropped.add(&[self.stage, self.policy.as_str()], 1);
+            log::debug!("{} 队列已满，按 {} 策略丢弃一个slot", self.stage, self.policy.as_str());
+        }
+    }
+}
+
+// 接收阶段交给流水线的一条原始消息
+#[derive(Debug, Clone)]
+pub struct RawSlot {
+    pub slot: u64,
+    pub payload: Vec<u8>,
+    pub received_at: Instant,
+}
+
+// 多个反序列化线程按出队序号轮流向下游交付，保证slot顺序与接收顺序一致
+struct Turns {
+    next: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl Turns {
+    fn new() -> Self {
+        Self {
+            next: Mutex::new(0),
+            changed: Condvar::new(),
+        }
+    }
+
+    // 等到轮到number时执行f，随后把轮次交给下一个序号
+    fn take<R>(&self, number: u64, f: impl FnOnce() -> R) -> R {
+        let mut next = self.next.lock().unwrap();
+        while *next != number {
+            next = self.changed.wait(next).unwrap();
+        }
+        let result = f();
+        *next += 1;
+        drop(next);
+        self.changed.notify_all();
+        result
+    }
+}
+
+// 录制一条原始消息：(slot, 载荷)
+type Record = Box<dyn FnMut(u64, &[u8]) + Send>;
+// 反序列化线程共用的录制回调，在轮次内调用，按接收顺序写入
+type Recorder = Mutex<Record>;
+
+struct DecodedSlot {
+    slot: u64,
+    entries: Vec<Entry>,
+    received_at: Instant,
+    decoded_at: Instant,
+}
+
+pub struct Pipeline {
+    decode: Arc<BoundedQueue<RawSlot>>,
+    parse: Arc<BoundedQueue<DecodedSlot>>,
+    emit: Arc<BoundedQueue<TransactionResults>>,
+    decoders: Vec<JoinHandle<()>>,
+    parser: JoinHandle<()>,
+    emitter: JoinHandle<()>,
+}
+
+impl Pipeline {
+    // 启动反序列化、解析与输出线程；每个slot的解析结果（包括没有匹配交易的slot）按解析顺序交给on_results
+    #[allow(dead_code)]
+    pub fn start<F>(config: &PipelineConfig, options: ProcessorOptions, on_results: F) -> Result<Self, io::Error>
+    where
+        F: FnMut(TransactionResults) + Send + 'static,
+    {
+        Self::start_with_recorder::<F, io::Sink>(config, options, None, on_results)
+    }
+
+    // 同上，并由反序列化线程把原始消息写入录制文件，写文件不占用接收阶段的tokio线程
+    #[allow(dead_code)]
+    pub fn start_with_recorder<F, W>(
+        config: &PipelineConfig,
+        options: ProcessorOptions,
+        recorder: Option<RecordingWriter<W>>,
+        mut on_results: F,
+    ) -> Result<Self, io::Error>
+    where
+        F: FnMut(TransactionResults) + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        let recorder = recorder.map(|mut writer| {
+            let record: Record = Box::new(move |slot, payload| {
+                if let Err(e) = writer.write(slot, payload).and_then(|_| writer.flush()) {
+                    log::warn!("录制失败: {e}");
+                }
+            });
+            Arc::new(Mutex::new(record))
+        });
+        let decode = Arc::new(BoundedQueue::new("decode", &config.decode));
+        let parse = Arc::new(BoundedQueue::new("parse", &config.parse));
+        let emit = Arc::new(BoundedQueue::new("emit", &config.emit));
+
+        let turns = Arc::new(Turns::new());
+        let mut decoders = Vec::with_capacity(config.decode_threads);
+        for i in 0..config.decode_threads.max(1) {
+            let (input, output, turns, recorder) = (decode.clone(), parse.clone(), turns.clone(), recorder.clone());
+            decoders.push(spawn(format!("shreds-decode-{}", i), move || {
+                decode_stage(&input, &output, &turns, recorder.as_deref())
+            })?);
+        }
+
+        let (input, output) = (parse.clone(), emit.clone());
+        let parser = spawn("shreds-parse".to_string(), move || parse_stage(&input, &output, &options))?;
+
+        let input = emit.clone();
+        let emitter = spawn("shreds-emit".to_string(), move || {
+            while let Some(results) = input.pop() {
+                on_results(results);
+            }
+        })?;
+
+        Ok(Self {
+            decode,
+            parse,
+            emit,
+            decoders,
+            parser,
+            emitter,
+        })
+    }
+
+    // 接收阶段提交一条原始消息
+    #[allow(dead_code)]
+    pub async fn submit(&self, raw: RawSlot) -> Pushed {
+        self.decode.push_async(raw).await
+    }
+
+    // 同步提交，供非异步的调用方（如回放）使用
+    #[allow(dead_code)]
+    pub fn submit_blocking(&self, raw: RawSlot) -> Pushed {
+        self.decode.push(raw)
+    }
+
+    // 关闭输入并等待各阶段处理完队列中剩余的数据
+    #[allow(dead_code)]
+    pub fn shutdown(self) {
+        self.decode.close();
+        for decoder in self.decoders {
+            let _ = decoder.join();
+        }
+        self.parse.close();
+        let _ = self.parser.join();
+        self.emit.close();
+        let _ = self.emitter.join();
+    }
+}
+
+fn spawn<F>(name: String, f: F) -> Result<JoinHandle<()>, io::Error>
+where
+    F: FnOnce() + Send + 'static,
+{
+    thread::Builder::new().name(name).spawn(f)
+}
+
+fn decode_stage(
+    input: &BoundedQueue<RawSlot>,
+    output: &BoundedQueue<DecodedSlot>,
+    turns: &Turns,
+    recorder: Option<&Recorder>,
+) {
+    let metrics = metrics::global();
+    while let Some((number, raw)) = input.pop_numbered() {
+        // 反序列化并行进行，录制与交付按出队顺序；失败的slot也要占一个轮次，否则后面的slot会一直等待
+        let decoded = match bincode::deserialize::<Vec<Entry>>(&raw.payload) {
+            Ok(entries) => {
+                metrics.entries_received.add(entries.len() as u64);
+                metrics
+                    .transactions_received
+                    .add(entries.iter().map(|e| e.transactions.len() as u64).sum());
+                Some(DecodedSlot {
+                    slot: raw.slot,
+                    entries,
+                    received_at: raw.received_at,
+                    decoded_at: Instant::now(),
+                })
+            }
+            Err(e) => {
+                metrics.deserialize_failures.inc();
+                log::warn!("slot {} 反序列化失败: {e}", raw.slot);
+                None
+            }
+        };
+
+        turns.take(number, || {
+            // 录制原始载荷（包括无法反序列化的消息），录制失败不影响处理
+            if let Some(recorder) = recorder {
+                (recorder.lock().unwrap())(raw.slot, &raw.payload);
+            }
+            if let Some(decoded) = decoded {
+                output.push(decoded);
+            }
+        });
+    }
+}
+
+fn parse_stage(input: &BoundedQueue<DecodedSlot>, output: &BoundedQueue<TransactionResults>, options: &ProcessorOptions) {
+    let metrics = metrics::global();
+    while let Some(decoded) = input.pop() {
+        let results = TransactionProcessor::process_received(
+            &decoded.entries,
+            decoded.slot,
+            options,
+            decoded.received_at,
+            decoded.decoded_at,
+        );
+        if let Some(timing) = &results.timing {
+            metrics.record_timing(timing);
+        }
+        metrics.record_results(&results);
+        output.push(results);
+    }
+}
@@ -0,0 +1,113 @@
+// 录制文件：按接收顺序保存原始的SubscribeEntries消息，供replay / stats / 测试回放使用
+// 格式为连续的记录，每条记录为 slot(u64 LE) + 载荷长度(u32 LE) + 载荷（bincode编码的Vec<Entry>）
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+// 单条载荷上限，防止损坏的文件导致过量分配
+const MAX_PAYLOAD_LEN: usize = 256 * 1024 * 1024;
+
+#[allow(dead_code)]
+pub struct RecordingWriter<W: Write> {
+    inner: W,
+}
+
+impl RecordingWriter<BufWriter<File>> {
+    // 创建（覆盖）录制文件
+    #[allow(dead_code)]
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("无法创建录制文件 {}: {}", path.display(), e)))?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> RecordingWriter<W> {
+    #[allow(dead_code)]
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    // 写入一条记录
+    #[allow(dead_code)]
+    pub fn write(&mut self, slot: u64, payload: &[u8]) -> io::Result<()> {
+        let len = u32::try_from(payload.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "载荷过大，无法录制"))?;
+        self.inner.write_all(&slot.to_le_bytes())?;
+        self.inner.write_all(&len.to_le_bytes())?;
+        self.inner.write_all(payload)
+    }
+
+    #[allow(dead_code)]
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    #[allow(dead_code)]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+// 逐条读取录制文件
+#[allow(dead_code)]
+pub struct RecordingReader<R: Read> {
+    inner: R,
+}
+
+impl RecordingReader<BufReader<File>> {
+    #[allow(dead_code)]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("无法打开录制文件 {}: {}", path.display(), e)))?;
+        Ok(Self::new(BufReader::new(file)))
+    }
+}
+
+impl<R: Read> RecordingReader<R> {
+    #[allow(dead_code)]
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<(u64, Vec<u8>)>> {
+        let mut header = [0u8; 12];
+        // 文件在记录边界结束时返回None
+        let mut filled = 0;
+        while filled < header.len() {
+            match self.inner.read(&mut header[filled..])? {
+                0 if filled == 0 => return Ok(None),
+                0 => return Err(io::Error::new(ErrorKind::UnexpectedEof, "录制文件在记录头中间结束")),
+                n => filled += n,
+            }
+        }
+
+        let slot = u64::from_le_bytes(header[..8].try_into().unwrap());
+        let len = u32::from_le_bytes(header[8..].try_into().unwrap()) as usize;
+        if len > MAX_PAYLOAD_LEN {
+            return Err(io::Error::new(ErrorKind::InvalidData, format!("slot {} 的载荷长度异常: {}", slot, len)));
+        }
+
+        let mut payload = vec![0u8; len];
+        self.inner
+            .read_exact(&mut payload)
+            .map_err(|e| io::Error::new(e.kind(), format!("slot {} 的载荷不完整", slot)))?;
+        Ok(Some((slot, payload)))
+    }
+}
+
+impl<R: Read> Iterator for RecordingReader<R> {
+    type Item = io::Result<(u64, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+// 读取整个录制文件，结果可直接交给MockShredstreamProxy::from_recording
+#[allow(dead_code)]
+pub fn read_recording<P: AsRef<Path>>(path: P) -> io::Result<Vec<(u64, Vec<u8>)>> {
+    RecordingReader::open(path)?.collect()
+}
@@ -0,0 +1,165 @@
+use jito_protos::auth::Role;
+use jito_protos::bundle::{Bundle, BundleResult};
+use jito_protos::packet::{Meta, Packet};
+use jito_protos::searcher::{
+    searcher_service_client::SearcherServiceClient, ConnectedLeadersRequest,
+    ConnectedLeadersResponse, GetTipAccountsRequest, NextScheduledLeaderRequest,
+    NextScheduledLeaderResponse, SendBundleRequest, SubscribeBundleResultsRequest,
+};
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::transaction::VersionedTransaction;
+use std::io;
+use std::sync::{Arc, RwLock};
+use tonic::codegen::InterceptedService;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::Streaming;
+
+use crate::config::Config;
+use crate::services::auth::{authenticate_and_refresh, AuthInterceptor, AuthSession};
+
+// Jito block engine 的 bundle 提交客户端
+#[allow(dead_code)]
+pub struct SearcherClient {
+    client: SearcherServiceClient<InterceptedService<Channel, AuthInterceptor>>,
+    // 客户端drop时随之停止令牌刷新
+    _auth: Option<AuthSession>,
+}
+
+impl SearcherClient {
+    // 连接block engine，提供keypair时先完成认证
+    #[allow(dead_code)]
+    pub async fn connect(url: String, keypair: Option<Arc<Keypair>>) -> Result<Self, io::Error> {
+        let channel = Self::create_channel(url).await?;
+
+        let auth = match keypair {
+            Some(keypair) => Some(authenticate_and_refresh(channel.clone(), keypair, Role::Searcher).await?),
+            None => None,
+        };
+        let access_token = auth.as_ref().map_or_else(Default::default, AuthSession::access_token);
+
+        Ok(Self {
+            _auth: auth,
+            ..Self::with_channel(channel, access_token)
+        })
+    }
+
+    // 按配置连接endpoints.block_engine_url，配置了auth.keypair_path时用其中的私钥认证
+    #[allow(dead_code)]
+    pub async fn from_config(config: &Config) -> Result<Self, io::Error> {
+        let url = config.endpoints.block_engine_url.clone().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "endpoints.block_engine_url 未设置")
+        })?;
+        let keypair = match &config.auth.keypair_path {
+            Some(path) => Some(Arc::new(read_keypair_file(path).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("无法读取私钥 {}: {}", path.display(), e))
+            })?)),
+            None => None,
+        };
+        Self::connect(url, keypair).await
+    }
+
+    // 使用已建立的channel和共享令牌创建客户端
+    #[allow(dead_code)]
+    pub fn with_channel(channel: Channel, access_token: Arc<RwLock<String>>) -> Self {
+        let client = SearcherServiceClient::with_interceptor(channel, AuthInterceptor::new(access_token));
+        Self { client, _auth: None }
+    }
+
+    async fn create_channel(url: String) -> Result<Channel, io::Error> {
+        let mut endpoint = Endpoint::from_shared(url.clone())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        if url.starts_with("https") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        }
+        endpoint
+            .connect()
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::ConnectionRefused, e.to_string()))
+    }
+
+    // 提交bundle，返回服务端分配的uuid
+    #[allow(dead_code)]
+    pub async fn send_bundle(&mut self, transactions: &[VersionedTransaction]) -> Result<String, io::Error> {
+        let packets = transactions
+            .iter()
+            .map(Self::transaction_to_packet)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let resp = self
+            .client
+            .send_bundle(SendBundleRequest {
+                bundle: Some(Bundle { header: None, packets }),
+            })
+            .await
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        Ok(resp.into_inner().uuid)
+    }
+
+    // 获取小费账户列表
+    #[allow(dead_code)]
+    pub async fn get_tip_accounts(&mut self) -> Result<Vec<String>, io::Error> {
+        let resp = self
+            .client
+            .get_tip_accounts(GetTipAccountsRequest {})
+            .await
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        Ok(resp.into_inner().accounts)
+    }
+
+    // 获取下一个连接到block engine的leader
+    #[allow(dead_code)]
+    pub async fn get_next_scheduled_leader(
+        &mut self,
+        regions: Vec<String>,
+    ) -> Result<NextScheduledLeaderResponse, io::Error> {
+        let resp = self
+            .client
+            .get_next_scheduled_leader(NextScheduledLeaderRequest { regions })
+            .await
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        Ok(resp.into_inner())
+    }
+
+    // 获取当前区域已连接的jito validator及其leader slot
+    #[allow(dead_code)]
+    pub async fn get_connected_leaders(&mut self) -> Result<ConnectedLeadersResponse, io::Error> {
+        let resp = self
+            .client
+            .get_connected_leaders(ConnectedLeadersRequest {})
+            .await
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        Ok(resp.into_inner())
+    }
+
+    // 订阅bundle执行结果
+    #[allow(dead_code)]
+    pub async fn subscribe_bundle_results(&mut self) -> Result<Streaming<BundleResult>, io::Error> {
+        let resp = self
+            .client
+            .subscribe_bundle_results(SubscribeBundleResultsRequest {})
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::ConnectionAborted, e.to_string()))?;
+
+        Ok(resp.into_inner())
+    }
+
+    // 将交易序列化为packet
+    fn transaction_to_packet(transaction: &VersionedTransaction) -> Result<Packet, io::Error> {
+        let data = bincode::serialize(transaction)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+        Ok(Packet {
+            meta: Some(Meta {
+                size: data.len() as u64,
+                ..Default::default()
+            }),
+            data,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+// 线程构建：命名的rayon解析线程池、tokio运行时，以及可选的CPU核心绑定。
+// 解析线程池不使用rayon全局池，避免与其他库或tokio工作线程争用
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::config::RuntimeConfig;
+
+// 构建命名的解析线程池；配置了核心时第i个线程绑定到 cores[i % cores.len()]
+#[allow(dead_code)]
+pub fn parser_pool(threads: Option<usize>, cores: &[usize]) -> Result<ThreadPool, io::Error> {
+    let cores = cores.to_vec();
+    ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or_else(num_cpus::get))
+        .thread_name(|i| format!("shreds-parser-{}", i))
+        .start_handler(move |i| {
+            if !cores.is_empty() {
+                pin_or_warn("解析", cores[i % cores.len()]);
+            }
+        })
+        .build()
+        .map_err(|e| io::Error::other(e.to_string()))
+}
+
+// 按配置构建tokio运行时；配置了tokio_cores时工作线程依次绑定到其中的核心
+#[allow(dead_code)]
+pub fn tokio_runtime(config: &RuntimeConfig) -> Result<Runtime, io::Error> {
+    let cores = Arc::new(config.tokio_cores.clone());
+    let next = Arc::new(AtomicUsize::new(0));
+
+    Builder::new_multi_thread()
+        .worker_threads(config.tokio_threads.max(1))
+        .thread_name("shreds-tokio")
+        .on_thread_start(move || {
+            if !cores.is_empty() {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                pin_or_warn("tokio", cores[i % cores.len()]);
+            }
+        })
+        .enable_all()
+        .build()
+        .map_err(|e| io::Error::other(e.to_string()))
+}
+
+// 绑定失败只记录警告，不影响运行
+#[allow(dead_code)]
+pub fn pin_or_warn(role: &str, core: usize) {
+    match pin_current_thread(core) {
+        Ok(()) => log::debug!("{}线程已绑定到核心 {}", role, core),
+        Err(e) => log::warn!("{}线程绑定核心 {} 失败: {e}", role, core),
+    }
+}
+
+// 把当前线程绑定到单个CPU核心；之后由该线程创建的线程会继承绑定
+#[cfg(target_os = "linux")]
+#[allow(dead_code)]
+pub fn pin_current_thread(core: usize) -> Result<(), io::Error> {
+    if core >= libc::CPU_SETSIZE as usize {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("核心编号超出范围: {}", core)));
+    }
+    // SAFETY: cpu_set_t是纯位图，全零是合法的空集合；core已检查小于CPU_SETSIZE，CPU_SET不会越界；
+    // sched_setaffinity只读取传入的集合，pid为0表示调用线程
+    let rc = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+#[allow(dead_code)]
+pub fn pin_current_thread(core: usize) -> Result<(), io::Error> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("当前平台不支持绑定CPU核心（{}）", core),
+    ))
+}
@@ -1,12 +1,117 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use rayon::prelude::*;
+use rayon::ThreadPool;
 use solana_entry::entry::Entry;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
 
-use crate::models::{TransactionResults, PumpParser, PumpAmmParser, BoopParser};
+use crate::config::{Config, DEFAULT_BATCH_SIZE};
+use crate::idl::IdlRegistry;
+use crate::models::{TransactionResults, PumpParser, PumpAmmParser, BoopParser, IdlParser, SlotTiming, TokenParser, SolFlowParser, AttributionRules};
+use crate::models::sol_flow;
+use crate::services::logging;
 
-// 批处理大小 - 可以根据系统性能调整
-#[allow(dead_code)]
-const BATCH_SIZE: usize = 200; // 增加批处理大小以提高并行效率
+// 交易处理参数，由配置生成；程序ID为None表示禁用对应解析器
+#[derive(Debug, Clone)]
+pub struct ProcessorOptions {
+    pub batch_size: usize,
+    pub pump_program_id: Option<Pubkey>,
+    pub pumpamm_program_id: Option<Pubkey>,
+    pub boop_program_id: Option<Pubkey>,
+    pub registry: Option<Arc<IdlRegistry>>,
+    // 非空时只处理引用了其中任一账户的交易
+    pub filter_accounts: HashSet<Pubkey>,
+    // 为匹配到的交易汇总代币层面的副作用
+    pub token_effects: bool,
+    // 为匹配到的交易汇总SOL流向
+    pub sol_flows: bool,
+    // SOL流向中归类为小费的账户
+    pub tip_accounts: HashSet<Pubkey>,
+    // 归因规则，None时不做归因
+    pub attribution: Option<Arc<AttributionRules>>,
+    // 解析线程池，None时使用rayon全局线程池
+    pub pool: Option<Arc<ThreadPool>>,
+}
+
+impl Default for ProcessorOptions {
+    fn default() -> Self {
+        let parsers = Config::default().parsers;
+        Self {
+            batch_size: DEFAULT_BATCH_SIZE,
+            pump_program_id: parsers.pump_program_id(),
+            pumpamm_program_id: parsers.pumpamm_program_id(),
+            boop_program_id: parsers.boop_program_id(),
+            registry: None,
+            filter_accounts: HashSet::new(),
+            token_effects: parsers.token_effects,
+            sol_flows: parsers.sol_flows,
+            tip_accounts: parsers.tip_account_keys(),
+            attribution: None,
+            pool: None,
+        }
+    }
+}
+
+impl ProcessorOptions {
+    // 根据已校验的配置生成处理参数并加载运行时IDL目录；解析线程池由调用方创建后通过with_pool传入
+    #[allow(dead_code)]
+    pub fn from_config(config: &Config) -> Result<Self, io::Error> {
+        let registry = match &config.parsers.idl_dir {
+            Some(dir) => Some(Arc::new(IdlRegistry::load_dir(dir)?)),
+            None => None,
+        };
+        let attribution = match &config.attribution.rules_file {
+            Some(path) if config.attribution.enabled => Some(Arc::new(AttributionRules::load(path)?)),
+            _ => None,
+        };
+
+        Ok(Self {
+            batch_size: config.processing.batch_size,
+            pump_program_id: config.parsers.pump_program_id(),
+            pumpamm_program_id: config.parsers.pumpamm_program_id(),
+            boop_program_id: config.parsers.boop_program_id(),
+            registry,
+            filter_accounts: config.filters.account_keys().into_iter().collect(),
+            token_effects: config.parsers.token_effects,
+            sol_flows: config.parsers.sol_flows,
+            tip_accounts: config.parsers.tip_account_keys(),
+            attribution,
+            pool: None,
+        })
+    }
+
+    // 使用指定的解析线程池
+    #[allow(dead_code)]
+    pub fn with_pool(mut self, pool: ThreadPool) -> Self {
+        self.pool = Some(Arc::new(pool));
+        self
+    }
+
+    // 解析使用的线程数
+    #[allow(dead_code)]
+    pub fn parser_threads(&self) -> usize {
+        match &self.pool {
+            Some(pool) => pool.current_num_threads(),
+            None => rayon::current_num_threads(),
+        }
+    }
+
+    // 交易是否通过账户过滤
+    #[inline]
+    fn accepts(&self, tx: &VersionedTransaction) -> bool {
+        self.filter_accounts.is_empty()
+            || tx
+                .message
+                .static_account_keys()
+                .iter()
+                .any(|key| self.filter_accounts.contains(key))
+    }
+}
 
 #[allow(dead_code)]
 pub struct TransactionProcessor;
@@ -15,6 +120,47 @@ impl TransactionProcessor {
     // 处理slot中的所有交易
     #[allow(dead_code)]
     pub fn process_entries(entries: &[Entry], slot: u64) -> TransactionResults {
+        Self::process_entries_with(entries, slot, &ProcessorOptions::default())
+    }
+
+    // 实时订阅路径：解析后把收到消息与反序列化完成的时间戳连同解析完成时间写入结果
+    #[allow(dead_code)]
+    pub fn process_received(
+        entries: &[Entry],
+        slot: u64,
+        options: &ProcessorOptions,
+        received_at: Instant,
+        decoded_at: Instant,
+    ) -> TransactionResults {
+        let mut results = Self::process_entries_with(entries, slot, options);
+        results.timing = Some(SlotTiming {
+            received_at,
+            decoded_at,
+            parsed_at: Instant::now(),
+        });
+        results
+    }
+
+    // 按处理参数处理slot中的所有交易
+    #[allow(dead_code)]
+    pub fn process_entries_with(
+        entries: &[Entry],
+        slot: u64,
+        options: &ProcessorOptions,
+    ) -> TransactionResults {
+        match &options.pool {
+            Some(pool) => pool.install(|| Self::parse_entries(entries, slot, options)),
+            None => Self::parse_entries(entries, slot, options),
+        }
+    }
+
+    fn parse_entries(entries: &[Entry], slot: u64, options: &ProcessorOptions) -> TransactionResults {
+        let results = Self::parse_matching(entries, slot, options);
+        logging::report_unknown_discriminators(&results);
+        results
+    }
+
+    fn parse_matching(entries: &[Entry], slot: u64, options: &ProcessorOptions) -> TransactionResults {
         let total_txs = entries.iter().map(|e| e.transactions.len()).sum::<usize>();
         
         // 预分配足够的空间
@@ -32,7 +178,7 @@ impl TransactionProcessor {
         // 将所有交易收集到一个向量中，避免重复检查签名
         for entry in entries {
             for tx in &entry.transactions {
-                if !tx.signatures.is_empty() {
+                if !tx.signatures.is_empty() && options.accepts(tx) {
                     all_transactions.push(tx);
                 }
             }
@@ -47,41 +193,161 @@ impl TransactionProcessor {
         }
         
         // 使用更大的批次进行并行处理
-        all_transactions.par_chunks(BATCH_SIZE).for_each(|batch| {
+        let chunk_size = options.batch_size.max(1);
+        all_transactions.par_chunks(chunk_size).enumerate().for_each(|(chunk, batch)| {
             // 本地收集结果，减少锁争用
             let mut local_pump_results = Vec::new();
             let mut local_pumpamm_results = Vec::new();
             let mut local_boop_results = Vec::new();
+            let mut local_idl_results = Vec::new();
             
             // 并行处理每个交易
-            let pump_results: Vec<_> = batch
-                .par_iter()
-                .filter_map(|tx| {
-                    PumpParser::parse_transaction(tx)
-                })
-                .collect();
+            let pump_results: Vec<_> = match &options.pump_program_id {
+                Some(program_id) => batch
+                    .par_iter()
+                    .filter_map(|tx| {
+                        PumpParser::parse_transaction_with_program_id(tx, program_id)
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
                 
-            let pumpamm_results: Vec<_> = batch
-                .par_iter()
-                .filter_map(|tx| {
-                    PumpAmmParser::parse_transaction(tx)
-                })
-                .collect();
+            let pumpamm_results: Vec<_> = match &options.pumpamm_program_id {
+                Some(program_id) => batch
+                    .par_iter()
+                    .filter_map(|tx| {
+                        PumpAmmParser::parse_transaction_with_program_id(tx, program_id)
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
             
-            let boop_results: Vec<_> = batch
-                .par_iter()
-                .filter_map(|tx| {
-                    BoopParser::parse_transaction(tx)
-                })
-                .collect();
+            let boop_results: Vec<_> = match &options.boop_program_id {
+                Some(program_id) => batch
+                    .par_iter()
+                    .filter_map(|tx| {
+                        BoopParser::parse_transaction_with_program_id(tx, program_id)
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
             
+            let idl_results: Vec<_> = match &options.registry {
+                Some(registry) => batch
+                    .par_iter()
+                    .filter_map(|tx| {
+                        IdlParser::parse_transaction(tx, registry)
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+            
+            // 只为至少被一个解析器匹配的交易汇总代币副作用与SOL流向，
+            // 同时从匹配的指令中取出手续费接收者
+            let effect_results: Vec<_> = if options.token_effects || options.sol_flows {
+                let mut matched: HashMap<&str, Vec<Pubkey>> = HashMap::new();
+                for tx in &pump_results {
+                    matched.entry(tx.signature.as_str()).or_default().extend(
+                        tx.instructions
+                            .iter()
+                            .flat_map(|ix| sol_flow::fee_recipients(ix.account_layout(), &ix.accounts)),
+                    );
+                }
+                for tx in &pumpamm_results {
+                    matched.entry(tx.signature.as_str()).or_default().extend(
+                        tx.instructions
+                            .iter()
+                            .flat_map(|ix| sol_flow::fee_recipients(ix.account_layout(), &ix.accounts)),
+                    );
+                }
+                for tx in &boop_results {
+                    matched.entry(tx.signature.as_str()).or_default().extend(
+                        tx.instructions
+                            .iter()
+                            .flat_map(|ix| sol_flow::fee_recipients(ix.account_layout(), &ix.accounts)),
+                    );
+                }
+                for tx in &idl_results {
+                    matched.entry(tx.signature.as_str()).or_default().extend(
+                        tx.instructions
+                            .iter()
+                            .flat_map(|ix| &ix.accounts)
+                            .filter(|(name, _)| sol_flow::FEE_RECIPIENT_KEYS.contains(&name.as_str()))
+                            .map(|(_, key)| *key),
+                    );
+                }
+                batch
+                    .par_iter()
+                    .filter_map(|tx| {
+                        let signature = tx.signatures[0].to_string();
+                        let fee_recipients = matched.get(signature.as_str())?;
+                        // SOL流向需要代币副作用识别WSOL账户
+                        let effects = TokenParser::parse_transaction(tx);
+                        let flow = if options.sol_flows {
+                            SolFlowParser::parse_transaction(tx, &options.tip_accounts, fee_recipients, effects.as_ref())
+                        } else {
+                            None
+                        };
+                        let effects = effects.filter(|_| options.token_effects);
+                        (effects.is_some() || flow.is_some()).then_some((signature, effects, flow))
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            // 为pump、PumpSwap和boop交易标注来源工具
+            let attribution_results: Vec<_> = match &options.attribution {
+                Some(rules) => {
+                    let trades: HashSet<&str> = pump_results
+                        .iter()
+                        .map(|tx| tx.signature.as_str())
+                        .chain(pumpamm_results.iter().map(|tx| tx.signature.as_str()))
+                        .chain(boop_results.iter().map(|tx| tx.signature.as_str()))
+                        .collect();
+                    batch
+                        .par_iter()
+                        .filter_map(|tx| {
+                            let signature = tx.signatures[0].to_string();
+                            if !trades.contains(signature.as_str()) {
+                                return None;
+                            }
+                            let tool = rules.classify_transaction(tx).to_string();
+                            Some((signature, tool))
+                        })
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+
+            // 匹配交易在消息中的序号，用于恢复并行合并前的entry顺序
+            let index_results: Vec<(String, usize)> = {
+                let matched: HashMap<Signature, &str> = pump_results
+                    .iter()
+                    .map(|tx| tx.signature.as_str())
+                    .chain(pumpamm_results.iter().map(|tx| tx.signature.as_str()))
+                    .chain(boop_results.iter().map(|tx| tx.signature.as_str()))
+                    .chain(idl_results.iter().map(|tx| tx.signature.as_str()))
+                    .filter_map(|signature| Some((Signature::from_str(signature).ok()?, signature)))
+                    .collect();
+                batch
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, tx)| {
+                        let signature = matched.get(&tx.signatures[0])?;
+                        Some((signature.to_string(), chunk * chunk_size + i))
+                    })
+                    .collect()
+            };
+
             // 收集本地结果
             local_pump_results.extend(pump_results);
             local_pumpamm_results.extend(pumpamm_results);
             local_boop_results.extend(boop_results);
+            local_idl_results.extend(idl_results);
             
             // 一次性获取锁并添加所有结果，减少锁争用
-            if !local_pump_results.is_empty() || !local_pumpamm_results.is_empty() || !local_boop_results.is_empty() {
+            if !local_pump_results.is_empty() || !local_pumpamm_results.is_empty() || !local_boop_results.is_empty() || !local_idl_results.is_empty() {
                 let mut tx_results = results.lock().unwrap();
                 
                 // 使用批量添加方法
@@ -96,6 +362,27 @@ impl TransactionProcessor {
                 if !local_boop_results.is_empty() {
                     tx_results.add_boop_transactions(local_boop_results);
                 }
+                
+                if !local_idl_results.is_empty() {
+                    tx_results.add_idl_transactions(local_idl_results);
+                }
+
+                for (signature, effects, flow) in effect_results {
+                    if let Some(effects) = effects {
+                        tx_results.add_token_effects(signature.clone(), effects);
+                    }
+                    if let Some(flow) = flow {
+                        tx_results.add_sol_flow(signature, flow);
+                    }
+                }
+
+                for (signature, tool) in attribution_results {
+                    tx_results.add_attribution(signature, tool);
+                }
+
+                for (signature, index) in index_results {
+                    tx_results.add_transaction_index(signature, index);
+                }
             }
         });
         
@@ -0,0 +1,146 @@
+// 一个slot要写入的全部行：交易与指令取自JSON记录（与 --format json 的输出一致），
+// 买卖与代币发行取自 models::events，买卖汇总是同一slot内每个mint在各场所的成交笔数与数量
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::models::events::{self, TokenLaunch, Trade, TradeSide};
+use crate::models::TransactionResults;
+use crate::utils::account_layout::Program;
+
+// transactions表的一行；JSON列为null时写入NULL
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionRow {
+    pub signature: String,
+    pub parser: String,
+    pub attribution: Option<String>,
+    pub token_effects: Option<String>,
+    pub sol_flow: Option<String>,
+}
+
+// instructions表的一行
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionRow {
+    pub signature: String,
+    pub parser: String,
+    pub position: usize,
+    pub kind: String,
+    pub args: Option<String>,
+    // 账户列表（JSON数组）
+    pub accounts: String,
+    // 指令数据（十六进制），运行时IDL解码的指令没有
+    pub data: Option<String>,
+}
+
+// 同一slot内一个mint在一个场所的买卖汇总；数量取自指令参数，见 models::events
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeSummary {
+    pub mint: String,
+    pub venue: Program,
+    pub pool: Option<String>,
+    pub buys: u64,
+    pub sells: u64,
+    pub token_bought: u64,
+    pub token_sold: u64,
+    pub quote_in: u64,
+    pub quote_out: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SlotBatch {
+    pub slot: u64,
+    pub transactions: Vec<TransactionRow>,
+    pub instructions: Vec<InstructionRow>,
+    pub trades: Vec<Trade>,
+    pub launches: Vec<TokenLaunch>,
+    pub summaries: Vec<TradeSummary>,
+}
+
+fn json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+impl SlotBatch {
+    #[allow(dead_code)]
+    pub fn from_results(results: &TransactionResults) -> Self {
+        let mut batch = Self {
+            slot: results.current_slot,
+            ..Self::default()
+        };
+
+        for record in results.to_json_records() {
+            let signature = record["signature"].as_str().unwrap_or_default().to_string();
+            let parser = record["parser"].as_str().unwrap_or_default().to_string();
+            if let Some(instructions) = record["instructions"].as_array() {
+                for (position, ix) in instructions.iter().enumerate() {
+                    batch.instructions.push(InstructionRow {
+                        signature: signature.clone(),
+                        parser: parser.clone(),
+                        position,
+                        kind: ix["type"].as_str().unwrap_or_default().to_string(),
+                        args: json_text(&ix["args"]),
+                        accounts: ix["accounts"].to_string(),
+                        data: json_text(&ix["data"]),
+                    });
+                }
+            }
+            batch.transactions.push(TransactionRow {
+                signature,
+                parser,
+                attribution: json_text(&record["attribution"]),
+                token_effects: json_text(&record["token_effects"]),
+                sol_flow: json_text(&record["sol_flow"]),
+            });
+        }
+
+        batch.trades = events::trades(results);
+        batch.launches = events::launches(results);
+        batch.summaries = trade_summaries(&batch.trades);
+        batch
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+}
+
+// 按 (场所, mint) 汇总买卖
+fn trade_summaries(trades: &[Trade]) -> Vec<TradeSummary> {
+    let mut summaries: BTreeMap<(Program, &str), TradeSummary> = BTreeMap::new();
+    for trade in trades {
+        let summary = summaries
+            .entry((trade.venue, trade.mint.as_str()))
+            .or_insert_with(|| TradeSummary {
+                mint: trade.mint.clone(),
+                venue: trade.venue,
+                pool: None,
+                buys: 0,
+                sells: 0,
+                token_bought: 0,
+                token_sold: 0,
+                quote_in: 0,
+                quote_out: 0,
+            });
+        if summary.pool.is_none() {
+            summary.pool = trade.pool.clone();
+        }
+        match trade.side {
+            TradeSide::Buy => {
+                summary.buys += 1;
+                summary.token_bought = summary.token_bought.saturating_add(trade.token_amount);
+                summary.quote_in = summary.quote_in.saturating_add(trade.quote_amount);
+            }
+            TradeSide::Sell => {
+                summary.sells += 1;
+                summary.token_sold = summary.token_sold.saturating_add(trade.token_amount);
+                summary.quote_out = summary.quote_out.saturating_add(trade.quote_amount);
+            }
+        }
+    }
+    summaries.into_values().collect()
+}
@@ -0,0 +1,14 @@
+// 持久化：把每个slot的解析结果整理成行（SlotBatch），由后台线程按slot在一个事务中写入SQLite。
+// SQLite通过rusqlite访问（内置编译SQLite），需要以 --features sqlite 编译；未启用时只保留行提取
+pub mod batch;
+pub mod schema;
+#[cfg(feature = "sqlite")]
+mod store;
+mod writer;
+
+pub use batch::{InstructionRow, SlotBatch, TradeSummary, TransactionRow};
+#[cfg(feature = "sqlite")]
+pub use rusqlite::types::Value;
+#[cfg(feature = "sqlite")]
+pub use store::Store;
+pub use writer::StorageWriter;
@@ -0,0 +1,88 @@
+// SQLite表结构与迁移：MIGRATIONS按顺序执行，已执行到的版本记录在 PRAGMA user_version。
+// 只能在末尾追加新的迁移，已发布的迁移不能修改。
+// 数量均为最小单位；超过i64范围的u64以REAL保存
+pub const MIGRATIONS: &[&str] = &[
+    // 1: 初始表结构
+    "
+    CREATE TABLE slots (
+        slot INTEGER PRIMARY KEY,
+        transactions INTEGER NOT NULL,
+        trades INTEGER NOT NULL,
+        launches INTEGER NOT NULL,
+        written_at INTEGER NOT NULL
+    );
+
+    CREATE TABLE transactions (
+        signature TEXT NOT NULL,
+        parser TEXT NOT NULL,
+        slot INTEGER NOT NULL,
+        attribution TEXT,
+        token_effects TEXT,
+        sol_flow TEXT,
+        PRIMARY KEY (signature, parser)
+    );
+    CREATE INDEX transactions_slot ON transactions (slot);
+
+    CREATE TABLE instructions (
+        signature TEXT NOT NULL,
+        parser TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        args TEXT,
+        accounts TEXT NOT NULL,
+        data TEXT,
+        PRIMARY KEY (signature, parser, position)
+    );
+
+    CREATE TABLE trades (
+        signature TEXT NOT NULL,
+        venue TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        slot INTEGER NOT NULL,
+        side TEXT NOT NULL,
+        mint TEXT NOT NULL,
+        pool TEXT,
+        user TEXT,
+        quote_mint TEXT NOT NULL,
+        token_amount INTEGER NOT NULL,
+        quote_amount INTEGER NOT NULL,
+        exact_quote INTEGER NOT NULL,
+        PRIMARY KEY (signature, venue, position)
+    );
+    CREATE INDEX trades_mint_slot ON trades (mint, slot);
+
+    CREATE TABLE token_launches (
+        mint TEXT PRIMARY KEY,
+        venue TEXT NOT NULL,
+        slot INTEGER NOT NULL,
+        signature TEXT NOT NULL,
+        name TEXT,
+        symbol TEXT,
+        uri TEXT,
+        creator TEXT,
+        bonding_curve TEXT
+    );
+
+    CREATE TABLE curve_snapshots (
+        mint TEXT NOT NULL,
+        slot INTEGER NOT NULL,
+        venue TEXT NOT NULL,
+        pool TEXT,
+        buys INTEGER NOT NULL,
+        sells INTEGER NOT NULL,
+        token_bought INTEGER NOT NULL,
+        token_sold INTEGER NOT NULL,
+        quote_in INTEGER NOT NULL,
+        quote_out INTEGER NOT NULL,
+        PRIMARY KEY (mint, slot, venue)
+    );
+    ",
+    // 2: curve_snapshots保存的是按slot的买卖汇总而不是曲线状态，改为与内容一致的表名
+    "ALTER TABLE curve_snapshots RENAME TO trade_summaries;",
+];
+
+// 当前代码对应的表结构版本
+#[allow(dead_code)]
+pub fn version() -> u32 {
+    MIGRATIONS.len() as u32
+}
@@ -0,0 +1,189 @@
+// SQLite存储：打开时执行尚未执行的迁移，每个slot的全部行在一个事务中写入
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::types::Value;
+use rusqlite::{params, Connection};
+
+use crate::storage::batch::SlotBatch;
+use crate::storage::schema::MIGRATIONS;
+
+const INSERT_SLOT: &str = "INSERT OR REPLACE INTO slots (slot, transactions, trades, launches, written_at) VALUES (?, ?, ?, ?, ?)";
+const INSERT_TRANSACTION: &str = "INSERT OR REPLACE INTO transactions \
+    (signature, parser, slot, attribution, token_effects, sol_flow) VALUES (?, ?, ?, ?, ?, ?)";
+const INSERT_INSTRUCTION: &str = "INSERT OR REPLACE INTO instructions \
+    (signature, parser, position, kind, args, accounts, data) VALUES (?, ?, ?, ?, ?, ?, ?)";
+const INSERT_TRADE: &str = "INSERT OR REPLACE INTO trades \
+    (signature, venue, position, slot, side, mint, pool, user, quote_mint, token_amount, quote_amount, exact_quote) \
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+// 同一mint只保留第一次看到的发行记录
+const INSERT_LAUNCH: &str = "INSERT OR IGNORE INTO token_launches \
+    (mint, venue, slot, signature, name, symbol, uri, creator, bonding_curve) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)";
+const INSERT_SUMMARY: &str = "INSERT OR REPLACE INTO trade_summaries \
+    (mint, slot, venue, pool, buys, sells, token_bought, token_sold, quote_in, quote_out) \
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    // 打开（或创建）数据库并迁移到当前版本；数据库版本比代码新时拒绝打开
+    #[allow(dead_code)]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let connection = Connection::open(path.as_ref()).map_err(sql_error)?;
+        connection
+            .execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+            .map_err(sql_error)?;
+        let mut store = Self { connection };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    // 数据库当前的表结构版本
+    #[allow(dead_code)]
+    pub fn schema_version(&self) -> Result<u32, Error> {
+        self.connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .map_err(sql_error)
+    }
+
+    fn migrate(&mut self) -> Result<(), Error> {
+        let current = self.schema_version()? as usize;
+        if current > MIGRATIONS.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("数据库表结构版本 {} 比当前程序支持的 {} 新", current, MIGRATIONS.len()),
+            ));
+        }
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(current) {
+            let version = i + 1;
+            self.transaction(|connection| {
+                connection.execute_batch(migration)?;
+                connection.pragma_update(None, "user_version", version as i64)
+            })
+            .map_err(|e| Error::new(e.kind(), format!("迁移到版本 {} 失败: {}", version, e)))?;
+            log::info!("数据库表结构已迁移到版本 {}", version);
+        }
+        Ok(())
+    }
+
+    // 在一个事务中执行，出错时回滚
+    fn transaction<F>(&mut self, body: F) -> Result<(), Error>
+    where
+        F: FnOnce(&Connection) -> rusqlite::Result<()>,
+    {
+        // Transaction在drop时回滚
+        let transaction = self.connection.transaction().map_err(sql_error)?;
+        body(&transaction).map_err(sql_error)?;
+        transaction.commit().map_err(sql_error)
+    }
+
+    // 写入一个slot；同一slot重复写入时覆盖
+    #[allow(dead_code)]
+    pub fn write(&mut self, batch: &SlotBatch) -> Result<(), Error> {
+        let slot = integer(batch.slot);
+        self.transaction(|connection| {
+            let written_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or(0);
+            connection.prepare_cached(INSERT_SLOT)?.execute(params![
+                slot,
+                integer(batch.transactions.len() as u64),
+                integer(batch.trades.len() as u64),
+                integer(batch.launches.len() as u64),
+                integer(written_at),
+            ])?;
+
+            let mut insert = connection.prepare_cached(INSERT_TRANSACTION)?;
+            for tx in &batch.transactions {
+                insert.execute(params![tx.signature, tx.parser, slot, tx.attribution, tx.token_effects, tx.sol_flow])?;
+            }
+
+            let mut insert = connection.prepare_cached(INSERT_INSTRUCTION)?;
+            for ix in &batch.instructions {
+                insert.execute(params![
+                    ix.signature,
+                    ix.parser,
+                    integer(ix.position as u64),
+                    ix.kind,
+                    ix.args,
+                    ix.accounts,
+                    ix.data,
+                ])?;
+            }
+
+            let mut insert = connection.prepare_cached(INSERT_TRADE)?;
+            for trade in &batch.trades {
+                insert.execute(params![
+                    trade.signature,
+                    trade.venue.parser_name(),
+                    integer(trade.position as u64),
+                    integer(trade.slot),
+                    trade.side.as_str(),
+                    trade.mint,
+                    trade.pool,
+                    trade.user,
+                    trade.quote_mint,
+                    integer(trade.token_amount),
+                    integer(trade.quote_amount),
+                    trade.exact_quote,
+                ])?;
+            }
+
+            let mut insert = connection.prepare_cached(INSERT_LAUNCH)?;
+            for launch in &batch.launches {
+                insert.execute(params![
+                    launch.mint,
+                    launch.venue.parser_name(),
+                    integer(launch.slot),
+                    launch.signature,
+                    launch.name,
+                    launch.symbol,
+                    launch.uri,
+                    launch.creator,
+                    launch.bonding_curve,
+                ])?;
+            }
+
+            let mut insert = connection.prepare_cached(INSERT_SUMMARY)?;
+            for summary in &batch.summaries {
+                insert.execute(params![
+                    summary.mint,
+                    slot,
+                    summary.venue.parser_name(),
+                    summary.pool,
+                    integer(summary.buys),
+                    integer(summary.sells),
+                    integer(summary.token_bought),
+                    integer(summary.token_sold),
+                    integer(summary.quote_in),
+                    integer(summary.quote_out),
+                ])?;
+            }
+            Ok(())
+        })
+    }
+
+    // 执行只读查询，测试与排查时使用
+    #[allow(dead_code)]
+    pub fn query(&self, sql: &str) -> Result<Vec<Vec<Value>>, Error> {
+        let mut statement = self.connection.prepare(sql).map_err(sql_error)?;
+        let columns = statement.column_count();
+        let rows = statement
+            .query_map([], |row| (0..columns).map(|i| row.get::<_, Value>(i)).collect())
+            .map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)
+    }
+}
+
+// u64超出i64范围时以REAL保存
+fn integer(value: u64) -> Value {
+    i64::try_from(value).map_or(Value::Real(value as f64), Value::Integer)
+}
+
+fn sql_error(e: rusqlite::Error) -> Error {
+    Error::other(format!("SQLite: {}", e))
+}
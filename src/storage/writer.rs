@@ -0,0 +1,81 @@
+// 后台写入线程：输出阶段把每个slot的结果整理成SlotBatch放入有界队列（阶段名 storage），
+// 写入线程逐个slot写入，数据库变慢时按队列策略阻塞或丢弃，不影响解析
+use std::io::Error;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::config::StorageConfig;
+use crate::models::TransactionResults;
+use crate::services::pipeline::{BoundedQueue, Pushed};
+use crate::storage::batch::SlotBatch;
+
+pub struct StorageWriter {
+    queue: Arc<BoundedQueue<SlotBatch>>,
+    worker: JoinHandle<()>,
+}
+
+impl StorageWriter {
+    // 在写入线程中打开数据库并完成迁移，打开失败时直接返回错误
+    #[allow(dead_code)]
+    #[cfg(feature = "sqlite")]
+    pub fn start(config: &StorageConfig) -> Result<Self, Error> {
+        use std::sync::mpsc;
+        use std::thread;
+
+        use crate::storage::store::Store;
+
+        let queue = Arc::new(BoundedQueue::new("storage", &config.queue));
+        let (opened, ready) = mpsc::channel();
+        let path = config.path.clone();
+        let input = queue.clone();
+        let worker = thread::Builder::new().name("shreds-storage".to_string()).spawn(move || {
+            let mut store = match Store::open(&path) {
+                Ok(store) => {
+                    let _ = opened.send(Ok(()));
+                    store
+                }
+                Err(e) => {
+                    let _ = opened.send(Err(e));
+                    return;
+                }
+            };
+            while let Some(batch) = input.pop() {
+                if let Err(e) = store.write(&batch) {
+                    log::error!("slot {} 写入数据库失败: {e}", batch.slot);
+                }
+            }
+        })?;
+
+        match ready.recv() {
+            Ok(Ok(())) => {
+                log::info!("解析结果写入数据库: {}", config.path.display());
+                Ok(Self { queue, worker })
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(Error::other("存储线程意外退出")),
+        }
+    }
+
+    #[allow(dead_code)]
+    #[cfg(not(feature = "sqlite"))]
+    pub fn start(_config: &StorageConfig) -> Result<Self, Error> {
+        Err(Error::new(std::io::ErrorKind::Unsupported, "storage 需要以 --features sqlite 编译"))
+    }
+
+    // 提交一个slot的结果，没有匹配交易的slot不写入
+    #[allow(dead_code)]
+    pub fn submit(&self, results: &TransactionResults) -> Pushed {
+        let batch = SlotBatch::from_results(results);
+        if batch.is_empty() {
+            return Pushed::Queued;
+        }
+        self.queue.push(batch)
+    }
+
+    // 关闭队列并等待剩余的slot写完
+    #[allow(dead_code)]
+    pub fn shutdown(self) {
+        self.queue.close();
+        let _ = self.worker.join();
+    }
+}
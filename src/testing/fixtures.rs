@@ -0,0 +1,255 @@
+// 测试用交易构造工具，按各程序IDL的账户布局与参数格式生成合成交易
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use solana_entry::entry::Entry;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::{Message, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::config::{BOOP_PROGRAM_ID, PUMPAMM_PROGRAM_ID, PUMP_PROGRAM_ID};
+use crate::idl::IdlInstructionDef;
+use crate::models::token_parser::{ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID, WSOL_MINT};
+use crate::models::TransactionResults;
+use crate::services::TransactionProcessor;
+
+// 用于生成互不相同的签名
+static SIGNATURE_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+// 生成唯一的伪签名
+pub fn unique_signature() -> Signature {
+    let n = SIGNATURE_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut bytes = [0u8; 64];
+    bytes[..8].copy_from_slice(&n.to_le_bytes());
+    Signature::from(bytes)
+}
+
+// 构造只包含一条指令的legacy交易，账户全部为新生成的地址
+pub fn instruction_transaction(program_id: Pubkey, data: Vec<u8>, account_count: usize) -> VersionedTransaction {
+    single_instruction_transaction(instruction(program_id, data, account_count))
+}
+
+// 构造一条指令，账户全部为新生成的地址，第一个账户为签名者
+pub fn instruction(program_id: Pubkey, data: Vec<u8>, account_count: usize) -> Instruction {
+    let accounts = (0..account_count)
+        .map(|i| {
+            if i == 0 {
+                AccountMeta::new(Pubkey::new_unique(), true)
+            } else {
+                AccountMeta::new(Pubkey::new_unique(), false)
+            }
+        })
+        .collect();
+    Instruction { program_id, accounts, data }
+}
+
+// 按IDL指令定义构造指令：账户数与IDL一致，按名称指定的账户使用给定地址，其余为新生成的地址
+pub fn idl_instruction(
+    program_id: Pubkey,
+    def: &IdlInstructionDef,
+    args: &[&[u8]],
+    accounts: &[(&str, Pubkey)],
+) -> Instruction {
+    let mut instruction = instruction(program_id, instruction_data(def.discriminator, args), def.accounts.len());
+    for (name, key) in accounts {
+        let index = def
+            .accounts
+            .iter()
+            .position(|account| account == name)
+            .unwrap_or_else(|| panic!("指令 {} 没有账户 {}", def.name, name));
+        instruction.accounts[index].pubkey = *key;
+    }
+    instruction
+}
+
+// 使用指定指令构造legacy交易
+pub fn single_instruction_transaction(instruction: Instruction) -> VersionedTransaction {
+    transaction(&[instruction])
+}
+
+// 使用多条指令构造legacy交易，付款人为第一个签名账户
+pub fn transaction(instructions: &[Instruction]) -> VersionedTransaction {
+    let payer = instructions
+        .iter()
+        .flat_map(|instruction| &instruction.accounts)
+        .find(|meta| meta.is_signer)
+        .map(|meta| meta.pubkey)
+        .unwrap_or_else(Pubkey::new_unique);
+    let message = Message::new_with_blockhash(instructions, Some(&payer), &Hash::default());
+    let signatures = (0..message.header.num_required_signatures)
+        .map(|_| unique_signature())
+        .collect();
+
+    VersionedTransaction {
+        signatures,
+        message: VersionedMessage::Legacy(message),
+    }
+}
+
+// 将交易打包成shredstream Entry负载（bincode编码的Vec<Entry>）
+pub fn entries_payload(transactions: Vec<VersionedTransaction>) -> Vec<u8> {
+    let entries = vec![Entry {
+        num_hashes: 1,
+        hash: Hash::new_unique(),
+        transactions,
+    }];
+    bincode::serialize(&entries).expect("Entry序列化失败")
+}
+
+// 把交易打包成一个Entry负载再按默认参数处理，得到一个slot的解析结果
+pub fn process(transactions: Vec<VersionedTransaction>, slot: u64) -> TransactionResults {
+    let entries: Vec<Entry> = bincode::deserialize(&entries_payload(transactions)).expect("Entry反序列化失败");
+    TransactionProcessor::process_entries(&entries, slot)
+}
+
+// 交易的第一个签名
+pub fn signature(transaction: &VersionedTransaction) -> String {
+    transaction.signatures[0].to_string()
+}
+
+// 组装 discriminator + 参数
+pub fn instruction_data(discriminator: [u8; 8], args: &[&[u8]]) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    for arg in args {
+        data.extend_from_slice(arg);
+    }
+    data
+}
+
+// Borsh字符串编码
+pub fn borsh_string(value: &str) -> Vec<u8> {
+    let mut bytes = (value.len() as u32).to_le_bytes().to_vec();
+    bytes.extend_from_slice(value.as_bytes());
+    bytes
+}
+
+pub fn pump_program_id() -> Pubkey {
+    Pubkey::from_str(PUMP_PROGRAM_ID).unwrap()
+}
+
+pub fn pumpamm_program_id() -> Pubkey {
+    Pubkey::from_str(PUMPAMM_PROGRAM_ID).unwrap()
+}
+
+pub fn boop_program_id() -> Pubkey {
+    Pubkey::from_str(BOOP_PROGRAM_ID).unwrap()
+}
+
+// PUMP Buy交易
+pub fn pump_buy(amount: u64, max_sol_cost: u64) -> VersionedTransaction {
+    let data = instruction_data(
+        [102, 6, 61, 18, 1, 218, 235, 234],
+        &[&amount.to_le_bytes(), &max_sol_cost.to_le_bytes()],
+    );
+    instruction_transaction(pump_program_id(), data, 12)
+}
+
+// PUMP Sell交易
+pub fn pump_sell(amount: u64, min_sol_output: u64) -> VersionedTransaction {
+    let data = instruction_data(
+        [51, 230, 133, 164, 1, 127, 131, 173],
+        &[&amount.to_le_bytes(), &min_sol_output.to_le_bytes()],
+    );
+    instruction_transaction(pump_program_id(), data, 12)
+}
+
+// PUMP Create交易
+pub fn pump_create(name: &str, symbol: &str, uri: &str, creator: &Pubkey) -> VersionedTransaction {
+    let data = instruction_data(
+        [24, 30, 200, 40, 5, 28, 7, 119],
+        &[&borsh_string(name), &borsh_string(symbol), &borsh_string(uri), creator.as_ref()],
+    );
+    instruction_transaction(pump_program_id(), data, 14)
+}
+
+// PumpSwap Buy交易
+pub fn pumpswap_buy(base_amount_out: u64, max_quote_amount_in: u64) -> VersionedTransaction {
+    let data = instruction_data(
+        [102, 6, 61, 18, 1, 218, 235, 234],
+        &[&base_amount_out.to_le_bytes(), &max_quote_amount_in.to_le_bytes()],
+    );
+    instruction_transaction(pumpamm_program_id(), data, 17)
+}
+
+// PumpSwap Sell交易
+pub fn pumpswap_sell(base_amount_in: u64, min_quote_amount_out: u64) -> VersionedTransaction {
+    let data = instruction_data(
+        [51, 230, 133, 164, 1, 127, 131, 173],
+        &[&base_amount_in.to_le_bytes(), &min_quote_amount_out.to_le_bytes()],
+    );
+    instruction_transaction(pumpamm_program_id(), data, 17)
+}
+
+// boop BuyToken交易
+pub fn boop_buy_token(buy_amount: u64, amount_out_min: u64) -> VersionedTransaction {
+    let data = instruction_data(
+        [138, 127, 14, 91, 38, 87, 115, 105],
+        &[&buy_amount.to_le_bytes(), &amount_out_min.to_le_bytes()],
+    );
+    instruction_transaction(boop_program_id(), data, 13)
+}
+
+// boop SellToken交易
+pub fn boop_sell_token(sell_amount: u64, amount_out_min: u64) -> VersionedTransaction {
+    let data = instruction_data(
+        [109, 61, 40, 187, 230, 176, 135, 174],
+        &[&sell_amount.to_le_bytes(), &amount_out_min.to_le_bytes()],
+    );
+    instruction_transaction(boop_program_id(), data, 12)
+}
+
+// System Program Transfer指令，from为签名者
+pub fn system_transfer(from: Pubkey, to: Pubkey, lamports: u64) -> Instruction {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Instruction {
+        program_id: SYSTEM_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(from, true), AccountMeta::new(to, false)],
+        data,
+    }
+}
+
+// 典型的PumpSwap买入：创建WSOL关联账户、转入lamports并同步、买入、最后关闭WSOL账户取回剩余SOL
+pub fn pumpswap_buy_with_wsol(base_amount_out: u64, max_quote_amount_in: u64, lamports: u64) -> VersionedTransaction {
+    let wallet = Pubkey::new_unique();
+    let wsol_account = Pubkey::new_unique();
+
+    let buy_data = instruction_data(
+        [102, 6, 61, 18, 1, 218, 235, 234],
+        &[&base_amount_out.to_le_bytes(), &max_quote_amount_in.to_le_bytes()],
+    );
+
+    transaction(&[
+        Instruction {
+            program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(wallet, true),
+                AccountMeta::new(wsol_account, false),
+                AccountMeta::new_readonly(wallet, false),
+                AccountMeta::new_readonly(WSOL_MINT, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            ],
+            data: vec![1],
+        },
+        system_transfer(wallet, wsol_account, lamports),
+        Instruction {
+            program_id: TOKEN_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(wsol_account, false)],
+            data: vec![17],
+        },
+        instruction(pumpamm_program_id(), buy_data, 17),
+        Instruction {
+            program_id: TOKEN_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(wsol_account, false),
+                AccountMeta::new(wallet, false),
+                AccountMeta::new_readonly(wallet, true),
+            ],
+            data: vec![9],
+        },
+    ])
+}
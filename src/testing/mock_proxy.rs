@@ -0,0 +1,131 @@
+// 本地 mock ShredstreamProxy 服务，按脚本推送Entry，用于离线端到端测试
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::stream::{self, Stream};
+
+use jito_protos::shredstream::shredstream_proxy_server::{ShredstreamProxy, ShredstreamProxyServer};
+use jito_protos::shredstream::{Entry, SubscribeEntriesRequest};
+use solana_sdk::transaction::VersionedTransaction;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status};
+
+use crate::testing::fixtures::entries_payload;
+
+// 脚本中的单个步骤
+#[derive(Debug, Clone)]
+pub enum MockEvent {
+    // 推送由交易组成的Entry
+    Entries { slot: u64, transactions: Vec<VersionedTransaction> },
+    // 原样推送录制的负载
+    Raw { slot: u64, payload: Vec<u8> },
+    // 推送无法反序列化的负载
+    Malformed { slot: u64 },
+    // 暂停一段时间
+    Delay(Duration),
+    // 以错误状态断开连接
+    Disconnect(String),
+}
+
+// 按脚本回放的ShredstreamProxy实现，每个订阅者都从头回放脚本
+#[derive(Clone)]
+pub struct MockShredstreamProxy {
+    script: Arc<Vec<MockEvent>>,
+    subscriptions: Arc<AtomicUsize>,
+}
+
+impl MockShredstreamProxy {
+    pub fn new(script: Vec<MockEvent>) -> Self {
+        Self {
+            script: Arc::new(script),
+            subscriptions: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    // 使用录制的 (slot, 负载) 序列构造
+    pub fn from_recording(recording: Vec<(u64, Vec<u8>)>) -> Self {
+        Self::new(
+            recording
+                .into_iter()
+                .map(|(slot, payload)| MockEvent::Raw { slot, payload })
+                .collect(),
+        )
+    }
+
+    // 已收到的订阅次数
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.load(Ordering::Relaxed)
+    }
+
+    // 在本地随机端口启动服务，返回可直接传给JitoClient的URL
+    pub async fn spawn(self) -> Result<(String, JoinHandle<()>), io::Error> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let addr = listener.local_addr()?;
+
+        let handle = tokio::spawn(async move {
+            if let Err(e) = tonic::transport::Server::builder()
+                .add_service(ShredstreamProxyServer::new(self))
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+            {
+                log::warn!("mock proxy 退出: {e}");
+            }
+        });
+
+        Ok((format!("http://{}", addr), handle))
+    }
+}
+
+#[tonic::async_trait]
+impl ShredstreamProxy for MockShredstreamProxy {
+    type SubscribeEntriesStream = Pin<Box<dyn Stream<Item = Result<Entry, Status>> + Send>>;
+
+    async fn subscribe_entries(
+        &self,
+        _request: Request<SubscribeEntriesRequest>,
+    ) -> Result<Response<Self::SubscribeEntriesStream>, Status> {
+        self.subscriptions.fetch_add(1, Ordering::Relaxed);
+
+        // 服务端直接轮询脚本，每条消息在上一条被取走后才生成
+        let stream = stream::unfold((self.script.clone(), 0), |(script, mut next)| async move {
+            loop {
+                let event = script.get(next)?;
+                next += 1;
+                let item = match event {
+                    MockEvent::Entries { slot, transactions } => Ok(Entry {
+                        slot: *slot,
+                        entries: entries_payload(transactions.clone()),
+                    }),
+                    MockEvent::Raw { slot, payload } => Ok(Entry {
+                        slot: *slot,
+                        entries: payload.clone(),
+                    }),
+                    MockEvent::Malformed { slot } => Ok(Entry {
+                        slot: *slot,
+                        entries: vec![0xff; 7],
+                    }),
+                    MockEvent::Delay(duration) => {
+                        tokio::time::sleep(*duration).await;
+                        continue;
+                    }
+                    MockEvent::Disconnect(reason) => {
+                        // tonic遇到错误时会丢弃已编码但尚未写出的消息；先让出一次，
+                        // 编码器在Pending时写出已缓冲的消息，之后的错误才作为trailers发送
+                        tokio::task::yield_now().await;
+                        next = script.len();
+                        Err(Status::unavailable(reason.clone()))
+                    }
+                };
+                return Some((item, (script, next)));
+            }
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
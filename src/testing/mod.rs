@@ -0,0 +1,5 @@
+// 测试支持模块：合成交易构造与本地mock服务
+pub mod fixtures;
+pub mod mock_proxy;
+
+pub use mock_proxy::{MockEvent, MockShredstreamProxy};
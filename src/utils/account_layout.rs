@@ -0,0 +1,233 @@
+// 账户布局注册表：按生成的指令枚举给出每个账户索引的规范键（见crate::labels），解析器与所有输出共用。
+// IDL中存在的指令直接引用build.rs生成的账户表，只有boop的历史指令在这里手写
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde_json::{json, Value};
+
+use crate::idl::{boop, pump, pump_amm};
+use crate::labels;
+
+// 注册表覆盖的程序，对应各专用解析器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Program {
+    Pump,
+    PumpAmm,
+    Boop,
+}
+
+impl Program {
+    // 输出中使用的解析器名
+    #[allow(dead_code)]
+    pub fn parser_name(&self) -> &'static str {
+        match self {
+            Program::Pump => "PUMP",
+            Program::PumpAmm => "PUMPAMM",
+            Program::Boop => "BOOP",
+        }
+    }
+}
+
+// 注册表的键：生成的指令枚举（含历史指令），新增到idl/*.json的指令自动有布局
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProgramInstruction {
+    Pump(pump::Instruction),
+    PumpAmm(pump_amm::Instruction),
+    Boop(boop::Instruction),
+}
+
+impl ProgramInstruction {
+    #[allow(dead_code)]
+    pub fn program(&self) -> Program {
+        match self {
+            ProgramInstruction::Pump(_) => Program::Pump,
+            ProgramInstruction::PumpAmm(_) => Program::PumpAmm,
+            ProgramInstruction::Boop(_) => Program::Boop,
+        }
+    }
+
+    // 解析器中的指令类型名，例如 "Buy"、"BuyToken"
+    #[allow(dead_code)]
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProgramInstruction::Pump(ix) => ix.name(),
+            ProgramInstruction::PumpAmm(ix) => ix.name(),
+            ProgramInstruction::Boop(ix) => ix.name(),
+        }
+    }
+
+    // IDL中的账户表，历史指令取手写的表
+    fn accounts(&self) -> &'static [&'static str] {
+        let def = match self {
+            ProgramInstruction::Pump(ix) => ix.def(),
+            ProgramInstruction::PumpAmm(ix) => ix.def(),
+            ProgramInstruction::Boop(ix) => ix.def(),
+        };
+        match (def, self) {
+            (Some(def), _) => def.accounts,
+            (None, ProgramInstruction::Boop(ix)) => BOOP_LEGACY
+                .iter()
+                .find(|(legacy, _)| legacy == ix)
+                .map(|(_, accounts)| *accounts)
+                .unwrap_or_else(|| panic!("BOOP历史指令 {:?} 没有账户表", ix)),
+            (None, _) => panic!("{:?} 不在IDL中，也没有手写的账户表", self),
+        }
+    }
+}
+
+impl From<pump::Instruction> for ProgramInstruction {
+    fn from(ix: pump::Instruction) -> Self {
+        ProgramInstruction::Pump(ix)
+    }
+}
+
+impl From<pump_amm::Instruction> for ProgramInstruction {
+    fn from(ix: pump_amm::Instruction) -> Self {
+        ProgramInstruction::PumpAmm(ix)
+    }
+}
+
+impl From<boop::Instruction> for ProgramInstruction {
+    fn from(ix: boop::Instruction) -> Self {
+        ProgramInstruction::Boop(ix)
+    }
+}
+
+// 单条指令的账户布局
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountLayout {
+    pub instruction: ProgramInstruction,
+    pub accounts: &'static [&'static str],
+}
+
+impl AccountLayout {
+    #[allow(dead_code)]
+    pub fn program(&self) -> Program {
+        self.instruction.program()
+    }
+
+    // 账户索引对应的规范键
+    #[allow(dead_code)]
+    pub fn label(&self, index: usize) -> Option<&'static str> {
+        self.accounts.get(index).copied()
+    }
+
+    // 规范键对应的账户索引
+    #[allow(dead_code)]
+    pub fn index_of(&self, key: &str) -> Option<usize> {
+        self.accounts.iter().position(|account| *account == key)
+    }
+
+    // 账户数与布局不一致时返回异常
+    #[allow(dead_code)]
+    pub fn check(&self, actual: usize) -> Option<AccountAnomaly> {
+        (actual != self.accounts.len()).then_some(AccountAnomaly {
+            expected: self.accounts.len(),
+            actual,
+        })
+    }
+}
+
+// 账户数异常：少于布局说明交易被截断或布局过时，多于布局通常是程序升级新增了账户
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountAnomaly {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl AccountAnomaly {
+    #[allow(dead_code)]
+    pub fn kind(&self) -> &'static str {
+        if self.actual < self.expected {
+            "missing"
+        } else {
+            "extra"
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind(),
+            "expected": self.expected,
+            "actual": self.actual,
+        })
+    }
+}
+
+impl fmt::Display for AccountAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}={} {}={}",
+            labels::text(self.kind()),
+            labels::text("expected"),
+            self.expected,
+            labels::text("actual"),
+            self.actual
+        )
+    }
+}
+
+// BOOP历史指令（当前IDL中已没有）的账户表；IDL中存在的指令直接引用build.rs生成的账户表
+const BOOP_LEGACY: &[(boop::Instruction, &[&str])] = &[
+    (
+        boop::Instruction::Create,
+        &["config", "metadata", "mint", "payer", "rent", "system_program", "token_program", "token_metadata_program"],
+    ),
+    (
+        boop::Instruction::Sell,
+        &[
+            "global",
+            "fee_recipient",
+            "mint",
+            "bonding_curve",
+            "associated_bonding_curve",
+            "user",
+            "token_program",
+            "event_authority",
+            "program",
+        ],
+    ),
+    (boop::Instruction::SetParams, &["global", "admin", "event_authority", "program"]),
+    (boop::Instruction::UpdateAuthority, &["global", "admin", "new_admin", "event_authority", "program"]),
+];
+
+static LAYOUTS: OnceLock<Vec<AccountLayout>> = OnceLock::new();
+
+// 全部布局：遍历生成的指令枚举，每个变体一条
+#[allow(dead_code)]
+pub fn layouts() -> &'static [AccountLayout] {
+    LAYOUTS.get_or_init(|| {
+        let instructions = pump::Instruction::ALL
+            .iter()
+            .map(|ix| ProgramInstruction::from(*ix))
+            .chain(pump_amm::Instruction::ALL.iter().map(|ix| ProgramInstruction::from(*ix)))
+            .chain(boop::Instruction::ALL.iter().map(|ix| ProgramInstruction::from(*ix)));
+        instructions
+            .map(|instruction| AccountLayout {
+                instruction,
+                accounts: instruction.accounts(),
+            })
+            .collect()
+    })
+}
+
+// 查找指令的账户布局
+#[allow(dead_code)]
+pub fn find(instruction: impl Into<ProgramInstruction>) -> Option<&'static AccountLayout> {
+    let instruction = instruction.into();
+    layouts().iter().find(|layout| layout.instruction == instruction)
+}
+
+// 按布局输出账户列表，账户数不一致时追加一行异常说明
+#[allow(dead_code)]
+pub fn write_accounts(f: &mut fmt::Formatter<'_>, layout: Option<&AccountLayout>, accounts: &[String]) -> fmt::Result {
+    for (i, account) in accounts.iter().enumerate() {
+        labels::account(f, i, layout.and_then(|layout| layout.label(i)), account)?;
+    }
+    if let Some(anomaly) = layout.and_then(|layout| layout.check(accounts.len())) {
+        labels::field(f, "account_anomaly", anomaly)?;
+    }
+    Ok(())
+}
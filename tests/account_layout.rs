@@ -0,0 +1,101 @@
+// 账户布局注册表与账户数异常测试
+use std::collections::HashSet;
+
+use shreds_client::models::boop_parser::BoopInstructionType;
+use shreds_client::models::pump_parser::PumpInstructionType;
+use shreds_client::models::pumpamm_parser::PumpAmmInstructionType;
+use shreds_client::services::metrics::Metrics;
+use shreds_client::testing::fixtures;
+use shreds_client::utils::account_layout::{self, AccountAnomaly, Program};
+use shreds_client::TransactionProcessor;
+use solana_sdk::transaction::VersionedTransaction;
+
+fn process(transactions: Vec<VersionedTransaction>) -> shreds_client::TransactionResults {
+    let entries: Vec<solana_entry::entry::Entry> =
+        bincode::deserialize(&fixtures::entries_payload(transactions)).unwrap();
+    TransactionProcessor::process_entries(&entries, 7)
+}
+
+#[test]
+fn every_parser_instruction_has_a_layout() {
+    let mut seen = HashSet::new();
+    for layout in account_layout::layouts() {
+        assert!(seen.insert(layout.instruction), "重复的布局: {:?}", layout);
+        assert!(!layout.accounts.is_empty());
+    }
+
+    // 生成的指令枚举（含历史指令）每个变体都有布局，IDL指令直接使用IDL的账户表
+    let total = PumpInstructionType::ALL.len() + PumpAmmInstructionType::ALL.len() + BoopInstructionType::ALL.len();
+    assert_eq!(seen.len(), total);
+    for ix in PumpInstructionType::ALL {
+        let layout = account_layout::find(*ix).unwrap();
+        assert_eq!((layout.program(), layout.instruction.name()), (Program::Pump, ix.name()));
+        assert_eq!(layout.accounts, ix.def().unwrap().accounts);
+    }
+    for ix in PumpAmmInstructionType::ALL {
+        assert_eq!(account_layout::find(*ix).unwrap().program(), Program::PumpAmm, "{:?}", ix);
+    }
+    for ix in BoopInstructionType::ALL {
+        assert_eq!(account_layout::find(*ix).unwrap().program(), Program::Boop, "{:?}", ix);
+    }
+    // 历史指令取手写的账户表
+    assert!(BoopInstructionType::UpdateAuthority.def().is_none());
+    assert_eq!(account_layout::find(BoopInstructionType::UpdateAuthority).unwrap().label(2), Some("new_admin"));
+}
+
+#[test]
+fn layouts_follow_the_idl() {
+    let sell = account_layout::find(PumpInstructionType::Sell).unwrap();
+    assert_eq!(sell.label(8), Some("associated_token_program"));
+    assert_eq!(sell.label(9), Some("token_program"));
+    assert_eq!(sell.label(12), None);
+
+    let buy = account_layout::find(PumpAmmInstructionType::Buy).unwrap();
+    assert_eq!(buy.accounts.len(), 17);
+    assert_eq!(buy.label(0), Some("pool"));
+}
+
+#[test]
+fn account_count_mismatches_are_flagged() {
+    let layout = account_layout::find(BoopInstructionType::BuyToken).unwrap();
+    assert_eq!(layout.check(13), None);
+    let missing = layout.check(9).unwrap();
+    assert_eq!(missing, AccountAnomaly { expected: 13, actual: 9 });
+    assert_eq!(missing.kind(), "missing");
+    assert_eq!(layout.check(15).unwrap().kind(), "extra");
+
+    let results = process(vec![
+        fixtures::pump_buy(1, 2),
+        fixtures::instruction_transaction(
+            fixtures::pump_program_id(),
+            fixtures::instruction_data([102, 6, 61, 18, 1, 218, 235, 234], &[&[0; 16]]),
+            14,
+        ),
+    ]);
+    let anomalies: Vec<_> = results
+        .pump_transactions
+        .iter()
+        .flat_map(|tx| tx.instructions.iter().map(|ix| ix.account_anomaly()))
+        .collect();
+    assert_eq!(anomalies.iter().filter(|a| a.is_none()).count(), 1);
+    assert!(anomalies.contains(&Some(AccountAnomaly { expected: 12, actual: 14 })));
+
+    let records = results.to_json_records();
+    let flagged: Vec<_> = records
+        .iter()
+        .filter_map(|record| record["instructions"][0].get("account_anomaly"))
+        .collect();
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0]["kind"], "extra");
+
+    let mut text = Vec::new();
+    results.write_to(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.contains("Account_Anomaly: extra expected=12 actual=14"));
+    assert!(text.contains("[12]Unknown: "));
+
+    let metrics = Metrics::new();
+    metrics.record_results(&results);
+    assert_eq!(metrics.account_anomalies.get(&["PUMP", "Buy", "extra"]), 1);
+    assert_eq!(metrics.parser_matches.get(&["PUMP", "Buy"]), 2);
+}
@@ -0,0 +1,263 @@
+// 跨场所套利测试：同一mint的PumpSwap池子之间、外部报告的池子、阈值、过期价格、重复机会与输出
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use shreds_client::analytics::{ArbitrageDetector, Opportunity, PoolRegistry, Venue, VenuePrice};
+use shreds_client::config::{ArbitrageConfig, Config, PoolsConfig};
+use shreds_client::idl::pump;
+use shreds_client::idl::pump_amm::Instruction;
+use shreds_client::models::token_parser::WSOL_MINT;
+use shreds_client::services::OutputSink;
+use shreds_client::testing::fixtures;
+use shreds_client::TransactionResults;
+
+struct Pool {
+    pool: Pubkey,
+    mint: Pubkey,
+    quote: Pubkey,
+}
+
+impl Pool {
+    fn new(mint: Pubkey) -> Self {
+        Self {
+            pool: Pubkey::new_unique(),
+            mint,
+            quote: WSOL_MINT,
+        }
+    }
+
+    fn instruction(&self, instruction: Instruction, args: &[&[u8]]) -> VersionedTransaction {
+        fixtures::single_instruction_transaction(fixtures::idl_instruction(
+            fixtures::pumpamm_program_id(),
+            instruction.def().unwrap(),
+            args,
+            &[("pool", self.pool), ("base_mint", self.mint), ("quote_mint", self.quote)],
+        ))
+    }
+
+    fn create(&self, base: u64, quote: u64) -> VersionedTransaction {
+        self.instruction(Instruction::CreatePool, &[&0u16.to_le_bytes(), &base.to_le_bytes(), &quote.to_le_bytes()])
+    }
+
+    fn buy(&self, base_out: u64, max_quote_in: u64) -> VersionedTransaction {
+        self.instruction(Instruction::Buy, &[&base_out.to_le_bytes(), &max_quote_in.to_le_bytes()])
+    }
+}
+
+// 默认费率 lp 20 + 协议 5 基点
+fn pools() -> PoolRegistry {
+    PoolRegistry::new(&PoolsConfig {
+        enabled: true,
+        ..PoolsConfig::default()
+    })
+}
+
+fn detector(threshold_bps: u64, max_age_slots: u64) -> ArbitrageDetector {
+    ArbitrageDetector::new(&ArbitrageConfig {
+        enabled: true,
+        threshold_bps,
+        max_age_slots,
+    })
+}
+
+fn record(
+    arbitrage: &mut ArbitrageDetector,
+    registry: &mut PoolRegistry,
+    results: &TransactionResults,
+    events: &mut Vec<Opportunity>,
+) {
+    registry.record(results);
+    arbitrage.record(results, registry, events);
+}
+
+#[test]
+fn pumpswap_pools_of_the_same_mint_are_compared_after_fees() {
+    let mint = Pubkey::new_unique();
+    let (cheap, dear) = (Pool::new(mint), Pool::new(mint));
+    let mut registry = pools();
+    let mut arbitrage = detector(40, 100);
+    let mut events = Vec::new();
+
+    // 1.01 * (1 - 0.0025) / (1 * 1.0025) - 1 ≈ 49.6 基点；触发交易是entry中最后改变价格的指令
+    let create = dear.create(1_000_000, 1_010_000);
+    let tx = fixtures::signature(&create);
+    let mut results = fixtures::process(vec![cheap.create(1_000_000, 1_000_000), create], 10);
+    results.pumpamm_transactions.reverse();
+    record(&mut arbitrage, &mut registry, &results, &mut events);
+
+    assert_eq!(events.len(), 1);
+    let opportunity = &events[0];
+    assert_eq!((opportunity.mint.clone(), opportunity.slot, opportunity.tx.clone()), (mint.to_string(), 10, tx));
+    assert_eq!((opportunity.buy.pool.clone(), opportunity.sell.pool.clone()), (cheap.pool.to_string(), dear.pool.to_string()));
+    assert_eq!((opportunity.buy.venue, opportunity.buy.fee_bps, opportunity.sell.price), (Venue::PumpSwap, 25, 1.01));
+    assert!((opportunity.net_bps - 49.6259).abs() < 1e-3, "{}", opportunity.net_bps);
+    assert_eq!(arbitrage.prices(&mint.to_string()).len(), 2);
+
+    // 按估算会失败的买入不改变价格，同一个机会不重复输出
+    record(&mut arbitrage, &mut registry, &fixtures::process(vec![cheap.buy(1_000, 0)], 11), &mut events);
+    assert_eq!(events.len(), 1);
+
+    // 买入便宜池子后价差收窄到阈值以下
+    record(&mut arbitrage, &mut registry, &fixtures::process(vec![cheap.buy(2_000, 3_000)], 12), &mut events);
+    assert_eq!(events.len(), 1);
+    assert_eq!(arbitrage.opportunities(), 1);
+}
+
+#[test]
+fn spreads_below_the_threshold_and_non_sol_pools_are_ignored() {
+    let mint = Pubkey::new_unique();
+    let mut other = Pool::new(mint);
+    other.quote = Pubkey::new_unique();
+    let mut registry = pools();
+    let mut arbitrage = detector(60, 100);
+    let mut events = Vec::new();
+
+    let results = fixtures::process(
+        vec![
+            Pool::new(mint).create(1_000_000, 1_000_000),
+            Pool::new(mint).create(1_000_000, 1_010_000),
+            other.create(1_000_000, 5_000_000),
+        ],
+        20,
+    );
+    record(&mut arbitrage, &mut registry, &results, &mut events);
+    assert!(events.is_empty());
+    assert_eq!(arbitrage.prices(&mint.to_string()).len(), 2);
+}
+
+#[test]
+fn other_venues_report_prices_and_stale_prices_expire() {
+    let mint = Pubkey::new_unique();
+    let pool = Pool::new(mint);
+    let mut registry = pools();
+    let mut arbitrage = detector(50, 10);
+    let mut events = Vec::new();
+    record(&mut arbitrage, &mut registry, &fixtures::process(vec![pool.create(1_000_000, 1_000_000)], 30), &mut events);
+
+    let raydium = |price, slot| VenuePrice {
+        venue: Venue::Raydium,
+        pool: "raydium-pool".to_string(),
+        price,
+        fee_bps: 25,
+        slot,
+    };
+    arbitrage.price_updated(&mint.to_string(), raydium(1.02, 35), "raydium-tx", &mut events);
+    assert_eq!(events.len(), 1);
+    assert_eq!((events[0].buy.venue, events[0].sell.venue, events[0].tx.as_str()), (Venue::PumpSwap, Venue::Raydium, "raydium-tx"));
+
+    // PumpSwap的价格超过max_age_slots没有更新，不再比较
+    arbitrage.price_updated(&mint.to_string(), raydium(1.03, 41), "raydium-tx-2", &mut events);
+    assert_eq!(events.len(), 1);
+    assert_eq!(arbitrage.prices(&mint.to_string()).len(), 1);
+    assert_eq!(arbitrage.mints(), 1);
+
+    // 全部价格都过期的mint被清理
+    let mut later = TransactionResults::new();
+    later.current_slot = 60;
+    record(&mut arbitrage, &mut registry, &later, &mut events);
+    assert_eq!(arbitrage.mints(), 0);
+}
+
+#[test]
+fn external_meteora_prices_are_compared_with_decoded_pumpswap_pools() {
+    let mint = Pubkey::new_unique();
+    let pool = Pool::new(mint);
+    let mut registry = pools();
+    let mut arbitrage = detector(40, 100);
+    let mut events = Vec::new();
+    record(&mut arbitrage, &mut registry, &fixtures::process(vec![pool.create(1_000_000, 1_000_000)], 50), &mut events);
+    assert!(events.is_empty());
+
+    // 外部解码器报告的Meteora价格比PumpSwap便宜：在Meteora买入、PumpSwap卖出
+    let meteora = VenuePrice {
+        venue: Venue::Meteora,
+        pool: "meteora-pool".to_string(),
+        price: 0.99,
+        fee_bps: 25,
+        slot: 50,
+    };
+    arbitrage.price_updated(&mint.to_string(), meteora, "meteora-tx", &mut events);
+    assert_eq!(events.len(), 1);
+    assert_eq!((events[0].buy.venue, events[0].sell.venue, events[0].tx.as_str()), (Venue::Meteora, Venue::PumpSwap, "meteora-tx"));
+    assert_eq!(events[0].sell.pool, pool.pool.to_string());
+
+    // 之后解码到的PumpSwap买入推高价格，与保存的Meteora价格比较后输出新的机会
+    let buy = pool.buy(10_000, 20_000);
+    let tx = fixtures::signature(&buy);
+    record(&mut arbitrage, &mut registry, &fixtures::process(vec![buy], 52), &mut events);
+    assert_eq!(events.len(), 2);
+    let opportunity = &events[1];
+    assert_eq!((opportunity.buy.venue, opportunity.sell.venue, opportunity.tx.clone(), opportunity.slot), (Venue::Meteora, Venue::PumpSwap, tx, 52));
+    assert!(opportunity.sell.price > 1.0 && opportunity.net_bps > events[0].net_bps);
+    assert_eq!(opportunity.to_json()["buy"]["venue"], "meteora");
+}
+
+#[test]
+fn pools_created_by_pump_migrate_are_priced() {
+    let mint = Pubkey::new_unique();
+    let listed = Pool::new(mint);
+    let migrated = Pubkey::new_unique();
+    let mut registry = PoolRegistry::new(&PoolsConfig {
+        enabled: true,
+        migration_base_reserve: 1_000_000,
+        migration_quote_reserve: 1_100_000,
+        ..PoolsConfig::default()
+    });
+    let mut arbitrage = detector(50, 100);
+    let mut events = Vec::new();
+
+    let migrate = fixtures::single_instruction_transaction(fixtures::idl_instruction(
+        fixtures::pump_program_id(),
+        pump::Instruction::Migrate.def().unwrap(),
+        &[],
+        &[("mint", mint), ("pool", migrated), ("wsol_mint", WSOL_MINT)],
+    ));
+    let tx = fixtures::signature(&migrate);
+    record(&mut arbitrage, &mut registry, &fixtures::process(vec![listed.create(1_000_000, 1_000_000), migrate], 70), &mut events);
+
+    assert_eq!(events.len(), 1);
+    assert_eq!((events[0].sell.pool.clone(), events[0].sell.price, events[0].tx.clone()), (migrated.to_string(), 1.1, tx));
+}
+
+#[test]
+fn opportunities_are_written_to_the_output() {
+    let path = std::env::temp_dir().join(format!("shreds-arbitrage-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut config = Config::default();
+    config.set("sinks.stdout", "false").unwrap();
+    config.set("sinks.format", "json").unwrap();
+    config.set("sinks.file", path.to_str().unwrap()).unwrap();
+    let mut sink = OutputSink::from_config(&config).unwrap();
+
+    let mint = Pubkey::new_unique();
+    let (cheap, dear) = (Pool::new(mint), Pool::new(mint));
+    let mut registry = pools();
+    let results = fixtures::process(vec![cheap.create(1_000_000, 1_000_000), dear.create(1_000_000, 1_100_000)], 40);
+    registry.record(&results);
+    detector(50, 100).record(&results, &registry, &mut sink);
+
+    let output = std::fs::read_to_string(&path).unwrap();
+    let record: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+    assert_eq!(record["arbitrage"]["mint"], mint.to_string());
+    assert_eq!(record["arbitrage"]["buy"]["pool"], cheap.pool.to_string());
+    assert_eq!(record["arbitrage"]["sell"]["venue"], "pumpswap");
+    assert_eq!(record["arbitrage"]["slot"], 40);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn arbitrage_config_is_validated() {
+    let config = Config::from_toml_str("[arbitrage]\nenabled = true\nthreshold_bps = 80\n").unwrap();
+    assert_eq!((config.arbitrage.threshold_bps, config.arbitrage.max_age_slots), (80, 150));
+
+    let mut config = Config::default();
+    config.set("arbitrage.enabled", "true").unwrap();
+    config.set("arbitrage.max_age_slots", "0").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("pools.enabled"), "{}", message);
+    assert!(message.contains("arbitrage.max_age_slots"), "{}", message);
+
+    config.set("pools.enabled", "true").unwrap();
+    config.set("arbitrage.max_age_slots", "10").unwrap();
+    assert!(config.validate_offline().is_ok());
+}
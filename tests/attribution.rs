@@ -0,0 +1,143 @@
+// 交易机器人 / 前端归因规则与按slot计数测试
+use std::str::FromStr;
+use std::sync::Arc;
+
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use shreds_client::config::Config;
+use shreds_client::models::attribution::{AttributionRules, MEMO_PROGRAM_ID, UNATTRIBUTED};
+use shreds_client::models::pump_parser::COMPUTE_BUDGET_PROGRAM_ID;
+use shreds_client::services::metrics::Metrics;
+use shreds_client::services::ProcessorOptions;
+use shreds_client::testing::fixtures;
+use shreds_client::TransactionProcessor;
+
+const FEE_WALLET: &str = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+const ROUTER: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+
+fn rules() -> AttributionRules {
+    AttributionRules::from_toml_str(&format!(
+        r#"
+        [[rules]]
+        name = "fee-bot"
+        fee_recipients = ["{FEE_WALLET}"]
+
+        [[rules]]
+        name = "router-bot"
+        programs = ["{ROUTER}"]
+
+        [[rules]]
+        name = "fingerprint-bot"
+        compute_unit_limit = 120000
+        memo = ["fp:"]
+        "#
+    ))
+    .unwrap()
+}
+
+fn pump_buy_with(extra: Vec<Instruction>) -> VersionedTransaction {
+    let data = fixtures::instruction_data(
+        [102, 6, 61, 18, 1, 218, 235, 234],
+        &[&1u64.to_le_bytes(), &2u64.to_le_bytes()],
+    );
+    let mut instructions = vec![fixtures::instruction(fixtures::pump_program_id(), data, 12)];
+    instructions.extend(extra);
+    fixtures::transaction(&instructions)
+}
+
+fn compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![2];
+    data.extend_from_slice(&units.to_le_bytes());
+    fixtures::instruction(Pubkey::from_str(COMPUTE_BUDGET_PROGRAM_ID).unwrap(), data, 0)
+}
+
+fn process(transactions: Vec<VersionedTransaction>) -> shreds_client::TransactionResults {
+    let options = ProcessorOptions {
+        attribution: Some(Arc::new(rules())),
+        ..ProcessorOptions::default()
+    };
+    let entries: Vec<solana_entry::entry::Entry> =
+        bincode::deserialize(&fixtures::entries_payload(transactions)).unwrap();
+    TransactionProcessor::process_entries_with(&entries, 21, &options)
+}
+
+#[test]
+fn first_matching_rule_wins() {
+    let rules = rules();
+    assert_eq!(rules.len(), 3);
+    let fee_wallet = Pubkey::from_str(FEE_WALLET).unwrap();
+
+    let fee = pump_buy_with(vec![fixtures::system_transfer(Pubkey::new_unique(), fee_wallet, 1_000)]);
+    assert_eq!(rules.classify_transaction(&fee), "fee-bot");
+
+    let routed = pump_buy_with(vec![fixtures::instruction(Pubkey::from_str(ROUTER).unwrap(), vec![1], 1)]);
+    assert_eq!(rules.classify_transaction(&routed), "router-bot");
+
+    // 指纹规则的两个条件必须同时满足
+    let memo = fixtures::instruction(MEMO_PROGRAM_ID, b"fp:v2".to_vec(), 0);
+    let fingerprint = pump_buy_with(vec![compute_unit_limit(120_000), memo.clone()]);
+    assert_eq!(rules.classify_transaction(&fingerprint), "fingerprint-bot");
+    let partial = pump_buy_with(vec![compute_unit_limit(200_000), memo]);
+    assert_eq!(rules.classify_transaction(&partial), UNATTRIBUTED);
+
+    assert_eq!(rules.classify_transaction(&fixtures::pump_buy(1, 2)), UNATTRIBUTED);
+}
+
+#[test]
+fn invalid_rules_are_rejected() {
+    assert!(AttributionRules::from_toml_str("[[rules]]\nname = \"empty\"\n").is_err());
+    assert!(AttributionRules::from_toml_str("[[rules]]\nname = \"bad\"\nprograms = [\"nope\"]\n").is_err());
+    assert!(AttributionRules::from_toml_str("[[rules]]\nname = \"hex\"\ninstruction_prefixes = [\"zz\"]\n").is_err());
+    assert!(AttributionRules::from_toml_str("[[rules]]\nprograms = [\"11111111111111111111111111111111\"]\n").is_err());
+
+    let example = AttributionRules::load(concat!(env!("CARGO_MANIFEST_DIR"), "/attribution.example.toml")).unwrap();
+    assert!(example.is_empty());
+}
+
+#[test]
+fn trades_are_attributed_and_counted_per_slot() {
+    let fee_wallet = Pubkey::from_str(FEE_WALLET).unwrap();
+    let results = process(vec![
+        pump_buy_with(vec![fixtures::system_transfer(Pubkey::new_unique(), fee_wallet, 1_000)]),
+        pump_buy_with(vec![fixtures::system_transfer(Pubkey::new_unique(), fee_wallet, 2_000)]),
+        fixtures::pumpswap_buy(1, 2),
+    ]);
+    assert_eq!(results.attributions.len(), 3);
+    let counts = results.attribution_counts();
+    assert_eq!(counts["fee-bot"], 2);
+    assert_eq!(counts[UNATTRIBUTED], 1);
+
+    let records = results.to_json_records();
+    assert!(records.iter().any(|record| record["attribution"] == "fee-bot"));
+
+    let mut json = Vec::new();
+    results.write_json_to(&mut json).unwrap();
+    let last: serde_json::Value = serde_json::from_str(String::from_utf8(json).unwrap().lines().last().unwrap()).unwrap();
+    assert_eq!(last["slot"], 21);
+    assert_eq!(last["attribution_counts"]["fee-bot"], 2);
+
+    let mut text = Vec::new();
+    results.write_to(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.contains("Attribution:fee-bot"));
+    assert!(text.contains("Attribution_Counts:21 fee-bot=2 unknown=1"));
+
+    let metrics = Metrics::new();
+    metrics.record_results(&results);
+    assert!(metrics.render().contains("shreds_attributed_transactions_total{tool=\"fee-bot\"} 2"));
+}
+
+#[test]
+fn attribution_requires_a_rules_file() {
+    let mut config = Config::default();
+    config.set("attribution.enabled", "true").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("attribution.rules_file"), "{}", message);
+
+    config.set("attribution.rules_file", concat!(env!("CARGO_MANIFEST_DIR"), "/attribution.example.toml")).unwrap();
+    config.validate_offline().unwrap();
+    let options = ProcessorOptions::from_config(&config).unwrap();
+    assert!(options.attribution.is_some());
+}
@@ -0,0 +1,335 @@
+// 按mint聚合K线的测试：时间桶、slot内顺序、迟到成交、保留窗口、模拟成交的价格与输出
+use solana_sdk::pubkey::Pubkey;
+
+use shreds_client::analytics::{
+    Candle, CandleAggregator, CandleInterval, CurveState, CurveTracker, EventSink, Fill, Graduated, Opportunity, PoolRegistry,
+    SlotAnalytics,
+};
+use shreds_client::config::{CandlesConfig, Config, CurveParams, CurvesConfig, PoolsConfig};
+use shreds_client::idl::{pump, pump_amm};
+use shreds_client::models::events::TradeSide;
+use shreds_client::models::token_parser::WSOL_MINT;
+use shreds_client::services::OutputSink;
+use shreds_client::testing::fixtures;
+use shreds_client::utils::account_layout::Program;
+use shreds_client::TransactionResults;
+
+const SECOND: CandleInterval = CandleInterval::from_secs(1);
+const MINUTE: CandleInterval = CandleInterval::from_secs(60);
+
+// 价格 = sol / token
+fn fill(slot: u64, mint: &Pubkey, side: TradeSide, token: u64, sol: u64) -> Fill {
+    Fill {
+        slot,
+        signature: format!("tx-{}-{}", slot, sol),
+        index: 0,
+        position: 0,
+        venue: Program::Pump,
+        pool: "curve".to_string(),
+        side,
+        mint: mint.to_string(),
+        quote_mint: WSOL_MINT.to_string(),
+        token_amount: token,
+        quote_amount: sol,
+    }
+}
+
+fn record(candles: &mut CandleAggregator, fill: Fill, closed: &mut Vec<Candle>) {
+    candles.record(fill.slot, [&fill], closed);
+}
+
+// 收集K线，忽略其他分析事件
+#[derive(Default)]
+struct Events {
+    candles: Vec<Candle>,
+}
+
+impl EventSink<Candle> for Events {
+    fn publish(&mut self, candle: &Candle) {
+        self.candles.push(candle.clone());
+    }
+}
+
+impl EventSink<CurveState> for Events {
+    fn publish(&mut self, _: &CurveState) {}
+}
+
+impl EventSink<Graduated> for Events {
+    fn publish(&mut self, _: &Graduated) {}
+}
+
+impl EventSink<Opportunity> for Events {
+    fn publish(&mut self, _: &Opportunity) {}
+}
+
+// slot 100 的时间为0，每个slot 400ms
+fn aggregator(grace_slots: u64, history: usize) -> CandleAggregator {
+    let config = CandlesConfig {
+        enabled: true,
+        intervals: vec![MINUTE, SECOND],
+        history,
+        grace_slots,
+        ..CandlesConfig::default()
+    };
+    let mut candles = CandleAggregator::new(&config);
+    candles.set_origin(100, 0);
+    candles
+}
+
+#[test]
+fn trades_are_bucketed_by_slot_time() {
+    let mint = Pubkey::new_unique();
+    let mut candles = aggregator(0, 100);
+    assert_eq!(candles.intervals(), &[SECOND, MINUTE]);
+    let mut closed: Vec<Candle> = Vec::new();
+
+    record(&mut candles, fill(100, &mint, TradeSide::Buy, 1_000, 2_000), &mut closed);
+    record(&mut candles, fill(101, &mint, TradeSide::Sell, 1_000, 1_000), &mut closed);
+    assert!(closed.is_empty());
+
+    // slot 102（800ms开始）结束后，开始于第一秒内的slot都已结束
+    record(&mut candles, fill(102, &mint, TradeSide::Buy, 1_000, 3_000), &mut closed);
+    assert_eq!(closed.len(), 1);
+    record(&mut candles, fill(103, &mint, TradeSide::Buy, 1_000, 4_000), &mut closed);
+    assert_eq!(closed.len(), 1);
+    let candle = &closed[0];
+    assert_eq!((candle.interval, candle.start_ms, candle.end_ms()), (SECOND, 0, 1_000));
+    assert_eq!((candle.open, candle.high, candle.low, candle.close), (2.0, 3.0, 1.0, 3.0));
+    assert_eq!((candle.token_volume, candle.quote_volume), (3_000, 6_000));
+    assert_eq!((candle.trades, candle.buys, candle.sells), (3, 2, 1));
+    assert_eq!((candle.first_slot, candle.last_slot), (100, 102));
+
+    assert_eq!(candles.last(&mint.to_string(), SECOND, 10), closed);
+    assert_eq!(candles.current(&mint.to_string(), SECOND).map(|candle| candle.close), Some(4.0));
+    assert!(candles.last(&mint.to_string(), MINUTE, 10).is_empty());
+
+    candles.flush(&mut closed);
+    let minute = closed.iter().find(|candle| candle.interval == MINUTE).unwrap();
+    assert_eq!((minute.open, minute.high, minute.low, minute.close, minute.trades), (2.0, 4.0, 1.0, 4.0, 4));
+    assert_eq!(closed.len(), 3);
+}
+
+#[test]
+fn order_within_a_slot_follows_entries_not_arrival() {
+    let mint = Pubkey::new_unique();
+    let mut candles = aggregator(2, 100);
+    let mut closed: Vec<Candle> = Vec::new();
+
+    // 并行解析后交易的合并顺序不确定，按消息中的序号恢复
+    let first = fill(100, &mint, TradeSide::Buy, 1_000, 2_000);
+    let second = Fill {
+        index: 1,
+        ..fill(100, &mint, TradeSide::Buy, 1_000, 5_000)
+    };
+    candles.record(100, [&second, &first], &mut closed);
+    let current = candles.current(&mint.to_string(), SECOND).unwrap();
+    assert_eq!((current.open, current.close), (2.0, 5.0));
+
+    // 较早slot的消息晚到，仍在grace_slots内
+    record(&mut candles, fill(102, &mint, TradeSide::Buy, 1_000, 6_000), &mut closed);
+    record(&mut candles, fill(101, &mint, TradeSide::Sell, 1_000, 7_000), &mut closed);
+    let current = candles.current(&mint.to_string(), SECOND).unwrap();
+    assert_eq!((current.open, current.high, current.close, current.last_slot), (2.0, 7.0, 6.0, 102));
+    assert_eq!(candles.late_trades(), 0);
+}
+
+#[test]
+fn trades_for_closed_buckets_are_dropped() {
+    let mint = Pubkey::new_unique();
+    let mut candles = aggregator(0, 100);
+    let mut closed: Vec<Candle> = Vec::new();
+
+    record(&mut candles, fill(110, &mint, TradeSide::Buy, 1_000, 2_000), &mut closed);
+    record(&mut candles, fill(113, &mint, TradeSide::Buy, 1_000, 2_000), &mut closed);
+    assert_eq!(closed.len(), 1);
+
+    record(&mut candles, fill(111, &mint, TradeSide::Buy, 1_000, 9_000), &mut closed);
+    assert_eq!(candles.late_trades(), 1);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(candles.last(&mint.to_string(), SECOND, 1)[0].high, 2.0);
+}
+
+#[test]
+fn history_keeps_the_latest_buckets() {
+    let mint = Pubkey::new_unique();
+    let mut candles = aggregator(0, 3);
+    let mut closed: Vec<Candle> = Vec::new();
+    for slot in [100, 103, 105, 108, 110] {
+        record(&mut candles, fill(slot, &mint, TradeSide::Buy, 1_000, slot), &mut closed);
+    }
+    assert_eq!(closed.len(), 4);
+
+    let starts = |n| -> Vec<i64> {
+        candles.last(&mint.to_string(), SECOND, n).iter().map(|candle| candle.start_ms).collect()
+    };
+    assert_eq!(starts(10), vec![1_000, 2_000, 3_000]);
+    assert_eq!(starts(2), vec![2_000, 3_000]);
+    assert!(candles.last(&Pubkey::new_unique().to_string(), SECOND, 10).is_empty());
+    assert!(candles.last(&mint.to_string(), CandleInterval::from_secs(5), 10).is_empty());
+}
+
+#[test]
+fn only_sol_quoted_trades_are_aggregated() {
+    let mint = Pubkey::new_unique();
+    let mut candles = aggregator(0, 100);
+    let mut closed: Vec<Candle> = Vec::new();
+    let pumpswap = Fill {
+        venue: Program::PumpAmm,
+        ..fill(100, &mint, TradeSide::Buy, 1_000, 8_000)
+    };
+    let other_quote = Fill {
+        quote_mint: Pubkey::new_unique().to_string(),
+        ..fill(100, &Pubkey::new_unique(), TradeSide::Buy, 1_000, 8_000)
+    };
+    candles.record(100, [&pumpswap, &other_quote], &mut closed);
+    assert_eq!(candles.mints(), 1);
+
+    let mut published = 0;
+    let mut count = |_: &Candle| published += 1;
+    candles.flush(&mut count);
+    assert_eq!(published, 2);
+    let candle = &candles.last(&mint.to_string(), MINUTE, 1)[0];
+    assert_eq!((candle.venue.parser_name(), candle.close), ("PUMPAMM", 8.0));
+}
+
+// 指令参数中一侧只是滑点限制，价格取自曲线与池子模拟的成交
+#[test]
+fn prices_come_from_simulated_fills() {
+    let (curve_mint, pool_mint, untracked) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let pool = Pubkey::new_unique();
+    let pump = |instruction: pump::Instruction, args: &[&[u8]], mint: Pubkey| {
+        fixtures::single_instruction_transaction(fixtures::idl_instruction(
+            fixtures::pump_program_id(),
+            instruction.def().unwrap(),
+            args,
+            &[("mint", mint)],
+        ))
+    };
+    let pumpswap = |instruction: pump_amm::Instruction, args: &[&[u8]]| {
+        fixtures::single_instruction_transaction(fixtures::idl_instruction(
+            fixtures::pumpamm_program_id(),
+            instruction.def().unwrap(),
+            args,
+            &[("pool", pool), ("base_mint", pool_mint), ("quote_mint", WSOL_MINT)],
+        ))
+    };
+    let (name, symbol, uri) = (fixtures::borsh_string("Token"), fixtures::borsh_string("TKN"), fixtures::borsh_string("uri"));
+    let creator = Pubkey::new_unique();
+
+    let mut analytics = SlotAnalytics {
+        candles: Some(aggregator(0, 100)),
+        // 虚拟储备各1_000_000、无手续费的曲线；无手续费的池子
+        curves: Some(CurveTracker::new(&CurvesConfig {
+            enabled: true,
+            pump: CurveParams {
+                initial_virtual_token_reserves: 1_000_000,
+                initial_virtual_sol_reserves: 1_000_000,
+                initial_real_token_reserves: 500_000,
+                fee_bps: 0,
+            },
+            ..CurvesConfig::default()
+        })),
+        pools: Some(PoolRegistry::new(&PoolsConfig {
+            enabled: true,
+            lp_fee_bps: 0,
+            protocol_fee_bps: 0,
+            ..PoolsConfig::default()
+        })),
+        migrations: None,
+        arbitrage: None,
+    };
+    let mut events = Events::default();
+    let results = fixtures::process(
+        vec![
+            pump(pump::Instruction::Create, &[&name, &symbol, &uri, creator.as_ref()], curve_mint),
+            // 上限远高于成交额：1_000个代币实际支付 ceil(1_000_000 * 1_000 / 999_000) = 1_002
+            pump(pump::Instruction::Buy, &[&1_000u64.to_le_bytes(), &u64::MAX.to_le_bytes()], curve_mint),
+            // 没有跟踪状态的曲线
+            pump(pump::Instruction::Buy, &[&1_000u64.to_le_bytes(), &5_000u64.to_le_bytes()], untracked),
+            pumpswap(pump_amm::Instruction::CreatePool, &[&0u16.to_le_bytes(), &1_000_000u64.to_le_bytes(), &2_000_000u64.to_le_bytes()]),
+            // ceil(2_000_000 * 1_000 / 999_000) = 2_003
+            pumpswap(pump_amm::Instruction::Buy, &[&1_000u64.to_le_bytes(), &9_000u64.to_le_bytes()]),
+            // 按估算会失败的买入
+            pumpswap(pump_amm::Instruction::Buy, &[&1_000u64.to_le_bytes(), &0u64.to_le_bytes()]),
+        ],
+        100,
+    );
+    analytics.record(&results, &mut events);
+
+    let candles = analytics.candles.as_mut().unwrap();
+    assert_eq!(candles.mints(), 2);
+    let candle = candles.current(&curve_mint.to_string(), SECOND).unwrap();
+    assert_eq!((candle.close, candle.quote_volume, candle.venue), (1.002, 1_002, Program::Pump));
+    let candle = candles.current(&pool_mint.to_string(), SECOND).unwrap();
+    assert_eq!((candle.close, candle.trades, candle.venue), (2.003, 1, Program::PumpAmm));
+
+    // 没有成交的slot也推进时间
+    analytics.record(&TransactionResults { current_slot: 103, ..TransactionResults::new() }, &mut events);
+    assert_eq!(events.candles.len(), 2);
+}
+
+#[test]
+fn closed_candles_are_written_to_the_output() {
+    let path = std::env::temp_dir().join(format!("shreds-candles-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut config = Config::default();
+    config.set("sinks.stdout", "false").unwrap();
+    config.set("sinks.format", "json").unwrap();
+    config.set("sinks.file", path.to_str().unwrap()).unwrap();
+    let mut sink = OutputSink::from_config(&config).unwrap();
+
+    let mint = Pubkey::new_unique();
+    let mut candles = aggregator(0, 100);
+    let trade = fill(100, &mint, TradeSide::Buy, 1_000, 2_000);
+    candles.record(100, [&trade], &mut sink);
+    candles.flush(&mut sink);
+
+    let output = std::fs::read_to_string(&path).unwrap();
+    let records: Vec<serde_json::Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["candle"]["mint"], mint.to_string());
+    assert_eq!(records[0]["candle"]["interval"], "1s");
+    assert_eq!(records[1]["candle"]["interval"], "1m");
+    assert_eq!(records[1]["candle"]["end_ms"], 60_000);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn intervals_and_config_are_parsed() {
+    assert_eq!("1s".parse(), Ok(SECOND));
+    assert_eq!("5m".parse(), Ok(CandleInterval::from_secs(300)));
+    assert_eq!("1h".parse::<CandleInterval>().map(|interval| interval.to_string()), Ok("1h".to_string()));
+    assert_eq!(CandleInterval::from_secs(90).to_string(), "90s");
+    for invalid in ["", "0s", "5x", "m", "-1m"] {
+        assert!(invalid.parse::<CandleInterval>().is_err(), "{}", invalid);
+    }
+
+    let config = Config::from_toml_str("[candles]\nenabled = true\nintervals = [\"1s\", \"15m\"]\n").unwrap();
+    assert_eq!(config.candles.intervals, vec![SECOND, CandleInterval::from_secs(900)]);
+    assert!(Config::from_toml_str("[candles]\nintervals = [\"1d\"]\n").is_err());
+
+    let mut config = Config::default();
+    config.set("candles.intervals", "1m, 5m").unwrap();
+    assert_eq!(config.candles.intervals, vec![MINUTE, CandleInterval::from_secs(300)]);
+    config.set("candles.enabled", "true").unwrap();
+    config.set("candles.history", "0").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("candles.history"), "{}", message);
+    assert!(message.contains("curves.enabled"), "{}", message);
+    config.set("candles.history", "10").unwrap();
+    config.set("pools.enabled", "true").unwrap();
+    assert!(config.validate_offline().is_ok());
+}
+
+// 闭包与Vec都可以作为sink
+#[test]
+fn closures_and_vectors_are_sinks() {
+    fn publish<S: EventSink<u32>>(sink: &mut S) {
+        sink.publish(&7);
+    }
+    let mut events = Vec::new();
+    publish(&mut events);
+    let mut total = 0;
+    publish(&mut |event: &u32| total += event);
+    assert_eq!((events, total), (vec![7], 7));
+}
@@ -0,0 +1,96 @@
+// 命令行子命令解析、交易解码与录制文件统计测试
+use std::path::PathBuf;
+
+use base64::Engine;
+use shreds_client::cli::commands::{decode_transaction, RecordingStats};
+use shreds_client::cli::{Cli, Command};
+use shreds_client::services::{ProcessorOptions, RecordingReader, RecordingWriter};
+use shreds_client::testing::fixtures;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn parses_subcommands_and_shared_flags() {
+    let cli = Cli::parse(&args(&["replay", "slots.rec", "--parsers", "pump,idl", "--format=json"])).unwrap();
+    assert_eq!(cli.command, Command::Replay { file: PathBuf::from("slots.rec") });
+    assert!(cli.config.parsers.pump.enabled);
+    assert!(!cli.config.parsers.pumpamm.enabled);
+    assert!(!cli.config.parsers.boop.enabled);
+
+    let cli = Cli::parse(&args(&["decode-entries", "entries.bin", "--slot", "42"])).unwrap();
+    assert_eq!(cli.command, Command::DecodeEntries { file: PathBuf::from("entries.bin"), slot: 42 });
+
+    let cli = Cli::parse(&args(&["--url", "http://127.0.0.1:1", "--record", "out.rec"])).unwrap();
+    assert_eq!(cli.command, Command::Stream { record: Some(PathBuf::from("out.rec")) });
+
+    let cli = Cli::parse(&args(&["tip-accounts", "--block-engine-url", "http://127.0.0.1:1"])).unwrap();
+    assert_eq!(cli.command, Command::TipAccounts);
+    assert_eq!(cli.config.endpoints.block_engine_url.as_deref(), Some("http://127.0.0.1:1"));
+
+    assert_eq!(Cli::parse(&args(&["--help"])).unwrap().command, Command::Help);
+}
+
+#[test]
+fn rejects_misused_arguments() {
+    for bad in [
+        &["replay"][..],
+        &["stats", "a.rec", "b.rec"],
+        &["decode", "tx", "--record", "out.rec"],
+        &["replay", "a.rec", "--parsers", "pump,unknown"],
+        &["frobnicate"],
+        &["tip-accounts"],
+        &["tip-accounts", "--block-engine-url", "127.0.0.1:1"],
+        &["tip-accounts", "--block-engine-url", "http://127.0.0.1:1", "--keypair", "missing-keypair.json"],
+    ] {
+        assert!(Cli::parse(&args(bad)).is_err(), "{:?}", bad);
+    }
+}
+
+#[test]
+fn decodes_base58_and_base64_transactions() {
+    let tx = fixtures::pump_buy(1, 2);
+    let bytes = bincode::serialize(&tx).unwrap();
+
+    let from_base58 = decode_transaction(&bs58::encode(&bytes).into_string()).unwrap();
+    let from_base64 = decode_transaction(&base64::engine::general_purpose::STANDARD.encode(&bytes)).unwrap();
+
+    assert_eq!(from_base58, tx);
+    assert_eq!(from_base64, tx);
+    assert!(decode_transaction("not a transaction!").is_err());
+}
+
+#[test]
+fn recording_round_trip_and_stats() {
+    let mut writer = RecordingWriter::new(Vec::new());
+    writer
+        .write(10, &fixtures::entries_payload(vec![fixtures::pump_buy(1, 2), fixtures::pump_sell(3, 4)]))
+        .unwrap();
+    writer.write(11, &[0xff; 7]).unwrap();
+    writer
+        .write(12, &fixtures::entries_payload(vec![fixtures::boop_buy_token(5, 6)]))
+        .unwrap();
+    let bytes = writer.into_inner();
+
+    let records: Vec<_> = RecordingReader::new(bytes.as_slice()).collect::<Result<_, _>>().unwrap();
+    assert_eq!(records.iter().map(|(slot, _)| *slot).collect::<Vec<_>>(), [10, 11, 12]);
+
+    let options = ProcessorOptions::default();
+    let mut stats = RecordingStats::default();
+    for (slot, payload) in &records {
+        stats.add(*slot, payload, &options);
+    }
+
+    assert_eq!(stats.records, 3);
+    assert_eq!(stats.malformed, 1);
+    assert_eq!((stats.first_slot, stats.last_slot), (Some(10), Some(12)));
+    assert_eq!(stats.transactions, 3);
+    assert_eq!(stats.parsers["PUMP"].transactions, 2);
+    assert_eq!(stats.parsers["PUMP"].instructions["Buy"], 1);
+    assert_eq!(stats.parsers["BOOP"].instructions["BuyToken"], 1);
+
+    // 截断的录制文件报错而不是静默结束
+    let truncated = &bytes[..bytes.len() - 1];
+    assert!(RecordingReader::new(truncated).collect::<Result<Vec<_>, _>>().is_err());
+}
@@ -0,0 +1,106 @@
+// 类型化配置的解析、分层覆盖与校验测试
+use shreds_client::config::{Config, LogFormat, DEFAULT_BATCH_SIZE};
+use shreds_client::PUMP_PROGRAM_ID;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn parses_toml_and_fills_defaults() {
+    let config = Config::from_toml_str(
+        r#"
+        [endpoints]
+        shredstream_url = "http://127.0.0.1:9999"
+
+        [parsers.boop]
+        enabled = false
+
+        [logging]
+        format = "json"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.endpoints.shredstream_url, "http://127.0.0.1:9999");
+    assert!(config.parsers.pump.enabled);
+    assert_eq!(config.parsers.pump_program_id().unwrap().to_string(), PUMP_PROGRAM_ID);
+    assert_eq!(config.parsers.boop_program_id(), None);
+    assert_eq!(config.processing.batch_size, DEFAULT_BATCH_SIZE);
+    assert!(config.sinks.stdout);
+    assert_eq!(config.logging.format, LogFormat::Json);
+    config.validate().unwrap();
+}
+
+#[test]
+fn rejects_unknown_fields() {
+    let err = Config::from_toml_str("[processing]\nbatch = 10\n").unwrap_err();
+    assert!(err.to_string().contains("batch"), "{}", err);
+}
+
+#[test]
+fn cli_overrides_env_which_overrides_file() {
+    let mut config = Config::from_toml_str("[processing]\nbatch_size = 10\nthreads = 2\n").unwrap();
+
+    config
+        .apply_env(vec![
+            ("SHREDS_PROCESSING_BATCH_SIZE".to_string(), "20".to_string()),
+            ("SHREDS_ENDPOINTS_SHREDSTREAM_URL".to_string(), "http://env:1".to_string()),
+            ("UNRELATED".to_string(), "x".to_string()),
+        ])
+        .unwrap();
+    assert_eq!(config.processing.batch_size, 20);
+    assert_eq!(config.processing.threads, Some(2));
+
+    let rest = config
+        .apply_args(&args(&[
+            "stream",
+            "--url",
+            "http://cli:2",
+            "--set",
+            "parsers.pump.enabled=false",
+            "--processing.batch_size=30",
+            "--accounts",
+            "11111111111111111111111111111111, SysvarRent111111111111111111111111111111111",
+        ]))
+        .unwrap();
+
+    assert_eq!(rest, args(&["stream"]));
+    assert_eq!(config.endpoints.shredstream_url, "http://cli:2");
+    assert!(!config.parsers.pump.enabled);
+    assert_eq!(config.processing.batch_size, 30);
+    assert_eq!(config.filters.account_keys().len(), 2);
+    config.validate().unwrap();
+}
+
+#[test]
+fn unknown_flags_and_bad_values_are_errors() {
+    let mut config = Config::default();
+    assert!(config.apply_args(&args(&["--nope", "1"])).is_err());
+    assert!(config.apply_args(&args(&["--batch-size", "many"])).is_err());
+    assert!(config.apply_args(&args(&["--threads"])).is_err());
+    assert!(config
+        .apply_env(vec![("SHREDS_SINKS_STDOUT".to_string(), "maybe".to_string())])
+        .is_err());
+}
+
+#[test]
+fn validation_reports_every_problem() {
+    let mut config = Config::default();
+    config.set("parsers.pumpamm.program_id", "not-a-key").unwrap();
+    config.set("processing.batch_size", "0").unwrap();
+    config.set("logging.level", "loud").unwrap();
+    config.set("sinks.stdout", "false").unwrap();
+
+    let message = config.validate().unwrap_err().to_string();
+
+    for expected in [
+        "endpoints.shredstream_url",
+        "parsers.pumpamm.program_id",
+        "processing.batch_size",
+        "logging.level",
+        "sinks",
+    ] {
+        assert!(message.contains(expected), "缺少 {}: {}", expected, message);
+    }
+}
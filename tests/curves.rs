@@ -0,0 +1,339 @@
+// 绑定曲线状态测试：pump与boop的建立、买卖推算、滑点、完成与毕业、SetParams与输出
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use shreds_client::analytics::{CurveState, CurveTracker, EventSink, Graduated, Venue};
+use shreds_client::config::{Config, CurveParams, CurvesConfig};
+use shreds_client::idl::{boop, pump};
+use shreds_client::services::OutputSink;
+use shreds_client::testing::fixtures;
+use shreds_client::utils::account_layout::Program;
+
+// 同时收集曲线状态与毕业事件
+#[derive(Default)]
+struct Events {
+    curves: Vec<CurveState>,
+    graduated: Vec<Graduated>,
+}
+
+impl EventSink<CurveState> for Events {
+    fn publish(&mut self, curve: &CurveState) {
+        self.curves.push(curve.clone());
+    }
+}
+
+impl EventSink<Graduated> for Events {
+    fn publish(&mut self, graduated: &Graduated) {
+        self.graduated.push(graduated.clone());
+    }
+}
+
+struct Token {
+    mint: Pubkey,
+    curve: Pubkey,
+}
+
+impl Token {
+    fn new() -> Self {
+        Self {
+            mint: Pubkey::new_unique(),
+            curve: Pubkey::new_unique(),
+        }
+    }
+
+    fn pump(&self, instruction: pump::Instruction, args: &[&[u8]]) -> VersionedTransaction {
+        fixtures::single_instruction_transaction(fixtures::idl_instruction(
+            fixtures::pump_program_id(),
+            instruction.def().unwrap(),
+            args,
+            &[("mint", self.mint), ("bonding_curve", self.curve)],
+        ))
+    }
+
+    fn boop(&self, instruction: boop::Instruction, args: &[&[u8]]) -> VersionedTransaction {
+        fixtures::single_instruction_transaction(fixtures::idl_instruction(
+            fixtures::boop_program_id(),
+            instruction.def().unwrap(),
+            args,
+            &[("mint", self.mint), ("bonding_curve", self.curve)],
+        ))
+    }
+
+    fn create(&self, creator: &Pubkey) -> VersionedTransaction {
+        let (name, symbol, uri) = (fixtures::borsh_string("Token"), fixtures::borsh_string("TKN"), fixtures::borsh_string("uri"));
+        self.pump(pump::Instruction::Create, &[&name, &symbol, &uri, creator.as_ref()])
+    }
+
+    fn buy(&self, amount: u64, max_sol_cost: u64) -> VersionedTransaction {
+        self.pump(pump::Instruction::Buy, &[&amount.to_le_bytes(), &max_sol_cost.to_le_bytes()])
+    }
+
+    fn sell(&self, amount: u64, min_sol_output: u64) -> VersionedTransaction {
+        self.pump(pump::Instruction::Sell, &[&amount.to_le_bytes(), &min_sol_output.to_le_bytes()])
+    }
+
+    fn deploy(&self, creator: &Pubkey) -> VersionedTransaction {
+        self.boop(boop::Instruction::DeployBondingCurve, &[creator.as_ref(), &7u64.to_le_bytes()])
+    }
+
+    fn buy_token(&self, buy_amount: u64, amount_out_min: u64) -> VersionedTransaction {
+        self.boop(boop::Instruction::BuyToken, &[&buy_amount.to_le_bytes(), &amount_out_min.to_le_bytes()])
+    }
+
+    fn sell_token(&self, sell_amount: u64, amount_out_min: u64) -> VersionedTransaction {
+        self.boop(boop::Instruction::SellToken, &[&sell_amount.to_le_bytes(), &amount_out_min.to_le_bytes()])
+    }
+}
+
+// 容易手算的boop参数：可卖代币为虚拟代币的一半
+fn small_params(fee_bps: u64) -> CurveParams {
+    CurveParams {
+        initial_virtual_token_reserves: 1_000_000,
+        initial_virtual_sol_reserves: 1_000_000,
+        initial_real_token_reserves: 500_000,
+        fee_bps,
+    }
+}
+
+fn tracker(boop: CurveParams) -> CurveTracker {
+    CurveTracker::new(&CurvesConfig {
+        enabled: true,
+        boop,
+        ..CurvesConfig::default()
+    })
+}
+
+#[test]
+fn pump_curves_follow_create_buy_and_sell() {
+    let token = Token::new();
+    let creator = Pubkey::new_unique();
+    let mut curves = tracker(small_params(100));
+    let mut events = Events::default();
+
+    // 1_000_000个代币（6位精度）：ceil(30e9 * 1e12 / 1.072e15) = 27_985_075，手续费1%向上取整
+    curves.record(&fixtures::process(vec![token.create(&creator), token.buy(1_000_000_000_000, 30_000_000)], 20), &mut events);
+    assert_eq!(events.curves.len(), 1);
+    let state = curves.get(&token.mint.to_string()).unwrap().clone();
+    assert_eq!(events.curves[0], state);
+    assert_eq!((state.venue, state.curve.clone(), state.creator.clone()), (Program::Pump, token.curve.to_string(), Some(creator.to_string())));
+    assert_eq!((state.virtual_token_reserves, state.virtual_sol_reserves), (1_072_000_000_000_000, 30_027_985_075));
+    assert_eq!((state.real_token_reserves, state.real_sol_reserves), (792_100_000_000_000, 27_985_075));
+    assert!((state.progress() - 1.0 / 793.1).abs() < 1e-12);
+    assert!((state.price().unwrap() - 30_027_985_075.0 / 1_072_000_000_000_000.0).abs() < 1e-18);
+    let quote = state.quote_buy(1_000_000_000_000).unwrap();
+    assert_eq!((quote.sol_amount, quote.fee), (28_317_709, 280_374));
+
+    // 卖回同样数量：曲线付出 floor(30_027_985_075 * 1e12 / 1.073e15) = 27_985_074
+    curves.record(&fixtures::process(vec![token.sell(1_000_000_000_000, 27_000_000)], 21), &mut events);
+    let state = curves.get(&token.mint.to_string()).unwrap();
+    assert_eq!((state.virtual_token_reserves, state.real_sol_reserves, state.last_slot), (1_073_000_000_000_000, 1, 21));
+    assert_eq!(state.progress(), 0.0);
+    assert_eq!(events.curves.len(), 2);
+    assert!(events.graduated.is_empty());
+}
+
+#[test]
+fn estimates_that_violate_slippage_and_unknown_curves_are_not_applied() {
+    let token = Token::new();
+    let mut curves = tracker(small_params(100));
+    let mut events = Events::default();
+    curves.record(&fixtures::process(vec![token.create(&Pubkey::new_unique())], 30), &mut events);
+    let created = curves.get(&token.mint.to_string()).unwrap().clone();
+
+    curves.record(
+        &fixtures::process(vec![token.buy(1_000_000_000_000, 28_000_000), token.sell(1_000, 0), Token::new().buy(1_000, 1_000)], 31),
+        &mut events,
+    );
+    assert_eq!(curves.get(&token.mint.to_string()), Some(&created));
+    assert_eq!((curves.rejected_updates(), curves.unknown_curve_updates()), (2, 1));
+    assert_eq!(events.curves.len(), 1);
+
+    // 重复的Create在链上失败
+    curves.record(&fixtures::process(vec![token.create(&Pubkey::new_unique())], 32), &mut events);
+    assert_eq!((curves.rejected_updates(), curves.len()), (3, 1));
+}
+
+#[test]
+fn boop_curves_use_the_same_state_and_graduate_when_sold_out() {
+    let token = Token::new();
+    let creator = Pubkey::new_unique();
+    let mut curves = tracker(small_params(100));
+    let mut events = Events::default();
+
+    curves.record(&fixtures::process(vec![token.deploy(&creator)], 40), &mut events);
+    let state = curves.get(&token.mint.to_string()).unwrap();
+    assert_eq!((state.venue, state.creator.clone(), state.virtual_sol_reserves), (Program::Boop, Some(creator.to_string()), 1_000_000));
+
+    // 精确的SOL：扣除1%后 1_000_000 * 990_000 / 1_990_000 = 497_487
+    curves.record(&fixtures::process(vec![token.buy_token(1_000_000, 497_487)], 41), &mut events);
+    let state = curves.get(&token.mint.to_string()).unwrap();
+    assert_eq!((state.real_token_reserves, state.real_sol_reserves), (2_513, 990_000));
+    assert!(!state.complete);
+    let sell = state.quote_sell(497_487).unwrap();
+    assert_eq!((sell.sol_amount, sell.fee), (980_099, 9_900));
+    // 卖出的代币不能多于从曲线买走的
+    assert_eq!(state.quote_sell(497_488), None);
+
+    // 超过剩余可卖代币的买入按剩余数量成交，曲线完成并毕业
+    let buy = token.buy_token(2_000_000, 2_000);
+    let tx = fixtures::signature(&buy);
+    curves.record(&fixtures::process(vec![buy], 42), &mut events);
+    let state = curves.get(&token.mint.to_string()).unwrap().clone();
+    assert!(state.complete);
+    assert_eq!((state.real_token_reserves, state.progress()), (0, 1.0));
+    assert_eq!(
+        events.graduated,
+        vec![Graduated {
+            mint: token.mint.to_string(),
+            curve: token.curve.to_string(),
+            pool: None,
+            venue: Venue::Raydium,
+            slot: 42,
+            tx,
+        }]
+    );
+    assert_eq!(events.curves.last(), Some(&state));
+
+    // 完成后的买卖不再应用
+    curves.record(&fixtures::process(vec![token.sell_token(1_000, 0), token.buy_token(1_000, 0)], 43), &mut events);
+    assert_eq!(curves.get(&token.mint.to_string()), Some(&state));
+    assert_eq!((curves.rejected_updates(), curves.graduated(), events.curves.len()), (2, 1, 3));
+}
+
+#[test]
+fn idle_curves_are_evicted() {
+    let (idle, active, graduated) = (Token::new(), Token::new(), Token::new());
+    let creator = Pubkey::new_unique();
+    let mut curves = CurveTracker::new(&CurvesConfig {
+        enabled: true,
+        boop: small_params(100),
+        idle_curve_slots: 80,
+        ..CurvesConfig::default()
+    });
+    let mut events = Events::default();
+    curves.record(
+        &fixtures::process(
+            vec![
+                idle.create(&creator),
+                active.create(&creator),
+                graduated.deploy(&creator),
+                graduated.buy_token(2_000_000, 2_000),
+            ],
+            10,
+        ),
+        &mut events,
+    );
+    assert!(curves.get(&graduated.mint.to_string()).unwrap().complete);
+    curves.record(&fixtures::process(vec![active.buy(1_000_000_000_000, 30_000_000)], 85), &mut events);
+    assert_eq!((curves.len(), curves.evicted()), (3, 0));
+
+    // 每10个slot清理一次：闲置的曲线与已毕业的曲线都不再跟踪
+    curves.record(&fixtures::process(vec![], 100), &mut events);
+    assert!(curves.get(&idle.mint.to_string()).is_none());
+    assert!(curves.get(&graduated.mint.to_string()).is_none());
+    assert_eq!(curves.get(&active.mint.to_string()).unwrap().last_slot, 85);
+    assert_eq!((curves.len(), curves.evicted()), (1, 2));
+}
+
+// 由user签名的SetParams
+fn set_params(user: Pubkey, signed: bool) -> VersionedTransaction {
+    let args: [u64; 5] = [2_000_000, 3_000_000, 1_000_000, 1_000_000_000, 50];
+    let args: Vec<[u8; 8]> = args.iter().map(|value| value.to_le_bytes()).collect();
+    let fee_recipient = Pubkey::new_unique();
+    let mut instruction = fixtures::idl_instruction(
+        fixtures::pump_program_id(),
+        pump::Instruction::SetParams.def().unwrap(),
+        &[fee_recipient.as_ref(), &args[0], &args[1], &args[2], &args[3], &args[4]],
+        &[("user", user)],
+    );
+    instruction.accounts[0].is_signer = false;
+    instruction.accounts[1].is_signer = signed;
+    fixtures::single_instruction_transaction(instruction)
+}
+
+#[test]
+fn pump_set_params_applies_to_curves_created_afterwards() {
+    let before = Token::new();
+    let after = Token::new();
+    let authority = Pubkey::new_unique();
+    let mut curves = CurveTracker::new(&CurvesConfig {
+        enabled: true,
+        pump_authority: Some(authority.to_string()),
+        ..CurvesConfig::default()
+    });
+    let mut events = Events::default();
+
+    curves.record(
+        &fixtures::process(vec![before.create(&Pubkey::new_unique()), set_params(authority, true), after.create(&Pubkey::new_unique())], 50),
+        &mut events,
+    );
+
+    assert_eq!(curves.get(&before.mint.to_string()).unwrap().virtual_sol_reserves, 30_000_000_000);
+    let state = curves.get(&after.mint.to_string()).unwrap();
+    assert_eq!((state.virtual_token_reserves, state.virtual_sol_reserves, state.real_token_reserves, state.fee_bps), (2_000_000, 3_000_000, 1_000_000, 50));
+    assert_eq!(curves.pump_params().initial_virtual_sol_reserves, 3_000_000);
+}
+
+#[test]
+fn set_params_without_the_authority_signature_is_ignored() {
+    let authority = Pubkey::new_unique();
+    let mut curves = CurveTracker::new(&CurvesConfig {
+        enabled: true,
+        pump_authority: Some(authority.to_string()),
+        ..CurvesConfig::default()
+    });
+    let mut events = Events::default();
+    curves.record(&fixtures::process(vec![set_params(authority, false), set_params(Pubkey::new_unique(), true)], 50), &mut events);
+    assert_eq!(curves.pump_params(), &CurveParams::default());
+
+    // 没有配置pump_authority时忽略全部SetParams
+    let mut curves = tracker(small_params(0));
+    curves.record(&fixtures::process(vec![set_params(authority, true)], 51), &mut events);
+    assert_eq!(curves.pump_params(), &CurveParams::default());
+    assert_eq!(curves.ignored_set_params(), 1);
+}
+
+#[test]
+fn curve_states_and_boop_graduations_are_written_to_the_output() {
+    let path = std::env::temp_dir().join(format!("shreds-curves-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut config = Config::default();
+    config.set("sinks.stdout", "false").unwrap();
+    config.set("sinks.format", "json").unwrap();
+    config.set("sinks.file", path.to_str().unwrap()).unwrap();
+    let mut sink = OutputSink::from_config(&config).unwrap();
+
+    let token = Token::new();
+    let mut curves = tracker(small_params(0));
+    curves.record(&fixtures::process(vec![token.deploy(&Pubkey::new_unique()), token.buy_token(1_000_000, 0)], 60), &mut sink);
+
+    let output = std::fs::read_to_string(&path).unwrap();
+    let records: Vec<serde_json::Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["graduated"]["venue"], "raydium");
+    assert!(records[0]["graduated"]["pool"].is_null());
+    assert_eq!(records[1]["curve"]["mint"], token.mint.to_string());
+    assert_eq!(records[1]["curve"]["venue"], "BOOP");
+    assert_eq!(records[1]["curve"]["price"], 4.0);
+    assert_eq!(records[1]["curve"]["complete"], true);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn curves_config_is_validated() {
+    let config = Config::from_toml_str("[curves]\nenabled = true\n[curves.boop]\nfee_bps = 200\n").unwrap();
+    assert_eq!(config.curves.boop.fee_bps, 200);
+    assert_eq!(config.curves.boop.initial_virtual_sol_reserves, CurveParams::default().initial_virtual_sol_reserves);
+
+    let mut config = Config::default();
+    config.set("curves.enabled", "true").unwrap();
+    config.set("curves.pump.initial_real_token_reserves", "2000000000000000").unwrap();
+    config.set("curves.boop.fee_bps", "10000").unwrap();
+    config.set("curves.pump_authority", "not-a-key").unwrap();
+    config.set("curves.idle_curve_slots", "0").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("curves.pump.initial_real_token_reserves"), "{}", message);
+    assert!(message.contains("curves.pump_authority"), "{}", message);
+    assert!(message.contains("curves.boop.fee_bps"), "{}", message);
+    assert!(message.contains("curves.idle_curve_slots"), "{}", message);
+}
@@ -0,0 +1,93 @@
+// 未知鉴别器发现报告测试
+use shreds_client::config::DiscoveryConfig;
+use shreds_client::idl::runtime::anchor_discriminator;
+use shreds_client::services::discovery::MAX_EXAMPLES;
+use shreds_client::services::DiscoveryReport;
+use shreds_client::testing::fixtures;
+use shreds_client::TransactionProcessor;
+use solana_sdk::transaction::VersionedTransaction;
+
+fn process(slot: u64, transactions: Vec<VersionedTransaction>) -> shreds_client::TransactionResults {
+    let entries: Vec<solana_entry::entry::Entry> =
+        bincode::deserialize(&fixtures::entries_payload(transactions)).unwrap();
+    TransactionProcessor::process_entries(&entries, slot)
+}
+
+#[test]
+fn aggregates_unknown_discriminators_per_parser() {
+    let unknown = [9u8; 8];
+    let mut report = DiscoveryReport::from_config(&DiscoveryConfig::default(), None);
+
+    report.record_results(&process(
+        100,
+        vec![
+            fixtures::instruction_transaction(fixtures::pump_program_id(), fixtures::instruction_data(unknown, &[&[1; 16]]), 5),
+            fixtures::instruction_transaction(fixtures::pump_program_id(), fixtures::instruction_data(unknown, &[&[1; 8]]), 7),
+            // 已知指令不计入
+            fixtures::pump_buy(1, 2),
+        ],
+    ));
+    let later: Vec<_> = (0..MAX_EXAMPLES + 1)
+        .map(|_| fixtures::instruction_transaction(fixtures::pump_program_id(), unknown.to_vec(), 5))
+        .collect();
+    report.record_results(&process(105, later));
+
+    assert_eq!(report.len(), 1);
+    let entry = report.get("PUMP", &hex::encode(unknown)).expect("应记录未知鉴别器");
+    assert_eq!(entry.count, 2 + MAX_EXAMPLES as u64 + 1);
+    assert_eq!((entry.first_slot, entry.last_slot), (100, 105));
+    assert_eq!(entry.examples.len(), MAX_EXAMPLES);
+    assert_eq!(entry.data_lengths[&24], 1);
+    assert_eq!(entry.data_lengths[&16], 1);
+    assert_eq!(entry.data_lengths[&8], MAX_EXAMPLES as u64 + 1);
+    assert_eq!(entry.account_counts[&7], 1);
+    assert_eq!(entry.guess, None);
+
+    let json = report.to_json();
+    assert_eq!(json["PUMP"][hex::encode(unknown)]["count"], entry.count);
+    assert!(report.to_string().contains(&format!("Parser:PUMP Discriminator:{}", hex::encode(unknown))));
+}
+
+#[test]
+fn parse_results_carry_unknown_instructions() {
+    let unknown = [9u8; 8];
+    let results = process(
+        100,
+        vec![
+            fixtures::instruction_transaction(fixtures::boop_program_id(), fixtures::instruction_data(unknown, &[&[1; 8]]), 4),
+            fixtures::pump_buy(1, 2),
+        ],
+    );
+
+    let unknowns = results.unknown_instructions();
+    assert_eq!(unknowns.len(), 1);
+    assert_eq!((unknowns[0].parser, unknowns[0].data.len(), unknowns[0].accounts), ("BOOP", 16, 4));
+    assert_eq!(unknowns[0].signature, results.boop_transactions[0].signature);
+}
+
+#[test]
+fn guesses_names_from_idls_and_candidates() {
+    let config = DiscoveryConfig {
+        candidate_names: vec!["collect_creator_fee".to_string()],
+        ..DiscoveryConfig::default()
+    };
+    let mut report = DiscoveryReport::from_config(&config, None);
+
+    // boop的指令出现在pump程序中：pump解析器不认识，但内置IDL里有这个名字
+    let buy_token = anchor_discriminator("buy_token");
+    let collect = anchor_discriminator("collect_creator_fee");
+    report.record_results(&process(
+        7,
+        vec![
+            fixtures::instruction_transaction(fixtures::pump_program_id(), buy_token.to_vec(), 3),
+            fixtures::instruction_transaction(fixtures::pumpamm_program_id(), collect.to_vec(), 3),
+        ],
+    ));
+
+    let guess = report.get("PUMP", &hex::encode(buy_token)).unwrap().guess.clone();
+    assert!(guess.is_some_and(|name| name.ends_with("buy_token")));
+    assert_eq!(
+        report.get("PUMPAMM", &hex::encode(collect)).unwrap().guess.as_deref(),
+        Some("collect_creator_fee")
+    );
+}
@@ -0,0 +1,204 @@
+// 列式批次、Parquet写入与读回、按小时分区导出测试
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use arrow_array::{Array, UInt64Array};
+use arrow_schema::{DataType, TimeUnit};
+use shreds_client::config::{Config, ExportConfig};
+use shreds_client::export::tables::{LIQUIDITY_SCHEMA, TRADES_SCHEMA};
+use shreds_client::export::writer::partition;
+use shreds_client::cli::commands;
+use shreds_client::export::{parquet, Cell, Column, EventTables, PartitionedWriter, RecordBatch, SlotClock};
+use shreds_client::idl::runtime::anchor_discriminator;
+use shreds_client::services::RecordingWriter;
+use shreds_client::testing::fixtures;
+use shreds_client::TransactionResults;
+
+// 2025-10-09 08:53:20 UTC
+const OBSERVED_AT: i64 = 1_760_000_000_000;
+
+fn pumpswap_deposit(lp: u64, max_base: u64, max_quote: u64) -> VersionedTransaction {
+    let data = fixtures::instruction_data(
+        anchor_discriminator("deposit"),
+        &[&lp.to_le_bytes(), &max_base.to_le_bytes(), &max_quote.to_le_bytes()],
+    );
+    fixtures::instruction_transaction(fixtures::pumpamm_program_id(), data, 15)
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("shreds-export-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn export_config(dir: &std::path::Path, rows_per_file: usize) -> ExportConfig {
+    ExportConfig {
+        enabled: true,
+        dir: dir.to_path_buf(),
+        rows_per_file,
+        ..ExportConfig::default()
+    }
+}
+
+#[test]
+fn events_fill_typed_columns() {
+    let creator = Pubkey::new_unique();
+    let results = fixtures::process(
+        vec![
+            fixtures::pump_buy(1_000, 50),
+            fixtures::boop_sell_token(500, 20),
+            fixtures::pump_create("Token", "TKN", "https://example.com", &creator),
+            pumpswap_deposit(7, 8, 9),
+        ],
+        30,
+    );
+    let mut tables = EventTables::new();
+    tables.append(&results, OBSERVED_AT).unwrap();
+    assert_eq!((tables.trades().len(), tables.launches().len(), tables.liquidity().len()), (2, 1, 1));
+    assert_eq!(tables.rows(), 4);
+
+    let liquidity = tables.liquidity();
+    let column = |name: &str| &liquidity.columns()[LIQUIDITY_SCHEMA.iter().position(|f| f.name == name).unwrap()];
+    assert_eq!(column("kind"), &Column::Utf8(vec![Some("deposit".to_string())]));
+    assert_eq!(column("lp_amount"), &Column::Int64(vec![Some(7)]));
+    assert_eq!(column("quote_amount"), &Column::Int64(vec![Some(9)]));
+    assert_eq!(liquidity.slot_range(), Some((30, 30)));
+}
+
+#[test]
+fn rows_are_checked_against_the_schema() {
+    let mut batch = RecordBatch::new(TRADES_SCHEMA);
+    assert!(batch.push_row(vec![Cell::Null]).is_err());
+    let mut row: Vec<Cell> = TRADES_SCHEMA.iter().map(|_| Cell::Null).collect();
+    assert!(batch.push_row(row.clone()).is_err());
+    row[0] = Cell::Int(1);
+    assert!(batch.push_row(row).is_err());
+    assert!(batch.is_empty());
+}
+
+#[test]
+fn parquet_files_read_back_with_the_same_schema_and_values() {
+    let results = fixtures::process(vec![fixtures::pump_buy(1_000, 50), fixtures::boop_sell_token(500, 20)], 31);
+    let mut tables = EventTables::new();
+    tables.append(&results, OBSERVED_AT).unwrap();
+    let dir = temp_dir("read-back");
+    let path = dir.join("trades.parquet");
+    parquet::write_file(&path, tables.trades(), &[("shreds.schema_version", "1")]).unwrap();
+    assert!(!dir.join("trades.parquet.tmp").exists());
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap();
+    let metadata = reader.metadata().file_metadata().key_value_metadata().unwrap();
+    assert!(metadata.iter().any(|kv| kv.key == "shreds.schema_version" && kv.value.as_deref() == Some("1")));
+    assert_eq!(reader.schema().fields(), parquet::arrow_schema(TRADES_SCHEMA).fields());
+
+    let batches: Vec<_> = reader.build().unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    assert_eq!(batch.columns(), parquet::to_arrow(tables.trades()).unwrap().columns());
+    let slots = batch.column_by_name("slot").unwrap().as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(slots.values().to_vec(), vec![31, 31]);
+    let observed = batch.column_by_name("observed_at").unwrap();
+    assert_eq!(observed.data_type(), &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())));
+    let amounts = batch.column_by_name("token_amount").unwrap().as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(amounts.values().to_vec(), vec![1_000, 500]);
+    assert_eq!(batch.column_by_name("attribution").unwrap().null_count(), 2);
+
+    assert!(parquet::write_file(&dir.join("empty.parquet"), &RecordBatch::new(TRADES_SCHEMA), &[]).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn files_are_partitioned_by_hour() {
+    assert_eq!(partition(0), "date=1970-01-01/hour=00");
+    assert_eq!(partition(OBSERVED_AT), "date=2025-10-09/hour=08");
+    assert_eq!(partition(951_782_400_000), "date=2000-02-29/hour=00");
+
+    let dir = temp_dir("hourly");
+    let mut writer = PartitionedWriter::new(&export_config(&dir, 1_000));
+    assert!(writer.append(&fixtures::process(vec![fixtures::pump_buy(1, 2)], 40), OBSERVED_AT).unwrap().is_empty());
+    assert!(writer.append(&fixtures::process(vec![fixtures::pump_buy(1, 2)], 41), OBSERVED_AT + 1_000).unwrap().is_empty());
+
+    // 跨过整点时写出上一小时的缓冲
+    let written = writer
+        .append(&fixtures::process(vec![fixtures::pump_buy(1, 2)], 42), OBSERVED_AT + 3_600_000)
+        .unwrap();
+    assert_eq!(written, vec![dir.join("trades/date=2025-10-09/hour=08/part-40-41.parquet")]);
+    assert!(written[0].is_file());
+
+    let written = writer.flush().unwrap();
+    assert_eq!(written, vec![dir.join("trades/date=2025-10-09/hour=09/part-42-42.parquet")]);
+    assert!(writer.flush().unwrap().is_empty());
+    // 不留下临时文件
+    assert!(!dir.join("trades/date=2025-10-09/hour=09/part-42-42.parquet.tmp").exists());
+}
+
+#[test]
+fn files_are_written_by_size_and_age() {
+    let dir = temp_dir("size");
+    let mut writer = PartitionedWriter::new(&export_config(&dir, 2));
+    assert!(writer.append(&fixtures::process(vec![fixtures::pump_buy(1, 2)], 50), OBSERVED_AT).unwrap().is_empty());
+    let written = writer.append(&fixtures::process(vec![fixtures::pump_buy(1, 2)], 51), OBSERVED_AT).unwrap();
+    assert_eq!(written.len(), 1);
+
+    // 超过flush_interval_secs后，下一个slot（即使没有事件）触发写出
+    let config = ExportConfig {
+        flush_interval_secs: 60,
+        ..export_config(&dir, 1_000)
+    };
+    let mut writer = PartitionedWriter::new(&config);
+    writer.append(&fixtures::process(vec![fixtures::pump_buy(1, 2)], 52), OBSERVED_AT).unwrap();
+    let written = writer.append(&TransactionResults::new(), OBSERVED_AT + 60_000).unwrap();
+    assert_eq!(written.len(), 1);
+}
+
+// 回放按slot推算observed_at，同一录制文件每次回放落在相同的分区
+#[test]
+fn replay_partitions_follow_the_slot_clock() {
+    let mut clock = SlotClock::from_config(&ExportConfig {
+        origin_slot: Some(100),
+        origin_unix_ms: Some(OBSERVED_AT),
+        ..ExportConfig::default()
+    });
+    assert_eq!((clock.time(100), clock.time(110), clock.time(90)), (OBSERVED_AT, OBSERVED_AT + 4_000, OBSERVED_AT - 4_000));
+
+    let dir = temp_dir("replay");
+    let recording = dir.join("recording.bin");
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut writer = RecordingWriter::create(&recording).unwrap();
+    // slot 100 在08时，之后9_000个slot（3_600秒）在09时
+    for slot in [100, 9_100] {
+        writer.write(slot, &fixtures::entries_payload(vec![fixtures::pump_buy(1, 2)])).unwrap();
+    }
+    writer.flush().unwrap();
+    drop(writer);
+
+    let mut config = Config::default();
+    for (key, value) in [
+        ("sinks.stdout", "false"),
+        ("export.enabled", "true"),
+        ("export.dir", dir.join("out").to_str().unwrap()),
+        ("export.origin_slot", "100"),
+        ("export.origin_unix_ms", &OBSERVED_AT.to_string()),
+    ] {
+        config.set(key, value).unwrap();
+    }
+    commands::replay(&config, &recording).unwrap();
+    assert!(dir.join("out/trades/date=2025-10-09/hour=08/part-100-100.parquet").is_file());
+    assert!(dir.join("out/trades/date=2025-10-09/hour=09/part-9100-9100.parquet").is_file());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn export_config_is_validated() {
+    let mut config = Config::default();
+    config.apply_args(&["--export-dir".to_string(), "research".to_string()]).unwrap();
+    assert_eq!(config.export.dir.to_str(), Some("research"));
+
+    config.set("export.enabled", "true").unwrap();
+    config.set("export.rows_per_file", "0").unwrap();
+    config.set("export.origin_slot", "100").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("export.rows_per_file"), "{}", message);
+    assert!(message.contains("export.origin_unix_ms"), "{}", message);
+}
@@ -0,0 +1,169 @@
+// IDL生成的指令表与Borsh参数解码测试
+use serde_json::json;
+use shreds_client::idl::{self, IdlType};
+use shreds_client::idl::IdlRegistry;
+use shreds_client::models::{BoopParser, IdlParser, PumpAmmParser, PumpParser};
+use shreds_client::testing::fixtures;
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn decodes_args_through_generated_tables() {
+    let pump = PumpParser::parse_transaction(&fixtures::pump_buy(1_000, 2_000)).unwrap();
+    let ix = &pump.instructions[0];
+    assert_eq!(ix.decoded_args(), Some(json!({"amount": 1_000, "max_sol_cost": 2_000})));
+    assert_eq!(ix.idl_account_name(1), Some("fee_recipient"));
+
+    let amm = PumpAmmParser::parse_transaction(&fixtures::pumpswap_sell(5, 6)).unwrap();
+    assert_eq!(
+        amm.instructions[0].decoded_args(),
+        Some(json!({"base_amount_in": 5, "min_quote_amount_out": 6}))
+    );
+
+    let boop = BoopParser::parse_transaction(&fixtures::boop_buy_token(7, 8)).unwrap();
+    assert_eq!(
+        boop.instructions[0].decoded_args(),
+        Some(json!({"buy_amount": 7, "amount_out_min": 8}))
+    );
+}
+
+#[test]
+fn boop_buy_token_text_uses_idl_arg_names() {
+    let boop = BoopParser::parse_transaction(&fixtures::boop_buy_token(7, 8)).unwrap();
+    let text = boop.instructions[0].to_string();
+    assert!(text.contains("Buy_Amount: 7"), "{}", text);
+    assert!(text.contains("Amount_Out_Min: 8"), "{}", text);
+
+    // 只有buy_amount完整时只打印buy_amount，不会越界
+    let mut data = idl::boop::Instruction::BuyToken.def().unwrap().discriminator.to_vec();
+    data.extend_from_slice(&[7, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4]);
+    let tx = fixtures::instruction_transaction(fixtures::boop_program_id(), data, 13);
+    let text = BoopParser::parse_transaction(&tx).unwrap().instructions[0].to_string();
+    assert!(text.contains("Buy_Amount: 7"), "{}", text);
+    assert!(!text.contains("Amount_Out_Min"), "{}", text);
+}
+
+#[test]
+fn decodes_strings_and_pubkeys() {
+    let creator = Pubkey::new_unique();
+    let tx = fixtures::pump_create("Name", "SYM", "https://x", &creator);
+    let pump = PumpParser::parse_transaction(&tx).unwrap();
+
+    assert_eq!(
+        pump.instructions[0].decoded_args(),
+        Some(json!({"name": "Name", "symbol": "SYM", "uri": "https://x", "creator": creator.to_string()}))
+    );
+}
+
+#[test]
+fn truncated_args_keep_decoded_prefix() {
+    let mut data = idl::pump::Instruction::Buy.def().unwrap().discriminator.to_vec();
+    data.extend_from_slice(&42u64.to_le_bytes());
+
+    let args = idl::pump::Instruction::Buy.def().unwrap().decode_args(&data);
+
+    assert_eq!(args, Some(json!({"amount": 42})));
+}
+
+#[test]
+fn decode_instruction_resolves_program_and_account_names() {
+    let program_id: Pubkey = idl::pump_amm::PROGRAM_ID.parse().unwrap();
+    let mut data = idl::pump_amm::Instruction::CreatePool.def().unwrap().discriminator.to_vec();
+    data.extend_from_slice(&3u16.to_le_bytes());
+    data.extend_from_slice(&100u64.to_le_bytes());
+    data.extend_from_slice(&200u64.to_le_bytes());
+    let accounts: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+
+    let decoded = idl::decode_instruction(&program_id, &data, &accounts).unwrap();
+
+    assert_eq!(decoded.program, "pump_amm");
+    assert_eq!(decoded.name, "create_pool");
+    assert_eq!(decoded.args, json!({"index": 3, "base_amount_in": 100, "quote_amount_in": 200}));
+    assert_eq!(decoded.accounts[1], ("global_config".to_string(), accounts[1]));
+}
+
+#[test]
+fn canonical_type_strings_round_trip() {
+    for ty in ["u64", "pubkey", "option<pubkey>", "vec<u8>", "[pubkey;8]", "defined:Fees"] {
+        assert_eq!(ty.parse::<IdlType>().unwrap().to_string(), ty);
+    }
+}
+
+#[test]
+fn runtime_registry_decodes_programs_from_directory() {
+    let program_id = Pubkey::new_unique();
+    let dir = std::env::temp_dir().join(format!("shreds-client-idl-{}", program_id));
+    std::fs::create_dir_all(&dir).unwrap();
+    // 旧版IDL格式：camelCase指令名、publicKey类型、嵌套账户组、自定义类型
+    let idl = json!({
+        "name": "vault",
+        "metadata": {"address": program_id.to_string()},
+        "instructions": [{
+            "name": "setConfig",
+            "accounts": [
+                {"name": "authority", "isMut": false, "isSigner": true},
+                {"name": "pool", "accounts": [{"name": "poolState"}, {"name": "poolVault"}]}
+            ],
+            "args": [
+                {"name": "config", "type": {"defined": "Config"}},
+                {"name": "mode", "type": {"defined": "Mode"}}
+            ]
+        }],
+        "types": [
+            {"name": "Config", "type": {"kind": "struct", "fields": [
+                {"name": "fee_bps", "type": "u16"},
+                {"name": "admin", "type": {"option": "publicKey"}}
+            ]}},
+            {"name": "Mode", "type": {"kind": "enum", "variants": [{"name": "Paused"}, {"name": "Active"}]}}
+        ]
+    });
+    std::fs::write(dir.join("vault.json"), idl.to_string()).unwrap();
+    std::fs::write(dir.join("README.md"), "ignored").unwrap();
+
+    let registry = IdlRegistry::load_dir(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(registry.len(), 1);
+
+    let admin = Pubkey::new_unique();
+    let discriminator = solana_sdk::hash::hashv(&[b"global:set_config".as_slice()]).to_bytes();
+    let mut data = discriminator[..8].to_vec();
+    data.extend_from_slice(&25u16.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(admin.as_ref());
+    data.push(1);
+    let tx = fixtures::instruction_transaction(program_id, data, 4);
+
+    let parsed = IdlParser::parse_transaction(&tx, &registry).unwrap();
+    let ix = &parsed.instructions[0];
+
+    assert_eq!(ix.program, "vault");
+    assert_eq!(ix.name, "setConfig");
+    assert_eq!(
+        ix.args,
+        json!({"config": {"fee_bps": 25, "admin": admin.to_string()}, "mode": "Active"})
+    );
+    let names: Vec<&str> = ix.accounts.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["authority", "poolState", "poolVault", "remaining_3"]);
+
+    // 注册表之外的程序不会被解码
+    assert!(IdlParser::parse_transaction(&fixtures::pump_buy(1, 2), &registry).is_none());
+}
+
+#[test]
+fn runtime_registry_rejects_malformed_discriminators() {
+    let program_id = Pubkey::new_unique();
+    for discriminator in [json!([1, 2, 3, 4, 5, 6, 7, 256]), json!([1, 2, 3]), json!([1, 2, 3, 4, 5, 6, 7, "8"])] {
+        let dir = std::env::temp_dir().join(format!("shreds-client-bad-idl-{}", Pubkey::new_unique()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let idl = json!({
+            "address": program_id.to_string(),
+            "metadata": {"name": "broken"},
+            "instructions": [{"name": "swap", "discriminator": discriminator, "accounts": [], "args": []}]
+        });
+        std::fs::write(dir.join("broken.json"), idl.to_string()).unwrap();
+
+        let err = IdlRegistry::load_dir(&dir).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "{}", discriminator);
+        assert!(err.to_string().contains("broken.swap"), "{}", err);
+    }
+}
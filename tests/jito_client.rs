@@ -0,0 +1,78 @@
+// JitoClient 与本地 mock ShredstreamProxy 的端到端测试
+use std::time::Duration;
+
+use shreds_client::testing::fixtures;
+use shreds_client::testing::{MockEvent, MockShredstreamProxy};
+use shreds_client::{JitoClient, TransactionResults};
+
+async fn run(script: Vec<MockEvent>) -> (Result<(), std::io::Error>, Vec<TransactionResults>) {
+    let (url, _handle) = MockShredstreamProxy::new(script).spawn().await.unwrap();
+    let mut collected = Vec::new();
+    let result = JitoClient::connect_and_process_with(url, |results| collected.push(results)).await;
+    (result, collected)
+}
+
+#[tokio::test]
+async fn parses_all_three_programs() {
+    let (result, collected) = run(vec![
+        MockEvent::Entries {
+            slot: 1,
+            transactions: vec![fixtures::pump_buy(1_000, 2_000), fixtures::pump_sell(500, 100)],
+        },
+        MockEvent::Entries {
+            slot: 2,
+            transactions: vec![fixtures::pumpswap_buy(10, 20), fixtures::boop_buy_token(30, 40)],
+        },
+    ])
+    .await;
+
+    assert!(result.is_ok());
+    assert_eq!(collected.len(), 2);
+    assert_eq!(collected[0].current_slot, 1);
+    assert_eq!(collected[0].pump_transactions.len(), 2);
+    assert_eq!(collected[1].current_slot, 2);
+    assert_eq!(collected[1].pumpamm_transactions.len(), 1);
+    assert_eq!(collected[1].boop_transactions.len(), 1);
+}
+
+#[tokio::test]
+async fn skips_malformed_payloads_and_delays() {
+    let (result, collected) = run(vec![
+        MockEvent::Malformed { slot: 1 },
+        MockEvent::Delay(Duration::from_millis(50)),
+        MockEvent::Entries { slot: 2, transactions: vec![fixtures::pump_buy(1, 2)] },
+    ])
+    .await;
+
+    assert!(result.is_ok());
+    assert_eq!(collected.len(), 1);
+    assert_eq!(collected[0].current_slot, 2);
+}
+
+#[tokio::test]
+async fn surfaces_server_disconnect() {
+    let (result, collected) = run(vec![
+        MockEvent::Entries { slot: 1, transactions: vec![fixtures::pump_buy(1, 2)] },
+        MockEvent::Disconnect("scripted".to_string()),
+        MockEvent::Entries { slot: 2, transactions: vec![fixtures::pump_buy(1, 2)] },
+    ])
+    .await;
+
+    assert!(result.is_err());
+    assert_eq!(collected.len(), 1);
+}
+
+#[tokio::test]
+async fn replays_recorded_payloads() {
+    let payload = fixtures::entries_payload(vec![fixtures::pumpswap_sell(7, 8)]);
+    let proxy = MockShredstreamProxy::from_recording(vec![(9, payload)]);
+    let (url, _handle) = proxy.clone().spawn().await.unwrap();
+
+    let mut collected = Vec::new();
+    JitoClient::connect_and_process_with(url, |results| collected.push(results))
+        .await
+        .unwrap();
+
+    assert_eq!(proxy.subscription_count(), 1);
+    assert_eq!(collected[0].pumpamm_transactions.len(), 1);
+}
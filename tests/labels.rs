@@ -0,0 +1,69 @@
+// 输出标签目录与语言切换测试
+use std::collections::HashSet;
+
+use shreds_client::config::Config;
+use shreds_client::labels::{self, Locale, CATALOG};
+use shreds_client::testing::fixtures;
+use shreds_client::utils::account_layout;
+use shreds_client::TransactionProcessor;
+
+#[test]
+fn catalog_keys_are_unique_and_translated() {
+    let mut keys = HashSet::new();
+    for (key, en, zh) in CATALOG {
+        assert!(keys.insert(*key), "重复的标签键: {}", key);
+        assert!(!en.is_empty() && !zh.is_empty(), "标签缺少翻译: {}", key);
+    }
+}
+
+#[test]
+fn account_layouts_use_catalog_keys() {
+    for layout in account_layout::layouts() {
+        for key in layout.accounts {
+            assert!(labels::contains(key), "账户标签不在目录中: {}", key);
+        }
+    }
+}
+
+#[test]
+fn unknown_keys_fall_back_to_the_key() {
+    assert_eq!(labels::text_in("remaining_3", Locale::Zh), "remaining_3");
+    assert_eq!(labels::text_in("bonding_curve", Locale::En), "Bonding_Curve");
+    assert_eq!(labels::text_in("bonding_curve", Locale::Zh), "绑定曲线");
+}
+
+#[test]
+fn locale_switches_text_output_but_not_json() {
+    let entries: Vec<solana_entry::entry::Entry> =
+        bincode::deserialize(&fixtures::entries_payload(vec![fixtures::pump_buy(1000, 2000)])).unwrap();
+    let results = TransactionProcessor::process_entries(&entries, 42);
+    let render = || {
+        let mut out = Vec::new();
+        results.write_to(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    labels::set_locale(Locale::En);
+    let english = render();
+    assert!(english.contains("Token_Amount: 1000"));
+    assert!(english.contains("Max_SOL_Cost: 2000"));
+    assert!(english.contains("[0]Global: "));
+
+    labels::set_locale(Locale::Zh);
+    let chinese = render();
+    assert!(chinese.contains("代币数量: 1000"));
+    assert!(chinese.contains("[0]全局状态: "));
+    assert_eq!(results.to_json_records(), {
+        labels::set_locale(Locale::En);
+        results.to_json_records()
+    });
+}
+
+#[test]
+fn locale_is_configurable() {
+    let mut config = Config::default();
+    assert_eq!(config.sinks.locale, Locale::En);
+    config.set("sinks.locale", "zh").unwrap();
+    assert_eq!(config.sinks.locale, Locale::Zh);
+    assert!(config.set("sinks.locale", "fr").is_err());
+}
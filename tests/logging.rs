@@ -0,0 +1,65 @@
+// 日志级别、格式与限流通道测试
+use std::time::{Duration, Instant};
+
+use log::{Level, LevelFilter, Record};
+use shreds_client::config::{Config, LogFormat};
+use shreds_client::services::logging::{Logger, RateLimitedLog};
+
+fn logger(level: &str, format: LogFormat) -> Logger {
+    let mut config = Config::default();
+    config.set("logging.level", level).unwrap();
+    config.logging.format = format;
+    Logger::new(&config.logging).unwrap()
+}
+
+#[test]
+fn per_module_levels_use_longest_prefix() {
+    let logger = logger("warn, shreds_client::services=info, shreds_client::services::jito_client=trace", LogFormat::Text);
+
+    assert_eq!(logger.level_for("shreds_client::models::boop_parser"), LevelFilter::Warn);
+    assert_eq!(logger.level_for("shreds_client::services::output"), LevelFilter::Info);
+    assert_eq!(logger.level_for("shreds_client::services::jito_client"), LevelFilter::Trace);
+    // 前缀必须落在模块边界上
+    assert_eq!(logger.level_for("shreds_client::services_extra"), LevelFilter::Warn);
+    assert_eq!(logger.max_level(), LevelFilter::Trace);
+}
+
+#[test]
+fn invalid_level_directives_are_rejected() {
+    let mut config = Config::default();
+    config.set("logging.level", "info,shreds_client=loud").unwrap();
+    assert!(Logger::new(&config.logging).is_err());
+    assert!(config.validate_offline().unwrap_err().to_string().contains("logging.level"));
+}
+
+#[test]
+fn json_format_emits_one_object_per_line() {
+    let logger = logger("info", LogFormat::Json);
+    let line = logger.format_record(
+        &Record::builder()
+            .args(format_args!("slot {} 反序列化失败", 7))
+            .level(Level::Warn)
+            .target("shreds_client::services::jito_client")
+            .build(),
+    );
+
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(value["level"], "WARN");
+    assert_eq!(value["target"], "shreds_client::services::jito_client");
+    assert_eq!(value["message"], "slot 7 反序列化失败");
+    assert!(value["ts"].is_f64());
+}
+
+#[test]
+fn rate_limited_log_deduplicates_and_counts() {
+    let limiter = RateLimitedLog::new(Duration::from_secs(60));
+    let start = Instant::now();
+
+    assert_eq!(limiter.check("BOOP:0102", start), Some(0));
+    assert_eq!(limiter.check("BOOP:0102", start + Duration::from_secs(1)), None);
+    assert_eq!(limiter.check("BOOP:0102", start + Duration::from_secs(2)), None);
+    // 不同的键互不影响
+    assert_eq!(limiter.check("BOOP:0304", start + Duration::from_secs(3)), Some(0));
+    assert_eq!(limiter.check("BOOP:0102", start + Duration::from_secs(61)), Some(2));
+    assert_eq!(limiter.check("BOOP:0102", start + Duration::from_secs(62)), None);
+}
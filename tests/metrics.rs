@@ -0,0 +1,156 @@
+// 指标记录、Prometheus文本输出与 /metrics 端点测试
+use std::time::{Duration, Instant};
+
+use shreds_client::services::metrics::{self, Metrics, SlotClock};
+use shreds_client::testing::fixtures;
+use shreds_client::testing::{MockEvent, MockShredstreamProxy};
+use shreds_client::{JitoClient, TransactionProcessor};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[test]
+fn renders_prometheus_text_format() {
+    let metrics = Metrics::new();
+    metrics.record_message("http://a:1", 100, 64);
+    metrics.record_message("http://a:1", 98, 36);
+    metrics.deserialize_failures.inc();
+    metrics.processing_seconds.observe(Duration::from_micros(300));
+    metrics.processing_seconds.observe(Duration::from_secs(2));
+
+    let entries: Vec<solana_entry::entry::Entry> = bincode::deserialize(&fixtures::entries_payload(vec![fixtures::pump_buy(1, 2)])).unwrap();
+    let results = TransactionProcessor::process_entries(&entries, 100);
+    metrics.record_results(&results);
+
+    let text = metrics.render();
+
+    for line in [
+        "# TYPE shreds_slots_received_total counter",
+        "shreds_slots_received_total 2",
+        "shreds_deserialize_failures_total 1",
+        "shreds_bytes_received_total{endpoint=\"http://a:1\"} 100",
+        "shreds_parser_matches_total{parser=\"PUMP\",instruction=\"Buy\"} 1",
+        "shreds_processing_duration_seconds_bucket{le=\"0.0005\"} 1",
+        "shreds_processing_duration_seconds_bucket{le=\"1\"} 1",
+        "shreds_processing_duration_seconds_bucket{le=\"+Inf\"} 2",
+        "shreds_processing_duration_seconds_count 2",
+        "shreds_highest_slot 100",
+        "shreds_slot_lag 2",
+    ] {
+        assert!(text.lines().any(|l| l == line), "缺少 `{}`:\n{}", line, text);
+    }
+}
+
+#[tokio::test]
+async fn serves_metrics_over_http() {
+    let metrics: &'static Metrics = Box::leak(Box::new(Metrics::new()));
+    metrics.reconnects.inc();
+    let listener = metrics::bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(metrics::serve(listener, metrics));
+
+    let get = |path: &'static str| async move {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    };
+
+    let response = get("/metrics").await;
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains("text/plain; version=0.0.4"));
+    assert!(response.contains("\nshreds_reconnects_total 1\n"));
+
+    assert!(get("/").await.starts_with("HTTP/1.1 404"));
+}
+
+#[tokio::test]
+async fn client_updates_global_metrics() {
+    let global = metrics::global();
+    let (slots, failures, transactions) = (
+        global.slots_received.get(),
+        global.deserialize_failures.get(),
+        global.transactions_received.get(),
+    );
+
+    let (url, _handle) = MockShredstreamProxy::new(vec![
+        MockEvent::Malformed { slot: 1 },
+        MockEvent::Entries { slot: 2, transactions: vec![fixtures::pump_buy(1, 2), fixtures::pump_sell(3, 4)] },
+    ])
+    .spawn()
+    .await
+    .unwrap();
+    JitoClient::connect_and_process_with(url, |_| {}).await.unwrap();
+
+    assert!(global.slots_received.get() >= slots + 2);
+    assert!(global.deserialize_failures.get() > failures);
+    assert!(global.transactions_received.get() >= transactions + 2);
+    assert!(global.parser_matches.get(&["PUMP", "Sell"]) >= 1);
+}
+
+#[test]
+fn slot_clock_measures_first_seen_delay() {
+    let mut clock = SlotClock::new(Duration::from_millis(400));
+    let start = Instant::now();
+
+    assert_eq!(clock.first_seen(10, start), Some(Duration::ZERO));
+    assert_eq!(clock.first_seen(11, start + Duration::from_millis(450)), Some(Duration::from_millis(50)));
+    // 同一slot的后续消息和乱序的旧slot不计入
+    assert_eq!(clock.first_seen(11, start + Duration::from_millis(460)), None);
+    assert_eq!(clock.first_seen(9, start + Duration::from_millis(470)), None);
+    // 比估算更早到达的slot成为新的基准
+    assert_eq!(clock.first_seen(12, start + Duration::from_millis(700)), Some(Duration::ZERO));
+    assert_eq!(clock.first_seen(13, start + Duration::from_millis(1200)), Some(Duration::from_millis(100)));
+}
+
+#[tokio::test]
+async fn results_carry_pipeline_timing() {
+    let global = metrics::global();
+    let decoded = global.decode_seconds.count();
+
+    let (url, _handle) = MockShredstreamProxy::new(vec![MockEvent::Entries {
+        slot: 7,
+        transactions: vec![fixtures::pump_buy(1, 2)],
+    }])
+    .spawn()
+    .await
+    .unwrap();
+    let mut collected = Vec::new();
+    JitoClient::connect_and_process_with(url, |results| collected.push(results)).await.unwrap();
+
+    let results = collected.first().expect("没有收到解析结果");
+    let timing = results.timing.expect("实时订阅的结果应带有时间戳");
+    assert!(timing.received_at <= timing.decoded_at && timing.decoded_at <= timing.parsed_at);
+
+    let record = &results.to_json_records()[0];
+    for field in ["decode", "parse", "total"] {
+        assert!(record["latency_us"][field].is_u64(), "缺少 latency_us.{}: {}", field, record);
+    }
+    assert!(global.decode_seconds.count() > decoded);
+
+    // 离线处理没有gRPC边界，不带时间戳
+    let entries: Vec<solana_entry::entry::Entry> = bincode::deserialize(&fixtures::entries_payload(vec![fixtures::pump_buy(1, 2)])).unwrap();
+    let offline = TransactionProcessor::process_entries(&entries, 7);
+    assert!(offline.timing.is_none());
+    assert!(offline.to_json_records()[0].get("latency_us").is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn closes_idle_connections() {
+    let metrics: &'static Metrics = Box::leak(Box::new(Metrics::new()));
+    let listener = metrics::bind("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(metrics::serve(listener, metrics));
+
+    // 连上后不发送请求，超时后服务端应直接关闭连接且不返回响应
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let mut response = Vec::new();
+    tokio::time::timeout(Duration::from_secs(60), stream.read_to_end(&mut response))
+        .await
+        .expect("空闲连接应被关闭")
+        .unwrap();
+    assert!(response.is_empty());
+}
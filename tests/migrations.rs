@@ -0,0 +1,254 @@
+// 绑定曲线毕业追踪测试：Migrate、Withdraw之后的建池、外部报告的池子、挂起超时与场所索引
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use shreds_client::analytics::{Graduated, MigrationTracker, Venue};
+use shreds_client::config::{Config, MigrationsConfig};
+use shreds_client::idl::{pump, pump_amm};
+use shreds_client::models::token_parser::WSOL_MINT;
+use shreds_client::services::OutputSink;
+use shreds_client::testing::fixtures;
+use shreds_client::TransactionResults;
+
+fn empty(slot: u64) -> TransactionResults {
+    let mut results = TransactionResults::new();
+    results.current_slot = slot;
+    results
+}
+
+struct Token {
+    mint: Pubkey,
+    curve: Pubkey,
+}
+
+impl Token {
+    fn new() -> Self {
+        Self {
+            mint: Pubkey::new_unique(),
+            curve: Pubkey::new_unique(),
+        }
+    }
+
+    fn pump(&self, instruction: pump::Instruction, args: &[&[u8]], accounts: &[(&str, Pubkey)]) -> VersionedTransaction {
+        let mut accounts = accounts.to_vec();
+        accounts.extend([("mint", self.mint), ("bonding_curve", self.curve)]);
+        fixtures::single_instruction_transaction(fixtures::idl_instruction(
+            fixtures::pump_program_id(),
+            instruction.def().unwrap(),
+            args,
+            &accounts,
+        ))
+    }
+
+    fn buy(&self) -> VersionedTransaction {
+        self.pump(pump::Instruction::Buy, &[&1_000u64.to_le_bytes(), &2_000u64.to_le_bytes()], &[])
+    }
+
+    fn migrate(&self, pool: Pubkey) -> VersionedTransaction {
+        self.pump(pump::Instruction::Migrate, &[], &[("pool", pool)])
+    }
+
+    fn withdraw(&self) -> VersionedTransaction {
+        self.pump(pump::Instruction::Withdraw, &[], &[])
+    }
+}
+
+fn pumpswap(instruction: pump_amm::Instruction, args: &[&[u8]], pool: Pubkey, base: Pubkey, quote: Pubkey) -> VersionedTransaction {
+    fixtures::single_instruction_transaction(fixtures::idl_instruction(
+        fixtures::pumpamm_program_id(),
+        instruction.def().unwrap(),
+        args,
+        &[("pool", pool), ("base_mint", base), ("quote_mint", quote)],
+    ))
+}
+
+fn create_pool(pool: Pubkey, base: Pubkey, quote: Pubkey) -> VersionedTransaction {
+    let args: [&[u8]; 3] = [&0u16.to_le_bytes(), &1_000u64.to_le_bytes(), &1_000u64.to_le_bytes()];
+    pumpswap(pump_amm::Instruction::CreatePool, &args, pool, base, quote)
+}
+
+fn tracker(pending_slots: u64) -> MigrationTracker {
+    MigrationTracker::new(&MigrationsConfig {
+        enabled: true,
+        pending_slots,
+        ..MigrationsConfig::default()
+    })
+}
+
+#[test]
+fn migrate_graduates_the_curve_to_its_pumpswap_pool() {
+    let token = Token::new();
+    let pool = Pubkey::new_unique();
+    let mut migrations = tracker(100);
+    let mut events: Vec<Graduated> = Vec::new();
+
+    migrations.record(&fixtures::process(vec![token.buy()], 10), &mut events);
+    let listing = migrations.venue(&token.mint.to_string()).unwrap();
+    assert_eq!((listing.venue, listing.address.clone(), listing.since_slot), (Venue::PumpCurve, token.curve.to_string(), 10));
+
+    let migrate = token.migrate(pool);
+    let tx = fixtures::signature(&migrate);
+    migrations.record(&fixtures::process(vec![migrate], 11), &mut events);
+    assert_eq!(
+        events,
+        vec![Graduated {
+            mint: token.mint.to_string(),
+            curve: token.curve.to_string(),
+            pool: Some(pool.to_string()),
+            venue: Venue::PumpSwap,
+            slot: 11,
+            tx,
+        }]
+    );
+    let listing = migrations.venue(&token.mint.to_string()).unwrap();
+    assert_eq!((listing.venue, listing.address.clone()), (Venue::PumpSwap, pool.to_string()));
+
+    // 迁移账户重复发送的Migrate与之后的曲线买入不改变场所
+    migrations.record(&fixtures::process(vec![token.migrate(pool), token.buy()], 12), &mut events);
+    assert_eq!(events.len(), 1);
+    assert_eq!(migrations.venue(&token.mint.to_string()).unwrap().venue, Venue::PumpSwap);
+    assert_eq!(migrations.graduated(), 1);
+}
+
+#[test]
+fn withdraw_waits_for_the_pool_created_for_the_mint() {
+    let token = Token::new();
+    let pool = Pubkey::new_unique();
+    let mut migrations = tracker(100);
+    let mut events: Vec<Graduated> = Vec::new();
+
+    migrations.record(&fixtures::process(vec![token.withdraw()], 20), &mut events);
+    assert!(migrations.is_pending(&token.mint.to_string()));
+
+    // 与挂起的mint无关的池子
+    let other = Pubkey::new_unique();
+    migrations.record(&fixtures::process(vec![create_pool(Pubkey::new_unique(), other, WSOL_MINT)], 21), &mut events);
+    assert!(events.is_empty());
+    assert_eq!(migrations.venue(&other.to_string()).unwrap().venue, Venue::PumpSwap);
+
+    // mint在quote一侧也能对应
+    let create = create_pool(pool, WSOL_MINT, token.mint);
+    let tx = fixtures::signature(&create);
+    migrations.record(&fixtures::process(vec![create], 22), &mut events);
+    assert_eq!(events.len(), 1);
+    assert_eq!((events[0].pool.clone(), events[0].curve.clone()), (Some(pool.to_string()), token.curve.to_string()));
+    assert_eq!((events[0].slot, events[0].tx.clone()), (22, tx));
+    assert!(!migrations.is_pending(&token.mint.to_string()));
+}
+
+#[test]
+fn withdraw_and_create_pool_in_one_slot_follow_entry_order() {
+    let token = Token::new();
+    let mut migrations = tracker(100);
+    let mut events: Vec<Graduated> = Vec::new();
+
+    let mut results = fixtures::process(vec![token.withdraw(), create_pool(Pubkey::new_unique(), token.mint, WSOL_MINT)], 30);
+    results.pump_transactions.reverse();
+    results.pumpamm_transactions.reverse();
+    migrations.record(&results, &mut events);
+    assert_eq!(events.len(), 1);
+
+    // 建池在Withdraw之前则不对应
+    let token = Token::new();
+    migrations.record(&fixtures::process(vec![create_pool(Pubkey::new_unique(), token.mint, WSOL_MINT), token.withdraw()], 31), &mut events);
+    assert_eq!(events.len(), 1);
+    assert!(migrations.is_pending(&token.mint.to_string()));
+}
+
+#[test]
+fn other_decoders_report_raydium_pools_and_pending_withdrawals_expire() {
+    let token = Token::new();
+    let late = Token::new();
+    let pool = Pubkey::new_unique().to_string();
+    let mut migrations = tracker(10);
+    let mut events: Vec<Graduated> = Vec::new();
+
+    migrations.record(&fixtures::process(vec![token.withdraw(), late.withdraw()], 40), &mut events);
+    migrations.pool_created(Venue::Raydium, &token.mint.to_string(), &pool, 45, "raydium-tx", &mut events);
+    assert_eq!(events.len(), 1);
+    assert_eq!((events[0].venue, events[0].tx.as_str()), (Venue::Raydium, "raydium-tx"));
+    assert_eq!(migrations.venue(&token.mint.to_string()).unwrap().address, pool);
+
+    migrations.record(&empty(50), &mut events);
+    assert!(migrations.is_pending(&late.mint.to_string()));
+    migrations.record(&empty(51), &mut events);
+    assert!(!migrations.is_pending(&late.mint.to_string()));
+    assert_eq!(migrations.expired(), 1);
+
+    // 没有挂起的mint不会因为报告的池子毕业
+    migrations.pool_created(Venue::Raydium, &late.mint.to_string(), &pool, 52, "raydium-tx", &mut events);
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn pumpswap_trades_index_mints_that_graduated_before_the_subscription() {
+    let mint = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    let mut migrations = tracker(100);
+    let args: [&[u8]; 2] = [&1_000u64.to_le_bytes(), &2_000u64.to_le_bytes()];
+    migrations.record(&fixtures::process(vec![pumpswap(pump_amm::Instruction::Buy, &args, pool, mint, WSOL_MINT)], 60), &mut Vec::<Graduated>::new());
+
+    let listing = migrations.venue(&mint.to_string()).unwrap();
+    assert_eq!((listing.venue, listing.address.clone()), (Venue::PumpSwap, pool.to_string()));
+    assert_eq!(migrations.mints(), 1);
+    assert!(migrations.venue(&Pubkey::new_unique().to_string()).is_none());
+}
+
+#[test]
+fn idle_curves_are_evicted() {
+    let (idle, active, withdrawn) = (Token::new(), Token::new(), Token::new());
+    let mut migrations = MigrationTracker::new(&MigrationsConfig {
+        enabled: true,
+        pending_slots: 100,
+        idle_curve_slots: 80,
+    });
+    let mut events = Vec::<Graduated>::new();
+    migrations.record(&fixtures::process(vec![idle.buy(), active.buy(), withdrawn.buy(), withdrawn.withdraw()], 10), &mut events);
+    migrations.record(&fixtures::process(vec![active.buy()], 85), &mut events);
+    assert_eq!((migrations.mints(), migrations.evicted()), (3, 0));
+    assert_eq!(migrations.venue(&active.mint.to_string()).unwrap().last_slot, 85);
+
+    // 每10个slot清理一次：闲置的曲线不再跟踪，仍在等待建池的曲线保留
+    migrations.record(&empty(100), &mut events);
+    assert!(migrations.venue(&idle.mint.to_string()).is_none());
+    assert!(migrations.venue(&active.mint.to_string()).is_some());
+    assert!(migrations.venue(&withdrawn.mint.to_string()).is_some());
+    assert_eq!(migrations.evicted(), 1);
+}
+
+#[test]
+fn graduations_are_written_to_the_output() {
+    let path = std::env::temp_dir().join(format!("shreds-migrations-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut config = Config::default();
+    config.set("sinks.stdout", "false").unwrap();
+    config.set("sinks.format", "json").unwrap();
+    config.set("sinks.file", path.to_str().unwrap()).unwrap();
+    let mut sink = OutputSink::from_config(&config).unwrap();
+
+    let token = Token::new();
+    let pool = Pubkey::new_unique();
+    tracker(100).record(&fixtures::process(vec![token.migrate(pool)], 70), &mut sink);
+
+    let output = std::fs::read_to_string(&path).unwrap();
+    let record: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+    assert_eq!(record["graduated"]["mint"], token.mint.to_string());
+    assert_eq!(record["graduated"]["pool"], pool.to_string());
+    assert_eq!(record["graduated"]["venue"], "pumpswap");
+    assert_eq!(record["graduated"]["slot"], 70);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn migrations_config_is_validated() {
+    let config = Config::from_toml_str("[migrations]\nenabled = true\npending_slots = 50\n").unwrap();
+    assert_eq!(config.migrations.pending_slots, 50);
+
+    let mut config = Config::default();
+    config.set("migrations.enabled", "true").unwrap();
+    config.set("migrations.pending_slots", "0").unwrap();
+    config.set("migrations.idle_curve_slots", "0").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("migrations.pending_slots"), "{}", message);
+    assert!(message.contains("migrations.idle_curve_slots"), "{}", message);
+}
@@ -0,0 +1,247 @@
+// 流水线有界队列策略与端到端处理测试
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use shreds_client::config::{Config, PipelineConfig, QueueConfig, QueuePolicy};
+use shreds_client::services::metrics;
+use shreds_client::services::pipeline::{BoundedQueue, Pipeline, Pushed, RawSlot};
+use shreds_client::services::{ProcessorOptions, RecordingReader, RecordingWriter};
+use shreds_client::testing::fixtures;
+use shreds_client::testing::{MockEvent, MockShredstreamProxy};
+use shreds_client::JitoClient;
+
+fn queue(stage: &'static str, capacity: usize, policy: QueuePolicy) -> BoundedQueue<u64> {
+    BoundedQueue::new(stage, &QueueConfig { capacity, policy })
+}
+
+#[test]
+fn drop_oldest_keeps_the_latest_items() {
+    let q = queue("test-drop-oldest", 2, QueuePolicy::DropOldest);
+    assert_eq!(q.push(1), Pushed::Queued);
+    assert_eq!(q.push(2), Pushed::Queued);
+    assert_eq!(q.push(3), Pushed::DroppedOldest);
+    assert_eq!(metrics::global().pipeline_queue_depth.get(&["test-drop-oldest"]), 2);
+
+    assert_eq!(q.pop(), Some(2));
+    assert_eq!(q.pop(), Some(3));
+    assert!(q.is_empty());
+    assert_eq!(metrics::global().pipeline_queue_depth.get(&["test-drop-oldest"]), 0);
+    assert_eq!(metrics::global().pipeline_dropped.get(&["test-drop-oldest", "drop-oldest"]), 1);
+}
+
+#[test]
+fn drop_newest_discards_incoming_items() {
+    let q = queue("test-drop-newest", 1, QueuePolicy::DropNewest);
+    assert_eq!(q.push(1), Pushed::Queued);
+    assert_eq!(q.push(2), Pushed::DroppedNewest);
+    assert_eq!(q.push(3), Pushed::DroppedNewest);
+
+    assert_eq!(q.pop(), Some(1));
+    assert!(q.is_empty());
+    assert_eq!(metrics::global().pipeline_dropped.get(&["test-drop-newest", "drop-newest"]), 2);
+}
+
+#[test]
+fn block_waits_for_space() {
+    let q = Arc::new(queue("test-block", 1, QueuePolicy::Block));
+    assert_eq!(q.push(1), Pushed::Queued);
+
+    let producer = {
+        let q = q.clone();
+        thread::spawn(move || q.push(2))
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(q.len(), 1);
+
+    assert_eq!(q.pop(), Some(1));
+    assert_eq!(producer.join().unwrap(), Pushed::Queued);
+    assert_eq!(q.pop(), Some(2));
+    assert_eq!(metrics::global().pipeline_dropped.get(&["test-block", "block"]), 0);
+}
+
+#[tokio::test]
+async fn async_push_yields_until_space_is_free() {
+    let q = Arc::new(queue("test-block-async", 1, QueuePolicy::Block));
+    assert_eq!(q.push_async(1).await, Pushed::Queued);
+
+    let consumer = {
+        let q = q.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            q.pop()
+        })
+    };
+    assert_eq!(q.push_async(2).await, Pushed::Queued);
+    assert_eq!(consumer.join().unwrap(), Some(1));
+    assert_eq!(q.pop(), Some(2));
+}
+
+#[test]
+fn closed_queue_drains_then_ends() {
+    let q = queue("test-close", 4, QueuePolicy::Block);
+    q.push(1);
+    q.close();
+    assert_eq!(q.push(2), Pushed::Closed);
+    assert_eq!(q.pop(), Some(1));
+    assert_eq!(q.pop(), None);
+}
+
+#[tokio::test]
+async fn pipeline_processes_a_stream_in_order() {
+    let (url, _handle) = MockShredstreamProxy::new(vec![
+        MockEvent::Entries { slot: 1, transactions: vec![fixtures::pump_buy(1, 2)] },
+        MockEvent::Malformed { slot: 2 },
+        MockEvent::Entries { slot: 3, transactions: vec![] },
+        MockEvent::Entries { slot: 4, transactions: vec![fixtures::boop_buy_token(3, 4)] },
+    ])
+    .spawn()
+    .await
+    .unwrap();
+
+    let collected = Arc::new(Mutex::new(Vec::new()));
+    let pipeline = {
+        let collected = collected.clone();
+        Pipeline::start(&PipelineConfig::default(), ProcessorOptions::default(), move |results| {
+            collected.lock().unwrap().push((results.current_slot, results.has_results()))
+        })
+        .unwrap()
+    };
+    let failures = metrics::global().deserialize_failures.get();
+
+    JitoClient::connect_and_submit(url, &pipeline).await.unwrap();
+    pipeline.shutdown();
+
+    // 没有匹配交易的slot也交给on_results，分析按slot推进
+    assert_eq!(*collected.lock().unwrap(), vec![(1, true), (3, false), (4, true)]);
+    assert!(metrics::global().deserialize_failures.get() > failures);
+
+    let text = metrics::global().render();
+    for line in [
+        "# TYPE shreds_pipeline_queue_depth gauge",
+        "shreds_pipeline_queue_capacity{stage=\"decode\"} 128",
+        "shreds_pipeline_queue_depth{stage=\"emit\"} 0",
+    ] {
+        assert!(text.lines().any(|l| l == line), "缺少 `{}`:\n{}", line, text);
+    }
+}
+
+#[test]
+fn popped_items_are_numbered_in_order() {
+    let q = queue("test", 4, QueuePolicy::DropOldest);
+    for i in 0..6 {
+        q.push(i);
+    }
+    // 被丢弃的数据不占序号，序号只随出队连续递增
+    assert_eq!(q.pop_numbered(), Some((0, 2)));
+    assert_eq!(q.pop(), Some(3));
+    assert_eq!(q.pop_numbered(), Some((2, 4)));
+}
+
+#[test]
+fn multiple_decoders_keep_slot_order() {
+    let config = PipelineConfig {
+        decode_threads: 4,
+        ..PipelineConfig::default()
+    };
+    let collected = Arc::new(Mutex::new(Vec::new()));
+    let pipeline = {
+        let collected = collected.clone();
+        Pipeline::start(&config, ProcessorOptions::default(), move |results| {
+            collected.lock().unwrap().push(results.current_slot)
+        })
+        .unwrap()
+    };
+
+    // 大小悬殊的负载让各线程的反序列化耗时不同，夹杂无法反序列化的slot
+    let mut expected = Vec::new();
+    for slot in 0..200u64 {
+        let payload = if slot % 7 == 0 {
+            vec![0xff; 3]
+        } else if slot % 3 == 0 {
+            expected.push(slot);
+            fixtures::entries_payload((0..20).map(|i| fixtures::pump_buy(i, i + 1)).collect())
+        } else {
+            expected.push(slot);
+            fixtures::entries_payload(vec![])
+        };
+        pipeline.submit_blocking(RawSlot {
+            slot,
+            payload,
+            received_at: Instant::now(),
+        });
+    }
+    pipeline.shutdown();
+
+    assert_eq!(*collected.lock().unwrap(), expected);
+}
+
+// 测试结束后仍可读取内容的录制目标
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn decoders_record_raw_payloads_in_order() {
+    let config = PipelineConfig {
+        decode_threads: 4,
+        ..PipelineConfig::default()
+    };
+    let buffer = SharedBuffer::default();
+    let recorder = RecordingWriter::new(buffer.clone());
+    let pipeline = Pipeline::start_with_recorder(&config, ProcessorOptions::default(), Some(recorder), |_| {}).unwrap();
+
+    // 无法反序列化的消息也原样录制
+    let mut expected = Vec::new();
+    for slot in 0..50u64 {
+        let payload = if slot % 5 == 0 {
+            vec![0xff; 3]
+        } else {
+            fixtures::entries_payload((0..slot % 4).map(|i| fixtures::pump_buy(i, i + 1)).collect())
+        };
+        expected.push((slot, payload.clone()));
+        pipeline.submit_blocking(RawSlot {
+            slot,
+            payload,
+            received_at: Instant::now(),
+        });
+    }
+    pipeline.shutdown();
+
+    let bytes = buffer.0.lock().unwrap().clone();
+    let recorded: Vec<(u64, Vec<u8>)> = RecordingReader::new(bytes.as_slice()).collect::<Result<_, _>>().unwrap();
+    assert_eq!(recorded, expected);
+}
+
+#[test]
+fn pipeline_is_configurable() {
+    let mut config = Config::default();
+    assert_eq!(config.pipeline.decode.policy, QueuePolicy::Block);
+    config.set("pipeline.emit.policy", "drop-oldest").unwrap();
+    config.set("pipeline.parse.capacity", "16").unwrap();
+    assert_eq!(config.pipeline.emit.policy, QueuePolicy::DropOldest);
+    assert_eq!(config.pipeline.parse.capacity, 16);
+    assert!(config.set("pipeline.emit.policy", "drop-all").is_err());
+
+    let config = Config::from_toml_str("[pipeline]\ndecode_threads = 2\n[pipeline.decode]\npolicy = \"drop-newest\"\n").unwrap();
+    assert_eq!(config.pipeline.decode_threads, 2);
+    assert_eq!(config.pipeline.decode.policy, QueuePolicy::DropNewest);
+    assert_eq!(config.pipeline.decode.capacity, 128);
+
+    let mut config = Config::default();
+    config.set("pipeline.decode_threads", "0").unwrap();
+    config.set("pipeline.emit.capacity", "0").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("pipeline.decode_threads"), "{}", message);
+    assert!(message.contains("pipeline.emit.capacity"), "{}", message);
+}
@@ -0,0 +1,375 @@
+// PumpSwap池子注册表测试：CreatePool建池、买卖与流动性推算、费率与Disable、快照恢复
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use shreds_client::analytics::{PoolFees, PoolRegistry, PoolSnapshot};
+use shreds_client::config::{Config, PoolsConfig};
+use shreds_client::idl::pump;
+use shreds_client::idl::pump_amm::Instruction;
+use shreds_client::models::token_parser::WSOL_MINT;
+use shreds_client::testing::fixtures;
+
+const FEES: PoolFees = PoolFees {
+    lp_fee_bps: 20,
+    protocol_fee_bps: 5,
+};
+
+// 配置的global_config管理员
+fn admin() -> Pubkey {
+    Pubkey::new_from_array([7; 32])
+}
+
+struct Pool {
+    pool: Pubkey,
+    global_config: Pubkey,
+    mint: Pubkey,
+}
+
+impl Pool {
+    fn new() -> Self {
+        Self {
+            pool: Pubkey::new_unique(),
+            global_config: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+        }
+    }
+
+    fn key(&self) -> String {
+        self.pool.to_string()
+    }
+
+    fn instruction(&self, instruction: Instruction, args: &[&[u8]]) -> VersionedTransaction {
+        let def = instruction.def().unwrap();
+        let mut accounts = vec![("global_config", self.global_config)];
+        // 管理指令的第一个账户admin是签名者
+        if def.accounts.contains(&"admin") {
+            accounts.push(("admin", admin()));
+        }
+        if def.accounts.contains(&"pool") {
+            accounts.extend([("pool", self.pool), ("base_mint", self.mint), ("quote_mint", WSOL_MINT)]);
+        }
+        fixtures::single_instruction_transaction(fixtures::idl_instruction(
+            fixtures::pumpamm_program_id(),
+            def,
+            args,
+            &accounts,
+        ))
+    }
+
+    fn create(&self, base: u64, quote: u64) -> VersionedTransaction {
+        self.instruction(Instruction::CreatePool, &[&0u16.to_le_bytes(), &base.to_le_bytes(), &quote.to_le_bytes()])
+    }
+
+    fn buy(&self, base_out: u64, max_quote_in: u64) -> VersionedTransaction {
+        self.instruction(Instruction::Buy, &[&base_out.to_le_bytes(), &max_quote_in.to_le_bytes()])
+    }
+
+    fn sell(&self, base_in: u64, min_quote_out: u64) -> VersionedTransaction {
+        self.instruction(Instruction::Sell, &[&base_in.to_le_bytes(), &min_quote_out.to_le_bytes()])
+    }
+
+    fn deposit(&self, lp_out: u64, max_base: u64, max_quote: u64) -> VersionedTransaction {
+        self.instruction(
+            Instruction::Deposit,
+            &[&lp_out.to_le_bytes(), &max_base.to_le_bytes(), &max_quote.to_le_bytes()],
+        )
+    }
+
+    fn withdraw(&self, lp_in: u64, min_base: u64, min_quote: u64) -> VersionedTransaction {
+        self.instruction(
+            Instruction::Withdraw,
+            &[&lp_in.to_le_bytes(), &min_base.to_le_bytes(), &min_quote.to_le_bytes()],
+        )
+    }
+
+    fn update_fees(&self, lp_fee_bps: u64, protocol_fee_bps: u64) -> VersionedTransaction {
+        self.instruction(
+            Instruction::UpdateFeeConfig,
+            &[&lp_fee_bps.to_le_bytes(), &protocol_fee_bps.to_le_bytes(), &[0u8; 32 * 8]],
+        )
+    }
+
+    fn disable(&self, buy: bool) -> VersionedTransaction {
+        self.instruction(Instruction::Disable, &[&[0], &[0], &[0], &[buy as u8], &[0]])
+    }
+}
+
+fn registry() -> PoolRegistry {
+    PoolRegistry::new(&PoolsConfig {
+        enabled: true,
+        admin: Some(admin().to_string()),
+        ..PoolsConfig::default()
+    })
+}
+
+fn temp_file(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("shreds-pools-{}-{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn create_pool_records_accounts_and_reserves() {
+    let pool = Pool::new();
+    let mut pools = registry();
+    pools.record(&fixtures::process(vec![pool.create(1_000_000_000, 2_000_000_000)], 10));
+
+    let state = pools.get(&pool.key()).unwrap();
+    assert_eq!(state.base_mint, pool.mint.to_string());
+    assert_eq!(state.quote_mint, WSOL_MINT.to_string());
+    assert_eq!(state.global_config, pool.global_config.to_string());
+    assert_ne!(state.pool_base_token_account, state.pool_quote_token_account);
+    assert_eq!((state.base_reserve, state.quote_reserve), (1_000_000_000, 2_000_000_000));
+    // sqrt(1e9 * 2e9)
+    assert_eq!(state.lp_supply, 1_414_213_562);
+    assert_eq!(state.spot_price(), Some(2.0));
+    assert_eq!(state.created_slot, 10);
+    assert_eq!(pools.pools_for_mint(&pool.mint.to_string()).len(), 1);
+
+    // 池子账户已存在时CreatePool会失败
+    pools.record(&fixtures::process(vec![pool.create(1, 1)], 11));
+    assert_eq!(pools.get(&pool.key()).unwrap().base_reserve, 1_000_000_000);
+    assert_eq!(pools.rejected_updates(), 1);
+}
+
+#[test]
+fn swaps_follow_constant_product_with_fees() {
+    let pool = Pool::new();
+    let mut pools = registry();
+    pools.record(&fixtures::process(vec![pool.create(1_000_000_000, 2_000_000_000)], 10));
+
+    // 不含费用需要 ceil(2e9 * 1e8 / 9e8) = 222_222_223，lp费 444_445，协议费 111_112
+    let quote = pools.get(&pool.key()).unwrap().quote_buy(100_000_000, FEES).unwrap();
+    assert_eq!((quote.quote_amount, quote.lp_fee, quote.protocol_fee), (222_777_780, 444_445, 111_112));
+    pools.record(&fixtures::process(vec![pool.buy(100_000_000, 222_777_780)], 11));
+    let state = pools.get(&pool.key()).unwrap();
+    assert_eq!((state.base_reserve, state.quote_reserve), (900_000_000, 2_222_666_668));
+    assert_eq!(state.last_slot, 11);
+
+    // 卖出后储备回到附近，lp费留在池中
+    let quote = state.quote_sell(100_000_000, FEES).unwrap();
+    pools.record(&fixtures::process(vec![pool.sell(100_000_000, quote.quote_amount)], 12));
+    let state = pools.get(&pool.key()).unwrap().clone();
+    assert_eq!((state.base_reserve, state.quote_reserve), (1_000_000_000, quote.quote_reserve));
+    assert!(state.quote_reserve > 2_000_000_000);
+    assert_eq!(pools.rejected_updates(), 0);
+
+    // 违反滑点限制的指令视为失败
+    pools.record(&fixtures::process(vec![pool.buy(100_000_000, 1), pool.sell(100_000_000, u64::MAX)], 13));
+    assert_eq!(pools.get(&pool.key()).unwrap(), &state);
+    assert_eq!(pools.rejected_updates(), 2);
+}
+
+#[test]
+fn liquidity_changes_lp_supply_in_proportion() {
+    let pool = Pool::new();
+    let mut pools = registry();
+    pools.record(&fixtures::process(vec![pool.create(1_000_000, 4_000_000)], 10));
+    assert_eq!(pools.get(&pool.key()).unwrap().lp_supply, 2_000_000);
+
+    pools.record(&fixtures::process(vec![pool.deposit(200_000, 100_000, 400_000)], 11));
+    let state = pools.get(&pool.key()).unwrap();
+    assert_eq!((state.base_reserve, state.quote_reserve, state.lp_supply), (1_100_000, 4_400_000, 2_200_000));
+
+    pools.record(&fixtures::process(vec![pool.withdraw(1_100_000, 550_000, 2_200_000)], 12));
+    let state = pools.get(&pool.key()).unwrap();
+    assert_eq!((state.base_reserve, state.quote_reserve, state.lp_supply), (550_000, 2_200_000, 1_100_000));
+
+    // 存入上限不足、赎回超过供应量
+    pools.record(&fixtures::process(vec![pool.deposit(200_000, 1, 1), pool.withdraw(5_000_000, 0, 0)], 13));
+    assert_eq!(pools.get(&pool.key()).unwrap().lp_supply, 1_100_000);
+    assert_eq!(pools.rejected_updates(), 2);
+}
+
+#[test]
+fn instructions_in_a_slot_apply_in_entry_order() {
+    let pool = Pool::new();
+    let mut pools = registry();
+    // 建池与买入在同一slot，合并顺序颠倒后仍先建池
+    let mut results = fixtures::process(vec![pool.create(1_000_000, 1_000_000), pool.buy(1_000, 2_000)], 10);
+    results.pumpamm_transactions.reverse();
+    pools.record(&results);
+    assert_eq!(pools.get(&pool.key()).unwrap().base_reserve, 999_000);
+    assert_eq!(pools.unknown_pool_updates(), 0);
+
+    // 没有见过的池子不跟踪
+    pools.record(&fixtures::process(vec![Pool::new().buy(1_000, 2_000)], 11));
+    assert_eq!(pools.unknown_pool_updates(), 1);
+    assert_eq!(pools.len(), 1);
+}
+
+#[test]
+fn fee_updates_and_disable_apply_per_global_config() {
+    let pool = Pool::new();
+    let other = Pool::new();
+    let mut pools = registry();
+    pools.record(&fixtures::process(vec![pool.create(1_000_000, 1_000_000), other.create(1_000_000, 1_000_000)], 10));
+
+    pools.record(&fixtures::process(vec![pool.update_fees(100, 0)], 11));
+    let fees = pools.fees(pools.get(&pool.key()).unwrap());
+    assert_eq!((fees.lp_fee_bps, fees.protocol_fee_bps), (100, 0));
+    assert_eq!(pools.fees(pools.get(&other.key()).unwrap()), FEES);
+
+    pools.record(&fixtures::process(vec![pool.disable(true)], 12));
+    let state = pools.get(&pool.key()).unwrap().clone();
+    assert!(state.disabled.buy && !state.disabled.sell);
+    assert!(!pools.get(&other.key()).unwrap().disabled.any());
+
+    // 被禁用的买入不应用，卖出仍然应用
+    pools.record(&fixtures::process(vec![pool.buy(1_000, u64::MAX), pool.sell(1_000, 0)], 13));
+    let after = pools.get(&pool.key()).unwrap();
+    assert_eq!(after.base_reserve, 1_001_000);
+    assert_eq!(pools.rejected_updates(), 1);
+
+    pools.record(&fixtures::process(vec![pool.disable(false)], 14));
+    assert!(!pools.get(&pool.key()).unwrap().disabled.any());
+}
+
+#[test]
+fn pump_migrate_registers_the_pool_with_the_migration_reserves() {
+    let pool = Pool::new();
+    let migrate = fixtures::single_instruction_transaction(fixtures::idl_instruction(
+        fixtures::pump_program_id(),
+        pump::Instruction::Migrate.def().unwrap(),
+        &[],
+        &[("mint", pool.mint), ("pool", pool.pool), ("amm_global_config", pool.global_config), ("wsol_mint", WSOL_MINT)],
+    ));
+    let mut pools = PoolRegistry::new(&PoolsConfig {
+        enabled: true,
+        migration_base_reserve: 4_000_000,
+        migration_quote_reserve: 1_000_000,
+        ..PoolsConfig::default()
+    });
+    pools.record(&fixtures::process(vec![migrate.clone(), pool.buy(1_000, u64::MAX)], 10));
+
+    let state = pools.get(&pool.key()).unwrap();
+    assert_eq!((state.base_mint.clone(), state.quote_mint.clone()), (pool.mint.to_string(), WSOL_MINT.to_string()));
+    assert_eq!((state.base_reserve, state.lp_supply, state.created_slot), (3_999_000, 2_000_000, 10));
+
+    // 重复的Migrate在链上失败
+    pools.record(&fixtures::process(vec![migrate], 11));
+    assert_eq!(pools.rejected_updates(), 1);
+}
+
+#[test]
+fn admin_instructions_need_the_configured_admin() {
+    let pool = Pool::new();
+
+    // 没有配置管理员时忽略全部管理指令
+    let mut pools = PoolRegistry::new(&PoolsConfig {
+        enabled: true,
+        ..PoolsConfig::default()
+    });
+    pools.record(&fixtures::process(vec![pool.create(1_000_000, 1_000_000), pool.update_fees(100, 0)], 10));
+    assert_eq!(pools.fees(pools.get(&pool.key()).unwrap()), FEES);
+    assert_eq!(pools.ignored_admin_updates(), 1);
+
+    // 管理员没有签名，或者由其他账户签名时忽略
+    let mut pools = registry();
+    pools.record(&fixtures::process(vec![pool.create(1_000_000, 1_000_000)], 10));
+    let mut unsigned = fixtures::idl_instruction(
+        fixtures::pumpamm_program_id(),
+        Instruction::UpdateFeeConfig.def().unwrap(),
+        &[&100u64.to_le_bytes(), &0u64.to_le_bytes(), &[0u8; 32 * 8]],
+        &[("admin", admin()), ("global_config", pool.global_config)],
+    );
+    unsigned.accounts[0].is_signer = false;
+    let impostor = fixtures::idl_instruction(
+        fixtures::pumpamm_program_id(),
+        Instruction::Disable.def().unwrap(),
+        &[&[1], &[1], &[1], &[1], &[1]],
+        &[("global_config", pool.global_config)],
+    );
+    let transactions = [unsigned, impostor].map(fixtures::single_instruction_transaction).to_vec();
+    pools.record(&fixtures::process(transactions, 11));
+    assert_eq!(pools.fees(pools.get(&pool.key()).unwrap()), FEES);
+    assert!(!pools.get(&pool.key()).unwrap().disabled.any());
+    assert_eq!(pools.ignored_admin_updates(), 2);
+}
+
+#[test]
+fn snapshots_restore_state_after_a_restart() {
+    let path = temp_file("restart");
+    let config = PoolsConfig {
+        enabled: true,
+        snapshot_file: Some(path.clone()),
+        snapshot_interval_secs: 60,
+        admin: Some(admin().to_string()),
+        ..PoolsConfig::default()
+    };
+    let pool = Pool::new();
+    let mut pools = PoolRegistry::open(&config).unwrap();
+    assert!(pools.is_empty());
+    pools.record(&fixtures::process(vec![pool.create(1_000_000, 2_000_000), pool.update_fees(30, 10)], 10));
+    pools.record(&fixtures::process(vec![pool.buy(1_000, u64::MAX)], 11));
+
+    // 第一次只开始计时，超过间隔后写出
+    assert!(!pools.checkpoint(0).unwrap());
+    assert!(!pools.checkpoint(59_999).unwrap());
+    assert!(pools.checkpoint(60_000).unwrap());
+    assert!(!path.with_extension("tmp").exists());
+
+    let mut restored = PoolRegistry::open(&config).unwrap();
+    assert_eq!(restored.snapshot(), pools.snapshot());
+    assert_eq!(restored.last_slot(), 11);
+    assert_eq!(restored.fees(restored.get(&pool.key()).unwrap()).lp_fee_bps, 30);
+
+    // 快照已包含的slot不重复应用
+    restored.record(&fixtures::process(vec![pool.buy(1_000, u64::MAX)], 11));
+    assert_eq!(restored.snapshot(), pools.snapshot());
+    restored.record(&fixtures::process(vec![pool.buy(1_000, u64::MAX)], 12));
+    assert_eq!(restored.get(&pool.key()).unwrap().base_reserve, 998_000);
+
+    restored.save(120_000).unwrap();
+    assert_eq!(PoolSnapshot::read(&path).unwrap().slot, 12);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn invalid_snapshots_are_rejected() {
+    let path = temp_file("invalid");
+    let config = PoolsConfig {
+        enabled: true,
+        snapshot_file: Some(path.clone()),
+        ..PoolsConfig::default()
+    };
+    std::fs::write(&path, "{\"version\": 99, \"slot\": 1}").unwrap();
+    assert!(PoolRegistry::open(&config).is_err());
+    std::fs::write(&path, "not json").unwrap();
+    assert!(PoolRegistry::open(&config).is_err());
+
+    // 手工写的快照只需要池子的字段
+    let pool = Pool::new();
+    let seeded = format!(
+        "{{\"version\": 1, \"slot\": 5, \"pools\": [{{\"pool\": \"{}\", \"global_config\": \"{}\", \"creator\": null, \
+         \"base_mint\": \"{}\", \"quote_mint\": \"{}\", \"lp_mint\": \"lp\", \"pool_base_token_account\": \"a\", \
+         \"pool_quote_token_account\": \"b\", \"base_reserve\": 100, \"quote_reserve\": 300, \"lp_supply\": 173, \
+         \"created_slot\": 1, \"last_slot\": 5}}]}}",
+        pool.pool, pool.global_config, pool.mint, WSOL_MINT
+    );
+    std::fs::write(&path, seeded).unwrap();
+    let pools = PoolRegistry::open(&config).unwrap();
+    assert_eq!(pools.get(&pool.key()).unwrap().spot_price(), Some(3.0));
+    assert_eq!(pools.pools_for_mint(&pool.mint.to_string()).len(), 1);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn pools_config_is_validated() {
+    let mut config = Config::default();
+    config.apply_args(&["--pool-snapshot".to_string(), "pools.json".to_string()]).unwrap();
+    assert_eq!(config.pools.snapshot_file.as_deref().and_then(|path| path.to_str()), Some("pools.json"));
+
+    config.set("pools.enabled", "true").unwrap();
+    config.set("pools.protocol_fee_bps", "10000").unwrap();
+    config.set("pools.admin", "not-a-key").unwrap();
+    config.set("pools.migration_quote_reserve", "0").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("pools.lp_fee_bps"), "{}", message);
+    assert!(message.contains("pools.admin"), "{}", message);
+    assert!(message.contains("pools.migration_quote_reserve"), "{}", message);
+
+    let config = Config::from_toml_str("[pools]\nenabled = true\nsnapshot_interval_secs = 5\n").unwrap();
+    assert_eq!(config.pools.snapshot_interval_secs, 5);
+    assert!(Config::from_toml_str("[pools]\nsnapshot_secs = 5\n").is_err());
+}
@@ -0,0 +1,300 @@
+// SearcherClient 与本地 mock block engine 的端到端测试
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::Stream;
+use jito_protos::auth::auth_service_server::{AuthService, AuthServiceServer};
+use jito_protos::auth::{
+    GenerateAuthChallengeRequest, GenerateAuthChallengeResponse, GenerateAuthTokensRequest,
+    GenerateAuthTokensResponse, RefreshAccessTokenRequest, RefreshAccessTokenResponse, Token,
+};
+use jito_protos::bundle::{bundle_result, Accepted, BundleResult};
+use jito_protos::searcher::searcher_service_server::{SearcherService, SearcherServiceServer};
+use jito_protos::searcher::*;
+use shreds_client::config::Config;
+use shreds_client::SearcherClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{Message, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::VersionedTransaction;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status};
+
+const TOKEN: &str = "mock-access-token";
+const TIP_ACCOUNT: &str = "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5";
+
+// 签发令牌的过期时间
+#[derive(Default, Clone, Copy)]
+enum Expiry {
+    #[default]
+    Far,
+    Past,
+    Missing,
+}
+
+#[derive(Default, Clone)]
+struct MockBlockEngine {
+    bundles: Arc<Mutex<Vec<Vec<VersionedTransaction>>>>,
+    expiry: Expiry,
+    // generate_auth_tokens被调用的次数
+    logins: Arc<AtomicUsize>,
+}
+
+impl MockBlockEngine {
+    // tonic的服务方法本身就返回Result<_, Status>
+    #[allow(clippy::result_large_err)]
+    fn check_auth<T>(request: &Request<T>) -> Result<(), Status> {
+        let expected = format!("Bearer {}", TOKEN);
+        match request.metadata().get("authorization").and_then(|v| v.to_str().ok()) {
+            Some(v) if v == expected => Ok(()),
+            _ => Err(Status::unauthenticated("missing token")),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl AuthService for MockBlockEngine {
+    async fn generate_auth_challenge(
+        &self,
+        _request: Request<GenerateAuthChallengeRequest>,
+    ) -> Result<Response<GenerateAuthChallengeResponse>, Status> {
+        Ok(Response::new(GenerateAuthChallengeResponse { challenge: "abc".to_string() }))
+    }
+
+    async fn generate_auth_tokens(
+        &self,
+        request: Request<GenerateAuthTokensRequest>,
+    ) -> Result<Response<GenerateAuthTokensResponse>, Status> {
+        let req = request.into_inner();
+        let pubkey = Pubkey::try_from(req.client_pubkey.as_slice())
+            .map_err(|_| Status::invalid_argument("bad pubkey"))?;
+        let signature = solana_sdk::signature::Signature::try_from(req.signed_challenge.as_slice())
+            .map_err(|_| Status::invalid_argument("bad signature"))?;
+        if req.challenge != format!("{}-abc", pubkey)
+            || !signature.verify(pubkey.as_ref(), req.challenge.as_bytes())
+        {
+            return Err(Status::permission_denied("bad challenge"));
+        }
+
+        self.logins.fetch_add(1, Ordering::SeqCst);
+        let seconds = match self.expiry {
+            Expiry::Far => Some(i64::MAX / 2),
+            Expiry::Past => Some(0),
+            Expiry::Missing => None,
+        };
+        let token = |value: &str| Token {
+            value: value.to_string(),
+            expires_at_utc: seconds.map(|seconds| prost_types::Timestamp { seconds, nanos: 0 }),
+        };
+        Ok(Response::new(GenerateAuthTokensResponse {
+            access_token: Some(token(TOKEN)),
+            refresh_token: Some(token("mock-refresh-token")),
+        }))
+    }
+
+    async fn refresh_access_token(
+        &self,
+        _request: Request<RefreshAccessTokenRequest>,
+    ) -> Result<Response<RefreshAccessTokenResponse>, Status> {
+        Err(Status::unimplemented("not needed"))
+    }
+}
+
+#[tonic::async_trait]
+impl SearcherService for MockBlockEngine {
+    type SubscribeBundleResultsStream =
+        Pin<Box<dyn Stream<Item = Result<BundleResult, Status>> + Send>>;
+
+    async fn subscribe_bundle_results(
+        &self,
+        request: Request<SubscribeBundleResultsRequest>,
+    ) -> Result<Response<Self::SubscribeBundleResultsStream>, Status> {
+        Self::check_auth(&request)?;
+        let result = BundleResult {
+            bundle_id: "bundle-0".to_string(),
+            result: Some(bundle_result::Result::Accepted(Accepted {
+                slot: 42,
+                validator_identity: "validator".to_string(),
+            })),
+        };
+        Ok(Response::new(Box::pin(futures_util::stream::iter(vec![Ok(result)]))))
+    }
+
+    async fn send_bundle(
+        &self,
+        request: Request<SendBundleRequest>,
+    ) -> Result<Response<SendBundleResponse>, Status> {
+        Self::check_auth(&request)?;
+        let bundle = request.into_inner().bundle.unwrap_or_default();
+        let transactions = bundle
+            .packets
+            .iter()
+            .map(|p| bincode::deserialize::<VersionedTransaction>(&p.data))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let mut bundles = self.bundles.lock().unwrap();
+        bundles.push(transactions);
+        Ok(Response::new(SendBundleResponse { uuid: format!("bundle-{}", bundles.len() - 1) }))
+    }
+
+    async fn get_next_scheduled_leader(
+        &self,
+        request: Request<NextScheduledLeaderRequest>,
+    ) -> Result<Response<NextScheduledLeaderResponse>, Status> {
+        Self::check_auth(&request)?;
+        Ok(Response::new(NextScheduledLeaderResponse {
+            current_slot: 100,
+            next_leader_slot: 104,
+            next_leader_identity: "leader".to_string(),
+            next_leader_region: request.into_inner().regions.first().cloned().unwrap_or_default(),
+        }))
+    }
+
+    async fn get_connected_leaders(
+        &self,
+        request: Request<ConnectedLeadersRequest>,
+    ) -> Result<Response<ConnectedLeadersResponse>, Status> {
+        Self::check_auth(&request)?;
+        let mut resp = ConnectedLeadersResponse::default();
+        resp.connected_validators
+            .insert("leader".to_string(), SlotList { slots: vec![104, 105, 106, 107] });
+        Ok(Response::new(resp))
+    }
+
+    async fn get_connected_leaders_regioned(
+        &self,
+        _request: Request<ConnectedLeadersRegionedRequest>,
+    ) -> Result<Response<ConnectedLeadersRegionedResponse>, Status> {
+        Err(Status::unimplemented("not needed"))
+    }
+
+    async fn get_tip_accounts(
+        &self,
+        request: Request<GetTipAccountsRequest>,
+    ) -> Result<Response<GetTipAccountsResponse>, Status> {
+        Self::check_auth(&request)?;
+        Ok(Response::new(GetTipAccountsResponse { accounts: vec![TIP_ACCOUNT.to_string()] }))
+    }
+
+    async fn get_regions(
+        &self,
+        _request: Request<GetRegionsRequest>,
+    ) -> Result<Response<GetRegionsResponse>, Status> {
+        Err(Status::unimplemented("not needed"))
+    }
+}
+
+async fn spawn_mock(engine: MockBlockEngine) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(AuthServiceServer::new(engine.clone()))
+            .add_service(SearcherServiceServer::new(engine))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    format!("http://{}", addr)
+}
+
+fn tip_transaction(payer: &Keypair, tip_account: &Pubkey) -> VersionedTransaction {
+    let ix = system_instruction::transfer(&payer.pubkey(), tip_account, 1_000);
+    let message = Message::new_with_blockhash(&[ix], Some(&payer.pubkey()), &Hash::default());
+    VersionedTransaction::try_new(VersionedMessage::Legacy(message), &[payer]).unwrap()
+}
+
+#[tokio::test]
+async fn send_bundle_round_trip() {
+    let engine = MockBlockEngine::default();
+    let url = spawn_mock(engine.clone()).await;
+    let keypair = Arc::new(Keypair::new());
+
+    let mut client = SearcherClient::connect(url, Some(keypair.clone())).await.unwrap();
+
+    let tip_accounts = client.get_tip_accounts().await.unwrap();
+    assert_eq!(tip_accounts, vec![TIP_ACCOUNT.to_string()]);
+
+    let tip_account: Pubkey = tip_accounts[0].parse().unwrap();
+    let tx = tip_transaction(&keypair, &tip_account);
+    let uuid = client.send_bundle(std::slice::from_ref(&tx)).await.unwrap();
+    assert_eq!(uuid, "bundle-0");
+    assert_eq!(engine.bundles.lock().unwrap()[0], vec![tx]);
+
+    let leader = client.get_next_scheduled_leader(vec!["ny".to_string()]).await.unwrap();
+    assert_eq!(leader.next_leader_slot, 104);
+    assert_eq!(leader.next_leader_region, "ny");
+
+    let leaders = client.get_connected_leaders().await.unwrap();
+    assert_eq!(leaders.connected_validators["leader"].slots.len(), 4);
+
+    let mut results = client.subscribe_bundle_results().await.unwrap();
+    let result = results.message().await.unwrap().unwrap();
+    assert_eq!(result.bundle_id, "bundle-0");
+}
+
+#[tokio::test]
+async fn connects_from_config_with_the_keypair_file() {
+    let url = spawn_mock(MockBlockEngine::default()).await;
+    let path = std::env::temp_dir().join(format!("shreds-searcher-keypair-{}.json", std::process::id()));
+    write_keypair_file(&Keypair::new(), &path).unwrap();
+
+    let mut config = Config::default();
+    assert!(SearcherClient::from_config(&config).await.is_err());
+    config.set("endpoints.block_engine_url", &url).unwrap();
+    config.set("auth.keypair_path", path.to_str().unwrap()).unwrap();
+    config.validate_block_engine().unwrap();
+
+    let mut client = SearcherClient::from_config(&config).await.unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(client.get_tip_accounts().await.unwrap(), vec![TIP_ACCOUNT.to_string()]);
+}
+
+#[tokio::test]
+async fn unauthenticated_client_is_rejected() {
+    let url = spawn_mock(MockBlockEngine::default()).await;
+
+    let mut client = SearcherClient::connect(url, None).await.unwrap();
+
+    assert!(client.get_tip_accounts().await.is_err());
+}
+
+#[tokio::test]
+async fn missing_expiry_uses_a_default_lifetime() {
+    let engine = MockBlockEngine {
+        expiry: Expiry::Missing,
+        ..MockBlockEngine::default()
+    };
+    let url = spawn_mock(engine.clone()).await;
+
+    let mut client = SearcherClient::connect(url, Some(Arc::new(Keypair::new()))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(2_500)).await;
+
+    // 没有过期时间时不当作已过期反复重新认证
+    assert_eq!(engine.logins.load(Ordering::SeqCst), 1);
+    assert!(client.get_tip_accounts().await.is_ok());
+}
+
+#[tokio::test]
+async fn refreshing_stops_when_the_client_is_dropped() {
+    let engine = MockBlockEngine {
+        expiry: Expiry::Past,
+        ..MockBlockEngine::default()
+    };
+    let url = spawn_mock(engine.clone()).await;
+
+    let client = SearcherClient::connect(url, Some(Arc::new(Keypair::new()))).await.unwrap();
+    // 令牌已过期，刷新任务每秒重新认证一次
+    tokio::time::sleep(Duration::from_millis(2_500)).await;
+    assert!(engine.logins.load(Ordering::SeqCst) > 1);
+
+    drop(client);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let logins = engine.logins.load(Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(2_000)).await;
+    assert_eq!(engine.logins.load(Ordering::SeqCst), logins);
+}
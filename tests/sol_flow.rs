@@ -0,0 +1,123 @@
+// System Program转账的SOL流向汇总测试
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use shreds_client::config::Config;
+use shreds_client::models::sol_flow::{decode_system_transfer, FlowKind, JITO_TIP_ACCOUNTS};
+use shreds_client::services::ProcessorOptions;
+use shreds_client::testing::fixtures;
+use shreds_client::TransactionProcessor;
+
+fn process(transactions: Vec<VersionedTransaction>, options: &ProcessorOptions) -> shreds_client::TransactionResults {
+    let entries: Vec<solana_entry::entry::Entry> =
+        bincode::deserialize(&fixtures::entries_payload(transactions)).unwrap();
+    TransactionProcessor::process_entries_with(&entries, 12, options)
+}
+
+// PUMP买入，附带Jito小费、转给fee_recipient的手续费与平台手续费
+fn pump_buy_with_fees(platform: Pubkey) -> VersionedTransaction {
+    let data = fixtures::instruction_data(
+        [102, 6, 61, 18, 1, 218, 235, 234],
+        &[&1u64.to_le_bytes(), &2u64.to_le_bytes()],
+    );
+    let buy = fixtures::instruction(fixtures::pump_program_id(), data, 12);
+    let payer = buy.accounts[0].pubkey;
+    let fee_recipient = buy.accounts[1].pubkey;
+    fixtures::transaction(&[
+        buy,
+        fixtures::system_transfer(payer, JITO_TIP_ACCOUNTS[3], 1_000),
+        fixtures::system_transfer(payer, fee_recipient, 2_000),
+        fixtures::system_transfer(payer, platform, 3_000),
+    ])
+}
+
+#[test]
+fn decodes_system_transfers() {
+    let accounts = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let mut create = 0u32.to_le_bytes().to_vec();
+    create.extend_from_slice(&10u64.to_le_bytes());
+    create.extend_from_slice(&165u64.to_le_bytes());
+    create.extend_from_slice(&[0; 32]);
+    let transfer = decode_system_transfer(&create, &accounts).unwrap();
+    assert_eq!((transfer.from, transfer.to, transfer.lamports, transfer.space), (accounts[0], accounts[1], 10, Some(165)));
+
+    let mut with_seed = 3u32.to_le_bytes().to_vec();
+    with_seed.extend_from_slice(&[0; 32]);
+    with_seed.extend_from_slice(&4u64.to_le_bytes());
+    with_seed.extend_from_slice(b"seed");
+    with_seed.extend_from_slice(&20u64.to_le_bytes());
+    with_seed.extend_from_slice(&82u64.to_le_bytes());
+    with_seed.extend_from_slice(&[0; 32]);
+    let transfer = decode_system_transfer(&with_seed, &accounts).unwrap();
+    assert_eq!((transfer.lamports, transfer.space), (20, Some(82)));
+
+    let mut transfer_with_seed = 11u32.to_le_bytes().to_vec();
+    transfer_with_seed.extend_from_slice(&30u64.to_le_bytes());
+    let transfer = decode_system_transfer(&transfer_with_seed, &accounts).unwrap();
+    assert_eq!((transfer.from, transfer.to, transfer.lamports), (accounts[0], accounts[2], 30));
+
+    // Assign(1)不转移lamports
+    assert!(decode_system_transfer(&1u32.to_le_bytes(), &accounts).is_none());
+    assert!(decode_system_transfer(&2u32.to_le_bytes(), &accounts).is_none());
+}
+
+#[test]
+fn classifies_tips_fee_recipients_and_third_parties() {
+    let platform = Pubkey::new_unique();
+    let results = process(vec![pump_buy_with_fees(platform)], &ProcessorOptions::default());
+    assert_eq!(results.pump_transactions.len(), 1);
+
+    let signature = &results.pump_transactions[0].signature;
+    let flow = &results.sol_flows[signature];
+    assert_eq!(flow.payer_outflow(), 6_000);
+    assert_eq!(flow.total(FlowKind::Tip), 1_000);
+    assert_eq!(flow.total(FlowKind::FeeRecipient), 2_000);
+    let third_parties: Vec<_> = flow.third_parties().collect();
+    assert_eq!(third_parties.len(), 1);
+    assert_eq!(third_parties[0].to, platform);
+    assert_eq!(third_parties[0].lamports, 3_000);
+
+    let record = &results.to_json_records()[0];
+    assert_eq!(record["sol_flow"]["payer_outflow"], 6_000);
+    assert_eq!(record["sol_flow"]["third_party"], 3_000);
+    assert_eq!(record["sol_flow"]["transfers"][0]["kind"], "tip");
+
+    let mut text = Vec::new();
+    results.write_to(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.contains("SOL_Flow: fee_payer="));
+    assert!(text.contains("Third_Party: "));
+}
+
+#[test]
+fn transfers_into_wsol_accounts_are_wraps() {
+    let results = process(vec![fixtures::pumpswap_buy_with_wsol(1, 2, 4_000)], &ProcessorOptions::default());
+    let flow = &results.sol_flows[&results.pumpamm_transactions[0].signature];
+    assert_eq!(flow.transfers.len(), 1);
+    assert_eq!(flow.transfers[0].kind, FlowKind::WsolWrap);
+    assert_eq!(flow.total(FlowKind::WsolWrap), 4_000);
+}
+
+#[test]
+fn tip_accounts_and_toggle_come_from_config() {
+    let extra = Pubkey::new_unique();
+    let mut config = Config::default();
+    config.set("parsers.tip_accounts", &extra.to_string()).unwrap();
+    let options = ProcessorOptions::from_config(&config).unwrap();
+    assert!(options.tip_accounts.contains(&extra));
+    assert!(options.tip_accounts.contains(&JITO_TIP_ACCOUNTS[0]));
+
+    config.set("parsers.tip_accounts", "not-a-key").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("parsers.tip_accounts"), "{}", message);
+
+    let options = ProcessorOptions {
+        sol_flows: false,
+        ..ProcessorOptions::default()
+    };
+    let results = process(vec![pump_buy_with_fees(Pubkey::new_unique())], &options);
+    assert_eq!(results.pump_transactions.len(), 1);
+    assert!(results.sol_flows.is_empty());
+    assert!(results.to_json_records()[0].get("sol_flow").is_none());
+}
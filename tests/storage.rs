@@ -0,0 +1,197 @@
+// 交易事件提取、按slot整理的写入批次与SQLite持久化测试；数据库部分需要 --features sqlite
+use solana_sdk::pubkey::Pubkey;
+
+use shreds_client::config::Config;
+use shreds_client::models::events::{self, TradeSide};
+use shreds_client::storage::SlotBatch;
+use shreds_client::testing::fixtures;
+use shreds_client::utils::account_layout::Program;
+use shreds_client::TransactionResults;
+
+#[test]
+fn trades_are_extracted_from_every_venue() {
+    let results = fixtures::process(
+        vec![
+            fixtures::pump_buy(1_000, 50),
+            fixtures::pump_sell(400, 10),
+            fixtures::pumpswap_buy(2_000, 70),
+            fixtures::boop_buy_token(90, 3_000),
+            fixtures::boop_sell_token(500, 20),
+        ],
+        7,
+    );
+    let trades = events::trades(&results);
+    assert_eq!(trades.len(), 5);
+    assert!(trades.iter().all(|trade| trade.slot == 7 && trade.pool.is_some() && trade.user.is_some()));
+
+    let pump_buy = trades.iter().find(|t| t.venue == Program::Pump && t.side == TradeSide::Buy).unwrap();
+    assert_eq!((pump_buy.token_amount, pump_buy.quote_amount, pump_buy.exact_quote), (1_000, 50, false));
+
+    let swap = trades.iter().find(|t| t.venue == Program::PumpAmm).unwrap();
+    assert_eq!((swap.token_amount, swap.quote_amount), (2_000, 70));
+
+    // boop买入给出精确的SOL数量，卖出给出精确的代币数量
+    let boop_buy = trades.iter().find(|t| t.venue == Program::Boop && t.side == TradeSide::Buy).unwrap();
+    assert_eq!((boop_buy.token_amount, boop_buy.quote_amount, boop_buy.exact_quote), (3_000, 90, true));
+    let boop_sell = trades.iter().find(|t| t.venue == Program::Boop && t.side == TradeSide::Sell).unwrap();
+    assert_eq!((boop_sell.token_amount, boop_sell.quote_amount, boop_sell.exact_quote), (500, 20, false));
+    assert_eq!(boop_buy.limit_price(), Some(0.03));
+}
+
+#[test]
+fn launches_carry_metadata_and_creator() {
+    let creator = Pubkey::new_unique();
+    let results = fixtures::process(vec![fixtures::pump_create("Token", "TKN", "https://example.com", &creator)], 8);
+    let launches = events::launches(&results);
+    assert_eq!(launches.len(), 1);
+    assert_eq!(launches[0].venue, Program::Pump);
+    assert_eq!(launches[0].symbol.as_deref(), Some("TKN"));
+    assert_eq!(launches[0].creator, Some(creator.to_string()));
+    assert!(launches[0].bonding_curve.is_some());
+}
+
+#[test]
+fn slot_batch_groups_rows_and_summaries() {
+    let results = fixtures::process(vec![fixtures::pump_buy(1_000, 50), fixtures::pump_sell(400, 10)], 9);
+    let batch = SlotBatch::from_results(&results);
+    assert_eq!(batch.slot, 9);
+    assert_eq!(batch.transactions.len(), 2);
+    assert_eq!(batch.instructions.len(), 2);
+    assert!(batch.instructions.iter().all(|ix| ix.args.is_some() && ix.data.is_some()));
+
+    // 两笔交易的mint不同，各有一条汇总
+    assert_eq!(batch.summaries.len(), 2);
+    let bought: u64 = batch.summaries.iter().map(|summary| summary.token_bought).sum();
+    let sold: u64 = batch.summaries.iter().map(|summary| summary.token_sold).sum();
+    assert_eq!((bought, sold), (1_000, 400));
+
+    assert!(SlotBatch::from_results(&TransactionResults::new()).is_empty());
+}
+
+#[test]
+fn storage_config_is_validated() {
+    let mut config = Config::default();
+    config.set("storage.queue.capacity", "0").unwrap();
+    config.validate_offline().unwrap();
+
+    config.set("storage.enabled", "true").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("storage.queue.capacity"), "{}", message);
+    assert_eq!(message.contains("--features sqlite"), !cfg!(feature = "sqlite"), "{}", message);
+
+    let mut config = Config::default();
+    config.apply_args(&["--db".to_string(), "slots.db".to_string()]).unwrap();
+    assert_eq!(config.storage.path.to_str(), Some("slots.db"));
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::path::PathBuf;
+
+    use shreds_client::config::StorageConfig;
+    use shreds_client::storage::schema;
+    use shreds_client::storage::{StorageWriter, Store, Value};
+
+    use super::*;
+
+    fn temp_db(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("shreds-storage-{}-{}.db", name, std::process::id()));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+        path
+    }
+
+    fn count(store: &Store, table: &str) -> i64 {
+        match store.query(&format!("SELECT COUNT(*) FROM {}", table)).unwrap()[0][0] {
+            Value::Integer(count) => count,
+            ref other => panic!("COUNT(*) 返回 {:?}", other),
+        }
+    }
+
+    #[test]
+    fn batches_are_written_per_slot() {
+        let path = temp_db("write");
+        let mut store = Store::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), schema::version());
+
+        let creator = Pubkey::new_unique();
+        let results = fixtures::process(
+            vec![
+                fixtures::pump_buy(1_000, 50),
+                fixtures::boop_buy_token(90, 3_000),
+                fixtures::pump_create("Token", "TKN", "https://example.com", &creator),
+            ],
+            10,
+        );
+        let batch = SlotBatch::from_results(&results);
+        store.write(&batch).unwrap();
+        // 同一slot重复写入时覆盖，不产生重复行
+        store.write(&batch).unwrap();
+
+        assert_eq!(count(&store, "slots"), 1);
+        assert_eq!(count(&store, "transactions"), 3);
+        assert_eq!(count(&store, "instructions"), 3);
+        assert_eq!(count(&store, "trades"), 2);
+        assert_eq!(count(&store, "token_launches"), 1);
+        assert_eq!(count(&store, "trade_summaries"), 2);
+
+        let rows = store
+            .query("SELECT venue, side, token_amount, quote_amount, exact_quote FROM trades ORDER BY venue")
+            .unwrap();
+        assert_eq!(
+            rows[0],
+            vec![Value::Text("BOOP".into()), Value::Text("buy".into()), Value::Integer(3_000), Value::Integer(90), Value::Integer(1)]
+        );
+        let creators = store.query("SELECT creator FROM token_launches").unwrap();
+        assert_eq!(creators[0][0], Value::Text(creator.to_string()));
+    }
+
+    #[test]
+    fn reopening_keeps_the_schema_version() {
+        let path = temp_db("reopen");
+        drop(Store::open(&path).unwrap());
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), schema::version());
+
+        // 比当前程序新的数据库拒绝打开
+        store.query(&format!("PRAGMA user_version = {}", schema::version() + 1)).unwrap();
+        drop(store);
+        assert!(Store::open(&path).is_err());
+    }
+
+    #[test]
+    fn version_one_databases_are_migrated() {
+        let path = temp_db("migrate");
+        let mut store = Store::open(&path).unwrap();
+        store.write(&SlotBatch::from_results(&fixtures::process(vec![fixtures::pump_buy(1_000, 50)], 7))).unwrap();
+        // 退回到版本1的表名与版本号
+        store.query("ALTER TABLE trade_summaries RENAME TO curve_snapshots").unwrap();
+        store.query("PRAGMA user_version = 1").unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), schema::version());
+        assert_eq!(count(&store, "trade_summaries"), 1);
+    }
+
+    #[test]
+    fn writer_flushes_on_shutdown() {
+        let path = temp_db("writer");
+        let config = StorageConfig {
+            enabled: true,
+            path: path.clone(),
+            ..StorageConfig::default()
+        };
+        let writer = StorageWriter::start(&config).unwrap();
+        for slot in 20..25 {
+            writer.submit(&fixtures::process(vec![fixtures::pump_buy(1, 2)], slot));
+        }
+        writer.submit(&TransactionResults::new());
+        writer.shutdown();
+
+        let store = Store::open(&path).unwrap();
+        assert_eq!(count(&store, "slots"), 5);
+        assert_eq!(count(&store, "trades"), 5);
+    }
+}
@@ -0,0 +1,65 @@
+// 解析线程池、tokio运行时与核心绑定配置测试
+use std::thread;
+
+use shreds_client::config::{Config, RuntimeConfig};
+use shreds_client::services::threads;
+use shreds_client::services::ProcessorOptions;
+use shreds_client::testing::fixtures;
+use shreds_client::TransactionProcessor;
+
+#[test]
+fn parser_pool_threads_are_named() {
+    let pool = threads::parser_pool(Some(3), &[]).unwrap();
+    assert_eq!(pool.current_num_threads(), 3);
+    let name = pool.install(|| thread::current().name().map(str::to_string));
+    assert!(name.unwrap().starts_with("shreds-parser-"));
+}
+
+#[test]
+fn processing_runs_on_the_configured_pool() {
+    let mut config = Config::default();
+    config.set("processing.threads", "2").unwrap();
+    // from_config不创建线程池
+    let options = ProcessorOptions::from_config(&config).unwrap();
+    assert!(options.pool.is_none());
+
+    let pool = threads::parser_pool(config.processing.threads, &config.runtime.parser_cores).unwrap();
+    let options = options.with_pool(pool);
+    assert_eq!(options.parser_threads(), 2);
+
+    let entries: Vec<solana_entry::entry::Entry> =
+        bincode::deserialize(&fixtures::entries_payload(vec![fixtures::pump_buy(1, 2)])).unwrap();
+    let results = TransactionProcessor::process_entries_with(&entries, 9, &options);
+    assert_eq!(results.pump_transactions.len(), 1);
+}
+
+#[test]
+fn tokio_runtime_uses_configured_workers() {
+    let config = RuntimeConfig {
+        tokio_threads: 1,
+        ..RuntimeConfig::default()
+    };
+    let rt = threads::tokio_runtime(&config).unwrap();
+    let name = rt.block_on(async { tokio::spawn(async { thread::current().name().map(str::to_string) }).await.unwrap() });
+    assert_eq!(name.as_deref(), Some("shreds-tokio"));
+}
+
+#[test]
+fn out_of_range_cores_are_rejected() {
+    assert!(threads::pin_current_thread(4096).is_err());
+
+    let mut config = Config::default();
+    config.set("runtime.parser_cores", "0, 1,").unwrap();
+    config.set("runtime.tokio_threads", "4").unwrap();
+    assert_eq!(config.runtime.parser_cores, vec![0, 1]);
+    assert_eq!(config.runtime.tokio_threads, 4);
+    assert!(config.set("runtime.receive_cores", "a").is_err());
+
+    config.set("runtime.receive_cores", "100000").unwrap();
+    config.set("runtime.tokio_cores", "100000").unwrap();
+    config.set("runtime.tokio_threads", "0").unwrap();
+    let message = config.validate_offline().unwrap_err().to_string();
+    assert!(message.contains("runtime.receive_cores"), "{}", message);
+    assert!(message.contains("runtime.tokio_cores"), "{}", message);
+    assert!(message.contains("runtime.tokio_threads"), "{}", message);
+}
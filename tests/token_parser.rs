@@ -0,0 +1,119 @@
+// SPL Token / Token-2022 / ATA 指令解码与代币副作用汇总测试
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use shreds_client::models::token_parser::{
+    rent_exempt_minimum, TokenInstructionType, TokenParser, TokenProgram, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+    WSOL_MINT,
+};
+use shreds_client::services::ProcessorOptions;
+use shreds_client::testing::fixtures;
+use shreds_client::TransactionProcessor;
+
+fn process(transactions: Vec<VersionedTransaction>, options: &ProcessorOptions) -> shreds_client::TransactionResults {
+    let entries: Vec<solana_entry::entry::Entry> =
+        bincode::deserialize(&fixtures::entries_payload(transactions)).unwrap();
+    TransactionProcessor::process_entries_with(&entries, 11, options)
+}
+
+#[test]
+fn decodes_token_instruction_tags() {
+    let mut data = vec![12];
+    data.extend_from_slice(&1_500u64.to_le_bytes());
+    data.push(6);
+    let ix = TokenParser::decode(TokenProgram::Token, &data, vec![]);
+    assert_eq!(ix.instruction_type, TokenInstructionType::TransferChecked);
+    assert_eq!(ix.amount, Some(1_500));
+    assert_eq!(ix.decimals, Some(6));
+
+    let create = TokenParser::decode(TokenProgram::AssociatedToken, &[], vec![]);
+    assert_eq!(create.instruction_type, TokenInstructionType::CreateAssociatedAccount);
+    let idempotent = TokenParser::decode(TokenProgram::AssociatedToken, &[1], vec![]);
+    assert_eq!(idempotent.instruction_type, TokenInstructionType::CreateAssociatedAccountIdempotent);
+
+    let extension = TokenParser::decode(TokenProgram::Token2022, &[30], vec![]);
+    assert_eq!(extension.instruction_type, TokenInstructionType::Token2022Extension);
+    let unknown = TokenParser::decode(TokenProgram::Token, &[30], vec![]);
+    assert_eq!(unknown.instruction_type, TokenInstructionType::Unknown);
+    assert_eq!(rent_exempt_minimum(165), 2_039_280);
+}
+
+#[test]
+fn annotates_matched_transactions_with_wsol_round_trip() {
+    let results = process(vec![fixtures::pumpswap_buy_with_wsol(10, 20, 5_000_000)], &ProcessorOptions::default());
+    assert_eq!(results.pumpamm_transactions.len(), 1);
+
+    let signature = &results.pumpamm_transactions[0].signature;
+    let effects = &results.token_effects[signature];
+    assert_eq!(effects.atas_created.len(), 1);
+    assert!(effects.atas_created[0].idempotent);
+    assert_eq!(effects.atas_created[0].mint, WSOL_MINT);
+    assert_eq!(effects.wsol_wrapped.len(), 1);
+    assert_eq!(effects.wsol_wrapped[0].lamports, 5_000_000);
+    assert_eq!(effects.wsol_unwrapped().count(), 1);
+    assert_eq!(effects.rent_reclaimed(), 2_039_280);
+
+    let record = &results.to_json_records()[0];
+    assert_eq!(record["token_effects"]["wsol_wrapped"][0]["lamports"], 5_000_000);
+    assert_eq!(record["token_effects"]["wsol_unwrapped"].as_array().unwrap().len(), 1);
+    assert_eq!(record["token_effects"]["atas_created"][0]["idempotent"], true);
+
+    let mut text = Vec::new();
+    results.write_to(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.contains("Token_Effects:"));
+    assert!(text.contains("WSOL_Wrapped: "));
+    assert!(text.contains("WSOL_Unwrapped: "));
+}
+
+#[test]
+fn wraps_sol_funded_through_create_account() {
+    let wallet = Pubkey::new_unique();
+    let account = Pubkey::new_unique();
+
+    let mut create = 0u32.to_le_bytes().to_vec();
+    create.extend_from_slice(&(rent_exempt_minimum(165) + 700).to_le_bytes());
+    create.extend_from_slice(&165u64.to_le_bytes());
+    create.extend_from_slice(TOKEN_PROGRAM_ID.as_ref());
+    let mut initialize = vec![18];
+    initialize.extend_from_slice(wallet.as_ref());
+
+    let tx = fixtures::transaction(&[
+        Instruction {
+            program_id: SYSTEM_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(wallet, true), AccountMeta::new(account, true)],
+            data: create,
+        },
+        Instruction {
+            program_id: TOKEN_PROGRAM_ID,
+            accounts: vec![AccountMeta::new(account, false), AccountMeta::new_readonly(WSOL_MINT, false)],
+            data: initialize,
+        },
+    ]);
+
+    let effects = TokenParser::parse_transaction(&tx).unwrap();
+    assert_eq!(effects.wsol_wrapped.len(), 1);
+    assert_eq!(effects.wsol_wrapped[0].account, account);
+    assert_eq!(effects.wsol_wrapped[0].lamports, 700);
+}
+
+#[test]
+fn only_matched_transactions_are_annotated() {
+    let token_only = fixtures::single_instruction_transaction(Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(Pubkey::new_unique(), true)],
+        data: vec![17],
+    });
+    let results = process(vec![token_only, fixtures::pump_buy(1, 2)], &ProcessorOptions::default());
+    assert!(results.token_effects.is_empty());
+
+    let options = ProcessorOptions {
+        token_effects: false,
+        ..ProcessorOptions::default()
+    };
+    let results = process(vec![fixtures::pumpswap_buy_with_wsol(1, 2, 3)], &options);
+    assert_eq!(results.pumpamm_transactions.len(), 1);
+    assert!(results.token_effects.is_empty());
+    assert!(results.to_json_records()[0].get("token_effects").is_none());
+}